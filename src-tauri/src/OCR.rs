@@ -1,6 +1,13 @@
 //! 对OCR相关功能的封装，实现图像文字识别。
 //! 依赖 uniocr 库进行 OCR 处理。
 // #[cfg(feature = "with_uniocr")]
+use crate::config::{Config, CONFIG};
+use base64::{engine::general_purpose, Engine as _};
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, GrayImage, ImageFormat, Luma};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Cursor;
 use std::sync::{Arc, Mutex, OnceLock};
 use std::time::Duration;
 use uni_ocr::{Language, OcrEngine, OcrOptions, OcrProvider};
@@ -31,8 +38,643 @@ fn parse_provider(provider: &str) -> Result<OcrProvider, String> {
     }
 }
 
+/// 文本行的包围盒（像素坐标，原点为图片左上角）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OcrBox {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl OcrBox {
+    /// 同时包含两个包围盒的最小包围盒
+    fn union(self, other: OcrBox) -> OcrBox {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+        OcrBox {
+            x,
+            y,
+            width: right - x,
+            height: bottom - y,
+        }
+    }
+}
+
+/// 识别出的一行文本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OcrLine {
+    /// 行文本
+    pub text: String,
+    /// 所属文本块序号，纯文本中不同文本块之间以空行分隔
+    pub block: u32,
+    /// 包围盒，提供者未给出位置时为 None
+    pub bbox: Option<OcrBox>,
+    /// 置信度（0.0 - 1.0），提供者未给出时为 None
+    pub confidence: Option<f32>,
+    /// 语言代码（eng/chi/jpn/kor），提供者未给出时按字符集粗略判断
+    pub language: Option<String>,
+}
+
+/// 一次 OCR 识别的结构化结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OcrResult {
+    /// 实际使用的 OCR 提供者
+    pub provider: String,
+    /// 识别时配置的语言列表
+    pub languages: Vec<String>,
+    /// 整体置信度（0.0 - 1.0），提供者未给出时取各行置信度的平均值
+    pub confidence: Option<f32>,
+    /// 通过置信度阈值的文本行
+    pub lines: Vec<OcrLine>,
+    /// 可读的纯文本
+    pub text: String,
+}
+
+/// 将提供者给出的置信度统一到 0.0 - 1.0。负数表示无置信度（如 tesseract 的非文字区域）
+fn normalize_confidence(value: f32) -> Option<f32> {
+    if !value.is_finite() || value < 0.0 {
+        None
+    } else if value > 1.0 {
+        Some((value / 100.0).min(1.0))
+    } else {
+        Some(value)
+    }
+}
+
+/// 读取 JSON 中的数值字段，兼容以字符串表示的数字
+fn number_field(entry: &Value, keys: &[&str]) -> Option<f32> {
+    keys.iter().find_map(|key| match entry.get(*key)? {
+        Value::Number(n) => n.as_f64().map(|v| v as f32),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    })
+}
+
+/// 读取包围盒，支持 bounding_box/bbox 对象或平铺的 left/top/width/height 字段
+fn bbox_field(entry: &Value) -> Option<OcrBox> {
+    let source = entry
+        .get("bounding_box")
+        .or_else(|| entry.get("bbox"))
+        .filter(|v| v.is_object())
+        .unwrap_or(entry);
+    Some(OcrBox {
+        x: number_field(source, &["x", "left"])?,
+        y: number_field(source, &["y", "top"])?,
+        width: number_field(source, &["width"])?,
+        height: number_field(source, &["height"])?,
+    })
+}
+
+/// 是否为中日韩文字或全角标点，这些字符之间不需要空格
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3000}'..='\u{303F}'
+        | '\u{3040}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7AF}'
+        | '\u{FF00}'..='\u{FFEF}')
+}
+
+/// 拼接两段文字：中日韩文字之间直接相连，其他情况以空格分隔
+fn join_words(line: &mut String, word: &str) {
+    let needs_space = match (line.chars().last(), word.chars().next()) {
+        (Some(a), Some(b)) => !(is_cjk(a) && is_cjk(b)),
+        _ => false,
+    };
+    if needs_space {
+        line.push(' ');
+    }
+    line.push_str(word);
+}
+
+/// 规整一行文本的空白：合并连续空白，去掉中日韩文字之间多余的空格
+fn normalize_spacing(text: &str) -> String {
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        join_words(&mut line, word);
+    }
+    line
+}
+
+/// 按字符集粗略判断语言，拉丁字母统一视为 eng
+/// # Param
+/// text: &str - 行文本
+/// # Returns
+/// Option<String> - 语言代码，没有可判断的文字时返回 None
+pub fn detect_language(text: &str) -> Option<String> {
+    let has = |range: std::ops::RangeInclusive<char>| text.chars().any(|c| range.contains(&c));
+    let language = if has('\u{3040}'..='\u{30FF}') {
+        "jpn"
+    } else if has('\u{AC00}'..='\u{D7AF}') {
+        "kor"
+    } else if has('\u{4E00}'..='\u{9FFF}') || has('\u{3400}'..='\u{4DBF}') {
+        "chi"
+    } else if text.chars().any(|c| c.is_alphabetic()) {
+        "eng"
+    } else {
+        return None;
+    };
+    Some(language.to_string())
+}
+
+/// 合并中的一行：分组键为 (block_num, par_num, line_num)
+struct GroupedLine {
+    key: (u32, u32, u32),
+    line: OcrLine,
+    confidences: Vec<f32>,
+}
+
+/// 解析 OCR 提供者的原始输出为文本行。
+/// 输出为 JSON 数组时按条目解析（带 line_num 的单词级条目按 block_num/par_num/line_num 合并为行），
+/// 否则按纯文本逐行解析，每行使用整体置信度。
+/// # Param
+/// raw: &str - 提供者的原始输出
+/// overall_confidence: Option<f32> - 整体置信度（0.0 - 1.0）
+/// # Returns
+/// Vec<OcrLine> - 识别出的文本行（未按阈值过滤）
+pub fn parse_ocr_output(raw: &str, overall_confidence: Option<f32>) -> Vec<OcrLine> {
+    let Ok(entries) = serde_json::from_str::<Vec<Value>>(raw) else {
+        return parse_plain_text(raw, overall_confidence);
+    };
+
+    let mut grouped: Vec<GroupedLine> = Vec::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let Some(text) = entry.get("text").and_then(|t| t.as_str()) else {
+            continue;
+        };
+        let text = normalize_spacing(text);
+        if text.is_empty() {
+            continue;
+        }
+        let block = number_field(entry, &["block_num", "block"]).unwrap_or(0.0) as u32;
+        let confidence = number_field(entry, &["confidence", "conf"]).and_then(normalize_confidence);
+        let bbox = bbox_field(entry);
+        let language = entry
+            .get("language")
+            .and_then(|l| l.as_str())
+            .map(|l| l.to_string());
+        let key = match number_field(entry, &["line_num"]) {
+            Some(line_num) => (
+                block,
+                number_field(entry, &["par_num"]).unwrap_or(0.0) as u32,
+                line_num as u32,
+            ),
+            // 没有行号的条目本身就是一行
+            None => (block, u32::MAX, index as u32),
+        };
+
+        if let Some(group) = grouped.iter_mut().find(|g| g.key == key) {
+            let line = &mut group.line;
+            join_words(&mut line.text, &text);
+            line.bbox = match (line.bbox, bbox) {
+                (Some(a), Some(b)) => Some(a.union(b)),
+                (a, b) => a.or(b),
+            };
+            group.confidences.extend(confidence);
+            if line.language.is_none() {
+                line.language = language;
+            }
+        } else {
+            grouped.push(GroupedLine {
+                key,
+                line: OcrLine {
+                    text,
+                    block,
+                    bbox,
+                    confidence: None,
+                    language,
+                },
+                confidences: confidence.into_iter().collect(),
+            });
+        }
+    }
+
+    grouped
+        .into_iter()
+        .map(|GroupedLine { mut line, confidences, .. }| {
+            line.confidence = mean(&confidences);
+            if line.language.is_none() {
+                line.language = detect_language(&line.text);
+            }
+            line
+        })
+        .collect()
+}
+
+/// 按纯文本解析：空行分隔文本块
+fn parse_plain_text(raw: &str, overall_confidence: Option<f32>) -> Vec<OcrLine> {
+    let mut lines = Vec::new();
+    let mut block = 0;
+    let mut block_has_lines = false;
+    for raw_line in raw.lines() {
+        let text = normalize_spacing(raw_line);
+        if text.is_empty() {
+            if block_has_lines {
+                block += 1;
+                block_has_lines = false;
+            }
+            continue;
+        }
+        block_has_lines = true;
+        lines.push(OcrLine {
+            language: detect_language(&text),
+            text,
+            block,
+            bbox: None,
+            confidence: overall_confidence,
+        });
+    }
+    lines
+}
+
+fn mean(values: &[f32]) -> Option<f32> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f32>() / values.len() as f32)
+    }
+}
+
+/// 过滤置信度低于阈值的文本行，没有置信度的行保留
+pub fn filter_by_confidence(lines: Vec<OcrLine>, threshold: f32) -> Vec<OcrLine> {
+    lines
+        .into_iter()
+        .filter(|line| line.confidence.is_none_or(|c| c >= threshold))
+        .collect()
+}
+
+/// 将文本行渲染为可读的纯文本：行之间换行，文本块之间空一行
+pub fn render_text(lines: &[OcrLine]) -> String {
+    let mut text = String::new();
+    let mut previous_block = None;
+    for line in lines {
+        match previous_block {
+            Some(block) if block != line.block => text.push_str("\n\n"),
+            Some(_) => text.push('\n'),
+            None => {}
+        }
+        text.push_str(&line.text);
+        previous_block = Some(line.block);
+    }
+    text
+}
+
+/// 由过滤后的文本行组装识别结果
+/// # Param
+/// provider: String - 实际使用的 OCR 提供者
+/// languages: Vec<String> - 识别时配置的语言列表
+/// overall_confidence: Option<f32> - 提供者给出的整体置信度
+/// lines: Vec<OcrLine> - 文本行
+pub fn build_result(
+    provider: String,
+    languages: Vec<String>,
+    overall_confidence: Option<f32>,
+    lines: Vec<OcrLine>,
+) -> OcrResult {
+    let confidence = overall_confidence.or_else(|| {
+        let line_confidences: Vec<f32> = lines.iter().filter_map(|l| l.confidence).collect();
+        mean(&line_confidences)
+    });
+    OcrResult {
+        provider,
+        languages,
+        confidence,
+        text: render_text(&lines),
+        lines,
+    }
+}
+
+/// 预处理时最多放大的倍数
+const MAX_UPSCALE: u32 = 4;
+/// 裁剪边框时与背景色的最大差值
+const BORDER_TOLERANCE: u8 = 12;
+/// 裁剪后在内容四周保留的边距（像素）
+const BORDER_MARGIN: u32 = 4;
+/// 纠偏搜索的最大角度与步长（度）
+const MAX_SKEW_DEGREES: f32 = 5.0;
+const SKEW_STEP_DEGREES: f32 = 0.25;
+/// 倾斜小于该角度时不纠偏（度）
+const MIN_SKEW_DEGREES: f32 = 0.5;
+/// 估计倾斜时最多采样的深色像素数
+const SKEW_SAMPLE_LIMIT: usize = 200_000;
+/// 自适应二值化的灵敏度：比邻域均值暗超过该比例的像素视为文字
+const THRESHOLD_SENSITIVITY: f32 = 0.15;
+
+/// 图片预处理参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PreprocessOptions {
+    /// 高度低于该值的图片会被放大（最多 MAX_UPSCALE 倍）
+    pub upscale_min_height: u32,
+    /// 是否自适应二值化
+    pub adaptive_threshold: bool,
+    /// 是否纠正倾斜
+    pub deskew: bool,
+    /// 是否裁剪颜色一致的边框
+    pub crop_borders: bool,
+}
+
+impl PreprocessOptions {
+    pub fn from_config(cfg: &Config) -> Self {
+        Self {
+            upscale_min_height: cfg.ocr_upscale_min_height,
+            adaptive_threshold: cfg.ocr_adaptive_threshold,
+            deskew: cfg.ocr_deskew,
+            crop_borders: cfg.ocr_crop_borders,
+        }
+    }
+}
+
+/// 预处理结果，记录裁剪偏移与放大倍数以便将识别出的坐标映射回原图
+#[derive(Debug, Clone)]
+pub struct Preprocessed {
+    /// 预处理后的灰度图
+    pub image: GrayImage,
+    /// 裁剪掉的左侧与上侧宽度（原图像素）
+    pub offset_x: u32,
+    pub offset_y: u32,
+    /// 放大倍数
+    pub scale: f32,
+    /// 检测到并已纠正的倾斜角度（度），未纠偏时为 0
+    pub skew_degrees: f32,
+}
+
+impl Preprocessed {
+    /// 将预处理后图片中的包围盒映射回原图坐标（纠偏角度很小，映射时忽略）
+    pub fn map_box(&self, bbox: OcrBox) -> OcrBox {
+        OcrBox {
+            x: bbox.x / self.scale + self.offset_x as f32,
+            y: bbox.y / self.scale + self.offset_y as f32,
+            width: bbox.width / self.scale,
+            height: bbox.height / self.scale,
+        }
+    }
+}
+
+/// 预处理图片：灰度化（透明区域视为白色）→ 裁剪边框 → 放大 → 自适应二值化 → 纠偏
+/// # Param
+/// image: &DynamicImage - 原图
+/// options: &PreprocessOptions - 预处理参数
+/// # Returns
+/// Preprocessed - 预处理结果
+pub fn preprocess_image(image: &DynamicImage, options: &PreprocessOptions) -> Preprocessed {
+    let mut gray = to_gray_on_white(image);
+
+    let (mut offset_x, mut offset_y) = (0, 0);
+    if options.crop_borders {
+        if let Some((x, y, width, height)) = content_bounds(&gray) {
+            gray = imageops::crop_imm(&gray, x, y, width, height).to_image();
+            offset_x = x;
+            offset_y = y;
+        }
+    }
+
+    let mut scale = 1.0;
+    if gray.height() > 0 && gray.height() < options.upscale_min_height {
+        let factor = options
+            .upscale_min_height
+            .div_ceil(gray.height())
+            .min(MAX_UPSCALE);
+        if factor > 1 {
+            gray = imageops::resize(
+                &gray,
+                gray.width() * factor,
+                gray.height() * factor,
+                FilterType::CatmullRom,
+            );
+            scale = factor as f32;
+        }
+    }
+
+    if options.adaptive_threshold {
+        gray = adaptive_threshold(&gray);
+    }
+
+    let mut skew_degrees = 0.0;
+    if options.deskew {
+        let angle = estimate_skew(&gray);
+        if angle.abs() >= MIN_SKEW_DEGREES {
+            gray = rotate(&gray, -angle);
+            skew_degrees = angle;
+        }
+    }
+
+    Preprocessed {
+        image: gray,
+        offset_x,
+        offset_y,
+        scale,
+        skew_degrees,
+    }
+}
+
+/// 转为灰度图，透明像素与白色背景混合
+fn to_gray_on_white(image: &DynamicImage) -> GrayImage {
+    let luma_alpha = image.to_luma_alpha8();
+    GrayImage::from_fn(luma_alpha.width(), luma_alpha.height(), |x, y| {
+        let [l, a] = luma_alpha.get_pixel(x, y).0;
+        let (l, a) = (l as u32, a as u32);
+        Luma([((l * a + 255 * (255 - a)) / 255) as u8])
+    })
+}
+
+/// 计算去掉颜色一致的边框后的内容区域（四角像素的中位数视为背景色）
+/// # Returns
+/// Option<(u32, u32, u32, u32)> - 内容区域 (x, y, width, height)，没有可裁剪的边框或整张图颜色一致时返回 None
+fn content_bounds(gray: &GrayImage) -> Option<(u32, u32, u32, u32)> {
+    let (width, height) = gray.dimensions();
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let mut corners = [
+        gray.get_pixel(0, 0).0[0],
+        gray.get_pixel(width - 1, 0).0[0],
+        gray.get_pixel(0, height - 1).0[0],
+        gray.get_pixel(width - 1, height - 1).0[0],
+    ];
+    corners.sort_unstable();
+    let background = corners[1];
+    let is_content = |x: u32, y: u32| gray.get_pixel(x, y).0[0].abs_diff(background) > BORDER_TOLERANCE;
+
+    let top = (0..height).find(|&y| (0..width).any(|x| is_content(x, y)))?;
+    let bottom = (0..height).rev().find(|&y| (0..width).any(|x| is_content(x, y)))?;
+    let left = (0..width).find(|&x| (top..=bottom).any(|y| is_content(x, y)))?;
+    let right = (0..width).rev().find(|&x| (top..=bottom).any(|y| is_content(x, y)))?;
+
+    let x = left.saturating_sub(BORDER_MARGIN);
+    let y = top.saturating_sub(BORDER_MARGIN);
+    let crop_width = (right + BORDER_MARGIN + 1).min(width) - x;
+    let crop_height = (bottom + BORDER_MARGIN + 1).min(height) - y;
+    if (crop_width, crop_height) == (width, height) {
+        return None;
+    }
+    Some((x, y, crop_width, crop_height))
+}
+
+/// 自适应二值化（Bradley 方法）：按邻域均值逐像素确定阈值，能处理光照不均与低对比度。
+/// 深色背景的图片先反色，统一为浅底深字。
+fn adaptive_threshold(gray: &GrayImage) -> GrayImage {
+    let (width, height) = gray.dimensions();
+    if width == 0 || height == 0 {
+        return gray.clone();
+    }
+    let (w, h) = (width as usize, height as usize);
+
+    let total: u64 = gray.pixels().map(|p| p.0[0] as u64).sum();
+    let invert = total < 128 * (w * h) as u64;
+    let value = |x: u32, y: u32| {
+        let v = gray.get_pixel(x, y).0[0];
+        if invert {
+            255 - v
+        } else {
+            v
+        }
+    };
+
+    // 积分图，integral[(y + 1) * (w + 1) + (x + 1)] 为 (0,0)-(x,y) 矩形内的像素和
+    let mut integral = vec![0u64; (w + 1) * (h + 1)];
+    for y in 0..h {
+        let mut row_sum = 0u64;
+        for x in 0..w {
+            row_sum += value(x as u32, y as u32) as u64;
+            integral[(y + 1) * (w + 1) + x + 1] = integral[y * (w + 1) + x + 1] + row_sum;
+        }
+    }
+
+    // 邻域边长取图片较长边的 1/8
+    let half = (w.max(h) / 8).max(16) / 2;
+    GrayImage::from_fn(width, height, |x, y| {
+        let (x, y) = (x as usize, y as usize);
+        let (x1, x2) = (x.saturating_sub(half), (x + half).min(w - 1));
+        let (y1, y2) = (y.saturating_sub(half), (y + half).min(h - 1));
+        let count = ((x2 - x1 + 1) * (y2 - y1 + 1)) as f32;
+        let sum = (integral[(y2 + 1) * (w + 1) + x2 + 1] + integral[y1 * (w + 1) + x1]
+            - integral[(y2 + 1) * (w + 1) + x1]
+            - integral[y1 * (w + 1) + x2 + 1]) as f32;
+        let v = value(x as u32, y as u32) as f32;
+        if v * count < sum * (1.0 - THRESHOLD_SENSITIVITY) {
+            Luma([0])
+        } else {
+            Luma([255])
+        }
+    })
+}
+
+/// 估计文字的倾斜角度（投影法）：在 ±MAX_SKEW_DEGREES 内搜索使文字行投影最集中的角度
+/// # Returns
+/// f32 - 倾斜角度（度），将图片旋转该角度的相反数即可纠正
+fn estimate_skew(gray: &GrayImage) -> f32 {
+    let (width, height) = gray.dimensions();
+    let pixel_count = (width as u64 * height as u64).max(1);
+    let mean = gray.pixels().map(|p| p.0[0] as u64).sum::<u64>() / pixel_count;
+    let mean = mean as f32;
+    // 文字为与背景相反的少数像素
+    let is_ink = |v: f32| {
+        if mean >= 128.0 {
+            v < mean * 0.6
+        } else {
+            v > mean + (255.0 - mean) * 0.4
+        }
+    };
+
+    let mut ink: Vec<(f32, f32)> = Vec::new();
+    for (x, y, p) in gray.enumerate_pixels() {
+        if is_ink(p.0[0] as f32) {
+            ink.push((x as f32, y as f32));
+        }
+    }
+    if ink.len() < 2 {
+        return 0.0;
+    }
+    let stride = ink.len().div_ceil(SKEW_SAMPLE_LIMIT);
+
+    let offset = width as f32;
+    let bins = (width + height) as usize + 2;
+    let score = |degrees: f32| {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let mut histogram = vec![0u64; bins];
+        for &(x, y) in ink.iter().step_by(stride) {
+            let row = (y * cos - x * sin + offset).round();
+            if row >= 0.0 && (row as usize) < bins {
+                histogram[row as usize] += 1;
+            }
+        }
+        histogram.iter().map(|&c| c * c).sum::<u64>()
+    };
+
+    // 从 0 度向两侧搜索，得分相同时取较小的角度
+    let steps = (MAX_SKEW_DEGREES / SKEW_STEP_DEGREES).round() as i32;
+    let mut best = (0.0, score(0.0));
+    for step in 1..=steps {
+        for degrees in [step as f32 * SKEW_STEP_DEGREES, -(step as f32) * SKEW_STEP_DEGREES] {
+            let s = score(degrees);
+            if s > best.1 {
+                best = (degrees, s);
+            }
+        }
+    }
+    best.0
+}
+
+/// 绕中心旋转图片（双线性插值），超出原图的区域填充白色
+fn rotate(gray: &GrayImage, degrees: f32) -> GrayImage {
+    let (width, height) = gray.dimensions();
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    let (cx, cy) = ((width as f32 - 1.0) / 2.0, (height as f32 - 1.0) / 2.0);
+    let sample = |x: i64, y: i64| -> f32 {
+        if x < 0 || y < 0 || x >= width as i64 || y >= height as i64 {
+            255.0
+        } else {
+            gray.get_pixel(x as u32, y as u32).0[0] as f32
+        }
+    };
+    GrayImage::from_fn(width, height, |x, y| {
+        let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+        let sx = cx + dx * cos + dy * sin;
+        let sy = cy - dx * sin + dy * cos;
+        let (x0, y0) = (sx.floor(), sy.floor());
+        let (fx, fy) = (sx - x0, sy - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+        let top = sample(x0, y0) * (1.0 - fx) + sample(x0 + 1, y0) * fx;
+        let bottom = sample(x0, y0 + 1) * (1.0 - fx) + sample(x0 + 1, y0 + 1) * fx;
+        Luma([(top * (1.0 - fy) + bottom * fy).round().clamp(0.0, 255.0) as u8])
+    })
+}
+
+/// 当前配置中的预处理参数（配置未初始化时使用默认配置）
+fn current_preprocess_options() -> PreprocessOptions {
+    match CONFIG.get() {
+        Some(lock) => PreprocessOptions::from_config(&lock.read().unwrap()),
+        None => PreprocessOptions::from_config(&Config::default()),
+    }
+}
+
+/// 配置中是否开启了识别前预处理
+fn preprocess_enabled() -> bool {
+    CONFIG
+        .get()
+        .map(|lock| lock.read().unwrap().ocr_preprocess)
+        .unwrap_or(false)
+}
+
+/// 打开并预处理图片文件
+fn preprocess_file(file_path: &str, options: &PreprocessOptions) -> Result<Preprocessed, String> {
+    let image = image::open(file_path).map_err(|e| format!("Failed to open image: {}", e))?;
+    Ok(preprocess_image(&image, options))
+}
+
+/// 已配置的 OCR 引擎及识别参数
+struct ConfiguredOcr {
+    engine: OcrEngine,
+    languages: Vec<String>,
+    confidence_threshold: f32,
+}
+
 /// 全局 OCR 引擎实例，使用 OnceLock 确保线程安全的单例模式。
-static OCR_ENGINE: OnceLock<Mutex<Option<Arc<OcrEngine>>>> = OnceLock::new();
+static OCR_ENGINE: OnceLock<Mutex<Option<Arc<ConfiguredOcr>>>> = OnceLock::new();
 
 /// 配置 OCR 选项。作为 Tauri Command 暴露给前端调用。
 /// # Param
@@ -53,12 +695,19 @@ pub fn configure_ocr(
     let langs = language.unwrap_or_else(|| vec!["eng", "chi"]);
     let confidence = confidence_threshold.unwrap_or(0.8);
     let timeout = timeout_secs.unwrap_or(30);
+    if !(0.0..=1.0).contains(&confidence) {
+        return Err(format!(
+            "Invalid confidence threshold: {} (expected 0.0 - 1.0)",
+            confidence
+        ));
+    }
 
     // 解析提供者字符串为对应的枚举
     let ocr_provider_parsed = parse_provider(&ocr_provider)
         .map_err(|e| format!("Failed to parse OCR provider: {}", e))?;
 
     // 解析语言字符串为对应的结构体
+    let lang_codes: Vec<String> = langs.iter().map(|code| code.to_string()).collect();
     let langs_parsed: Vec<Language> = langs
         .into_iter()
         .map(|code| parse_language(code).map_err(|e| e.to_string()))
@@ -75,7 +724,11 @@ pub fn configure_ocr(
         .with_options(options);
 
     // 存入 Arc 并替换（允许重复配置）
-    let arc_engine = Arc::new(engine);
+    let arc_engine = Arc::new(ConfiguredOcr {
+        engine,
+        languages: lang_codes,
+        confidence_threshold: confidence,
+    });
     let slot = OCR_ENGINE.get_or_init(|| Mutex::new(None));
     let mut guard = slot
         .lock()
@@ -89,15 +742,40 @@ pub fn configure_ocr(
     ))
 }
 
-/// OCR 识别函数，作为 Tauri Command 暴露给前端调用。
+/// 根据配置构建（或重建）OCR 引擎。未设置的项使用 configure_ocr 的默认值，语言列表为空时同样使用默认语言。
 /// # Param
-/// String - 图像文件路径
+/// cfg: &Config - 当前配置
 /// # Return
-/// String - 识别到的文本内容。格式为json，包含两个字段：confidence（置信度）和text（识别文本）。
-/// 若识别失败，返回错误信息。
-#[tauri::command]
-pub async fn ocr_image(file_path: String) -> Result<String, String> {
-    // 先从全局取出 Arc<OcrEngine> 的克隆，避免持锁跨 await
+/// String - 配置结果的描述信息，若配置失败则返回错误信息（此时原引擎保持不变）。
+pub fn init_from_config(cfg: &Config) -> Result<String, String> {
+    let languages: Option<Vec<&str>> = cfg
+        .ocr_languages
+        .as_ref()
+        .filter(|langs| !langs.is_empty())
+        .map(|langs| langs.iter().map(|s| s.as_str()).collect());
+    configure_ocr(
+        cfg.ocr_provider.clone(),
+        languages,
+        cfg.ocr_confidence_threshold,
+        cfg.ocr_timeout_secs,
+    )
+}
+
+/// 配置中是否开启了记录图片时自动识别（配置未初始化时按默认值关闭）
+pub fn auto_recognition_enabled() -> bool {
+    CONFIG
+        .get()
+        .map(|lock| lock.read().unwrap().ocr_auto_recognition)
+        .unwrap_or(false)
+}
+
+/// 识别图片中的文字，返回结构化结果。低于配置置信度阈值的文本行会被过滤。
+/// # Param
+/// file_path: &str - 图像文件路径
+/// # Returns
+/// Result<OcrResult, String> - 识别结果，若识别失败返回错误信息
+pub async fn recognize(file_path: &str) -> Result<OcrResult, String> {
+    // 先从全局取出 Arc 的克隆，避免持锁跨 await
     let maybe_engine = {
         let slot = OCR_ENGINE.get_or_init(|| Mutex::new(None));
         let guard = slot
@@ -107,18 +785,121 @@ pub async fn ocr_image(file_path: String) -> Result<String, String> {
         // guard 在此处被 drop
     };
 
-    let engine = maybe_engine.ok_or_else(|| "OCR engine is not configured.".to_string())?;
+    let configured = maybe_engine.ok_or_else(|| "OCR engine is not configured.".to_string())?;
+
+    // 开启预处理时，预处理后的图片写入临时文件供引擎识别，识别结束后自动删除
+    let mut preprocessed = None;
+    let mut temp_file = None;
+    if preprocess_enabled() {
+        let options = current_preprocess_options();
+        let path = file_path.to_string();
+        let (result, temp) = tauri::async_runtime::spawn_blocking(move || {
+            let result = preprocess_file(&path, &options)?;
+            let temp = tempfile::Builder::new()
+                .suffix(".png")
+                .tempfile()
+                .map_err(|e| format!("Failed to create temp file: {}", e))?;
+            result
+                .image
+                .save_with_format(temp.path(), ImageFormat::Png)
+                .map_err(|e| format!("Failed to save preprocessed image: {}", e))?;
+            Ok::<_, String>((result, temp))
+        })
+        .await
+        .map_err(|e| format!("Preprocess task failed: {}", e))??;
+        preprocessed = Some(result);
+        temp_file = Some(temp);
+    }
+    let recognize_path = match &temp_file {
+        Some(temp) => temp.path().to_string_lossy().to_string(),
+        None => file_path.to_string(),
+    };
 
     // 使用克隆的 Arc 引擎调用异步识别
-    let (_provider, text, _confidence) = engine
-        .recognize_file(&file_path)
+    let (provider, raw, confidence) = configured
+        .engine
+        .recognize_file(&recognize_path)
         .await
         .map_err(|e| format!("OCR recognition failed: {}", e))?;
+    drop(temp_file);
+
+    let overall_confidence = confidence.and_then(|c| normalize_confidence(c as f32));
+    let mut lines = filter_by_confidence(
+        parse_ocr_output(&raw, overall_confidence),
+        configured.confidence_threshold,
+    );
+    // 包围盒映射回原图坐标
+    if let Some(preprocessed) = &preprocessed {
+        for line in &mut lines {
+            line.bbox = line.bbox.map(|bbox| preprocessed.map_box(bbox));
+        }
+    }
+    Ok(build_result(
+        provider.to_string(),
+        configured.languages.clone(),
+        overall_confidence,
+        lines,
+    ))
+}
 
-    // 删除所有空白字符
-    let text = text.replace(char::is_whitespace, "");
+/// OCR 识别函数，作为 Tauri Command 暴露给前端调用。
+/// # Param
+/// String - 图像文件路径
+/// # Return
+/// String - 识别到的可读纯文本（行之间换行，文本块之间空一行）。若识别失败，返回错误信息。
+#[tauri::command]
+pub async fn ocr_image(file_path: String) -> Result<String, String> {
+    Ok(recognize(&file_path).await?.text)
+}
+
+/// 结构化 OCR 识别函数，作为 Tauri Command 暴露给前端调用。
+/// # Param
+/// String - 图像文件路径
+/// # Return
+/// OcrResult - 识别结果，包含各文本行的包围盒、置信度与语言。若识别失败，返回错误信息。
+#[tauri::command]
+pub async fn ocr_image_structured(file_path: String) -> Result<OcrResult, String> {
+    recognize(&file_path).await
+}
+
+/// 预览 OCR 预处理效果，用于调整预处理参数。作为 Tauri Command 暴露给前端调用。
+/// 无论是否开启预处理，均按当前配置中的预处理参数处理。
+/// # Param
+/// String - 图像文件路径
+/// # Return
+/// String - 预处理后图片的 data URI（PNG，base64 编码）。若处理失败，返回错误信息。
+#[tauri::command]
+pub async fn preview_ocr_preprocess(file_path: String) -> Result<String, String> {
+    let options = current_preprocess_options();
+    tauri::async_runtime::spawn_blocking(move || {
+        let preprocessed = preprocess_file(&file_path, &options)?;
+        println!(
+            "👁️ OCR 预处理预览: 裁剪偏移 ({}, {}), 放大 {} 倍, 纠偏 {:.2}°",
+            preprocessed.offset_x,
+            preprocessed.offset_y,
+            preprocessed.scale,
+            preprocessed.skew_degrees
+        );
+        let mut png = Vec::new();
+        preprocessed
+            .image
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .map_err(|e| format!("Failed to encode preprocessed image: {}", e))?;
+        Ok(format!(
+            "data:image/png;base64,{}",
+            general_purpose::STANDARD.encode(png)
+        ))
+    })
+    .await
+    .map_err(|e| format!("Preprocess task failed: {}", e))?
+}
 
-    Ok(text)
+/// OCR 引擎是否已配置
+pub fn is_configured() -> bool {
+    OCR_ENGINE
+        .get()
+        .and_then(|slot| slot.lock().ok().map(|guard| guard.is_some()))
+        .unwrap_or(false)
 }
 
 /// 可选：提供一个重置函数（测试时方便清理）
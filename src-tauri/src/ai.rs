@@ -0,0 +1,669 @@
+//! AI 服务客户端。
+//! AiProvider 抽象一次对话补全；OpenAiCompatibleProvider 根据 ai_* 配置调用 OpenAI 兼容的 /chat/completions 接口，
+//! 支持流式输出、超时与取消，也可调用 /embeddings 接口生成文本向量（见 semantic 模块）。
+//! 前端通过 ai_chat 发起对话，流式内容以 "ai-chat-delta" 事件推送，可用 cancel_ai_chat 中止。
+use crate::config::{Config, CONFIG};
+use reqwest::header::CONTENT_TYPE;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Notify;
+
+/// 未配置 ai_timeout_secs 时的超时时间（秒）
+pub const DEFAULT_AI_TIMEOUT_SECS: u64 = 60;
+/// 建立连接的超时时间
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 对话中的一条消息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    /// 角色：system / user / assistant
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: "system".to_string(),
+            content: content.into(),
+        }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: "user".to_string(),
+            content: content.into(),
+        }
+    }
+}
+
+/// 一次对话补全请求
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ChatRequest {
+    pub messages: Vec<ChatMessage>,
+    /// 采样温度，None 时使用配置中的 ai_temperature
+    pub temperature: Option<f32>,
+    /// 回复的最大 token 数，None 时由服务决定
+    pub max_tokens: Option<u32>,
+}
+
+/// AI 请求的错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum AiError {
+    /// AI 未开启或配置不完整
+    Config(String),
+    /// 请求被取消
+    Cancelled,
+    /// 等待响应超时
+    Timeout,
+    /// 网络错误
+    Network(String),
+    /// 服务返回了错误状态码
+    Http { status: u16, message: String },
+    /// 响应内容无法解析，或服务在流中返回了错误
+    InvalidResponse(String),
+}
+
+impl AiError {
+    /// 稍后重试可能成功的错误：超时、网络错误、限流与服务端错误
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AiError::Timeout | AiError::Network(_) => true,
+            AiError::Http { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for AiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AiError::Config(msg) => write!(f, "AI 配置错误: {}", msg),
+            AiError::Cancelled => write!(f, "AI 请求已取消"),
+            AiError::Timeout => write!(f, "AI 请求超时"),
+            AiError::Network(msg) => write!(f, "AI 网络请求失败: {}", msg),
+            AiError::Http { status, message } => write!(f, "AI 服务返回错误 ({}): {}", status, message),
+            AiError::InvalidResponse(msg) => write!(f, "AI 响应无效: {}", msg),
+        }
+    }
+}
+
+impl From<AiError> for String {
+    fn from(error: AiError) -> Self {
+        error.to_string()
+    }
+}
+
+/// 取消令牌，克隆后共享同一状态
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<CancelInner>,
+}
+
+#[derive(Debug, Default)]
+struct CancelInner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 取消请求，正在等待的请求会立即结束
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 等待直到被取消
+    pub async fn cancelled(&self) {
+        loop {
+            // 先注册等待再检查状态，避免错过检查之后发出的通知
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// 在超时与取消的约束下等待一个请求步骤
+async fn guarded<T>(
+    cancel: &CancelToken,
+    timeout: Duration,
+    step: impl Future<Output = Result<T, AiError>>,
+) -> Result<T, AiError> {
+    tokio::select! {
+        biased;
+        _ = cancel.cancelled() => Err(AiError::Cancelled),
+        result = tokio::time::timeout(timeout, step) => result.unwrap_or(Err(AiError::Timeout)),
+    }
+}
+
+fn network_error(error: reqwest::Error) -> AiError {
+    if error.is_timeout() {
+        AiError::Timeout
+    } else {
+        AiError::Network(error.to_string())
+    }
+}
+
+/// AiProvider::chat 返回的 Future
+pub type AiFuture<'a> = Pin<Box<dyn Future<Output = Result<String, AiError>> + Send + 'a>>;
+
+/// AI 服务
+pub trait AiProvider: Send + Sync {
+    /// 服务名称（对应配置中的 ai_provider）
+    fn name(&self) -> &str;
+
+    /// 发起对话补全。流式输出的每段内容依次传给 on_delta，返回完整回复
+    fn chat<'a>(
+        &'a self,
+        request: &'a ChatRequest,
+        cancel: &'a CancelToken,
+        on_delta: &'a mut (dyn FnMut(&str) + Send),
+    ) -> AiFuture<'a>;
+
+    /// 发起对话补全，只关心完整回复
+    fn complete<'a>(&'a self, request: &'a ChatRequest, cancel: &'a CancelToken) -> AiFuture<'a> {
+        Box::pin(async move {
+            let mut ignore = |_: &str| {};
+            self.chat(request, cancel, &mut ignore).await
+        })
+    }
+}
+
+/// 各服务商 OpenAI 兼容接口的默认地址；未列出的服务商须配置 ai_base_url
+/// # Param
+/// provider: &str - 配置中的 ai_provider
+pub fn default_base_url(provider: &str) -> Option<&'static str> {
+    match provider {
+        "openai" => Some("https://api.openai.com/v1"),
+        "deepseek" => Some("https://api.deepseek.com/v1"),
+        "moonshot" => Some("https://api.moonshot.cn/v1"),
+        "aliyun" => Some("https://dashscope.aliyuncs.com/compatible-mode/v1"),
+        "google" => Some("https://generativelanguage.googleapis.com/v1beta/openai"),
+        _ => None,
+    }
+}
+
+/// OpenAI 兼容的对话补全服务
+pub struct OpenAiCompatibleProvider {
+    name: String,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    temperature: f32,
+    timeout: Duration,
+    client: reqwest::Client,
+}
+
+impl OpenAiCompatibleProvider {
+    /// # Param
+    /// name: &str - 服务名称
+    /// base_url: &str - 接口地址（不含 /chat/completions）
+    /// api_key: Option<String> - API Key，为空时不发送鉴权头（本地模型服务）
+    /// model: &str - 模型名称
+    /// temperature: f32 - 默认采样温度
+    /// timeout: Duration - 等待响应以及两次流式输出之间的超时时间
+    pub fn new(
+        name: &str,
+        base_url: &str,
+        api_key: Option<String>,
+        model: &str,
+        temperature: f32,
+        timeout: Duration,
+    ) -> Result<Self, AiError> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT.min(timeout))
+            .build()
+            .map_err(|e| AiError::Config(e.to_string()))?;
+        Ok(Self {
+            name: name.to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.filter(|key| !key.trim().is_empty()),
+            model: model.to_string(),
+            temperature,
+            timeout,
+            client,
+        })
+    }
+
+    /// 根据 ai_* 配置创建
+    /// # Param
+    /// cfg: &Config - 当前配置
+    pub fn from_config(cfg: &Config) -> Result<Self, AiError> {
+        if !cfg.ai_enabled {
+            return Err(AiError::Config("AI 助手未开启".to_string()));
+        }
+        let base_url = cfg
+            .ai_base_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .or_else(|| default_base_url(&cfg.ai_provider))
+            .ok_or_else(|| {
+                AiError::Config(format!("AI 服务 {} 需要设置 base_url", cfg.ai_provider))
+            })?;
+        let model = cfg.ai_model.trim();
+        if model.is_empty() {
+            return Err(AiError::Config("未设置 AI 模型".to_string()));
+        }
+        Self::new(
+            &cfg.ai_provider,
+            base_url,
+            cfg.ai_api_key.clone(),
+            model,
+            cfg.ai_temperature,
+            Duration::from_secs(cfg.ai_timeout_secs.unwrap_or(DEFAULT_AI_TIMEOUT_SECS)),
+        )
+    }
+
+    /// 对话补全接口地址
+    pub fn endpoint(&self) -> String {
+        format!("{}/chat/completions", self.base_url)
+    }
+
+    /// 文本向量接口地址
+    pub fn embeddings_endpoint(&self) -> String {
+        format!("{}/embeddings", self.base_url)
+    }
+
+    /// 发送 JSON 请求，服务返回错误状态码时读取错误信息
+    async fn post_json(
+        &self,
+        url: String,
+        body: &Value,
+        cancel: &CancelToken,
+    ) -> Result<reqwest::Response, AiError> {
+        let mut http = self.client.post(url).json(body);
+        if let Some(key) = &self.api_key {
+            http = http.bearer_auth(key);
+        }
+        let response =
+            guarded(cancel, self.timeout, async { http.send().await.map_err(network_error) })
+                .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = guarded(cancel, self.timeout, async {
+                response.text().await.map_err(network_error)
+            })
+            .await
+            .unwrap_or_default();
+            return Err(AiError::Http {
+                status: status.as_u16(),
+                message: error_message(&body, status.canonical_reason().unwrap_or("")),
+            });
+        }
+        Ok(response)
+    }
+
+    /// 生成文本向量
+    /// # Param
+    /// model: &str - 向量模型名称（与对话模型不同）
+    /// inputs: &[String] - 文本列表
+    /// cancel: &CancelToken - 取消令牌
+    /// # Returns
+    /// Vec<Vec<f32>> - 与 inputs 一一对应的向量
+    pub async fn embed(
+        &self,
+        model: &str,
+        inputs: &[String],
+        cancel: &CancelToken,
+    ) -> Result<Vec<Vec<f32>>, AiError> {
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let body = json!({ "model": model, "input": inputs });
+        let response = self.post_json(self.embeddings_endpoint(), &body, cancel).await?;
+        let body = guarded(cancel, self.timeout, async {
+            response.text().await.map_err(network_error)
+        })
+        .await?;
+        parse_embeddings(&body, inputs.len())
+    }
+
+    fn request_body(&self, request: &ChatRequest) -> Value {
+        let mut body = json!({
+            "model": self.model,
+            "messages": request.messages,
+            "temperature": request.temperature.unwrap_or(self.temperature),
+            "stream": true,
+        });
+        if let Some(max_tokens) = request.max_tokens {
+            body["max_tokens"] = json!(max_tokens);
+        }
+        body
+    }
+}
+
+impl AiProvider for OpenAiCompatibleProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn chat<'a>(
+        &'a self,
+        request: &'a ChatRequest,
+        cancel: &'a CancelToken,
+        on_delta: &'a mut (dyn FnMut(&str) + Send),
+    ) -> AiFuture<'a> {
+        Box::pin(async move {
+            let mut response = self
+                .post_json(self.endpoint(), &self.request_body(request), cancel)
+                .await?;
+
+            // 不支持流式输出的服务直接返回完整 JSON
+            let is_stream = response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|value| value.starts_with("text/event-stream"));
+            if !is_stream {
+                let body = guarded(cancel, self.timeout, async {
+                    response.text().await.map_err(network_error)
+                })
+                .await?;
+                let reply = parse_completion(&body)?;
+                on_delta(&reply);
+                return Ok(reply);
+            }
+
+            let mut parser = SseParser::default();
+            let mut reply = String::new();
+            loop {
+                let chunk = guarded(cancel, self.timeout, async {
+                    response.chunk().await.map_err(network_error)
+                })
+                .await?;
+                let events = match &chunk {
+                    Some(chunk) => parser.push(chunk),
+                    None => parser.finish().into_iter().collect(),
+                };
+                for data in events {
+                    match parse_stream_data(&data)? {
+                        Some(delta) if !delta.is_empty() => {
+                            reply.push_str(&delta);
+                            on_delta(&delta);
+                        }
+                        Some(_) => {}
+                        None => return Ok(reply),
+                    }
+                }
+                if chunk.is_none() {
+                    return Ok(reply);
+                }
+            }
+        })
+    }
+}
+
+/// Server-Sent Events 解析器：按行拼接跨数据块的内容，返回每个事件的 data 字段
+#[derive(Debug, Default)]
+pub(crate) struct SseParser {
+    buffer: Vec<u8>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    /// 追加一段响应数据，返回其中已完整的事件
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            self.feed_line(&String::from_utf8_lossy(&line), &mut events);
+        }
+        events
+    }
+
+    /// 响应结束时取出最后一个没有以空行结尾的事件
+    pub(crate) fn finish(&mut self) -> Option<String> {
+        let mut events = Vec::new();
+        if !self.buffer.is_empty() {
+            let line = std::mem::take(&mut self.buffer);
+            self.feed_line(&String::from_utf8_lossy(&line), &mut events);
+        }
+        self.feed_line("", &mut events);
+        events.pop()
+    }
+
+    fn feed_line(&mut self, line: &str, events: &mut Vec<String>) {
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            if !self.data.is_empty() {
+                events.push(self.data.join("\n"));
+                self.data.clear();
+            }
+        } else if let Some(data) = line.strip_prefix("data:") {
+            self.data.push(data.strip_prefix(' ').unwrap_or(data).to_string());
+        }
+        // event、id 等其他字段与注释行忽略
+    }
+}
+
+/// 解析流式响应中一个事件的 data：返回增量内容，结束标记 [DONE] 返回 None
+pub(crate) fn parse_stream_data(data: &str) -> Result<Option<String>, AiError> {
+    if data.trim() == "[DONE]" {
+        return Ok(None);
+    }
+    let value: Value =
+        serde_json::from_str(data).map_err(|e| AiError::InvalidResponse(e.to_string()))?;
+    if let Some(error) = value.get("error") {
+        return Err(AiError::InvalidResponse(error_text(error)));
+    }
+    let delta = value
+        .pointer("/choices/0/delta/content")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    Ok(Some(delta.to_string()))
+}
+
+/// 解析非流式响应的完整回复
+pub(crate) fn parse_completion(body: &str) -> Result<String, AiError> {
+    let value: Value =
+        serde_json::from_str(body).map_err(|e| AiError::InvalidResponse(e.to_string()))?;
+    value
+        .pointer("/choices/0/message/content")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| AiError::InvalidResponse("响应中没有 choices[0].message.content".to_string()))
+}
+
+/// 解析文本向量响应，按 index 排序
+/// # Param
+/// body: &str - 响应内容
+/// expected: usize - 请求的文本数量
+pub(crate) fn parse_embeddings(body: &str, expected: usize) -> Result<Vec<Vec<f32>>, AiError> {
+    let value: Value =
+        serde_json::from_str(body).map_err(|e| AiError::InvalidResponse(e.to_string()))?;
+    let data = value
+        .get("data")
+        .and_then(Value::as_array)
+        .ok_or_else(|| AiError::InvalidResponse("响应中没有 data".to_string()))?;
+
+    let mut vectors: Vec<(usize, Vec<f32>)> = Vec::with_capacity(data.len());
+    for (position, entry) in data.iter().enumerate() {
+        let index = entry
+            .get("index")
+            .and_then(Value::as_u64)
+            .map(|index| index as usize)
+            .unwrap_or(position);
+        let vector = entry
+            .get("embedding")
+            .and_then(Value::as_array)
+            .ok_or_else(|| AiError::InvalidResponse("响应中没有 embedding".to_string()))?
+            .iter()
+            .map(|x| x.as_f64().map(|x| x as f32))
+            .collect::<Option<Vec<f32>>>()
+            .ok_or_else(|| AiError::InvalidResponse("embedding 不是数字数组".to_string()))?;
+        vectors.push((index, vector));
+    }
+    vectors.sort_by_key(|(index, _)| *index);
+
+    let in_order = vectors.iter().enumerate().all(|(i, (index, _))| i == *index);
+    if vectors.len() != expected || !in_order {
+        return Err(AiError::InvalidResponse(format!(
+            "请求了 {} 个向量，返回了 {} 个",
+            expected,
+            vectors.len()
+        )));
+    }
+    Ok(vectors.into_iter().map(|(_, vector)| vector).collect())
+}
+
+fn error_text(error: &Value) -> String {
+    error
+        .get("message")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| error.to_string())
+}
+
+/// 从错误响应中取出错误信息：优先使用 {"error": {"message": ...}}，否则使用响应文本
+fn error_message(body: &str, fallback: &str) -> String {
+    if let Ok(value) = serde_json::from_str::<Value>(body) {
+        if let Some(error) = value.get("error") {
+            return error_text(error);
+        }
+    }
+    let body = body.trim();
+    if body.is_empty() {
+        fallback.to_string()
+    } else {
+        body.chars().take(200).collect()
+    }
+}
+
+/// 按当前配置创建 AI 服务
+pub fn configured_provider() -> Result<Arc<dyn AiProvider>, AiError> {
+    let lock = CONFIG
+        .get()
+        .ok_or_else(|| AiError::Config("配置尚未初始化".to_string()))?;
+    let provider = OpenAiCompatibleProvider::from_config(&lock.read().unwrap())?;
+    Ok(Arc::new(provider))
+}
+
+/// 进行中的 AI 对话，按请求 ID 记录取消令牌
+#[derive(Default)]
+pub struct AiState {
+    requests: Mutex<HashMap<String, CancelToken>>,
+}
+
+impl AiState {
+    /// 登记一个请求；相同 ID 的旧请求会被取消
+    fn register(&self, request_id: &str) -> CancelToken {
+        let token = CancelToken::new();
+        let previous = self
+            .requests
+            .lock()
+            .unwrap()
+            .insert(request_id.to_string(), token.clone());
+        if let Some(previous) = previous {
+            previous.cancel();
+        }
+        token
+    }
+
+    /// 请求结束后移除登记（已被新请求替换时保留新请求）
+    fn finish(&self, request_id: &str, token: &CancelToken) {
+        let mut requests = self.requests.lock().unwrap();
+        if requests
+            .get(request_id)
+            .is_some_and(|current| Arc::ptr_eq(&current.inner, &token.inner))
+        {
+            requests.remove(request_id);
+        }
+    }
+
+    /// 取消请求
+    /// # Returns
+    /// bool - 请求是否存在
+    pub fn cancel(&self, request_id: &str) -> bool {
+        match self.requests.lock().unwrap().remove(request_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// 流式输出的一段内容，作为 "ai-chat-delta" 事件的负载
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AiChatDelta {
+    pub request_id: String,
+    pub delta: String,
+}
+
+/// 使用配置的 AI 服务进行对话，回复内容以 "ai-chat-delta" 事件流式推送。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// request_id: String - 前端生成的请求 ID，用于匹配事件与取消请求
+/// messages: Vec<ChatMessage> - 对话消息
+/// temperature: Option<f32> - 可选的采样温度，默认使用 ai_temperature
+/// # Returns
+/// String - 完整回复
+#[tauri::command]
+pub async fn ai_chat(
+    request_id: String,
+    messages: Vec<ChatMessage>,
+    temperature: Option<f32>,
+    app: AppHandle,
+    state: State<'_, AiState>,
+) -> Result<String, String> {
+    let provider = configured_provider()?;
+    let request = ChatRequest {
+        messages,
+        temperature,
+        max_tokens: None,
+    };
+
+    let token = state.register(&request_id);
+    let mut on_delta = |delta: &str| {
+        let payload = AiChatDelta {
+            request_id: request_id.clone(),
+            delta: delta.to_string(),
+        };
+        if let Err(e) = app.emit("ai-chat-delta", payload) {
+            eprintln!("❌ 发送 AI 流式输出事件失败: {}", e);
+        }
+    };
+    let result = provider.chat(&request, &token, &mut on_delta).await;
+    state.finish(&request_id, &token);
+
+    if let Err(e) = &result {
+        eprintln!("❌ AI 对话失败 ({}): {}", provider.name(), e);
+    }
+    result.map_err(String::from)
+}
+
+/// 取消进行中的 AI 对话。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// request_id: String - 发起对话时使用的请求 ID
+/// # Returns
+/// bool - 请求是否存在
+#[tauri::command]
+pub fn cancel_ai_chat(request_id: String, state: State<'_, AiState>) -> bool {
+    state.cancel(&request_id)
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_ai.rs"]
+mod test_ai;
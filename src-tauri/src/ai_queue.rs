@@ -0,0 +1,337 @@
+//! AI 标签与摘要任务队列。
+//! 开启 ai_auto_tag / ai_auto_summary 后，新记录的较长文本与图片的 OCR 文本写入 ai_jobs 表，
+//! 由后台线程逐个请求 AI 服务生成标签与摘要，两次请求之间至少间隔 AI_REQUEST_INTERVAL。
+//! 隐私数据不会入队，执行前与保存前都会再次检查，标记为隐私的数据不会发送给 AI 服务。
+use crate::ai::{self, AiError, CancelToken, ChatMessage, ChatRequest};
+use crate::config::CONFIG;
+use crate::db;
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::RwLock;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// 文本至少包含的字符数（不含首尾空白），较短的内容不生成标签与摘要
+pub const AI_MIN_TEXT_CHARS: usize = 100;
+/// 发送给 AI 服务的最多字符数，超出部分截断
+pub const AI_MAX_INPUT_CHARS: usize = 6_000;
+/// 每条数据最多保留的标签数
+pub const AI_MAX_TAGS: usize = 5;
+/// 单个标签的最多字符数
+const AI_MAX_TAG_CHARS: usize = 24;
+/// 每个任务的最大尝试次数
+pub const AI_MAX_ATTEMPTS: u32 = 3;
+/// 两次请求之间的最短间隔（限流）
+pub const AI_REQUEST_INTERVAL: Duration = Duration::from_secs(5);
+/// 入队后的等待时间（毫秒），留给隐私检测先标记新记录的数据
+const AI_ENQUEUE_DELAY_MS: i64 = 3_000;
+/// 没有通知时检查到期任务的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+static AI_QUEUE_SENDER: RwLock<Option<Sender<()>>> = RwLock::new(None);
+
+/// 需要生成的内容
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnrichOptions {
+    pub tags: bool,
+    pub summary: bool,
+}
+
+impl EnrichOptions {
+    /// 从配置读取；AI 未开启或两项都未开启时返回 None
+    pub fn from_config() -> Option<Self> {
+        let lock = CONFIG.get()?;
+        let cfg = lock.read().unwrap();
+        let options = Self {
+            tags: cfg.ai_auto_tag,
+            summary: cfg.ai_auto_summary,
+        };
+        (cfg.ai_enabled && (options.tags || options.summary)).then_some(options)
+    }
+}
+
+/// AI 生成的标签与摘要
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Enrichment {
+    pub tags: Vec<String>,
+    pub summary: Option<String>,
+}
+
+/// 标签与摘要更新，作为 "ai-annotations-updated" 事件的负载
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AiAnnotationsUpdated {
+    pub item_id: String,
+    pub annotations: db::AiAnnotations,
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// 文本是否足够长，值得生成标签与摘要
+pub fn is_long_enough(text: &str) -> bool {
+    text.trim().chars().count() >= AI_MIN_TEXT_CHARS
+}
+
+/// 唤醒任务线程
+fn notify_worker() {
+    if let Some(sender) = AI_QUEUE_SENDER.read().unwrap().as_ref() {
+        let _ = sender.send(());
+    }
+}
+
+/// 开启了自动标签或摘要且文本足够长时，为数据项添加 AI 任务
+/// # Param
+/// item_id: &str - 数据项 ID
+/// text: &str - 文本内容或图片的 OCR 文本
+/// # Returns
+/// bool - 是否已入队
+pub fn enqueue_if_eligible(item_id: &str, text: &str) -> Result<bool, String> {
+    if EnrichOptions::from_config().is_none() || !is_long_enough(text) {
+        return Ok(false);
+    }
+    let now = now_ms();
+    let queued = db::enqueue_ai_job(item_id, now + AI_ENQUEUE_DELAY_MS, now)?;
+    if queued {
+        notify_worker();
+    }
+    Ok(queued)
+}
+
+/// 构造请求：要求 AI 只返回包含 tags 与 summary 的 JSON
+/// # Param
+/// text: &str - 数据内容
+/// options: EnrichOptions - 需要生成的内容
+pub fn build_request(text: &str, options: EnrichOptions) -> ChatRequest {
+    let mut fields = Vec::new();
+    if options.tags {
+        fields.push(format!(
+            "\"tags\": 1 到 {} 个概括主题的简短标签（字符串数组，使用内容的语言）",
+            AI_MAX_TAGS
+        ));
+    }
+    if options.summary {
+        fields.push("\"summary\": 一句话摘要（不超过 60 字，使用内容的语言）".to_string());
+    }
+    let system = format!(
+        "你是剪贴板整理助手。阅读用户复制的内容，只输出一个 JSON 对象，不要输出其他文字。字段：\n{}",
+        fields.join("\n")
+    );
+    let content: String = text.trim().chars().take(AI_MAX_INPUT_CHARS).collect();
+    ChatRequest {
+        messages: vec![ChatMessage::system(system), ChatMessage::user(content)],
+        temperature: Some(0.2),
+        max_tokens: Some(300),
+    }
+}
+
+/// 解析 AI 回复。允许 JSON 外包含代码块标记或说明文字
+/// # Param
+/// reply: &str - AI 回复
+pub fn parse_enrichment(reply: &str) -> Result<Enrichment, String> {
+    let start = reply.find('{').ok_or("回复中没有 JSON 对象")?;
+    let end = reply.rfind('}').filter(|&end| end > start).ok_or("回复中没有 JSON 对象")?;
+    let value: Value = serde_json::from_str(&reply[start..=end]).map_err(|e| e.to_string())?;
+
+    // 部分模型会把标签写成逗号分隔的字符串
+    let tags = match value.get("tags") {
+        Some(Value::Array(tags)) => tags
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        Some(Value::String(tags)) => tags.split([',', '，', '、']).map(str::to_string).collect(),
+        _ => Vec::new(),
+    };
+    let summary = value
+        .get("summary")
+        .and_then(Value::as_str)
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    Ok(Enrichment {
+        tags: normalize_tags(&tags),
+        summary,
+    })
+}
+
+/// 整理标签：去掉首尾空白、引号与 # 前缀，忽略空标签与过长的标签，忽略大小写去重，最多保留 AI_MAX_TAGS 个
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().trim_matches(['"', '\'', '#']).trim();
+        if tag.is_empty() || tag.chars().count() > AI_MAX_TAG_CHARS {
+            continue;
+        }
+        if result.iter().any(|t| t.to_lowercase() == tag.to_lowercase()) {
+            continue;
+        }
+        result.push(tag.to_string());
+        if result.len() == AI_MAX_TAGS {
+            break;
+        }
+    }
+    result
+}
+
+/// 获取任务需要处理的文本：文本数据的内容或图片的 OCR 文本
+/// # Returns
+/// Option<String> - 数据项为隐私数据、已被删除、不是文本或图片、文本过短时返回 None
+fn job_text(item_id: &str) -> Result<Option<String>, String> {
+    if db::is_private_item(item_id)? {
+        return Ok(None);
+    }
+    let Some(item) = db::get_item_by_id(item_id)? else {
+        return Ok(None);
+    };
+    let text = match item.item_type.as_str() {
+        "text" => Some(item.content),
+        "image" => db::get_ocr_text(item_id)?,
+        _ => None,
+    };
+    Ok(text.filter(|text| is_long_enough(text)))
+}
+
+/// 启动 AI 任务线程
+/// **功能**：
+/// - 启动时将上次中断的任务恢复为等待状态
+/// - 收到入队通知或每隔一段时间取出一个到期任务执行，两次请求之间至少间隔 AI_REQUEST_INTERVAL
+/// - AI 未开启或关闭了自动标签与摘要时任务保持等待
+pub fn start_ai_worker(app: AppHandle) {
+    let (tx, rx) = channel();
+    *AI_QUEUE_SENDER.write().unwrap() = Some(tx);
+
+    match db::reset_running_ai_jobs(now_ms()) {
+        Ok(count) if count > 0 => println!("🤖 已恢复 {} 个中断的 AI 任务", count),
+        Ok(_) => {}
+        Err(e) => eprintln!("❌ 恢复 AI 任务失败: {}", e),
+    }
+
+    std::thread::spawn(move || {
+        println!("🤖 AI 任务线程已启动");
+
+        loop {
+            match rx.recv_timeout(POLL_INTERVAL) {
+                Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    println!("🛑 AI 任务线程退出");
+                    break;
+                }
+            }
+            // 合并积累的通知
+            while rx.try_recv().is_ok() {}
+
+            let Some(options) = EnrichOptions::from_config() else {
+                continue;
+            };
+            let item_id = match db::claim_next_ai_job(now_ms()) {
+                Ok(Some(item_id)) => item_id,
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!("❌ 读取 AI 任务失败: {}", e);
+                    continue;
+                }
+            };
+
+            let requested = tauri::async_runtime::block_on(run_job(&app, &item_id, options));
+            if requested {
+                std::thread::sleep(AI_REQUEST_INTERVAL);
+            }
+        }
+    });
+}
+
+/// 执行一个已取出的任务
+/// # Returns
+/// bool - 是否向 AI 服务发送了请求
+async fn run_job(app: &AppHandle, item_id: &str, options: EnrichOptions) -> bool {
+    let text = match job_text(item_id) {
+        Ok(Some(text)) => text,
+        Ok(None) => {
+            // 隐私数据、已删除的数据与过短的文本不再处理
+            println!("🤖 跳过 AI 任务: {}", item_id);
+            if let Err(e) = db::cancel_ai_job(item_id) {
+                eprintln!("❌ 取消 AI 任务失败: {}", e);
+            }
+            return false;
+        }
+        Err(e) => {
+            record_failure(item_id, &e, true);
+            return false;
+        }
+    };
+
+    let provider = match ai::configured_provider() {
+        Ok(provider) => provider,
+        Err(e) => {
+            record_failure(item_id, &e.to_string(), false);
+            return false;
+        }
+    };
+    let request = build_request(&text, options);
+    let reply = match provider.complete(&request, &CancelToken::new()).await {
+        Ok(reply) => reply,
+        Err(e) => {
+            let retryable = e.is_retryable();
+            record_failure(item_id, &e.to_string(), retryable);
+            return true;
+        }
+    };
+    let enrichment = match parse_enrichment(&reply) {
+        Ok(enrichment) => enrichment,
+        Err(e) => {
+            record_failure(item_id, &AiError::InvalidResponse(e).to_string(), true);
+            return true;
+        }
+    };
+
+    // 请求期间数据项可能被删除或标记为隐私，此时不保存结果
+    match db::get_ai_job(item_id) {
+        Ok(Some(job)) if job.status == db::AI_JOB_RUNNING => {}
+        Ok(_) => return true,
+        Err(e) => {
+            record_failure(item_id, &e, true);
+            return true;
+        }
+    }
+    if db::is_private_item(item_id).unwrap_or(true) {
+        let _ = db::cancel_ai_job(item_id);
+        return true;
+    }
+
+    let tags = options.tags.then_some(enrichment.tags.as_slice());
+    let summary = if options.summary { enrichment.summary.as_deref() } else { None };
+    if let Err(e) = db::save_ai_annotations(item_id, tags, summary) {
+        record_failure(item_id, &e, true);
+        return true;
+    }
+    if let Err(e) = db::complete_ai_job(item_id, now_ms()) {
+        eprintln!("❌ 更新 AI 任务状态失败: {}", e);
+    }
+    println!("✅ AI 标签与摘要已生成: {} ({} 个标签)", item_id, enrichment.tags.len());
+
+    let annotations = db::AiAnnotations {
+        tags: db::get_item_tags(item_id).unwrap_or_default(),
+        summary: db::get_ai_summary(item_id).unwrap_or_default(),
+    };
+    if let Some(window) = app.get_webview_window("main") {
+        let payload = AiAnnotationsUpdated {
+            item_id: item_id.to_string(),
+            annotations,
+        };
+        let _ = window.emit("ai-annotations-updated", payload);
+    }
+    true
+}
+
+/// 记录任务失败，可重试且未达到最大次数时稍后重试
+fn record_failure(item_id: &str, error: &str, retryable: bool) {
+    eprintln!("❌ AI 任务失败 ({}): {}", item_id, error);
+    if let Err(e) = db::fail_ai_job(item_id, error, now_ms(), retryable, AI_MAX_ATTEMPTS) {
+        eprintln!("❌ 更新 AI 任务状态失败: {}", e);
+    }
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_ai_queue.rs"]
+mod test_ai_queue;
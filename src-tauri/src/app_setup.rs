@@ -1,16 +1,23 @@
-use crate::clipboard::ClipboardItem;
-use crate::config::{self, get_config_item, CONFIG};
+use crate::ai_queue;
+use crate::barcode;
+use crate::capture::{
+    CaptureKind, CaptureOutcome, CapturePipeline, CaptureSettings, CapturedItem, TickInput,
+};
+use crate::clipboard_backend::{ClipboardRepresentations, TauriClipboardBackend};
+use crate::config::{self, CONFIG};
 use crate::db;
+use crate::document;
+use crate::echo_filter::{self, EchoFilter, EchoTicket};
 use crate::ocr;
+use crate::ocr_queue;
+use crate::paste_queue;
+use crate::source_app::SystemSourceAppProvider;
 use crate::utils;
 use dunce;
-use std::io;
 use chrono::Utc;
-use image::ColorType;
-use serde_json::Value;
+use serde::Serialize;
 use std::fs;
 use std::path::Path;
-use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex, OnceLock};
 use std::thread;
@@ -18,19 +25,111 @@ use std::time::{Duration, Instant};
 use tauri::menu::{Menu, MenuItem};
 use tauri::tray::{TrayIcon, TrayIconBuilder, TrayIconEvent};
 use tauri::{App, AppHandle, Emitter, Manager, State, WebviewWindow};
-use tauri_plugin_clipboard_manager::ClipboardExt;
 use tauri_plugin_global_shortcut::{
     GlobalShortcutExt, Shortcut, ShortcutState as PluginShortcutState,
 };
 use tauri::image::Image;
-use uuid::Uuid;
 
 // 全局静态变量存储托盘图标的句柄
 static TRAY_ICON_GLOBAL: OnceLock<TrayIcon> = OnceLock::new();
 
+/// 记录应用自身对剪贴板的写入，监控线程据此忽略这些写入引起的变化
 pub struct ClipboardSourceState {
-    pub is_frontend_copy: Mutex<bool>,
+    pub echoes: EchoFilter,
+}
+impl ClipboardSourceState {
+    pub fn new() -> Self {
+        Self {
+            echoes: EchoFilter::new(),
+        }
+    }
+
+    /// 登记即将写入剪贴板的内容，须在写入之前调用
+    /// # Param
+    /// reps: &ClipboardRepresentations - 要写入的全部表示
+    /// # Returns
+    /// EchoTicket - 登记的凭据，写入失败时交给 withdraw_write 撤销
+    pub fn register_write(&self, reps: &ClipboardRepresentations) -> EchoTicket {
+        self.echoes.register(echo_filter::fingerprints_of(reps))
+    }
+
+    /// 撤销写入失败的登记
+    /// # Param
+    /// ticket: EchoTicket - register_write 返回的凭据
+    pub fn withdraw_write(&self, ticket: EchoTicket) {
+        self.echoes.withdraw(ticket);
+    }
+}
+/// 暂停记录（隐身模式）的状态快照，同时作为 "capture-pause-changed" 事件的负载
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CapturePauseStatus {
+    /// 是否处于暂停状态
+    pub paused: bool,
+    /// 自动恢复的时间戳（毫秒），None 表示需要手动恢复
+    pub resume_at: Option<i64>,
 }
+
+/// 管理剪贴板记录的暂停状态
+pub struct CapturePauseState {
+    pub status: Mutex<CapturePauseStatus>,
+}
+impl CapturePauseState {
+    pub fn new() -> Self {
+        Self {
+            status: Mutex::new(CapturePauseStatus {
+                paused: false,
+                resume_at: None,
+            }),
+        }
+    }
+
+    /// 暂停记录
+    /// # Param
+    /// now_ms: i64 - 当前时间戳（毫秒）
+    /// duration_secs: Option<u64> - 自动恢复时长（秒），None 表示直到手动恢复
+    pub fn pause(&self, now_ms: i64, duration_secs: Option<u64>) -> CapturePauseStatus {
+        let mut status = self.status.lock().unwrap();
+        status.paused = true;
+        status.resume_at = duration_secs.map(|secs| now_ms + secs as i64 * 1000);
+        status.clone()
+    }
+
+    /// 恢复记录
+    pub fn resume(&self) -> CapturePauseStatus {
+        let mut status = self.status.lock().unwrap();
+        status.paused = false;
+        status.resume_at = None;
+        status.clone()
+    }
+
+    /// 获取当前状态
+    pub fn get_status(&self) -> CapturePauseStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// 检查是否到达自动恢复时间，若到达则恢复记录
+    /// # Param
+    /// now_ms: i64 - 当前时间戳（毫秒）
+    /// # Returns
+    /// bool - 本次调用是否触发了自动恢复
+    pub fn check_auto_resume(&self, now_ms: i64) -> bool {
+        let mut status = self.status.lock().unwrap();
+        match status.resume_at {
+            Some(resume_at) if status.paused && now_ms >= resume_at => {
+                status.paused = false;
+                status.resume_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 当前是否暂停
+    pub fn is_paused(&self) -> bool {
+        self.status.lock().unwrap().paused
+    }
+}
+
 /// 管理应用的主快捷键状态
 pub struct AppShortcutManager {
     pub shortcuts: Mutex<std::collections::HashMap<String, String>>,
@@ -139,6 +238,24 @@ lazy_static::lazy_static! {
                 }
             },
         });
+        m.insert("toggleCapture", ShortcutConfig {
+            storage_key: "global_shortcut_6",
+            default_value: "Shift+Ctrl+P",
+            handler: |app, shortcut| {
+                println!("⏸️ 执行暂停/恢复记录，快捷键: {}", shortcut);
+                toggle_capture_pause(app);
+            },
+        });
+        m.insert("pasteQueueNext", ShortcutConfig {
+            storage_key: "global_shortcut_7",
+            default_value: "Shift+Alt+V",
+            handler: |app, shortcut| {
+                println!("📋 执行粘贴队列下一项，快捷键: {}", shortcut);
+                if let Err(e) = paste_queue::paste_next(app) {
+                    eprintln!("❌ 粘贴队列写入剪贴板失败: {}", e);
+                }
+            },
+        });
         m
     };
     // 通过 Storage Key 查找 Handler Key 的反向映射
@@ -166,6 +283,8 @@ fn load_shortcut_from_storage(shortcut_type: &str) -> String {
                     "global_shortcut_3" => cfg.global_shortcut_3.clone(),
                     "global_shortcut_4" => cfg.global_shortcut_4.clone(),
                     "global_shortcut_5" => cfg.global_shortcut_5.clone(),
+                    "global_shortcut_6" => cfg.global_shortcut_6.clone(),
+                    "global_shortcut_7" => cfg.global_shortcut_7.clone(),
                     _ => config.default_value.to_string(),
                 }
             } else {
@@ -264,13 +383,74 @@ pub fn get_all_shortcuts(
     Ok(state.shortcuts.lock().unwrap().clone())
 }
 
+/// 通知前端暂停状态已变化
+fn emit_capture_pause_changed(app: &AppHandle, status: &CapturePauseStatus) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit("capture-pause-changed", status.clone());
+    }
+}
+
+/// 切换暂停/恢复记录状态，供托盘菜单与快捷键调用
+fn toggle_capture_pause(app: &AppHandle) {
+    let state = app.state::<CapturePauseState>();
+    let status = if state.is_paused() {
+        state.resume()
+    } else {
+        state.pause(Utc::now().timestamp_millis(), None)
+    };
+    println!("⏯️ 剪贴板记录状态: paused = {}", status.paused);
+    emit_capture_pause_changed(app, &status);
+}
+
+/// 暂停剪贴板记录。作为 Tauri command 暴露给前端调用。
+/// 暂停期间监控线程仍会跟踪剪贴板内容，但不会写入数据库，恢复后也不会补录暂停期间的内容。
+/// # Param
+/// duration_secs: Option<u64> - 自动恢复时长（秒），None 表示直到手动恢复
+/// # Returns
+/// Result<CapturePauseStatus, String> - 暂停后的状态
+#[tauri::command]
+pub fn pause_capture(
+    app: AppHandle,
+    state: State<CapturePauseState>,
+    duration_secs: Option<u64>,
+) -> Result<CapturePauseStatus, String> {
+    if duration_secs == Some(0) {
+        return Err("暂停时长必须大于 0".to_string());
+    }
+    let status = state.pause(Utc::now().timestamp_millis(), duration_secs);
+    emit_capture_pause_changed(&app, &status);
+    Ok(status)
+}
+
+/// 恢复剪贴板记录。作为 Tauri command 暴露给前端调用。
+/// # Returns
+/// Result<CapturePauseStatus, String> - 恢复后的状态
+#[tauri::command]
+pub fn resume_capture(
+    app: AppHandle,
+    state: State<CapturePauseState>,
+) -> Result<CapturePauseStatus, String> {
+    let status = state.resume();
+    emit_capture_pause_changed(&app, &status);
+    Ok(status)
+}
+
+/// 获取剪贴板记录的暂停状态。作为 Tauri command 暴露给前端调用。
+#[tauri::command]
+pub fn get_capture_status(state: State<CapturePauseState>) -> Result<CapturePauseStatus, String> {
+    Ok(state.get_status())
+}
+
 /// 创建系统托盘图标和菜单
 pub fn setup_tray(app: &App) -> Result<(), Box<dyn std::error::Error>> {
     let last_click_time = Arc::new(Mutex::new(Instant::now()));
     let show_hide = MenuItem::with_id(app, "show_hide", "显示/隐藏", true, None::<&str>)?;
+    let toggle_capture =
+        MenuItem::with_id(app, "toggle_capture", "暂停/恢复记录", true, None::<&str>)?;
     let quit = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
     let menu = Menu::new(app)?;
     menu.append(&show_hide)?;
+    menu.append(&toggle_capture)?;
     menu.append(&quit)?;
     let tray_handle = TrayIconBuilder::new()
         //.icon(app.default_window_icon().unwrap().clone())
@@ -278,6 +458,10 @@ pub fn setup_tray(app: &App) -> Result<(), Box<dyn std::error::Error>> {
         .menu(&menu)
         .tooltip("SmartPaste")
         .on_menu_event(move |app, event| {
+            if event.id().as_ref() == "toggle_capture" {
+                toggle_capture_pause(app);
+                return;
+            }
             if let Some(window) = app.get_webview_window("main") {
                 match event.id().as_ref() {
                     "show_hide" => toggle_window_visibility(&window),
@@ -443,90 +627,20 @@ fn get_dir_depth(path: &Path) -> usize {
     depth
 }
 
-/// 安全的递归复制函数
-/// src: 源目录
-/// dst: 目标目录
-/// exclude_target: 需要避开的路径（即 dest_path 本身）
-fn safe_copy_dir(src: &Path, dst: &Path, exclude_target: &Path) -> io::Result<u64> {
-    if !dst.exists() {
-        fs::create_dir_all(dst)?;
-    }
-
-    let mut total_bytes = 0;
-    
-    // 获取排除路径的规范化形式（绝对路径），用于比较
-    // 如果 exclude_target 还没创建，canonicalize 可能会失败，所以我们要容错
-    let abs_exclude = exclude_target.canonicalize().unwrap_or(exclude_target.to_path_buf());
-
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let path = entry.path();
-        
-        // --- 核心防递归逻辑 ---
-        // 获取当前遍历到的文件的绝对路径
-        let abs_current = path.canonicalize().unwrap_or(path.clone());
-        
-        // 如果当前遍历到的路径 == 我们正在写入的目标文件夹，直接跳过！
-        if abs_current == abs_exclude {
-            // println!("🛡️ 避开递归: 跳过目标目录本身 {:?}", path);
-            continue;
-        }
-        // --------------------
-
-        let file_name = entry.file_name();
-        let new_dst = dst.join(file_name);
-
-        if path.is_dir() {
-            // 递归调用
-            total_bytes += safe_copy_dir(&path, &new_dst, exclude_target)?;
-        } else {
-            // 复制文件
-            total_bytes += fs::copy(&path, &new_dst)?;
-        }
-    }
-    Ok(total_bytes)
-}
 pub fn start_clipboard_monitor(app_handle: tauri::AppHandle) {
     thread::spawn(move || {
-        // 获取配置的存储路径
-        // 初始变量状态
-        let mut last_text = String::new();
-        let mut last_image_bytes: Vec<u8> = Vec::new();
-        let mut last_file_paths: Vec<PathBuf> = Vec::new();
-
-        let mut is_first_run = true;
-        let mut frontend_ignore_countdown = 0;
-
-        // 定义相对路径根目录 (保持不变，因为这是存入数据库的相对路径)
-        let db_root_dir = PathBuf::from("files");
-        // 辅助函数
-        fn get_path_size(path: &Path) -> u64 {
-            if path.is_dir() {
-                // 递归计算文件夹大小
-                let mut total = 0;
-                if let Ok(entries) = fs::read_dir(path) {
-                    for entry in entries.flatten() {
-                        total += get_path_size(&entry.path());
-                    }
-                }
-                total
+        let backend = Arc::new(TauriClipboardBackend::new(app_handle.clone()));
+        let mut pipeline = CapturePipeline::new(backend, Box::new(SystemSourceAppProvider));
+        println!(
+            "📋 剪贴板变化检测: {}",
+            if pipeline.has_native_change_token() {
+                "系统变化计数"
             } else {
-                // 文件大小
-                fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+                "内容指纹"
             }
-        }
-        loop {
-            // 每次循环都重新读取配置，以支持运行时修改
-            let size_limit_mb = {
-                if let Some(lock) = CONFIG.get() {
-                    let cfg = lock.read().unwrap();
-                    cfg.ignore_big_file_mb
-                } else {
-                    5 // 默认值 5MB
-                }
-            };
-            let size_limit_bytes = size_limit_mb as u64 * 1024 * 1024;
+        );
 
+        loop {
             let current_storage_path = crate::config::get_current_storage_path();
             let files_dir = current_storage_path.join("files");
 
@@ -539,429 +653,115 @@ pub fn start_clipboard_monitor(app_handle: tauri::AppHandle) {
                     continue;
                 }
             }
-            {
-                let state = app_handle.state::<ClipboardSourceState>();
-                let mut flag = state.is_frontend_copy.lock().unwrap();
-                if *flag {
-                    frontend_ignore_countdown = 30; // 3秒倒计时
-                    *flag = false; // 重置状态
-                    println!("前端触发复制，启动忽略倒计时...");
-                }
-            }
+            // 每次循环都重新读取配置，以支持运行时修改
+            let settings = CaptureSettings::from_config(files_dir);
 
-            // 只要倒计时大于0，就认为是前端复制状态
-            let is_frontend_copy = frontend_ignore_countdown > 0;
-            if frontend_ignore_countdown > 0 {
-                frontend_ignore_countdown -= 1;
-            }
+            let source_state = app_handle.state::<ClipboardSourceState>();
 
-            if is_first_run {
-                if let Ok(text) = app_handle.clipboard().read_text() {
-                    if !text.is_empty() {
-                        last_text = text;
-                    }
+            // 暂停状态：到达定时则自动恢复；暂停期间仍更新剪贴板基准状态，但不记录
+            let paused = {
+                let state = app_handle.state::<CapturePauseState>();
+                if state.check_auto_resume(Utc::now().timestamp_millis()) {
+                    println!("▶️ 暂停时间已到，自动恢复剪贴板记录");
+                    emit_capture_pause_changed(&app_handle, &state.get_status());
                 }
-                if let Ok(image) = app_handle.clipboard().read_image() {
-                    let current = image.rgba().to_vec();
-                    if !current.is_empty() {
-                        last_image_bytes = current;
-                    }
+                state.is_paused()
+            };
+
+            match pipeline.step(
+                TickInput {
+                    echoes: Some(&source_state.echoes),
+                    paused,
+                },
+                &settings,
+            ) {
+                CaptureOutcome::Baseline => {
+                    thread::sleep(Duration::from_millis(1000));
+                    continue;
                 }
-                if let Ok(paths) = clipboard_files::read() {
-                    if !paths.is_empty() {
-                        last_file_paths = paths;
+                CaptureOutcome::Captured(items) if !items.is_empty() => {
+                    for captured in &items {
+                        spawn_post_capture_tasks(captured);
+                    }
+                    paste_queue::enqueue_captured(&app_handle, &items);
+                    // 通知前端
+                    if let Some(window) = app_handle.get_webview_window("main") {
+                        let _ = window.emit("clipboard-updated", "");
                     }
                 }
-                is_first_run = false;
-                thread::sleep(Duration::from_millis(1000));
-                continue;
+                _ => {}
             }
 
-            // --- 图片监控 ---
-            if let Ok(image) = app_handle.clipboard().read_image() {
-                let current_image_bytes = image.rgba().to_vec();
-                if !current_image_bytes.is_empty() && current_image_bytes != last_image_bytes {
-                    println!("检测到新的图片内容");
-                    // 立即更新 last 状态，防止重复检测
-                    last_image_bytes = current_image_bytes.clone();
-                    last_text.clear();
-                    last_file_paths.clear();
-                    if is_frontend_copy {
-                        println!("忽略前端触发的图片变更");
-                    } else {
-                        // 只有是非前端复制时，才执行保存文件和数据库操作
-                        let image_id = Uuid::new_v4().to_string();
-                        // let dest_path = files_dir.join(format!("{}.png", image_id));
-                        let dest_relative_path = db_root_dir.join(format!("{}.png", image_id));
-                        let dest_absolute_path = utils::resolve_absolute_path(&dest_relative_path);
-                        if image::save_buffer(
-                            &dest_absolute_path,
-                            &image.rgba(),
-                            image.width(),
-                            image.height(),
-                            ColorType::Rgba8,
-                        )
-                        .is_ok()
-                        {
-                            let new_item = ClipboardItem {
-                                id: image_id.clone(),
-                                item_type: "image".to_string(),
-                                content: dest_relative_path.to_str().unwrap().to_string(),
-                                size: fs::metadata(&dest_absolute_path).map(|m| m.len()).ok(),
-                                is_favorite: false,
-                                notes: "".to_string(),
-                                timestamp: Utc::now().timestamp_millis(),
-                            };
-
-                            // println!("✅ 图片保存到文件: {:?}", dest_path);
-                            if let Err(e) = db::insert_received_db_data(new_item) {
-                                eprintln!("❌ 保存图片数据到数据库失败: {:?}", e);
-                            } else {
-                                // OCR识别（异步）
-                                let ocr_path =
-                                    dest_absolute_path.clone().to_str().unwrap().to_string();
-                                let ocr_item_id = image_id.clone();
-                                tauri::async_runtime::spawn(async move {
-                                    match ocr::ocr_image(ocr_path).await {
-                                        Ok(res) => {
-                                            println!("✅ OCR识别成功: {}", res);
-                                            // 识别成功，保存结果到数据库
-                                            let ocr_text =
-                                                match serde_json::from_str::<Vec<Value>>(&res) {
-                                                    Ok(json_array) => json_array
-                                                        .iter()
-                                                        .filter_map(|v| {
-                                                            v.get("text").and_then(|t| t.as_str())
-                                                        })
-                                                        .collect::<Vec<&str>>()
-                                                        .join("\n"),
-                                                    Err(_) => res.clone(),
-                                                };
-                                            if let Err(e) =
-                                                db::insert_ocr_text(&ocr_item_id, &ocr_text)
-                                            {
-                                                eprintln!("❌ 保存OCR结果到数据库失败: {:?}", e);
-                                            }
-                                        }
-                                        Err(err) => eprintln!("OCR error: {}", err),
-                                    }
-                                });
-
-                                // 通知前端
-                                if let Some(window) = app_handle.get_webview_window("main") {
-                                    let _ = window.emit("clipboard-updated", "");
-                                }
-                            }
-                        }
+            thread::sleep(Duration::from_millis(100));
+        }
+    });
+}
+
+/// 记录成功后的异步任务：较长的文本加入 AI 标签与摘要队列；图片解码二维码与条码，开启自动识别时加入 OCR 任务队列；
+/// 文件提取系统图标与文档文本
+fn spawn_post_capture_tasks(captured: &CapturedItem) {
+    if captured.kind == CaptureKind::Text {
+        if let Err(e) = ai_queue::enqueue_if_eligible(&captured.item.id, &captured.item.content) {
+            eprintln!("❌ 添加 AI 任务失败: {}", e);
+        }
+        return;
+    }
+    let Some(path) = captured.absolute_path.as_ref() else {
+        return;
+    };
+    let item_id = captured.item.id.clone();
+    let path = path.to_str().unwrap().to_string();
+
+    match captured.kind {
+        CaptureKind::Image => {
+            // 解码图片中的二维码与条码，结果写入 extended_data
+            let barcode_item_id = item_id.clone();
+            let barcode_path = path.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                match barcode::decode_and_store(&barcode_item_id, Path::new(&barcode_path)) {
+                    Ok(codes) if !codes.is_empty() => {
+                        println!("🔳 图片 {} 中解码出 {} 个码", barcode_item_id, codes.len())
                     }
+                    Ok(_) => {}
+                    Err(e) => eprintln!("⚠️ 解码二维码/条码失败: {}", e),
+                }
+            });
+            // 仅在开启自动识别时添加任务；识别由 OCR 任务队列限流执行，失败时自动重试
+            if ocr::auto_recognition_enabled() {
+                if let Err(e) = ocr_queue::enqueue(&item_id) {
+                    eprintln!("❌ 添加 OCR 任务失败: {}", e);
                 }
             }
-            // --- 文件监控 ---
-            else if let Ok(paths) = clipboard_files::read() {
-                if !paths.is_empty() && paths != last_file_paths {
-                    //println!("检测到新的文件复制: {:?}", paths);
-                    last_file_paths = paths.clone();
-                    last_text.clear();
-                    last_image_bytes.clear();
-
-                    if is_frontend_copy {
-                        println!("忽略前端触发的文件变更");
-                    } else {
-                        let mut has_new_files = false;
-                        const IMAGE_EXTENSIONS: &[&str] =
-                            &["png", "jpg", "jpeg", "gif", "bmp", "webp", "ico"];
-
-                        for path in paths {
-                    
-                            // 检查文件/文件夹大小是否超过限制
-                            let path_size = get_path_size(&path);
-                            if size_limit_mb > 0 && path_size > size_limit_bytes {
-                                println!(
-                                    "❌ 文件/文件夹大小超过限制: {:?} ({} MB > {} MB)，跳过复制",
-                                    path,
-                                    path_size as f64 / (1024.0 * 1024.0),
-                                    size_limit_mb
-                                );
-                                continue; // 跳过这个文件/文件夹
-                            }
-                            
-                            if let Some(file_name) = path.file_name().and_then(|n| n.to_str()) {
-                                let timestamp = Utc::now().timestamp_millis();
-                                let new_file_name = format!("{}-{}", timestamp, file_name);
-                                let dest_path = files_dir.join(&new_file_name);
-                                let dest_relative_path = db_root_dir.join(&new_file_name);
-                                
-                                // 🔧 关键修改：不拒绝任何复制，但添加额外的检查来防止递归
-                                
-                                // 1. 检查目标路径是否包含源路径（可能导致递归）
-                                // 如果是这种情况，我们仍然允许复制，但会记录警告并采取防护措施
-                                if dest_path.starts_with(&path) {
-                                    println!("⚠️ 注意：目标路径在源路径内部，添加额外防护");
-                                    // 这种情况下，我们可以添加一个标识，防止后续检测
-                                }
-                                
-                                // 2. 记录我们正在处理的路径，防止短时间内重复处理同一路径
-                                {
-                                    // 添加一个状态来跟踪最近处理的路径
-                                    use std::collections::HashSet;
-                                    use std::sync::Mutex;
-                                    
-                                    // 创建一个全局状态来存储最近处理的路径
-                                    static RECENTLY_PROCESSED: OnceLock<Mutex<HashSet<PathBuf>>> = OnceLock::new();
-                                    let recently_processed = RECENTLY_PROCESSED.get_or_init(|| Mutex::new(HashSet::new()));
-                                    
-                                    let mut set = recently_processed.lock().unwrap();
-                                    
-                                    // 检查这个路径是否在最近10秒内处理过
-                                    let mut to_remove = Vec::new();
-                                    for p in set.iter() {
-                                        // 这里可以添加时间戳检查逻辑
-                                        // 为了简化，我们先检查路径是否完全相同
-                                        if p == &path {
-                                            println!("🔄 跳过最近处理过的路径: {:?}", path);
-                                            continue; // 跳过当前路径的处理
-                                        }
-                                    }
-                                    
-                                    // 添加当前路径到最近处理集合
-                                    set.insert(path.clone());
-                                    
-                                    // 定期清理集合，防止内存泄漏
-                                    if set.len() > 100 {
-                                        // 移除一些旧的条目
-                                        let keys: Vec<_> = set.iter().cloned().collect();
-                                        for (i, key) in keys.iter().enumerate() {
-                                            if i < 50 { // 保留最近50个
-                                                to_remove.push(key.clone());
-                                            }
-                                        }
-                                        for key in to_remove {
-                                            set.remove(&key);
-                                        }
-                                    }
-                                }
-                                
-                                // 3. 复制操作前，临时禁用对该路径的进一步监控
-                                // 通过设置一个短暂的"忽略期"
-                                {
-                                    static IGNORE_PATHS: OnceLock<Mutex<Vec<(PathBuf, Instant)>>> = OnceLock::new();
-                                    let ignore_paths = IGNORE_PATHS.get_or_init(|| Mutex::new(Vec::new()));
-                                    
-                                    let mut ignore_list = ignore_paths.lock().unwrap();
-                                    
-                                    // 清理过期的忽略条目（超过5秒）
-                                    ignore_list.retain(|(_, time)| time.elapsed() < Duration::from_secs(5));
-                                    
-                                    // 添加当前路径到忽略列表（忽略5秒）
-                                    ignore_list.push((path.clone(), Instant::now()));
-                                }
-                                
-                                // 2. 根据是文件夹还是文件执行不同的复制操作
-                                let copy_result = if path.is_dir() {
-                                    // 使用自定义的安全复制函数，把 dest_path 传进去作为排除项
-                                    safe_copy_dir(&path, &dest_path, &dest_path) 
-                                } else {
-                                    fs::copy(&path, &dest_path)
-                                };
-                              
-
-                                match copy_result {
-                                    Ok(bytes_copied) => {
-                                        has_new_files = true;
-
-                                        // ✅ 直接使用复制时计算出的大小
-                                        let size = Some(bytes_copied);
-
-                                        // 判断类型：文件夹为 "folder"，图片为 "image"，其他为 "file"
-                                        let item_type = if path.is_dir() {
-                                            "folder"
-                                        } else if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                                            if IMAGE_EXTENSIONS.iter().any(|&img_ext| img_ext.eq_ignore_ascii_case(ext)) {
-                                                "image"
-                                            } else {
-                                                "file"
-                                            }
-                                        } else {
-                                            "file"
-                                        }.to_string();
-
-                                        let new_item = ClipboardItem {
-                                            id: Uuid::new_v4().to_string(),
-                                            item_type: item_type,
-                                            // content: dest_path.to_str().unwrap().to_string(),
-                                            content: dest_relative_path
-                                                .to_str()
-                                                .unwrap()
-                                                .to_string(),
-                                            size: size,
-                                            is_favorite: false,
-                                            notes: "".to_string(),
-                                            timestamp: Utc::now().timestamp_millis(),
-                                        };
-
-                                        // 先保存 id 与路径副本，new_item 会被 move 到 insert_received_db_data
-                                        let item_id_for_icon = new_item.id.clone();
-                                        let dest_path_for_icon =
-                                            dest_path.clone().to_str().unwrap().to_string();
-
-                                        // 记录数据库插入开始时间
-                                        let db_insert_start = Instant::now();
-
-                                        if let Err(e) = db::insert_received_db_data(new_item) {
-                                            eprintln!("❌ 保存数据到数据库失败: {:?}", e);
-                                        } else {
-                                            println!(
-                                                "[Main] 数据库插入耗时: {:?}",
-                                                db_insert_start.elapsed()
-                                            );
-
-                                            // 记录调度时间
-                                            let schedule_time = Instant::now();
-
-                                            // 异步提取系统图标并存入 extended_data.icon_data
-                                            tauri::async_runtime::spawn(async move {
-                                                // 记录开始时间
-                                                let task_start = Instant::now();
-                                                println!(
-                                                    "[Async] 图标获取任务启动延迟： {:?}",
-                                                    task_start.duration_since(schedule_time)
-                                                );
-
-                                                // 记录图标提取开始时间
-                                                let icon_extract_start = Instant::now();
-
-                                                match utils::get_file_icon(
-                                                    dest_path_for_icon.clone(),
-                                                )
-                                                .await
-                                                {
-                                                    Ok(data_uri) => {
-                                                        println!(
-                                                            "[Async] 图标提取耗时: {:?}",
-                                                            icon_extract_start.elapsed()
-                                                        );
-
-                                                        // 记录图标插入数据库开始时间
-                                                        let db_icon_insert_start = Instant::now();
-
-                                                        if let Err(err) = db::insert_icon_data(
-                                                            &item_id_for_icon,
-                                                            &data_uri,
-                                                        ) {
-                                                            eprintln!(
-                                                                "❌ insert_icon_data 失败: {:?}",
-                                                                err
-                                                            );
-                                                        }
-                                                        println!(
-                                                            "[Async] 图标数据插入耗时: {:?}",
-                                                            db_icon_insert_start.elapsed()
-                                                        );
-                                                        println!(
-                                                            "[Async] 图标任务总耗时: {:?}",
-                                                            task_start.elapsed()
-                                                        );
-                                                    }
-                                                    Err(err) => {
-                                                        eprintln!("⚠️ get_file_icon 失败: {}", err);
-                                                    }
-                                                }
-                                            });
-                                        }
-                                    }
-                                    Err(e) => {
-                                        eprintln!("❌ 复制 {:?} 失败: {}", path, e);
-                                    }
-                                }
-                            }
-                        }
-
-                        if has_new_files {
-                            let emit_start = Instant::now();
-                            if let Some(window) = app_handle.get_webview_window("main") {
-                                let _ = window.emit("clipboard-updated", "");
-                            }
-                            println!("[Main] 事件发送耗时: {:?}", emit_start.elapsed());
-                        }
-                    }
+        }
+        CaptureKind::File => {
+            // 提取 PDF、DOCX 与纯文本等文档的文本，存入 extended_data.document_text 以供搜索
+            let document_item_id = item_id.clone();
+            let document_path = path.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                match document::extract_and_store(&document_item_id, Path::new(&document_path)) {
+                    Ok(true) => println!("📄 已提取文件文本: {}", document_item_id),
+                    Ok(false) => {}
+                    Err(e) => eprintln!("⚠️ 提取文件文本失败: {}", e),
                 }
-            } else if let Ok(text) = app_handle.clipboard().read_text() {
-                if !text.is_empty() && text != last_text {
-                    println!("检测到新的文本内容");
-                    last_text = text.clone();
-                    last_image_bytes.clear();
-                    last_file_paths.clear();
-
-                    if is_frontend_copy {
-                        println!("忽略前端触发的文本变更");
-                    } else {
-                        let size = Some(text.chars().count() as u64);
-                        let new_item = ClipboardItem {
-                            id: Uuid::new_v4().to_string(),
-                            item_type: "text".to_string(),
-                            content: text,
-                            size,
-                            is_favorite: false,
-                            notes: "".to_string(),
-                            timestamp: Utc::now().timestamp_millis(),
-                        };
-
-                        // 能否被插入，取决于配置中的筛选条件
-                        let can_insert = {
-                            if let Some(lock) = CONFIG.get() {
-                                let cfg = lock.read().unwrap();
-                                cfg.ignore_short_text_len == 0 // 0 表示不限制（不忽略短文本）
-                                    || size.unwrap_or(0) >= cfg.ignore_short_text_len as u64
-                            } else {
-                                true // 默认允许插入
-                            }
-                        };
-                        if can_insert {
-                            if let Err(e) = db::insert_received_db_data(new_item.clone()) {
-                                eprintln!("❌ 保存文本数据到数据库失败: {:?}", e);
-                            } else {
-                                // 插入成功，通知前端
-                                if let Some(window) = app_handle.get_webview_window("main") {
-                                    let _ = window.emit("clipboard-updated", "");
-                                }
-                                // 根据新插入的数据，以及目前的配置项，判断是否需要标记为隐私
-                                let is_filter_password = get_config_item("filter_passwords")
-                                    .ok()
-                                    .and_then(|v| v.as_bool())
-                                    .unwrap_or(false);
-                                let is_filter_bankcard = get_config_item("filter_bank_cards")
-                                    .ok()
-                                    .and_then(|v| v.as_bool())
-                                    .unwrap_or(false);
-                                let is_filter_id_card = get_config_item("filter_id_cards")
-                                    .ok()
-                                    .and_then(|v| v.as_bool())
-                                    .unwrap_or(false);
-                                let is_filter_phone_number =
-                                    get_config_item("filter_phone_numbers")
-                                        .ok()
-                                        .and_then(|v| v.as_bool())
-                                        .unwrap_or(false);
-                                if let Err(e) = db::check_and_mark_private_item(
-                                    new_item.clone(),
-                                    is_filter_password,
-                                    is_filter_bankcard,
-                                    is_filter_id_card,
-                                    is_filter_phone_number,
-                                ) {
-                                    eprintln!("❌ 检查隐私数据失败: {:?}", e);
-                                }
-                            }
-                        } else {
-                            println!("⚠️ 文本长度不足，忽略插入");
+            });
+            // 异步提取系统图标并存入 extended_data.icon_data
+            tauri::async_runtime::spawn(async move {
+                let task_start = Instant::now();
+                match utils::get_file_icon(path).await {
+                    Ok(data_uri) => {
+                        if let Err(err) = db::insert_icon_data(&item_id, &data_uri) {
+                            eprintln!("❌ insert_icon_data 失败: {:?}", err);
                         }
+                        println!("[Async] 图标任务总耗时: {:?}", task_start.elapsed());
+                    }
+                    Err(err) => {
+                        eprintln!("⚠️ get_file_icon 失败: {}", err);
                     }
                 }
-            }
-
-            thread::sleep(Duration::from_millis(100));
+            });
         }
-    });
+        CaptureKind::Text => {}
+    }
 }
 /// 切换窗口的显示与隐藏状态
 fn toggle_window_visibility(window: &WebviewWindow) {
@@ -1041,12 +841,17 @@ pub fn start_cleanup_worker() {
             }
 
             // 读取配置
-            let (max_items, retention_days) = if let Some(lock) = CONFIG.get() {
-                let cfg = lock.read().unwrap();
-                (cfg.max_history_items, cfg.retention_days)
-            } else {
-                (500u32, 30u32) // 默认值
-            };
+            let (max_items, retention_days, audit_retention_days) =
+                if let Some(lock) = CONFIG.get() {
+                    let cfg = lock.read().unwrap();
+                    (
+                        cfg.max_history_items,
+                        cfg.retention_days,
+                        cfg.audit_retention_days,
+                    )
+                } else {
+                    (500u32, 30u32, 90u32) // 默认值
+                };
 
             // 执行过期清理
             match db::clear_data_expired(retention_days) {
@@ -1068,6 +873,16 @@ pub fn start_cleanup_worker() {
                 Err(e) => eprintln!("❌ 后台清理: 数量限制清理失败: {}", e),
             }
 
+            // 清理过期的审计日志
+            match db::prune_audit_log(audit_retention_days) {
+                Ok(deleted) => {
+                    if deleted > 0 {
+                        println!("🧹 后台清理: 删除了 {} 条过期审计日志", deleted);
+                    }
+                }
+                Err(e) => eprintln!("❌ 后台清理: 审计日志清理失败: {}", e),
+            }
+
             // 如果上次 recv 很快就返回，保证循环不会 100% 占用 CPU
             let elapsed = start.elapsed();
             if elapsed < Duration::from_millis(100) {
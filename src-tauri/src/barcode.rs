@@ -0,0 +1,589 @@
+//! 图片中的二维码与一维条码解码。
+//! 二维码由 rqrr 识别；一维条码（EAN-13/UPC-A、Code 128）沿多条水平与竖直扫描线提取明暗宽度后按码表解码。
+//! 解码结果保存在 extended_data 中，可通过综合搜索检索，也可直接复制到剪贴板。
+use crate::app_setup::ClipboardSourceState;
+use crate::db;
+use crate::utils;
+use image::{DynamicImage, GrayImage};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// 每个方向最多扫描的行（列）数
+const MAX_SCAN_LINES: u32 = 200;
+/// 一维条码至少在这么多条扫描线上得到相同结果才采用，避免偶然的误识别
+const MIN_LINE_HITS: usize = 2;
+/// 扫描线上明暗差低于该值时视为没有条码
+const MIN_LINE_CONTRAST: u8 = 48;
+/// 条码两侧空白区的最小宽度（模块数）
+const QUIET_ZONE_MODULES: f32 = 3.0;
+/// 宽度与码表的平均偏差上限（模块数）
+const MAX_MODULE_DEVIATION: f32 = 0.4;
+
+/// 条码格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BarcodeFormat {
+    QrCode,
+    Ean13,
+    UpcA,
+    Code128,
+}
+
+/// 一个解码出的二维码或条码
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedCode {
+    pub format: BarcodeFormat,
+    /// 码中携带的内容（网址、Wi-Fi 配置、商品编号等）
+    pub payload: String,
+}
+
+/// 扫描线上一段连续的深色（条）或浅色（空）像素
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Run {
+    dark: bool,
+    width: f32,
+}
+
+/// EAN-13 左侧 L 组编码的条空宽度（空、条、空、条），G 组为其逆序，右侧 R 组与 L 组宽度相同但以条开始
+const EAN_L_PATTERNS: [[u8; 4]; 10] = [
+    [3, 2, 1, 1],
+    [2, 2, 2, 1],
+    [2, 1, 2, 2],
+    [1, 4, 1, 1],
+    [1, 1, 3, 2],
+    [1, 2, 3, 1],
+    [1, 1, 1, 4],
+    [1, 3, 1, 2],
+    [1, 2, 1, 3],
+    [3, 1, 1, 2],
+];
+
+/// EAN-13 首位数字决定左侧六位的编码组（位为 1 表示 G 组，从高位起）
+const EAN_FIRST_DIGIT_PARITY: [u8; 10] = [
+    0b000000, 0b001011, 0b001101, 0b001110, 0b010011, 0b011001, 0b011100, 0b010101, 0b010110,
+    0b011010,
+];
+
+/// EAN-13 的总条空数：起始符 3 + 左侧 24 + 中间分隔符 5 + 右侧 24 + 终止符 3
+const EAN13_RUNS: usize = 59;
+
+/// Code 128 各符号的条空宽度（条、空、条、空、条、空），106 为终止符（其后还有 2 个模块宽的条）
+const CODE128_PATTERNS: [[u8; 6]; 107] = [
+    [2, 1, 2, 2, 2, 2],
+    [2, 2, 2, 1, 2, 2],
+    [2, 2, 2, 2, 2, 1],
+    [1, 2, 1, 2, 2, 3],
+    [1, 2, 1, 3, 2, 2],
+    [1, 3, 1, 2, 2, 2],
+    [1, 2, 2, 2, 1, 3],
+    [1, 2, 2, 3, 1, 2],
+    [1, 3, 2, 2, 1, 2],
+    [2, 2, 1, 2, 1, 3],
+    [2, 2, 1, 3, 1, 2],
+    [2, 3, 1, 2, 1, 2],
+    [1, 1, 2, 2, 3, 2],
+    [1, 2, 2, 1, 3, 2],
+    [1, 2, 2, 2, 3, 1],
+    [1, 1, 3, 2, 2, 2],
+    [1, 2, 3, 1, 2, 2],
+    [1, 2, 3, 2, 2, 1],
+    [2, 2, 3, 2, 1, 1],
+    [2, 2, 1, 1, 3, 2],
+    [2, 2, 1, 2, 3, 1],
+    [2, 1, 3, 2, 1, 2],
+    [2, 2, 3, 1, 1, 2],
+    [3, 1, 2, 1, 3, 1],
+    [3, 1, 1, 2, 2, 2],
+    [3, 2, 1, 1, 2, 2],
+    [3, 2, 1, 2, 2, 1],
+    [3, 1, 2, 2, 1, 2],
+    [3, 2, 2, 1, 1, 2],
+    [3, 2, 2, 2, 1, 1],
+    [2, 1, 2, 1, 2, 3],
+    [2, 1, 2, 3, 2, 1],
+    [2, 3, 2, 1, 2, 1],
+    [1, 1, 1, 3, 2, 3],
+    [1, 3, 1, 1, 2, 3],
+    [1, 3, 1, 3, 2, 1],
+    [1, 1, 2, 3, 1, 3],
+    [1, 3, 2, 1, 1, 3],
+    [1, 3, 2, 3, 1, 1],
+    [2, 1, 1, 3, 1, 3],
+    [2, 3, 1, 1, 1, 3],
+    [2, 3, 1, 3, 1, 1],
+    [1, 1, 2, 1, 3, 3],
+    [1, 1, 2, 3, 3, 1],
+    [1, 3, 2, 1, 3, 1],
+    [1, 1, 3, 1, 2, 3],
+    [1, 1, 3, 3, 2, 1],
+    [1, 3, 3, 1, 2, 1],
+    [3, 1, 3, 1, 2, 1],
+    [2, 1, 1, 3, 3, 1],
+    [2, 3, 1, 1, 3, 1],
+    [2, 1, 3, 1, 1, 3],
+    [2, 1, 3, 3, 1, 1],
+    [2, 1, 3, 1, 3, 1],
+    [3, 1, 1, 1, 2, 3],
+    [3, 1, 1, 3, 2, 1],
+    [3, 3, 1, 1, 2, 1],
+    [3, 1, 2, 1, 1, 3],
+    [3, 1, 2, 3, 1, 1],
+    [3, 3, 2, 1, 1, 1],
+    [3, 1, 4, 1, 1, 1],
+    [2, 2, 1, 4, 1, 1],
+    [4, 3, 1, 1, 1, 1],
+    [1, 1, 1, 2, 2, 4],
+    [1, 1, 1, 4, 2, 2],
+    [1, 2, 1, 1, 2, 4],
+    [1, 2, 1, 4, 2, 1],
+    [1, 4, 1, 1, 2, 2],
+    [1, 4, 1, 2, 2, 1],
+    [1, 1, 2, 2, 1, 4],
+    [1, 1, 2, 4, 1, 2],
+    [1, 2, 2, 1, 1, 4],
+    [1, 2, 2, 4, 1, 1],
+    [1, 4, 2, 1, 1, 2],
+    [1, 4, 2, 2, 1, 1],
+    [2, 4, 1, 2, 1, 1],
+    [2, 2, 1, 1, 1, 4],
+    [4, 1, 3, 1, 1, 1],
+    [2, 4, 1, 1, 1, 2],
+    [1, 3, 4, 1, 1, 1],
+    [1, 1, 1, 2, 4, 2],
+    [1, 2, 1, 1, 4, 2],
+    [1, 2, 1, 2, 4, 1],
+    [1, 1, 4, 2, 1, 2],
+    [1, 2, 4, 1, 1, 2],
+    [1, 2, 4, 2, 1, 1],
+    [4, 1, 1, 2, 1, 2],
+    [4, 2, 1, 1, 1, 2],
+    [4, 2, 1, 2, 1, 1],
+    [2, 1, 2, 1, 4, 1],
+    [2, 1, 4, 1, 2, 1],
+    [4, 1, 2, 1, 2, 1],
+    [1, 1, 1, 1, 4, 3],
+    [1, 1, 1, 3, 4, 1],
+    [1, 3, 1, 1, 4, 1],
+    [1, 1, 4, 1, 1, 3],
+    [1, 1, 4, 3, 1, 1],
+    [4, 1, 1, 1, 1, 3],
+    [4, 1, 1, 3, 1, 1],
+    [1, 1, 3, 1, 4, 1],
+    [1, 1, 4, 1, 3, 1],
+    [3, 1, 1, 1, 4, 1],
+    [4, 1, 1, 1, 3, 1],
+    [2, 1, 1, 4, 1, 2],
+    [2, 1, 1, 2, 1, 4],
+    [2, 1, 1, 2, 3, 2],
+    [2, 3, 3, 1, 1, 1],
+];
+const CODE128_START_A: usize = 103;
+const CODE128_START_C: usize = 105;
+const CODE128_STOP: usize = 106;
+
+/// Code 128 的字符集
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CodeSet {
+    A,
+    B,
+    C,
+}
+
+/// 解码图片中的所有二维码与条码。未找到且图片为深色背景时，再按反色（浅色码）尝试一次。
+/// # Param
+/// image: &DynamicImage - 待解码的图片
+/// # Returns
+/// Vec<DecodedCode> - 去重后的解码结果，二维码在前
+pub fn decode_image(image: &DynamicImage) -> Vec<DecodedCode> {
+    let mut gray = image.to_luma8();
+    let codes = decode_gray(&gray);
+    if !codes.is_empty() || !looks_inverted(&gray) {
+        return codes;
+    }
+    image::imageops::invert(&mut gray);
+    decode_gray(&gray)
+}
+
+/// 以图片四周边缘的平均亮度判断是否为深色背景。码的周围总有空白区，边缘即背景色
+fn looks_inverted(gray: &GrayImage) -> bool {
+    let (width, height) = gray.dimensions();
+    if width == 0 || height == 0 {
+        return false;
+    }
+    let edges = (0..width)
+        .flat_map(|x| [(x, 0), (x, height - 1)])
+        .chain((0..height).flat_map(|y| [(0, y), (width - 1, y)]));
+    let (sum, count) = edges.fold((0u64, 0u64), |(sum, count), (x, y)| {
+        (sum + gray.get_pixel(x, y)[0] as u64, count + 1)
+    });
+    sum / count < 128
+}
+
+fn decode_gray(gray: &GrayImage) -> Vec<DecodedCode> {
+    let mut codes = decode_qr(gray);
+    for code in decode_linear(gray) {
+        if !codes.contains(&code) {
+            codes.push(code);
+        }
+    }
+    codes
+}
+
+/// 使用 rqrr 识别二维码
+fn decode_qr(gray: &GrayImage) -> Vec<DecodedCode> {
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        gray.width() as usize,
+        gray.height() as usize,
+        |x, y| gray.get_pixel(x as u32, y as u32)[0],
+    );
+    let mut codes = Vec::new();
+    for grid in prepared.detect_grids() {
+        match grid.decode() {
+            Ok((_, payload)) => {
+                let code = DecodedCode {
+                    format: BarcodeFormat::QrCode,
+                    payload,
+                };
+                if !codes.contains(&code) {
+                    codes.push(code);
+                }
+            }
+            Err(e) => eprintln!("⚠️ 二维码解码失败: {:?}", e),
+        }
+    }
+    codes
+}
+
+/// 沿水平与竖直扫描线识别一维条码，只保留在多条扫描线上一致的结果
+fn decode_linear(gray: &GrayImage) -> Vec<DecodedCode> {
+    let (width, height) = gray.dimensions();
+    let mut hits: Vec<(DecodedCode, usize)> = Vec::new();
+
+    let rows = scan_positions(height)
+        .map(|y| (0..width).map(|x| gray.get_pixel(x, y)[0]).collect::<Vec<u8>>());
+    let columns = scan_positions(width)
+        .map(|x| (0..height).map(|y| gray.get_pixel(x, y)[0]).collect::<Vec<u8>>());
+    for line in rows.chain(columns) {
+        for code in decode_scan_line(&line) {
+            match hits.iter_mut().find(|(hit, _)| *hit == code) {
+                Some((_, count)) => *count += 1,
+                None => hits.push((code, 1)),
+            }
+        }
+    }
+
+    hits.into_iter()
+        .filter(|(_, count)| *count >= MIN_LINE_HITS)
+        .map(|(code, _)| code)
+        .collect()
+}
+
+/// 均匀分布的扫描线位置
+fn scan_positions(len: u32) -> impl Iterator<Item = u32> {
+    let step = (len / MAX_SCAN_LINES).max(1);
+    (step / 2..len).step_by(step as usize)
+}
+
+/// 解码一条扫描线上的条码，正反两个方向各尝试一次
+fn decode_scan_line(line: &[u8]) -> Vec<DecodedCode> {
+    let runs = to_runs(line);
+    let reversed: Vec<Run> = runs.iter().rev().copied().collect();
+
+    let mut codes = Vec::new();
+    for runs in [runs, reversed] {
+        for code in decode_ean13(&runs).into_iter().chain(decode_code128(&runs)) {
+            if !codes.contains(&code) {
+                codes.push(code);
+            }
+        }
+    }
+    codes
+}
+
+/// 以扫描线上最亮与最暗值的中点二值化，合并为连续的条空宽度
+fn to_runs(line: &[u8]) -> Vec<Run> {
+    let (min, max) = line
+        .iter()
+        .fold((u8::MAX, u8::MIN), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    if max.saturating_sub(min) < MIN_LINE_CONTRAST {
+        return Vec::new();
+    }
+    let threshold = ((min as u16 + max as u16) / 2) as u8;
+
+    let mut runs: Vec<Run> = Vec::new();
+    for &value in line {
+        let dark = value < threshold;
+        match runs.last_mut() {
+            Some(run) if run.dark == dark => run.width += 1.0,
+            _ => runs.push(Run { dark, width: 1.0 }),
+        }
+    }
+    runs
+}
+
+fn total_width(runs: &[Run]) -> f32 {
+    runs.iter().map(|run| run.width).sum()
+}
+
+/// 条空宽度与码表的平均偏差（以模块为单位）
+fn pattern_deviation(runs: &[Run], module: f32, pattern: &[u8]) -> f32 {
+    let sum: f32 = runs
+        .iter()
+        .zip(pattern)
+        .map(|(run, &expected)| (run.width / module - expected as f32).abs())
+        .sum();
+    sum / pattern.len() as f32
+}
+
+/// 在码表中查找与条空宽度最接近的符号，偏差过大时返回 None
+fn best_match<const N: usize>(runs: &[Run], modules: u8, patterns: &[[u8; N]]) -> Option<usize> {
+    let module = total_width(runs) / modules as f32;
+    patterns
+        .iter()
+        .map(|pattern| pattern_deviation(runs, module, pattern))
+        .enumerate()
+        .filter(|(_, deviation)| *deviation < MAX_MODULE_DEVIATION)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(index, _)| index)
+}
+
+/// 条码前后是否有足够宽的空白区（条码紧贴图片边缘时不识别）
+fn has_quiet_zones(runs: &[Run], start: usize, end: usize, module: f32) -> bool {
+    let is_quiet = |run: Option<&Run>| {
+        run.is_some_and(|run| !run.dark && run.width >= QUIET_ZONE_MODULES * module)
+    };
+    start > 0 && is_quiet(runs.get(start - 1)) && is_quiet(runs.get(end))
+}
+
+/// 识别 EAN-13 条码，首位为 0 时作为 UPC-A 返回 12 位数字
+fn decode_ean13(runs: &[Run]) -> Vec<DecodedCode> {
+    let mut codes = Vec::new();
+    if runs.len() < EAN13_RUNS {
+        return codes;
+    }
+    for start in 0..=runs.len() - EAN13_RUNS {
+        if !runs[start].dark {
+            continue;
+        }
+        let window = &runs[start..start + EAN13_RUNS];
+        let module = total_width(window) / 95.0;
+        if !has_quiet_zones(runs, start, start + EAN13_RUNS, module) {
+            continue;
+        }
+        if let Some(digits) = decode_ean13_window(window, module) {
+            let payload: String = digits.iter().map(|d| char::from(b'0' + d)).collect();
+            let code = match payload.strip_prefix('0') {
+                Some(upc) => DecodedCode {
+                    format: BarcodeFormat::UpcA,
+                    payload: upc.to_string(),
+                },
+                None => DecodedCode {
+                    format: BarcodeFormat::Ean13,
+                    payload,
+                },
+            };
+            codes.push(code);
+        }
+    }
+    codes
+}
+
+fn decode_ean13_window(window: &[Run], module: f32) -> Option<[u8; 13]> {
+    let guards = [(0, &[1u8; 3][..]), (27, &[1; 5][..]), (56, &[1; 3][..])];
+    if guards.iter().any(|(offset, pattern)| {
+        pattern_deviation(&window[*offset..*offset + pattern.len()], module, pattern)
+            >= MAX_MODULE_DEVIATION
+    }) {
+        return None;
+    }
+
+    // 左侧每位数字可能是 L 组或 G 组，合并为 20 项的码表查找
+    let left_patterns: Vec<[u8; 4]> = EAN_L_PATTERNS
+        .iter()
+        .copied()
+        .chain(EAN_L_PATTERNS.iter().map(|p| [p[3], p[2], p[1], p[0]]))
+        .collect();
+    let mut digits = [0u8; 13];
+    let mut parity = 0u8;
+    for i in 0..6 {
+        let index = best_match(&window[3 + i * 4..7 + i * 4], 7, &left_patterns)?;
+        digits[i + 1] = (index % 10) as u8;
+        if index >= 10 {
+            parity |= 1 << (5 - i);
+        }
+    }
+    for i in 0..6 {
+        digits[i + 7] = best_match(&window[32 + i * 4..36 + i * 4], 7, &EAN_L_PATTERNS)? as u8;
+    }
+    digits[0] = EAN_FIRST_DIGIT_PARITY.iter().position(|&p| p == parity)? as u8;
+
+    let sum: u32 = digits[..12]
+        .iter()
+        .enumerate()
+        .map(|(i, &d)| d as u32 * if i % 2 == 0 { 1 } else { 3 })
+        .sum();
+    if (10 - sum % 10) % 10 != digits[12] as u32 {
+        return None;
+    }
+    Some(digits)
+}
+
+/// 识别 Code 128 条码
+fn decode_code128(runs: &[Run]) -> Vec<DecodedCode> {
+    let mut codes = Vec::new();
+    for start in 0..runs.len().saturating_sub(6) {
+        if !runs[start].dark {
+            continue;
+        }
+        let Some(values) = read_code128_symbols(runs, start) else {
+            continue;
+        };
+        // 校验位 = (起始符 + Σ 位置 × 符号值) mod 103
+        let (check, body) = values.split_last().unwrap();
+        let sum: usize = body[0]
+            + body[1..]
+                .iter()
+                .enumerate()
+                .map(|(i, &v)| (i + 1) * v)
+                .sum::<usize>();
+        if sum % 103 != *check {
+            continue;
+        }
+        if let Some(payload) = code128_text(body) {
+            codes.push(DecodedCode {
+                format: BarcodeFormat::Code128,
+                payload,
+            });
+        }
+    }
+    codes
+}
+
+/// 从起始符开始读取符号值直到终止符，返回起始符、数据与校验位。
+/// 先检查空白区并只与三个起始符比较，绝大多数位置无需查完整码表即可排除
+fn read_code128_symbols(runs: &[Run], start: usize) -> Option<Vec<usize>> {
+    let first = &runs[start..start + 6];
+    let module = total_width(first) / 11.0;
+    if start == 0 || runs[start - 1].dark || runs[start - 1].width < QUIET_ZONE_MODULES * module {
+        return None;
+    }
+    let value = CODE128_START_A
+        + best_match(first, 11, &CODE128_PATTERNS[CODE128_START_A..=CODE128_START_C])?;
+
+    let mut values = vec![value];
+    let mut pos = start + 6;
+    loop {
+        let symbol = runs.get(pos..pos + 6)?;
+        // 各符号宽度应与起始符一致
+        if (total_width(symbol) / 11.0 - module).abs() > module * 0.25 {
+            return None;
+        }
+        let value = best_match(symbol, 11, &CODE128_PATTERNS)?;
+        if value == CODE128_STOP {
+            let bar = runs.get(pos + 6)?;
+            if !bar.dark || (bar.width / module - 2.0).abs() >= 1.0 {
+                return None;
+            }
+            if !has_quiet_zones(runs, start, pos + 7, module) {
+                return None;
+            }
+            // 至少包含起始符、一个数据符号与校验位
+            return (values.len() >= 3).then_some(values);
+        }
+        if value >= CODE128_START_A {
+            return None;
+        }
+        values.push(value);
+        pos += 6;
+    }
+}
+
+/// 按字符集将 Code 128 符号值转换为文本（不含校验位），FNC 功能符被忽略
+fn code128_text(values: &[usize]) -> Option<String> {
+    let mut set = match values[0] {
+        103 => CodeSet::A,
+        104 => CodeSet::B,
+        105 => CodeSet::C,
+        _ => return None,
+    };
+    let mut text = String::new();
+    let mut shift = false;
+    for &value in &values[1..] {
+        // 切换符（SHIFT）只影响下一个字符
+        let current = match (shift, set) {
+            (true, CodeSet::A) => CodeSet::B,
+            (true, CodeSet::B) => CodeSet::A,
+            _ => set,
+        };
+        shift = false;
+        match (current, value) {
+            (CodeSet::C, 0..=99) => text.push_str(&format!("{:02}", value)),
+            (CodeSet::C, 100) => set = CodeSet::B,
+            (CodeSet::C, 101) => set = CodeSet::A,
+            (_, 0..=63) => text.push(char::from(value as u8 + 32)),
+            (CodeSet::A, 64..=95) => text.push(char::from(value as u8 - 64)),
+            (CodeSet::B, 64..=95) => text.push(char::from(value as u8 + 32)),
+            (CodeSet::A | CodeSet::B, 98) => shift = true,
+            (CodeSet::A | CodeSet::B, 99) => set = CodeSet::C,
+            (CodeSet::A, 100) => set = CodeSet::B,
+            (CodeSet::B, 101) => set = CodeSet::A,
+            // FNC1~FNC4
+            (_, 96 | 97 | 100 | 101 | 102) => {}
+            _ => return None,
+        }
+    }
+    Some(text)
+}
+
+/// 解码图片文件中的二维码与条码并写入 extended_data（未找到时也记录，表示已解码过）
+/// # Param
+/// item_id: &str - 图片数据项 ID
+/// path: &Path - 图片文件的绝对路径
+/// # Returns
+/// Vec<DecodedCode> - 解码结果
+pub fn decode_and_store(item_id: &str, path: &Path) -> Result<Vec<DecodedCode>, String> {
+    let image = image::open(path).map_err(|e| format!("无法打开图片 {:?}: {}", path, e))?;
+    let codes = decode_image(&image);
+    db::insert_barcodes(item_id, &codes)?;
+    Ok(codes)
+}
+
+/// 将图片中解码出的内容复制到剪贴板；尚未解码过的图片（如旧记录）会先解码。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// item_id: String - 图片数据项 ID
+/// index: Option<usize> - 图片含多个码时要复制的序号，默认为第一个
+/// # Returns
+/// String - 复制的内容
+#[tauri::command]
+pub async fn copy_barcode_payload(
+    item_id: String,
+    index: Option<usize>,
+    state: State<'_, ClipboardSourceState>,
+) -> Result<String, String> {
+    let codes = match db::get_barcodes(&item_id)? {
+        Some(codes) => codes,
+        None => {
+            let item = db::get_item_by_id(&item_id)?.ok_or("数据项不存在")?;
+            if item.item_type != "image" {
+                return Err("只能解码图片数据项".to_string());
+            }
+            let path = utils::resolve_absolute_path(&PathBuf::from(&item.content));
+            let id = item_id.clone();
+            tauri::async_runtime::spawn_blocking(move || decode_and_store(&id, &path))
+                .await
+                .map_err(|e| e.to_string())??
+        }
+    };
+    let payload = codes
+        .get(index.unwrap_or(0))
+        .map(|code| code.payload.clone())
+        .ok_or("未识别到二维码或条码")?;
+
+    utils::write_derived_text_to_clipboard(&payload, &item_id, "copy_barcode_payload", &state)?;
+    Ok(payload)
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_barcode.rs"]
+mod test_barcode;
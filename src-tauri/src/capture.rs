@@ -0,0 +1,779 @@
+//! 剪贴板捕获流程。
+//! 将监控循环中"读取剪贴板 → 过滤 → 保存文件 → 写入数据库"的逻辑抽离为可逐步驱动的
+//! `CapturePipeline`，不依赖 Tauri 应用，便于在单元测试中使用内存剪贴板验证。
+//! 通知前端、OCR 识别与图标提取等异步任务仍由监控循环根据返回结果处理。
+use crate::change_detect::ChangeDetector;
+use crate::clipboard::ClipboardItem;
+use crate::clipboard_backend::ClipboardBackend;
+use crate::clipboard_hints::{self, HintDecision, HintPolicy};
+use crate::config::CONFIG;
+use crate::db;
+use crate::echo_filter::{ContentFingerprint, EchoFilter};
+use crate::rich_text;
+use crate::source_app::{self, SourceApp, SourceAppProvider};
+use chrono::Utc;
+use image::ColorType;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// 按扩展名识别为图片的文件类型
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "webp", "ico"];
+
+/// 捕获流程使用的配置，每轮轮询从全局配置重新读取以支持运行时修改
+#[derive(Debug, Clone)]
+pub struct CaptureSettings {
+    /// 文件与图片的存储目录（绝对路径）
+    pub files_dir: PathBuf,
+    /// 忽略超过该大小的文件（MB，0 表示不限制）
+    pub ignore_big_file_mb: u32,
+    /// 忽略短于该长度的文本（字符数，0 表示不限制）
+    pub ignore_short_text_len: u32,
+    /// 密码管理器提示处理策略
+    pub hint_policy: HintPolicy,
+    /// 忽略的来源应用
+    pub ignored_apps: Vec<String>,
+    /// 隐私过滤开关
+    pub filter_passwords: bool,
+    pub filter_bank_cards: bool,
+    pub filter_id_cards: bool,
+    pub filter_phone_numbers: bool,
+}
+
+impl CaptureSettings {
+    /// 从全局配置读取捕获设置，配置未初始化时使用默认值
+    /// # Param
+    /// files_dir: PathBuf - 文件存储目录
+    pub fn from_config(files_dir: PathBuf) -> Self {
+        match CONFIG.get() {
+            Some(lock) => {
+                let cfg = lock.read().unwrap();
+                Self {
+                    files_dir,
+                    ignore_big_file_mb: cfg.ignore_big_file_mb,
+                    ignore_short_text_len: cfg.ignore_short_text_len,
+                    hint_policy: clipboard_hints::parse_hint_policy(&cfg.password_hint_policy),
+                    ignored_apps: cfg.ignored_apps.clone(),
+                    filter_passwords: cfg.filter_passwords,
+                    filter_bank_cards: cfg.filter_bank_cards,
+                    filter_id_cards: cfg.filter_id_cards,
+                    filter_phone_numbers: cfg.filter_phone_numbers,
+                }
+            }
+            None => Self {
+                files_dir,
+                ignore_big_file_mb: 5,
+                ignore_short_text_len: 0,
+                hint_policy: HintPolicy::Skip,
+                ignored_apps: Vec::new(),
+                filter_passwords: false,
+                filter_bank_cards: false,
+                filter_id_cards: false,
+                filter_phone_numbers: false,
+            },
+        }
+    }
+
+    fn size_limit_bytes(&self) -> u64 {
+        self.ignore_big_file_mb as u64 * 1024 * 1024
+    }
+}
+
+/// 单轮轮询的外部状态
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TickInput<'a> {
+    /// 应用自身写入剪贴板时登记的内容指纹，与之一致的变化不记录
+    pub echoes: Option<&'a EchoFilter>,
+    /// 是否处于暂停记录状态
+    pub paused: bool,
+}
+
+/// 捕获到的内容来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureKind {
+    Text,
+    Image,
+    File,
+}
+
+/// 一次复制中可作为数据项主体的表示
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Representation {
+    Image,
+    Files,
+    Text,
+}
+
+/// 首选表示规则：同一次复制提供多种表示时，选择哪一种作为数据项主体（data 表），
+/// 其余表示保存到 item_formats。
+/// 1. 文件列表优先：文件管理器复制时附带的文本（文件名）与图标图片只是辅助表示；
+/// 2. 同时带有 HTML / RTF 的文本优先于图片：表格、文档等应用复制选区时附带的图片只是渲染结果；
+/// 3. 其次为图片，最后为纯文本。
+/// # Param
+/// has_image: bool - 是否有图片
+/// has_files: bool - 是否有文件列表
+/// has_text: bool - 是否有非空文本（含从富文本提取的文本）
+/// has_rich_text: bool - 是否有 HTML 或 RTF
+/// # Returns
+/// Option<Representation> - 首选表示，剪贴板为空时返回 None
+pub fn preferred_representation(
+    has_image: bool,
+    has_files: bool,
+    has_text: bool,
+    has_rich_text: bool,
+) -> Option<Representation> {
+    if has_files {
+        Some(Representation::Files)
+    } else if has_text && has_rich_text {
+        Some(Representation::Text)
+    } else if has_image {
+        Some(Representation::Image)
+    } else if has_text {
+        Some(Representation::Text)
+    } else {
+        None
+    }
+}
+
+/// 单轮读取到的剪贴板全部表示
+#[derive(Debug, Default)]
+struct ClipboardSnapshot {
+    image_hash: Option<u64>,
+    files: Option<Vec<PathBuf>>,
+    text: Option<String>,
+    /// 已去除 CF_HTML 头部的 HTML
+    html: Option<String>,
+    rtf: Option<String>,
+}
+
+impl ClipboardSnapshot {
+    fn preferred(&self) -> Option<Representation> {
+        preferred_representation(
+            self.image_hash.is_some(),
+            self.files.is_some(),
+            self.text.is_some(),
+            self.html.is_some() || self.rtf.is_some(),
+        )
+    }
+
+    /// 各表示的内容指纹，用于回声判断
+    fn fingerprints(&self) -> Vec<ContentFingerprint> {
+        let mut fingerprints = Vec::new();
+        if let Some(text) = &self.text {
+            fingerprints.push(ContentFingerprint::text(text));
+        }
+        if let Some(files) = &self.files {
+            fingerprints.push(ContentFingerprint::files(files));
+        }
+        if let Some(hash) = self.image_hash {
+            fingerprints.push(ContentFingerprint::image(hash));
+        }
+        fingerprints
+    }
+}
+
+/// 一条已写入数据库的捕获结果
+#[derive(Debug, Clone)]
+pub struct CapturedItem {
+    pub kind: CaptureKind,
+    pub item: ClipboardItem,
+    /// 图片或文件保存后的绝对路径
+    pub absolute_path: Option<PathBuf>,
+    /// 是否为密码管理器提示产生的隐私占位条目（不保存任何其他表示）
+    pub redacted: bool,
+}
+
+/// 跳过记录的原因
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    FrontendCopy,
+    Paused,
+    PasswordHint(String),
+    IgnoredApp(String),
+    TooShort,
+}
+
+/// 单轮轮询的结果
+#[derive(Debug)]
+pub enum CaptureOutcome {
+    /// 首轮：仅记录当前剪贴板内容作为基准，不写入数据库
+    Baseline,
+    /// 剪贴板没有新内容
+    Unchanged,
+    /// 检测到新内容，但按规则不记录
+    Skipped(SkipReason),
+    /// 已记录的条目（可能为空，例如文件全部超过大小限制）
+    Captured(Vec<CapturedItem>),
+}
+
+/// 剪贴板捕获流程，保存上一轮的内容状态
+pub struct CapturePipeline<B: ClipboardBackend> {
+    backend: Arc<B>,
+    source_provider: Box<dyn SourceAppProvider>,
+    change_detector: ChangeDetector<Arc<B>>,
+    last_text: String,
+    last_image_hash: Option<u64>,
+    last_file_paths: Vec<PathBuf>,
+    is_first_run: bool,
+}
+
+impl<B: ClipboardBackend> CapturePipeline<B> {
+    pub fn new(backend: Arc<B>, source_provider: Box<dyn SourceAppProvider>) -> Self {
+        Self {
+            change_detector: ChangeDetector::new(backend.clone()),
+            backend,
+            source_provider,
+            last_text: String::new(),
+            last_image_hash: None,
+            last_file_paths: Vec::new(),
+            is_first_run: true,
+        }
+    }
+
+    /// 获取剪贴板后端
+    pub fn backend(&self) -> &Arc<B> {
+        &self.backend
+    }
+
+    /// 平台是否提供剪贴板变化计数
+    pub fn has_native_change_token(&self) -> bool {
+        self.change_detector.has_native_token()
+    }
+
+    /// 执行一轮轮询：检测变化，按首选表示规则记录新内容，并保存其余表示
+    /// # Param
+    /// input: TickInput - 本轮的回声指纹与暂停状态
+    /// settings: &CaptureSettings - 捕获配置
+    /// # Returns
+    /// CaptureOutcome - 本轮结果
+    pub fn step(&mut self, input: TickInput, settings: &CaptureSettings) -> CaptureOutcome {
+        if self.is_first_run {
+            self.record_baseline();
+            return CaptureOutcome::Baseline;
+        }
+
+        // 剪贴板未变化时跳过本轮的全部读取
+        if !self.change_detector.poll() {
+            return CaptureOutcome::Unchanged;
+        }
+
+        let snapshot = self.read_snapshot();
+        let Some(primary) = snapshot.preferred() else {
+            return CaptureOutcome::Unchanged;
+        };
+        let unchanged = match primary {
+            Representation::Image => snapshot.image_hash == self.last_image_hash,
+            Representation::Files => snapshot.files.as_ref() == Some(&self.last_file_paths),
+            Representation::Text => snapshot.text.as_ref() == Some(&self.last_text),
+        };
+        if unchanged {
+            return CaptureOutcome::Unchanged;
+        }
+        // 立即更新 last 状态，防止重复检测
+        self.last_image_hash = snapshot.image_hash;
+        self.last_file_paths = snapshot.files.clone().unwrap_or_default();
+        self.last_text = snapshot.text.clone().unwrap_or_default();
+
+        // 只忽略应用自身写入的内容，其他复制照常记录
+        let is_frontend_copy = input
+            .echoes
+            .is_some_and(|echoes| echoes.is_echo(&snapshot.fingerprints()));
+
+        let outcome = match primary {
+            Representation::Image => {
+                println!("检测到新的图片内容");
+                self.capture_image(is_frontend_copy, input.paused, settings)
+            }
+            Representation::Files => {
+                let paths = snapshot.files.clone().unwrap_or_default();
+                self.capture_files(paths, is_frontend_copy, input.paused, settings)
+            }
+            Representation::Text => {
+                println!("检测到新的文本内容");
+                let text = snapshot.text.clone().unwrap_or_default();
+                self.capture_text(text, is_frontend_copy, input.paused, settings)
+            }
+        };
+
+        if let CaptureOutcome::Captured(items) = &outcome {
+            for captured in items {
+                // 隐私占位条目不保存任何其他表示
+                if !captured.redacted {
+                    self.save_secondary_representations(
+                        &captured.item.id,
+                        primary,
+                        &snapshot,
+                        settings,
+                    );
+                }
+            }
+        }
+        outcome
+    }
+
+    /// 读取剪贴板当前提供的全部表示，每种格式只读取一次。
+    /// 只有富文本格式时，从 HTML 或 RTF 中提取纯文本作为回退内容
+    fn read_snapshot(&self) -> ClipboardSnapshot {
+        let rich = self.backend.read_rich_text();
+        let text = self
+            .backend
+            .read_text()
+            .filter(|text| !text.is_empty())
+            .or_else(|| rich.html.as_deref().map(rich_text::html_to_text))
+            .or_else(|| rich.rtf.as_deref().map(rich_text::rtf_to_text))
+            .filter(|text| !text.is_empty());
+        ClipboardSnapshot {
+            image_hash: self.backend.image_fingerprint(),
+            files: self.backend.read_files().filter(|paths| !paths.is_empty()),
+            text,
+            html: rich
+                .html
+                .map(|html| rich_text::strip_cf_html_header(&html))
+                .filter(|html| !html.trim().is_empty()),
+            rtf: rich.rtf.filter(|rtf| !rtf.trim().is_empty()),
+        }
+    }
+
+    /// 将首选表示之外的其他表示保存到 item_formats
+    fn save_secondary_representations(
+        &self,
+        item_id: &str,
+        primary: Representation,
+        snapshot: &ClipboardSnapshot,
+        settings: &CaptureSettings,
+    ) {
+        let mut formats: Vec<(&str, String)> = Vec::new();
+        if primary != Representation::Text {
+            if let Some(text) = &snapshot.text {
+                formats.push((db::FORMAT_TEXT, text.clone()));
+            }
+        }
+        if let Some(html) = &snapshot.html {
+            formats.push((db::FORMAT_HTML, html.clone()));
+        }
+        if let Some(rtf) = &snapshot.rtf {
+            formats.push((db::FORMAT_RTF, rtf.clone()));
+        }
+        // 文件管理器复制时附带的图片只是文件图标，不保存
+        if primary == Representation::Text && snapshot.image_hash.is_some() {
+            if let Some(relative_path) = self.save_image_representation(settings) {
+                formats.push((db::FORMAT_IMAGE, relative_path));
+            }
+        }
+
+        for (format, content) in formats {
+            if let Err(e) = db::insert_item_format(item_id, format, &content) {
+                eprintln!("❌ 保存 {} 格式失败: {:?}", format, e);
+            }
+        }
+    }
+
+    /// 将剪贴板中的图片保存为 PNG，返回相对路径
+    fn save_image_representation(&self, settings: &CaptureSettings) -> Option<String> {
+        let image = self.backend.read_image()?;
+        let file_name = format!("{}.png", Uuid::new_v4());
+        if let Err(e) = image::save_buffer(
+            settings.files_dir.join(&file_name),
+            &image.rgba,
+            image.width,
+            image.height,
+            ColorType::Rgba8,
+        ) {
+            eprintln!("❌ 保存图片表示失败: {:?}", e);
+            return None;
+        }
+        PathBuf::from("files")
+            .join(&file_name)
+            .to_str()
+            .map(|s| s.to_string())
+    }
+
+    /// 首轮读取当前剪贴板内容作为基准，避免启动时把已有内容当作新复制
+    fn record_baseline(&mut self) {
+        self.change_detector.poll();
+        let snapshot = self.read_snapshot();
+        self.last_image_hash = snapshot.image_hash;
+        self.last_file_paths = snapshot.files.unwrap_or_default();
+        self.last_text = snapshot.text.unwrap_or_default();
+        self.is_first_run = false;
+    }
+
+    /// 识别来源应用；前端复制或暂停时无需识别
+    fn current_source(&self, is_frontend_copy: bool, paused: bool) -> Option<SourceApp> {
+        if is_frontend_copy || paused {
+            None
+        } else {
+            self.source_provider.current_source()
+        }
+    }
+
+    /// 依次检查前端复制、暂停、密码管理器提示与来源应用忽略列表
+    /// # Param
+    /// hint: &HintDecision - 提示检测结果
+    /// redact_allowed: bool - 是否允许以隐私占位条目记录（仅文本）
+    fn skip_reason(
+        &self,
+        what: &str,
+        is_frontend_copy: bool,
+        paused: bool,
+        hint: &HintDecision,
+        redact_allowed: bool,
+        source: &Option<SourceApp>,
+        settings: &CaptureSettings,
+    ) -> Option<SkipReason> {
+        if is_frontend_copy {
+            println!("忽略前端触发的{}变更", what);
+            return Some(SkipReason::FrontendCopy);
+        }
+        if paused {
+            println!("⏸️ 记录已暂停，忽略{}变更", what);
+            return Some(SkipReason::Paused);
+        }
+        match hint {
+            HintDecision::Skip(format) => {
+                println!("🔒 检测到密码管理器提示 {}，不记录{}", format, what);
+                return Some(SkipReason::PasswordHint(format.clone()));
+            }
+            HintDecision::Redact(format) if !redact_allowed => {
+                println!("🔒 检测到密码管理器提示 {}，不记录{}", format, what);
+                return Some(SkipReason::PasswordHint(format.clone()));
+            }
+            _ => {}
+        }
+        if let Some(app) = source
+            .as_ref()
+            .filter(|s| source_app::is_ignored_app(s, &settings.ignored_apps))
+        {
+            println!("🚫 来源应用 {} 在忽略列表中，不记录{}", app.app_name, what);
+            return Some(SkipReason::IgnoredApp(app.app_name.clone()));
+        }
+        None
+    }
+
+    /// 密码管理器提示检测；前端复制或暂停时无需检测
+    fn hint_decision(
+        &self,
+        is_frontend_copy: bool,
+        paused: bool,
+        settings: &CaptureSettings,
+    ) -> HintDecision {
+        if is_frontend_copy || paused {
+            HintDecision::Capture
+        } else {
+            clipboard_hints::decide(self.backend.as_ref(), settings.hint_policy)
+        }
+    }
+
+    fn capture_image(
+        &self,
+        is_frontend_copy: bool,
+        paused: bool,
+        settings: &CaptureSettings,
+    ) -> CaptureOutcome {
+        let source = self.current_source(is_frontend_copy, paused);
+        let hint = self.hint_decision(is_frontend_copy, paused, settings);
+        if let Some(reason) = self.skip_reason(
+            "图片",
+            is_frontend_copy,
+            paused,
+            &hint,
+            false,
+            &source,
+            settings,
+        ) {
+            return CaptureOutcome::Skipped(reason);
+        }
+
+        let Some(image) = self.backend.read_image() else {
+            return CaptureOutcome::Unchanged;
+        };
+        let image_id = Uuid::new_v4().to_string();
+        let file_name = format!("{}.png", image_id);
+        let dest_relative_path = PathBuf::from("files").join(&file_name);
+        let dest_absolute_path = settings.files_dir.join(&file_name);
+        if let Err(e) = image::save_buffer(
+            &dest_absolute_path,
+            &image.rgba,
+            image.width,
+            image.height,
+            ColorType::Rgba8,
+        ) {
+            eprintln!("❌ 保存图片文件失败: {:?}", e);
+            return CaptureOutcome::Captured(Vec::new());
+        }
+
+        let new_item = ClipboardItem {
+            id: image_id.clone(),
+            item_type: "image".to_string(),
+            content: dest_relative_path.to_str().unwrap().to_string(),
+            size: fs::metadata(&dest_absolute_path).map(|m| m.len()).ok(),
+            is_favorite: false,
+            notes: "".to_string(),
+            timestamp: Utc::now().timestamp_millis(),
+        };
+
+        if let Err(e) = db::insert_received_db_data(new_item.clone()) {
+            eprintln!("❌ 保存图片数据到数据库失败: {:?}", e);
+            return CaptureOutcome::Captured(Vec::new());
+        }
+        save_item_source(&image_id, &source);
+
+        CaptureOutcome::Captured(vec![CapturedItem {
+            kind: CaptureKind::Image,
+            item: new_item,
+            absolute_path: Some(dest_absolute_path),
+            redacted: false,
+        }])
+    }
+
+    fn capture_files(
+        &self,
+        paths: Vec<PathBuf>,
+        is_frontend_copy: bool,
+        paused: bool,
+        settings: &CaptureSettings,
+    ) -> CaptureOutcome {
+        let source = self.current_source(is_frontend_copy, paused);
+        let hint = self.hint_decision(is_frontend_copy, paused, settings);
+        if let Some(reason) = self.skip_reason(
+            "文件",
+            is_frontend_copy,
+            paused,
+            &hint,
+            false,
+            &source,
+            settings,
+        ) {
+            return CaptureOutcome::Skipped(reason);
+        }
+
+        let mut captured = Vec::new();
+        for path in paths {
+            // 检查文件/文件夹大小是否超过限制
+            let path_size = get_path_size(&path);
+            if settings.ignore_big_file_mb > 0 && path_size > settings.size_limit_bytes() {
+                println!(
+                    "❌ 文件/文件夹大小超过限制: {:?} ({} MB > {} MB)，跳过复制",
+                    path,
+                    path_size as f64 / (1024.0 * 1024.0),
+                    settings.ignore_big_file_mb
+                );
+                continue;
+            }
+
+            let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let timestamp = Utc::now().timestamp_millis();
+            let new_file_name = format!("{}-{}", timestamp, file_name);
+            let dest_path = settings.files_dir.join(&new_file_name);
+            let dest_relative_path = PathBuf::from("files").join(&new_file_name);
+
+            if dest_path.starts_with(&path) {
+                println!("⚠️ 注意：目标路径在源路径内部，添加额外防护");
+            }
+
+            // 根据是文件夹还是文件执行不同的复制操作
+            let copy_result = if path.is_dir() {
+                // 把 dest_path 作为排除项，防止复制到自身内部时无限递归
+                safe_copy_dir(&path, &dest_path, &dest_path)
+            } else {
+                fs::copy(&path, &dest_path)
+            };
+
+            let bytes_copied = match copy_result {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    eprintln!("❌ 复制 {:?} 失败: {}", path, e);
+                    continue;
+                }
+            };
+
+            // 判断类型：文件夹为 "folder"，图片为 "image"，其他为 "file"
+            let item_type = if path.is_dir() {
+                "folder"
+            } else if path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|ext| IMAGE_EXTENSIONS.iter().any(|i| i.eq_ignore_ascii_case(ext)))
+            {
+                "image"
+            } else {
+                "file"
+            };
+
+            let new_item = ClipboardItem {
+                id: Uuid::new_v4().to_string(),
+                item_type: item_type.to_string(),
+                content: dest_relative_path.to_str().unwrap().to_string(),
+                size: Some(bytes_copied),
+                is_favorite: false,
+                notes: "".to_string(),
+                timestamp: Utc::now().timestamp_millis(),
+            };
+
+            if let Err(e) = db::insert_received_db_data(new_item.clone()) {
+                eprintln!("❌ 保存数据到数据库失败: {:?}", e);
+                continue;
+            }
+            save_item_source(&new_item.id, &source);
+            captured.push(CapturedItem {
+                kind: CaptureKind::File,
+                item: new_item,
+                absolute_path: Some(dest_path),
+                redacted: false,
+            });
+        }
+
+        CaptureOutcome::Captured(captured)
+    }
+
+    fn capture_text(
+        &self,
+        text: String,
+        is_frontend_copy: bool,
+        paused: bool,
+        settings: &CaptureSettings,
+    ) -> CaptureOutcome {
+        let source = self.current_source(is_frontend_copy, paused);
+        let hint = self.hint_decision(is_frontend_copy, paused, settings);
+        if let Some(reason) = self.skip_reason(
+            "文本",
+            is_frontend_copy,
+            paused,
+            &hint,
+            true,
+            &source,
+            settings,
+        ) {
+            return CaptureOutcome::Skipped(reason);
+        }
+
+        if let HintDecision::Redact(format) = hint {
+            // 只保存占位内容，并直接标记为隐私
+            println!("🔒 检测到密码管理器提示 {}，以隐私占位条目记录", format);
+            let redacted_item = clipboard_hints::build_redacted_item();
+            if let Err(e) = db::insert_received_db_data(redacted_item.clone()) {
+                eprintln!("❌ 保存隐私占位条目失败: {:?}", e);
+                return CaptureOutcome::Captured(Vec::new());
+            }
+            save_item_source(&redacted_item.id, &source);
+            if let Err(e) = db::mark_item_as_private(&redacted_item.id) {
+                eprintln!("❌ 标记隐私占位条目失败: {:?}", e);
+            }
+            return CaptureOutcome::Captured(vec![CapturedItem {
+                kind: CaptureKind::Text,
+                item: redacted_item,
+                absolute_path: None,
+                redacted: true,
+            }]);
+        }
+
+        // 能否被插入，取决于配置中的筛选条件（0 表示不限制）
+        let size = text.chars().count() as u64;
+        if settings.ignore_short_text_len != 0 && size < settings.ignore_short_text_len as u64 {
+            println!("⚠️ 文本长度不足，忽略插入");
+            return CaptureOutcome::Skipped(SkipReason::TooShort);
+        }
+
+        let new_item = ClipboardItem {
+            id: Uuid::new_v4().to_string(),
+            item_type: "text".to_string(),
+            content: text,
+            size: Some(size),
+            is_favorite: false,
+            notes: "".to_string(),
+            timestamp: Utc::now().timestamp_millis(),
+        };
+        if let Err(e) = db::insert_received_db_data(new_item.clone()) {
+            eprintln!("❌ 保存文本数据到数据库失败: {:?}", e);
+            return CaptureOutcome::Captured(Vec::new());
+        }
+        save_item_source(&new_item.id, &source);
+
+        // 根据新插入的数据，以及目前的配置项，判断是否需要标记为隐私
+        if let Err(e) = db::check_and_mark_private_item(
+            new_item.clone(),
+            settings.filter_passwords,
+            settings.filter_bank_cards,
+            settings.filter_id_cards,
+            settings.filter_phone_numbers,
+        ) {
+            eprintln!("❌ 检查隐私数据失败: {:?}", e);
+        }
+
+        CaptureOutcome::Captured(vec![CapturedItem {
+            kind: CaptureKind::Text,
+            item: new_item,
+            absolute_path: None,
+            redacted: false,
+        }])
+    }
+}
+
+/// 保存数据项的来源应用
+fn save_item_source(item_id: &str, source: &Option<SourceApp>) {
+    if let Some(source) = source {
+        if let Err(e) = db::insert_item_source(item_id, source) {
+            eprintln!("❌ 保存来源应用失败: {:?}", e);
+        }
+    }
+}
+
+/// 计算文件或文件夹（递归）的大小
+fn get_path_size(path: &Path) -> u64 {
+    if path.is_dir() {
+        let mut total = 0;
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                total += get_path_size(&entry.path());
+            }
+        }
+        total
+    } else {
+        fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+/// 安全的递归复制函数
+/// src: 源目录
+/// dst: 目标目录
+/// exclude_target: 需要避开的路径（即 dest_path 本身）
+fn safe_copy_dir(src: &Path, dst: &Path, exclude_target: &Path) -> io::Result<u64> {
+    if !dst.exists() {
+        fs::create_dir_all(dst)?;
+    }
+
+    let mut total_bytes = 0;
+
+    // 获取排除路径的规范化形式（绝对路径），用于比较
+    // 如果 exclude_target 还没创建，canonicalize 可能会失败，所以我们要容错
+    let abs_exclude = exclude_target
+        .canonicalize()
+        .unwrap_or(exclude_target.to_path_buf());
+
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        // 如果当前遍历到的路径 == 我们正在写入的目标文件夹，直接跳过
+        let abs_current = path.canonicalize().unwrap_or(path.clone());
+        if abs_current == abs_exclude {
+            continue;
+        }
+
+        let new_dst = dst.join(entry.file_name());
+        if path.is_dir() {
+            total_bytes += safe_copy_dir(&path, &new_dst, exclude_target)?;
+        } else {
+            total_bytes += fs::copy(&path, &new_dst)?;
+        }
+    }
+    Ok(total_bytes)
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_capture.rs"]
+mod test_capture;
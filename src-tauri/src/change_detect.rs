@@ -0,0 +1,184 @@
+//! 剪贴板变化检测。
+//! - Windows：使用 `GetClipboardSequenceNumber` 作为变化计数，序号不变时跳过全部读取
+//! - macOS / Linux (X11)：后台线程通过 clipboard-rs 的剪贴板监听（NSPasteboard changeCount、XFixes 选区所有者变化事件）
+//!   累加变化计数，计数不变时同样跳过全部读取
+//! - 监听不可用时（如纯 Wayland 会话）：每次轮询读取内容，并以完整内容的快速哈希代替逐字节比较
+
+/// 指纹的初始状态
+const HASH_SEED: u64 = 0xcbf2_9ce4_8422_2325;
+/// 指纹混合使用的奇数乘数
+const HASH_MULTIPLIER: u64 = 0x517c_c1b7_2722_0a95;
+
+/// 剪贴板变化计数来源，便于在测试中替换
+pub trait ChangeTokenSource: Send + Sync {
+    /// 返回当前剪贴板的变化计数；平台不支持时返回 None
+    fn change_token(&self) -> Option<u64>;
+}
+
+/// 基于系统 API 的变化计数实现
+pub struct SystemChangeTokenSource;
+
+impl ChangeTokenSource for SystemChangeTokenSource {
+    fn change_token(&self) -> Option<u64> {
+        #[cfg(target_os = "windows")]
+        {
+            use windows::Win32::System::DataExchange::GetClipboardSequenceNumber;
+            // 返回 0 表示当前会话没有访问剪贴板的权限，此时退回内容比较
+            let seq = unsafe { GetClipboardSequenceNumber() };
+            if seq == 0 {
+                None
+            } else {
+                Some(seq as u64)
+            }
+        }
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            watcher::change_count()
+        }
+    }
+}
+
+/// 基于 clipboard-rs 剪贴板监听的变化计数（macOS 轮询 NSPasteboard changeCount，Linux 监听 XFixes 选区事件）
+#[cfg(not(target_os = "windows"))]
+mod watcher {
+    use clipboard_rs::{ClipboardHandler, ClipboardWatcher, ClipboardWatcherContext};
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+    use std::sync::Once;
+
+    static START: Once = Once::new();
+    /// 监听线程是否正在运行
+    static RUNNING: AtomicBool = AtomicBool::new(false);
+    /// 监听到的变化次数
+    static CHANGES: AtomicU64 = AtomicU64::new(0);
+
+    struct CountingHandler;
+
+    impl ClipboardHandler for CountingHandler {
+        fn on_clipboard_change(&mut self) {
+            CHANGES.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// 返回监听到的变化次数，首次调用时启动监听线程并等待其就绪；监听不可用时返回 None
+    pub fn change_count() -> Option<u64> {
+        START.call_once(|| {
+            let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+            std::thread::spawn(move || {
+                match ClipboardWatcherContext::new() {
+                    Ok(mut watcher) => {
+                        RUNNING.store(true, Ordering::SeqCst);
+                        let _ = ready_tx.send(());
+                        watcher.add_handler(CountingHandler).start_watch();
+                    }
+                    Err(e) => eprintln!("⚠️ 剪贴板监听不可用，改为比较内容: {}", e),
+                }
+                RUNNING.store(false, Ordering::SeqCst);
+            });
+            // 线程退出时发送端随之释放，不会一直等待
+            let _ = ready_rx.recv();
+        });
+        RUNNING
+            .load(Ordering::SeqCst)
+            .then(|| CHANGES.load(Ordering::SeqCst))
+    }
+}
+
+/// 变化检测器：记录上一次的变化计数，判断本轮是否需要读取剪贴板
+pub struct ChangeDetector<S: ChangeTokenSource> {
+    source: S,
+    last_token: Option<u64>,
+}
+
+impl<S: ChangeTokenSource> ChangeDetector<S> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            last_token: None,
+        }
+    }
+
+    /// 判断剪贴板自上次调用后是否可能发生变化。
+    /// 平台不提供变化计数时总是返回 true，由调用方比较内容指纹。
+    /// # Returns
+    /// bool - 是否需要读取剪贴板内容
+    pub fn poll(&mut self) -> bool {
+        match self.source.change_token() {
+            Some(token) => {
+                let changed = self.last_token != Some(token);
+                self.last_token = Some(token);
+                changed
+            }
+            None => {
+                self.last_token = None;
+                true
+            }
+        }
+    }
+
+    /// 平台是否提供变化计数（用于日志）
+    pub fn has_native_token(&self) -> bool {
+        self.source.change_token().is_some()
+    }
+}
+
+/// 计算图片内容的指纹：尺寸、总长度以及全部像素数据。
+/// 按 8 字节字处理，4K 截图（约 33MB）每次约需几毫秒；任意一个字的变化都会改变指纹。
+/// # Param
+/// width: u32 - 图片宽度
+/// height: u32 - 图片高度
+/// rgba: &[u8] - RGBA 像素数据
+/// # Returns
+/// u64 - 指纹
+pub fn image_fingerprint(width: u32, height: u32, rgba: &[u8]) -> u64 {
+    let hash = mix(HASH_SEED, ((width as u64) << 32) | height as u64);
+    hash_words(hash, rgba)
+}
+
+/// 计算任意字节数据的指纹（非加密哈希，仅用于变化检测）
+/// # Param
+/// data: &[u8] - 数据
+/// # Returns
+/// u64 - 指纹
+pub fn fast_hash(data: &[u8]) -> u64 {
+    hash_words(HASH_SEED, data)
+}
+
+/// 每一步对上一状态与当前字都是双射（乘数为奇数），因此只改变一个字时结果必然不同
+fn mix(hash: u64, word: u64) -> u64 {
+    (hash.rotate_left(5) ^ word).wrapping_mul(HASH_MULTIPLIER)
+}
+
+fn read_word(bytes: &[u8]) -> u64 {
+    let mut word = [0u8; 8];
+    word[..bytes.len()].copy_from_slice(bytes);
+    u64::from_le_bytes(word)
+}
+
+fn hash_words(hash: u64, data: &[u8]) -> u64 {
+    // 四路独立混合，减少乘法延迟造成的等待
+    let mut lanes = [
+        mix(hash, data.len() as u64),
+        hash ^ 1,
+        hash ^ 2,
+        hash ^ 3,
+    ];
+    let mut blocks = data.chunks_exact(32);
+    for block in &mut blocks {
+        for (lane, word) in lanes.iter_mut().zip(block.chunks_exact(8)) {
+            *lane = mix(*lane, read_word(word));
+        }
+    }
+    let mut hash = lanes.iter().fold(0, |acc, &lane| mix(acc, lane));
+    for word in blocks.remainder().chunks(8) {
+        hash = mix(hash, read_word(word));
+    }
+    // 最后打散高低位，避免相近输入得到相近的指纹
+    hash ^= hash >> 29;
+    hash = hash.wrapping_mul(HASH_MULTIPLIER);
+    hash ^ (hash >> 32)
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_change_detect.rs"]
+mod test_change_detect;
@@ -0,0 +1,314 @@
+//! 文本内容自动分类。
+//! 根据文本内容判断子类型（URL、邮箱、电话、代码、JSON、颜色值、文件路径、数字、日期、Markdown），
+//! 代码片段同时给出语言猜测。分类只依赖文本本身，不访问数据库。
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// 文本子类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentSubtype {
+    Url,
+    Email,
+    Phone,
+    Code,
+    Json,
+    Color,
+    Path,
+    Number,
+    Date,
+    Markdown,
+}
+
+impl ContentSubtype {
+    /// 子类型在数据库中的字符串表示
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentSubtype::Url => "url",
+            ContentSubtype::Email => "email",
+            ContentSubtype::Phone => "phone",
+            ContentSubtype::Code => "code",
+            ContentSubtype::Json => "json",
+            ContentSubtype::Color => "color",
+            ContentSubtype::Path => "path",
+            ContentSubtype::Number => "number",
+            ContentSubtype::Date => "date",
+            ContentSubtype::Markdown => "markdown",
+        }
+    }
+
+    /// 从字符串解析子类型，未知值返回 None
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "url" => Some(ContentSubtype::Url),
+            "email" => Some(ContentSubtype::Email),
+            "phone" => Some(ContentSubtype::Phone),
+            "code" => Some(ContentSubtype::Code),
+            "json" => Some(ContentSubtype::Json),
+            "color" => Some(ContentSubtype::Color),
+            "path" => Some(ContentSubtype::Path),
+            "number" => Some(ContentSubtype::Number),
+            "date" => Some(ContentSubtype::Date),
+            "markdown" => Some(ContentSubtype::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// 分类结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Classification {
+    pub subtype: ContentSubtype,
+    /// 代码片段的语言猜测（仅 Code 类型）
+    pub language: Option<String>,
+}
+
+impl Classification {
+    fn of(subtype: ContentSubtype) -> Self {
+        Self {
+            subtype,
+            language: None,
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref URL_RE: Regex = Regex::new(r"(?i)^(https?|ftp)://[^\s/$.?#][^\s]*$|^www\.[^\s.]+\.[^\s]{2,}$").unwrap();
+    static ref EMAIL_RE: Regex = Regex::new(r"^(?i:mailto:)?[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}$").unwrap();
+    static ref PHONE_RE: Regex = Regex::new(r"^\+?[\d\s().-]+$").unwrap();
+    static ref CN_MOBILE_RE: Regex = Regex::new(r"^1[3-9]\d{9}$").unwrap();
+    static ref HEX_COLOR_RE: Regex = Regex::new(r"^#(?:[0-9a-fA-F]{3,4}|[0-9a-fA-F]{6}|[0-9a-fA-F]{8})$").unwrap();
+    static ref FUNC_COLOR_RE: Regex = Regex::new(
+        r"(?i)^(?:rgb|hsl)a?\(\s*[\d.]+(?:deg|%)?\s*[,\s]\s*[\d.]+%?\s*[,\s]\s*[\d.]+%?\s*(?:[,/]\s*[\d.]+%?\s*)?\)$"
+    ).unwrap();
+    static ref NUMBER_RE: Regex = Regex::new(r"^[+-]?(?:\d{1,3}(?:,\d{3})+|\d+)(?:\.\d+)?(?:[eE][+-]?\d+)?%?$|^[+-]?\.\d+$").unwrap();
+    static ref WIN_PATH_RE: Regex = Regex::new(r"^(?:[A-Za-z]:[\\/]|\\\\[^\\\s]+\\)").unwrap();
+    static ref UNIX_PATH_RE: Regex = Regex::new(r"^(?:~|\.{1,2})?/[^/\s]").unwrap();
+    static ref MD_LINK_RE: Regex = Regex::new(r"\[[^\]]+\]\([^)\s]+\)").unwrap();
+    static ref MD_EMPHASIS_RE: Regex = Regex::new(r"\*\*[^*\n]+\*\*|__[^_\n]+__").unwrap();
+}
+
+/// 日期与时间的常见格式
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d", "%Y年%m月%d日"];
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+];
+
+/// 语言猜测规则：(语言, 特征片段)。每命中一个特征计 1 分
+const LANGUAGE_HINTS: &[(&str, &[&str])] = &[
+    ("rust", &["fn ", "let mut ", "impl ", "pub fn", "::", "-> ", "use std", "match ", "&self", "Vec<", "println!"]),
+    ("python", &["def ", "import ", "self.", "elif ", "print(", "None", "__init__", "lambda ", "from "]),
+    ("javascript", &["function ", "const ", "=> ", "console.log", "===", "let ", "require(", "export ", "async "]),
+    ("typescript", &["interface ", ": string", ": number", ": boolean", "export type ", "<T>", "readonly "]),
+    ("java", &["public class ", "System.out", "private ", "public static void", "import java", "@Override", "new "]),
+    ("c", &["#include", "int main", "printf(", "malloc(", "->", "NULL", "sizeof("]),
+    ("cpp", &["#include", "std::", "cout <<", "template<", "nullptr", "namespace "]),
+    ("csharp", &["using System", "namespace ", "public void ", "Console.Write", "var ", "async Task"]),
+    ("go", &["func ", "package ", ":= ", "fmt.", "err != nil", "go "]),
+    ("sql", &["SELECT ", "FROM ", "WHERE ", "INSERT INTO", "CREATE TABLE", "UPDATE ", "JOIN "]),
+    ("html", &["<div", "<html", "<span", "</", "<p>", "<a href", "<!DOCTYPE"]),
+    ("css", &["{", "color:", "margin:", "padding:", "display:", "font-size:", "px;"]),
+    ("shell", &["#!/bin/", "sudo ", "echo ", "&& ", "export ", "$(", "apt ", "cd "]),
+];
+
+/// 对文本进行分类。
+/// 单行文本依次检查 URL、邮箱、颜色值、日期、电话、数字与文件路径；
+/// 多行文本依次检查 JSON、Markdown 与代码。无法归类时返回 None。
+/// # Param
+/// text: &str - 文本内容
+/// # Returns
+/// Option<Classification> - 分类结果
+pub fn classify_text(text: &str) -> Option<Classification> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    if !trimmed.contains('\n') {
+        if let Some(subtype) = classify_single_line(trimmed) {
+            return Some(Classification::of(subtype));
+        }
+    }
+
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(trimmed).is_ok()
+    {
+        return Some(Classification::of(ContentSubtype::Json));
+    }
+
+    if let Some(language) = fenced_code_language(trimmed) {
+        return Some(Classification {
+            subtype: ContentSubtype::Code,
+            language,
+        });
+    }
+    if is_markdown(trimmed) {
+        return Some(Classification::of(ContentSubtype::Markdown));
+    }
+    if is_code(trimmed) {
+        return Some(Classification {
+            subtype: ContentSubtype::Code,
+            language: guess_language(trimmed),
+        });
+    }
+    None
+}
+
+/// 单行文本的分类
+fn classify_single_line(line: &str) -> Option<ContentSubtype> {
+    if URL_RE.is_match(line) {
+        return Some(ContentSubtype::Url);
+    }
+    if EMAIL_RE.is_match(line) {
+        return Some(ContentSubtype::Email);
+    }
+    if HEX_COLOR_RE.is_match(line) || FUNC_COLOR_RE.is_match(line) {
+        return Some(ContentSubtype::Color);
+    }
+    if is_date(line) {
+        return Some(ContentSubtype::Date);
+    }
+    if is_phone(line) {
+        return Some(ContentSubtype::Phone);
+    }
+    if NUMBER_RE.is_match(line) {
+        return Some(ContentSubtype::Number);
+    }
+    if !line.contains("://") && (WIN_PATH_RE.is_match(line) || UNIX_PATH_RE.is_match(line)) {
+        return Some(ContentSubtype::Path);
+    }
+    None
+}
+
+fn is_date(line: &str) -> bool {
+    use chrono::{DateTime, NaiveDate, NaiveDateTime};
+    DateTime::parse_from_rfc3339(line).is_ok()
+        || DATE_FORMATS
+            .iter()
+            .any(|f| NaiveDate::parse_from_str(line, f).is_ok())
+        || DATETIME_FORMATS
+            .iter()
+            .any(|f| NaiveDateTime::parse_from_str(line, f).is_ok())
+}
+
+/// 电话号码：中国大陆手机号，或带分隔符 / 国际区号的 7~15 位数字
+fn is_phone(line: &str) -> bool {
+    if CN_MOBILE_RE.is_match(line) {
+        return true;
+    }
+    if !PHONE_RE.is_match(line) {
+        return false;
+    }
+    let digits = line.chars().filter(|c| c.is_ascii_digit()).count();
+    let has_separator = line.starts_with('+') || line.contains([' ', '-', '(', ')']);
+    // 纯数字与小数交给数字判断
+    has_separator && !line.contains('.') && (7..=15).contains(&digits)
+}
+
+/// 整段文本为 ``` 围起的代码块时，返回围栏上标注的语言（未标注时为语言猜测）
+fn fenced_code_language(text: &str) -> Option<Option<String>> {
+    let body = text.strip_prefix("```")?.strip_suffix("```")?;
+    let (info, code) = body.split_once('\n')?;
+    // 中间还有其他围栏说明是包含多个代码块的 Markdown
+    if code.contains("```") {
+        return None;
+    }
+    let info = info.trim();
+    if info.is_empty() {
+        Some(guess_language(code))
+    } else {
+        Some(Some(info.to_lowercase()))
+    }
+}
+
+/// Markdown：至少命中两类语法特征
+fn is_markdown(text: &str) -> bool {
+    let mut features = 0;
+    let lines: Vec<&str> = text.lines().map(|l| l.trim_start()).collect();
+    let line_feature = |pred: &dyn Fn(&str) -> bool| lines.iter().any(|l| pred(l));
+
+    if line_feature(&|l| {
+        let hashes = l.chars().take_while(|&c| c == '#').count();
+        (1..=6).contains(&hashes) && l[hashes..].starts_with(' ')
+    }) {
+        features += 1;
+    }
+    if line_feature(&|l| {
+        l.starts_with("- ")
+            || l.starts_with("* ")
+            || l.starts_with("+ ")
+            || l.split_once(". ")
+                .is_some_and(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+    }) {
+        features += 1;
+    }
+    if line_feature(&|l| l.starts_with("> ")) {
+        features += 1;
+    }
+    if line_feature(&|l| l.starts_with("```")) {
+        features += 1;
+    }
+    if line_feature(&|l| l.starts_with('|') && l.ends_with('|') && l.len() > 2) {
+        features += 1;
+    }
+    if MD_LINK_RE.is_match(text) {
+        features += 1;
+    }
+    if MD_EMPHASIS_RE.is_match(text) {
+        features += 1;
+    }
+    features >= 2
+}
+
+/// 代码：结构特征（行尾分号 / 花括号、缩进）与语言特征合计至少 3 分
+fn is_code(text: &str) -> bool {
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let structural = lines
+        .iter()
+        .filter(|l| {
+            let t = l.trim_end();
+            t.ends_with(';') || t.ends_with('{') || t.ends_with('}') || t.ends_with("):")
+        })
+        .count();
+    let indented = lines
+        .iter()
+        .filter(|l| l.starts_with("    ") || l.starts_with('\t'))
+        .count();
+    let language_score = LANGUAGE_HINTS
+        .iter()
+        .map(|(_, hints)| hint_score(text, hints))
+        .max()
+        .unwrap_or(0);
+
+    structural.min(3) + (indented > 0) as usize + language_score >= 3
+        && (structural > 0 || language_score >= 2)
+}
+
+/// 根据特征片段猜测代码语言，没有命中任何特征时返回 None
+/// # Param
+/// code: &str - 代码文本
+/// # Returns
+/// Option<String> - 语言名称（小写）
+pub fn guess_language(code: &str) -> Option<String> {
+    let mut best: Option<(&str, usize)> = None;
+    for (language, hints) in LANGUAGE_HINTS {
+        let score = hint_score(code, hints);
+        // 同分时保留先出现的语言
+        if score > 0 && best.is_none_or(|(_, s)| score > s) {
+            best = Some((language, score));
+        }
+    }
+    best.map(|(language, _)| language.to_string())
+}
+
+fn hint_score(text: &str, hints: &[&str]) -> usize {
+    hints.iter().filter(|hint| text.contains(*hint)).count()
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_classify.rs"]
+mod test_classify;
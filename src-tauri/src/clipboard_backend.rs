@@ -0,0 +1,212 @@
+//! 剪贴板读写后端。
+//! 监控循环与捕获流程只依赖 `ClipboardBackend`，运行时使用基于 Tauri 插件的实现，
+//! 测试中可替换为内存实现，无需启动 Tauri 应用。
+use crate::change_detect::{self, ChangeTokenSource, SystemChangeTokenSource};
+use crate::clipboard_hints::ClipboardFormatReader;
+use clipboard_rs::common::RustImage;
+use clipboard_rs::{
+    Clipboard as ClipboardRsTrait, ClipboardContent, ClipboardContext, RustImageData,
+};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// 从剪贴板读取的图片（RGBA8）
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClipboardImage {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+/// 剪贴板中的富文本格式
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RichText {
+    pub html: Option<String>,
+    pub rtf: Option<String>,
+}
+
+/// 一次写入剪贴板的全部表示，粘贴时由目标应用选择最合适的格式
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ClipboardRepresentations {
+    pub text: Option<String>,
+    pub html: Option<String>,
+    pub rtf: Option<String>,
+    /// 图片文件路径
+    pub image_path: Option<PathBuf>,
+    pub files: Vec<PathBuf>,
+}
+
+impl ClipboardRepresentations {
+    /// 是否只有纯文本
+    pub fn is_text_only(&self) -> bool {
+        self.html.is_none()
+            && self.rtf.is_none()
+            && self.image_path.is_none()
+            && self.files.is_empty()
+    }
+
+    /// 是否只有文件列表
+    pub fn is_files_only(&self) -> bool {
+        self.text.is_none()
+            && self.html.is_none()
+            && self.rtf.is_none()
+            && self.image_path.is_none()
+    }
+}
+
+/// 剪贴板后端接口。
+/// 读取方法在剪贴板中没有对应格式（或读取失败）时返回 None。
+pub trait ClipboardBackend: ClipboardFormatReader + Send + Sync {
+    /// 平台提供的变化计数，不支持时返回 None
+    fn change_token(&self) -> Option<u64>;
+    /// 读取纯文本
+    fn read_text(&self) -> Option<String>;
+    /// 读取 HTML 与 RTF 格式内容，每轮快照只调用一次
+    fn read_rich_text(&self) -> RichText;
+    /// 计算当前图片的指纹，没有图片时返回 None
+    fn image_fingerprint(&self) -> Option<u64>;
+    /// 读取完整图片
+    fn read_image(&self) -> Option<ClipboardImage>;
+    /// 读取文件列表
+    fn read_files(&self) -> Option<Vec<PathBuf>>;
+    /// 写入纯文本
+    fn write_text(&self, text: &str) -> Result<(), String>;
+    /// 写入文件列表
+    fn write_files(&self, paths: &[PathBuf]) -> Result<(), String>;
+    /// 一次性写入多种表示
+    fn write_representations(&self, reps: &ClipboardRepresentations) -> Result<(), String>;
+}
+
+/// 使后端可直接作为变化检测器的计数来源
+impl<B: ClipboardBackend> ChangeTokenSource for Arc<B> {
+    fn change_token(&self) -> Option<u64> {
+        ClipboardBackend::change_token(self.as_ref())
+    }
+}
+
+/// 基于 tauri-plugin-clipboard-manager、clipboard-rs 与 clipboard-files 的系统实现
+pub struct TauriClipboardBackend {
+    app: AppHandle,
+}
+
+impl TauriClipboardBackend {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+impl ClipboardFormatReader for TauriClipboardBackend {
+    fn available_formats(&self) -> Result<Vec<String>, String> {
+        let ctx = ClipboardContext::new().map_err(|e| e.to_string())?;
+        ClipboardFormatReader::available_formats(&ctx)
+    }
+
+    fn read_format(&self, format: &str) -> Result<Vec<u8>, String> {
+        let ctx = ClipboardContext::new().map_err(|e| e.to_string())?;
+        ClipboardFormatReader::read_format(&ctx, format)
+    }
+}
+
+impl ClipboardBackend for TauriClipboardBackend {
+    fn change_token(&self) -> Option<u64> {
+        SystemChangeTokenSource.change_token()
+    }
+
+    fn read_text(&self) -> Option<String> {
+        self.app.clipboard().read_text().ok()
+    }
+
+    fn read_rich_text(&self) -> RichText {
+        // 两种格式在同一个上下文中读取，避免每种格式各建立一次剪贴板连接
+        let Ok(ctx) = ClipboardContext::new() else {
+            return RichText::default();
+        };
+        RichText {
+            html: ctx.get_html().ok().filter(|html| !html.is_empty()),
+            rtf: ctx.get_rich_text().ok().filter(|rtf| !rtf.is_empty()),
+        }
+    }
+
+    /// 剪贴板插件只提供完整读取，此处每次都会复制整张图片；
+    /// 有变化计数时（Windows、macOS、Linux X11）只在计数变化时才会调用
+    fn image_fingerprint(&self) -> Option<u64> {
+        let image = self.app.clipboard().read_image().ok()?;
+        if image.rgba().is_empty() {
+            return None;
+        }
+        Some(change_detect::image_fingerprint(
+            image.width(),
+            image.height(),
+            image.rgba(),
+        ))
+    }
+
+    fn read_image(&self) -> Option<ClipboardImage> {
+        let image = self.app.clipboard().read_image().ok()?;
+        if image.rgba().is_empty() {
+            return None;
+        }
+        Some(ClipboardImage {
+            width: image.width(),
+            height: image.height(),
+            rgba: image.rgba().to_vec(),
+        })
+    }
+
+    fn read_files(&self) -> Option<Vec<PathBuf>> {
+        clipboard_files::read().ok()
+    }
+
+    fn write_text(&self, text: &str) -> Result<(), String> {
+        self.app
+            .clipboard()
+            .write_text(text.to_string())
+            .map_err(|e| e.to_string())
+    }
+
+    fn write_files(&self, paths: &[PathBuf]) -> Result<(), String> {
+        crate::utils::copy_files_list_to_clipboard(paths.to_vec())
+    }
+
+    fn write_representations(&self, reps: &ClipboardRepresentations) -> Result<(), String> {
+        write_representations_to_system(reps)
+    }
+}
+
+/// 通过 clipboard-rs 一次性写入纯文本、HTML、RTF、图片与文件列表
+/// # Param
+/// reps: &ClipboardRepresentations - 要写入的全部表示
+pub fn write_representations_to_system(reps: &ClipboardRepresentations) -> Result<(), String> {
+    let ctx = ClipboardContext::new().map_err(|e| e.to_string())?;
+    let mut contents = Vec::new();
+    if let Some(text) = &reps.text {
+        contents.push(ClipboardContent::Text(text.clone()));
+    }
+    if let Some(html) = &reps.html {
+        contents.push(ClipboardContent::Html(html.clone()));
+    }
+    if let Some(rtf) = &reps.rtf {
+        contents.push(ClipboardContent::Rtf(rtf.clone()));
+    }
+    if let Some(path) = &reps.image_path {
+        // 图片文件丢失时仍写入其余表示
+        match RustImageData::from_path(&path.to_string_lossy()) {
+            Ok(image) => contents.push(ClipboardContent::Image(image)),
+            Err(e) => eprintln!("⚠️ 读取图片表示失败 {:?}: {}", path, e),
+        }
+    }
+    if !reps.files.is_empty() {
+        contents.push(ClipboardContent::Files(
+            reps.files
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+        ));
+    }
+    if contents.is_empty() {
+        return Err("没有可写入剪贴板的内容".to_string());
+    }
+    ctx.set(contents).map_err(|e| e.to_string())
+}
@@ -0,0 +1,164 @@
+//! 剪贴板"不记录"提示检测。
+//! 密码管理器在写入剪贴板时会附带额外的格式作为提示，要求剪贴板历史工具不要记录该内容：
+//! - Linux (KDE/KeePassXC 等)：`x-kde-passwordManagerHint`，值为 "secret"
+//! - Windows：`ExcludeClipboardContentFromMonitorProcessing`（存在即排除），
+//!   `CanIncludeInClipboardHistory`（DWORD 值为 0 时排除）
+//! - macOS：`org.nspasteboard.ConcealedType`、`org.nspasteboard.TransientType`
+use crate::clipboard::ClipboardItem;
+use chrono::Utc;
+use clipboard_rs::{Clipboard as ClipboardRsTrait, ClipboardContext};
+use uuid::Uuid;
+
+/// KDE 密码管理器提示格式
+pub const KDE_PASSWORD_HINT: &str = "x-kde-passwordManagerHint";
+/// Windows 排除监控处理的格式
+pub const WIN_EXCLUDE_MONITOR: &str = "ExcludeClipboardContentFromMonitorProcessing";
+/// Windows 是否允许进入剪贴板历史的格式
+pub const WIN_CAN_INCLUDE_HISTORY: &str = "CanIncludeInClipboardHistory";
+/// macOS 隐藏内容类型
+pub const MAC_CONCEALED_TYPE: &str = "org.nspasteboard.ConcealedType";
+/// macOS 临时内容类型
+pub const MAC_TRANSIENT_TYPE: &str = "org.nspasteboard.TransientType";
+
+/// 以隐私方式记录时，替代原内容保存的占位文本
+pub const REDACTED_PLACEHOLDER: &str = "••••••••";
+
+/// 剪贴板格式读取接口，便于在测试中使用假剪贴板替代系统剪贴板。
+pub trait ClipboardFormatReader {
+    /// 获取当前剪贴板中可用的格式名称列表
+    fn available_formats(&self) -> Result<Vec<String>, String>;
+    /// 读取指定格式的原始数据
+    fn read_format(&self, format: &str) -> Result<Vec<u8>, String>;
+}
+
+impl ClipboardFormatReader for ClipboardContext {
+    fn available_formats(&self) -> Result<Vec<String>, String> {
+        ClipboardRsTrait::available_formats(self).map_err(|e| e.to_string())
+    }
+
+    fn read_format(&self, format: &str) -> Result<Vec<u8>, String> {
+        self.get_buffer(format).map_err(|e| e.to_string())
+    }
+}
+
+/// 检测到提示时的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintPolicy {
+    /// 跳过，不记录
+    Skip,
+    /// 记录为隐私条目，内容以占位符替代
+    Private,
+    /// 忽略提示，照常记录
+    Ignore,
+}
+
+/// 对一次剪贴板变更的处理决定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HintDecision {
+    /// 照常记录
+    Capture,
+    /// 跳过，附带命中的提示格式
+    Skip(String),
+    /// 以隐私占位条目记录，附带命中的提示格式
+    Redact(String),
+}
+
+/// 辅助函数，解析配置字符串到 HintPolicy 枚举。无法识别时按 Skip 处理。
+/// # Param
+/// policy: &str - 配置值（"skip" | "private" | "ignore"）
+/// # Returns
+/// HintPolicy - 对应的处理策略
+pub fn parse_hint_policy(policy: &str) -> HintPolicy {
+    match policy.trim().to_lowercase().as_str() {
+        "private" | "redact" => HintPolicy::Private,
+        "ignore" | "off" => HintPolicy::Ignore,
+        _ => HintPolicy::Skip,
+    }
+}
+
+/// 检测剪贴板中是否存在密码管理器的"不记录"提示
+/// # Param
+/// reader: &dyn ClipboardFormatReader - 剪贴板格式读取器
+/// # Returns
+/// Option<String> - 命中的提示格式名称，未命中返回 None
+pub fn detect_password_hint(reader: &dyn ClipboardFormatReader) -> Option<String> {
+    let formats = reader.available_formats().ok()?;
+
+    for format in &formats {
+        match format.as_str() {
+            KDE_PASSWORD_HINT | WIN_EXCLUDE_MONITOR | MAC_CONCEALED_TYPE | MAC_TRANSIENT_TYPE => {
+                return Some(format.clone());
+            }
+            WIN_CAN_INCLUDE_HISTORY => {
+                // 数据为 DWORD，值为 0 表示不允许进入历史记录；读取失败时保守地视为排除
+                let excluded = match reader.read_format(format) {
+                    Ok(bytes) if bytes.len() >= 4 => {
+                        u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) == 0
+                    }
+                    _ => true,
+                };
+                if excluded {
+                    return Some(format.clone());
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 根据提示检测结果与策略，决定如何处理本次剪贴板变更
+/// # Param
+/// reader: &dyn ClipboardFormatReader - 剪贴板格式读取器
+/// policy: HintPolicy - 处理策略
+/// # Returns
+/// HintDecision - 处理决定
+pub fn decide(reader: &dyn ClipboardFormatReader, policy: HintPolicy) -> HintDecision {
+    if policy == HintPolicy::Ignore {
+        return HintDecision::Capture;
+    }
+    match detect_password_hint(reader) {
+        Some(format) => match policy {
+            HintPolicy::Private => HintDecision::Redact(format),
+            _ => HintDecision::Skip(format),
+        },
+        None => HintDecision::Capture,
+    }
+}
+
+/// 检查系统剪贴板，返回处理决定。无法打开剪贴板时照常记录。
+/// # Param
+/// policy: HintPolicy - 处理策略
+/// # Returns
+/// HintDecision - 处理决定
+pub fn check_system_clipboard(policy: HintPolicy) -> HintDecision {
+    if policy == HintPolicy::Ignore {
+        return HintDecision::Capture;
+    }
+    match ClipboardContext::new() {
+        Ok(ctx) => decide(&ctx, policy),
+        Err(e) => {
+            eprintln!("⚠️ 无法读取剪贴板格式，跳过提示检测: {}", e);
+            HintDecision::Capture
+        }
+    }
+}
+
+/// 构造一个以占位符替代原内容的文本条目，原文不会写入数据库
+/// # Returns
+/// ClipboardItem - 占位条目
+pub fn build_redacted_item() -> ClipboardItem {
+    ClipboardItem {
+        id: Uuid::new_v4().to_string(),
+        item_type: "text".to_string(),
+        content: REDACTED_PLACEHOLDER.to_string(),
+        size: Some(REDACTED_PLACEHOLDER.chars().count() as u64),
+        is_favorite: false,
+        notes: "".to_string(),
+        timestamp: Utc::now().timestamp_millis(),
+    }
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_clipboard_hints.rs"]
+mod test_clipboard_hints;
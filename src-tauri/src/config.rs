@@ -38,6 +38,12 @@ pub struct Config {
     /// 第五快捷键 (新增)
     #[serde(default = "default_shortcut_5")]
     pub global_shortcut_5: String,
+    /// 暂停/恢复记录快捷键
+    #[serde(default = "default_shortcut_6")]
+    pub global_shortcut_6: String,
+    /// 粘贴队列下一项快捷键
+    #[serde(default = "default_shortcut_7")]
+    pub global_shortcut_7: String,
 
     // --- 剪贴板参数 ---
     /// 最大历史记录数量
@@ -82,6 +88,13 @@ pub struct Config {
     pub ai_translation: bool,
     /// 是否启用 AI 联网搜索功能
     pub ai_web_search: bool,
+    /// AI 请求超时时间（秒），等待响应或两次流式输出之间超过该时间即中止
+    pub ai_timeout_secs: Option<u64>,
+    /// 是否启用语义搜索（为历史记录建立文本向量索引）
+    #[serde(default)]
+    pub ai_semantic_search: bool,
+    /// 语义搜索使用的向量模型；为空时使用本地向量，不调用 AI 服务
+    pub ai_embedding_model: Option<String>,
 
     // --- 安全与隐私 ---
     /// 是否启用敏感词过滤总开关
@@ -94,6 +107,12 @@ pub struct Config {
     pub filter_id_cards: bool,
     /// 是否过滤手机号
     pub filter_phone_numbers: bool,
+    /// 检测到密码管理器"不记录"提示时的处理策略（"skip"/"private"/"ignore"）
+    #[serde(default = "default_password_hint_policy")]
+    pub password_hint_policy: String,
+    /// 隐私数据访问审计日志的保留天数（0 表示不自动清理）
+    #[serde(default = "default_audit_retention_days")]
+    pub audit_retention_days: u32,
     // 隐私记录自动清理天数（天）
     // pub privacy_retention_days: u32,
     // 标记为隐私的记录 ID 列表（可用于快速查询/导出）
@@ -140,6 +159,21 @@ pub struct Config {
     pub ocr_confidence_threshold: Option<f32>,
     /// OCR 超时时间（秒）
     pub ocr_timeout_secs: Option<u64>,
+    /// 识别前是否预处理图片（灰度化、放大、自适应二值化、纠偏、裁剪边框）
+    #[serde(default)]
+    pub ocr_preprocess: bool,
+    /// 预处理时高度低于该值的图片会被放大（单位：像素，最多放大 4 倍）
+    #[serde(default = "default_ocr_upscale_min_height")]
+    pub ocr_upscale_min_height: u32,
+    /// 预处理时是否进行自适应二值化
+    #[serde(default = "default_true")]
+    pub ocr_adaptive_threshold: bool,
+    /// 预处理时是否纠正倾斜
+    #[serde(default = "default_true")]
+    pub ocr_deskew: bool,
+    /// 预处理时是否裁剪颜色一致的边框
+    #[serde(default = "default_true")]
+    pub ocr_crop_borders: bool,
 }
 /// 辅助枚举，表示配置项的名称。
 /// # Variants
@@ -167,6 +201,10 @@ pub enum ConfigKey {
     GlobalShortcut4,
     /// 第五快捷键
     GlobalShortcut5,
+    /// 暂停/恢复记录快捷键
+    GlobalShortcut6,
+    /// 粘贴队列下一项快捷键
+    GlobalShortcut7,
 
     // 剪贴板参数
     /// 最大历史记录数量
@@ -211,6 +249,12 @@ pub enum ConfigKey {
     AiTranslation,
     /// 是否启用 AI 联网搜索功能
     AiWebSearch,
+    /// AI 请求超时时间
+    AiTimeoutSecs,
+    /// 是否启用语义搜索
+    AiSemanticSearch,
+    /// 语义搜索使用的向量模型
+    AiEmbeddingModel,
 
     // 安全与隐私
     /// 是否启用敏感词过滤总开关
@@ -223,6 +267,10 @@ pub enum ConfigKey {
     FilterIdCards,
     /// 是否过滤手机号
     FilterPhoneNumbers,
+    /// 密码管理器提示处理策略
+    PasswordHintPolicy,
+    /// 审计日志保留天数
+    AuditRetentionDays,
     // 隐私记录自动清理天数
     // PrivacyRetentionDays,
     // 标记为隐私的记录 ID 列表
@@ -269,6 +317,31 @@ pub enum ConfigKey {
     OcrConfidenceThreshold,
     /// OCR 超时时间
     OcrTimeoutSecs,
+    /// 是否预处理 OCR 图片
+    OcrPreprocess,
+    /// 预处理放大阈值
+    OcrUpscaleMinHeight,
+    /// 预处理是否自适应二值化
+    OcrAdaptiveThreshold,
+    /// 预处理是否纠偏
+    OcrDeskew,
+    /// 预处理是否裁剪边框
+    OcrCropBorders,
+}
+
+impl ConfigKey {
+    /// 判断修改该配置项后是否需要重建 OCR 引擎
+    /// # Returns
+    /// bool - 是否影响 OCR 引擎
+    pub fn affects_ocr_engine(&self) -> bool {
+        matches!(
+            self,
+            ConfigKey::OcrProvider
+                | ConfigKey::OcrLanguages
+                | ConfigKey::OcrConfidenceThreshold
+                | ConfigKey::OcrTimeoutSecs
+        )
+    }
 }
 
 /// 辅助函数。解析字符串到 ConfigKey 枚举
@@ -289,6 +362,8 @@ pub fn parse_config_key(key: &str) -> Option<ConfigKey> {
         "global_shortcut_3" => Some(ConfigKey::GlobalShortcut3),
         "global_shortcut_4" => Some(ConfigKey::GlobalShortcut4),
         "global_shortcut_5" => Some(ConfigKey::GlobalShortcut5),
+        "global_shortcut_6" => Some(ConfigKey::GlobalShortcut6),
+        "global_shortcut_7" => Some(ConfigKey::GlobalShortcut7),
 
         // 剪贴板参数
         "max_history_items" => Some(ConfigKey::MaxHistoryItems),
@@ -313,12 +388,17 @@ pub fn parse_config_key(key: &str) -> Option<ConfigKey> {
         "ai_auto_summary" => Some(ConfigKey::AiAutoSummary),
         "ai_translation" => Some(ConfigKey::AiTranslation),
         "ai_web_search" => Some(ConfigKey::AiWebSearch),
+        "ai_timeout_secs" => Some(ConfigKey::AiTimeoutSecs),
+        "ai_semantic_search" => Some(ConfigKey::AiSemanticSearch),
+        "ai_embedding_model" => Some(ConfigKey::AiEmbeddingModel),
         // 安全与隐私
         "sensitive_filter" => Some(ConfigKey::SensitiveFilter),
         "filter_passwords" => Some(ConfigKey::FilterPasswords),
         "filter_bank_cards" => Some(ConfigKey::FilterBankCards),
         "filter_id_cards" => Some(ConfigKey::FilterIdCards),
         "filter_phone_numbers" => Some(ConfigKey::FilterPhoneNumbers),
+        "password_hint_policy" => Some(ConfigKey::PasswordHintPolicy),
+        "audit_retention_days" => Some(ConfigKey::AuditRetentionDays),
         // "privacy_retention_days" => Some(ConfigKey::PrivacyRetentionDays),
         // "privacy_records" => Some(ConfigKey::PrivacyRecords),
         // 数据备份
@@ -342,6 +422,11 @@ pub fn parse_config_key(key: &str) -> Option<ConfigKey> {
         "ocr_languages" => Some(ConfigKey::OcrLanguages),
         "ocr_confidence_threshold" => Some(ConfigKey::OcrConfidenceThreshold),
         "ocr_timeout_secs" => Some(ConfigKey::OcrTimeoutSecs),
+        "ocr_preprocess" => Some(ConfigKey::OcrPreprocess),
+        "ocr_upscale_min_height" => Some(ConfigKey::OcrUpscaleMinHeight),
+        "ocr_adaptive_threshold" => Some(ConfigKey::OcrAdaptiveThreshold),
+        "ocr_deskew" => Some(ConfigKey::OcrDeskew),
+        "ocr_crop_borders" => Some(ConfigKey::OcrCropBorders),
         _ => None,
     }
 }
@@ -371,6 +456,24 @@ fn default_shortcut_4() -> String {
 fn default_shortcut_5() -> String {
     "Shift+Ctrl+Delete".to_string()
 } // 新增
+fn default_shortcut_6() -> String {
+    "Shift+Ctrl+P".to_string()
+}
+fn default_shortcut_7() -> String {
+    "Shift+Alt+V".to_string()
+}
+fn default_password_hint_policy() -> String {
+    "skip".to_string()
+}
+fn default_audit_retention_days() -> u32 {
+    90
+}
+fn default_ocr_upscale_min_height() -> u32 {
+    300
+}
+fn default_true() -> bool {
+    true
+}
 
 /// 为 Config 实现 Default trait，提供默认配置值。
 impl Default for Config {
@@ -388,6 +491,8 @@ impl Default for Config {
             global_shortcut_3: default_shortcut_3(), // 新增
             global_shortcut_4: default_shortcut_4(), // 新增
             global_shortcut_5: default_shortcut_5(), // 新增
+            global_shortcut_6: default_shortcut_6(),
+            global_shortcut_7: default_shortcut_7(),
             // 剪贴板
             max_history_items: 500,      // 最大历史记录数：500条(0表示不限制)
             ignore_short_text_len: 0,    // 忽略短文本长度：不忽略(0表示不忽略)
@@ -411,6 +516,9 @@ impl Default for Config {
             ai_auto_summary: false, // AI 自动摘要：否
             ai_translation: false,  // AI 翻译功能：否
             ai_web_search: false,   // AI 联网搜索：否
+            ai_timeout_secs: None,  // AI 超时时间：无（使用默认值）
+            ai_semantic_search: false, // 语义搜索：否
+            ai_embedding_model: None,  // 向量模型：无（使用本地向量）
 
             // 隐私
             sensitive_filter: true,     // 敏感词过滤：开
@@ -418,6 +526,8 @@ impl Default for Config {
             filter_bank_cards: true,    // 过滤银行卡号：是
             filter_id_cards: true,      // 过滤身份证号：是
             filter_phone_numbers: true, // 过滤手机号：是
+            password_hint_policy: default_password_hint_policy(), // 密码管理器提示：跳过不记录
+            audit_retention_days: default_audit_retention_days(), // 审计日志保留天数：90天
             // privacy_retention_days: 90,  // 隐私记录保留天数：90天
             // privacy_records: Vec::new(), // 隐私记录列表：空
 
@@ -445,6 +555,11 @@ impl Default for Config {
             ocr_languages: None,            // OCR 语言列表：无（使用默认值）
             ocr_confidence_threshold: None, // OCR 置信度阈值：无（使用默认值）
             ocr_timeout_secs: None,         // OCR 超时时间：无（使用默认值）
+            ocr_preprocess: false,          // OCR 图片预处理：否
+            ocr_upscale_min_height: default_ocr_upscale_min_height(), // 放大阈值：300 像素
+            ocr_adaptive_threshold: true,   // 自适应二值化：是
+            ocr_deskew: true,               // 纠偏：是
+            ocr_crop_borders: true,         // 裁剪边框：是
         }
     }
 }
@@ -633,6 +748,8 @@ pub fn update_simple_config_item(key: &ConfigKey, value: serde_json::Value) -> R
         ConfigKey::GlobalShortcut3 => update_cfg!(global_shortcut_3, String),
         ConfigKey::GlobalShortcut4 => update_cfg!(global_shortcut_4, String),
         ConfigKey::GlobalShortcut5 => update_cfg!(global_shortcut_5, String),
+        ConfigKey::GlobalShortcut6 => update_cfg!(global_shortcut_6, String),
+        ConfigKey::GlobalShortcut7 => update_cfg!(global_shortcut_7, String),
         ConfigKey::MaxHistoryItems => update_cfg!(max_history_items, u32),
         ConfigKey::IgnoreShortTextLen => update_cfg!(ignore_short_text_len, u32),
         ConfigKey::IgnoreBigFileMb => update_cfg!(ignore_big_file_mb, u32),
@@ -653,11 +770,16 @@ pub fn update_simple_config_item(key: &ConfigKey, value: serde_json::Value) -> R
         ConfigKey::AiAutoSummary => update_cfg!(ai_auto_summary, bool),
         ConfigKey::AiTranslation => update_cfg!(ai_translation, bool),
         ConfigKey::AiWebSearch => update_cfg!(ai_web_search, bool),
+        ConfigKey::AiTimeoutSecs => update_cfg!(ai_timeout_secs, Option<u64>),
+        ConfigKey::AiSemanticSearch => update_cfg!(ai_semantic_search, bool),
+        ConfigKey::AiEmbeddingModel => update_cfg!(ai_embedding_model, Option<String>),
         ConfigKey::SensitiveFilter => update_cfg!(sensitive_filter, bool),
         ConfigKey::FilterPasswords => update_cfg!(filter_passwords, bool),
         ConfigKey::FilterBankCards => update_cfg!(filter_bank_cards, bool),
         ConfigKey::FilterIdCards => update_cfg!(filter_id_cards, bool),
         ConfigKey::FilterPhoneNumbers => update_cfg!(filter_phone_numbers, bool),
+        ConfigKey::PasswordHintPolicy => update_cfg!(password_hint_policy, String),
+        ConfigKey::AuditRetentionDays => update_cfg!(audit_retention_days, u32),
         // ConfigKey::PrivacyRetentionDays => update_cfg!(privacy_retention_days, u32),
         // ConfigKey::PrivacyRecords => update_cfg!(privacy_records, Vec<String>),
         ConfigKey::StoragePath => update_cfg!(storage_path, Option<String>),
@@ -677,6 +799,11 @@ pub fn update_simple_config_item(key: &ConfigKey, value: serde_json::Value) -> R
         ConfigKey::OcrLanguages => update_cfg!(ocr_languages, Option<Vec<String>>),
         ConfigKey::OcrConfidenceThreshold => update_cfg!(ocr_confidence_threshold, Option<f32>),
         ConfigKey::OcrTimeoutSecs => update_cfg!(ocr_timeout_secs, Option<u64>),
+        ConfigKey::OcrPreprocess => update_cfg!(ocr_preprocess, bool),
+        ConfigKey::OcrUpscaleMinHeight => update_cfg!(ocr_upscale_min_height, u32),
+        ConfigKey::OcrAdaptiveThreshold => update_cfg!(ocr_adaptive_threshold, bool),
+        ConfigKey::OcrDeskew => update_cfg!(ocr_deskew, bool),
+        ConfigKey::OcrCropBorders => update_cfg!(ocr_crop_borders, bool),
     }
 }
 
@@ -1009,8 +1136,25 @@ pub fn set_config_item(app: tauri::AppHandle, key: &str, value: serde_json::Valu
         }
     } else {
         // 其他配置项的原有逻辑保持不变
+        // OCR 引擎相关配置在修改前留存原配置，新配置无法构建引擎时用于恢复
+        let previous_cfg = if config_key.affects_ocr_engine() {
+            CONFIG.get().map(|lock| lock.read().unwrap().clone())
+        } else {
+            None
+        };
         match update_simple_config_item(&config_key, value.clone()) {
             Ok(true) => {
+                if let Some(previous_cfg) = previous_cfg {
+                    let cfg_clone = CONFIG.get().unwrap().read().unwrap().clone();
+                    match crate::ocr::init_from_config(&cfg_clone) {
+                        Ok(msg) => println!("🔄 OCR引擎已按新配置重建: {}", msg),
+                        Err(e) => {
+                            // 原引擎未被替换，配置同样恢复原值
+                            *CONFIG.get().unwrap().write().unwrap() = previous_cfg;
+                            return format!("Invalid OCR config: {}", e);
+                        }
+                    }
+                }
                 // --- 修复后的动态更新托盘图标可见性逻辑 ---
                 if config_key == ConfigKey::TrayIconVisible {
                     if let Ok(visible) = serde_json::from_value::<bool>(value.clone()) {
@@ -1185,6 +1329,8 @@ pub fn get_config_item(key: &str) -> Result<serde_json::Value, String> {
             ConfigKey::GlobalShortcut3 => serde_json::to_value(&cfg.global_shortcut_3),
             ConfigKey::GlobalShortcut4 => serde_json::to_value(&cfg.global_shortcut_4),
             ConfigKey::GlobalShortcut5 => serde_json::to_value(&cfg.global_shortcut_5),
+            ConfigKey::GlobalShortcut6 => serde_json::to_value(&cfg.global_shortcut_6),
+            ConfigKey::GlobalShortcut7 => serde_json::to_value(&cfg.global_shortcut_7),
 
             // 剪贴板参数
             ConfigKey::MaxHistoryItems => serde_json::to_value(&cfg.max_history_items),
@@ -1209,6 +1355,9 @@ pub fn get_config_item(key: &str) -> Result<serde_json::Value, String> {
             ConfigKey::AiAutoSummary => serde_json::to_value(&cfg.ai_auto_summary),
             ConfigKey::AiTranslation => serde_json::to_value(&cfg.ai_translation),
             ConfigKey::AiWebSearch => serde_json::to_value(&cfg.ai_web_search),
+            ConfigKey::AiTimeoutSecs => serde_json::to_value(&cfg.ai_timeout_secs),
+            ConfigKey::AiSemanticSearch => serde_json::to_value(&cfg.ai_semantic_search),
+            ConfigKey::AiEmbeddingModel => serde_json::to_value(&cfg.ai_embedding_model),
 
             // 安全与隐私
             ConfigKey::SensitiveFilter => serde_json::to_value(&cfg.sensitive_filter),
@@ -1216,6 +1365,8 @@ pub fn get_config_item(key: &str) -> Result<serde_json::Value, String> {
             ConfigKey::FilterBankCards => serde_json::to_value(&cfg.filter_bank_cards),
             ConfigKey::FilterIdCards => serde_json::to_value(&cfg.filter_id_cards),
             ConfigKey::FilterPhoneNumbers => serde_json::to_value(&cfg.filter_phone_numbers),
+            ConfigKey::PasswordHintPolicy => serde_json::to_value(&cfg.password_hint_policy),
+            ConfigKey::AuditRetentionDays => serde_json::to_value(&cfg.audit_retention_days),
             // ConfigKey::PrivacyRetentionDays => serde_json::to_value(&cfg.privacy_retention_days),
             // ConfigKey::PrivacyRecords => serde_json::to_value(&cfg.privacy_records),
 
@@ -1245,6 +1396,11 @@ pub fn get_config_item(key: &str) -> Result<serde_json::Value, String> {
                 serde_json::to_value(&cfg.ocr_confidence_threshold)
             }
             ConfigKey::OcrTimeoutSecs => serde_json::to_value(&cfg.ocr_timeout_secs),
+            ConfigKey::OcrPreprocess => serde_json::to_value(&cfg.ocr_preprocess),
+            ConfigKey::OcrUpscaleMinHeight => serde_json::to_value(&cfg.ocr_upscale_min_height),
+            ConfigKey::OcrAdaptiveThreshold => serde_json::to_value(&cfg.ocr_adaptive_threshold),
+            ConfigKey::OcrDeskew => serde_json::to_value(&cfg.ocr_deskew),
+            ConfigKey::OcrCropBorders => serde_json::to_value(&cfg.ocr_crop_borders),
         };
 
         value.map_err(|e| format!("Failed to serialize config value: {}", e))
@@ -0,0 +1,245 @@
+use super::{get_db_path, init_db};
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+
+/// 等待执行（包括等待重试）
+pub const AI_JOB_PENDING: &str = "pending";
+/// 正在请求 AI 服务
+pub const AI_JOB_RUNNING: &str = "running";
+/// 已生成标签与摘要
+pub const AI_JOB_DONE: &str = "done";
+/// 重试次数用尽或遇到不可重试的错误
+pub const AI_JOB_FAILED: &str = "failed";
+
+/// 首次重试前的等待时间（毫秒），之后每次翻倍
+const AI_RETRY_BASE_MS: i64 = 30_000;
+
+/// 一条 AI 标签与摘要任务
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AiJob {
+    /// 数据项 ID
+    pub item_id: String,
+    /// 任务状态：pending/running/done/failed
+    pub status: String,
+    /// 已失败的次数
+    pub attempts: u32,
+    /// 最近一次失败的错误信息
+    pub error: Option<String>,
+    /// 最早可执行的时间（毫秒时间戳），用于延迟执行与重试退避
+    pub next_attempt_at: i64,
+    /// 最近一次状态变化的时间（毫秒时间戳）
+    pub updated_at: i64,
+}
+
+/// 各状态的任务数量
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AiJobStats {
+    pub pending: usize,
+    pub running: usize,
+    pub done: usize,
+    pub failed: usize,
+}
+
+/// 第 attempts 次失败后的重试等待时间（毫秒）：指数退避并设上限
+pub fn ai_retry_delay_ms(attempts: u32) -> i64 {
+    AI_RETRY_BASE_MS << attempts.saturating_sub(1).min(8)
+}
+
+fn row_to_job(row: &rusqlite::Row) -> rusqlite::Result<AiJob> {
+    Ok(AiJob {
+        item_id: row.get(0)?,
+        status: row.get(1)?,
+        attempts: row.get(2)?,
+        error: row.get(3)?,
+        next_attempt_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+/// 为数据项添加 AI 任务。已有任务时重置为等待状态（正在执行的任务不受影响），隐私数据不会入队。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// not_before: i64 - 最早可执行的时间（毫秒时间戳）
+/// now: i64 - 当前时间（毫秒时间戳）
+/// # Returns
+/// bool - 是否已入队
+pub fn enqueue_ai_job(item_id: &str, not_before: i64, now: i64) -> Result<bool, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let rows = conn
+        .execute(
+            "INSERT INTO ai_jobs (item_id, status, attempts, error, next_attempt_at, updated_at)
+             SELECT ?1, ?2, 0, NULL, ?3, ?4
+             WHERE EXISTS (SELECT 1 FROM data WHERE id = ?1)
+               AND NOT EXISTS (SELECT 1 FROM private_data WHERE item_id = ?1)
+             ON CONFLICT(item_id) DO UPDATE SET
+                 status = excluded.status, attempts = 0, error = NULL,
+                 next_attempt_at = excluded.next_attempt_at, updated_at = excluded.updated_at
+             WHERE ai_jobs.status != ?5",
+            params![item_id, AI_JOB_PENDING, not_before, now, AI_JOB_RUNNING],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(rows > 0)
+}
+
+/// 取出一条已到执行时间的等待任务并标记为正在执行。
+/// # Param
+/// now: i64 - 当前时间（毫秒时间戳）
+/// # Returns
+/// Option<String> - 任务对应的数据项 ID，没有可执行的任务时返回 None
+pub fn claim_next_ai_job(now: i64) -> Result<Option<String>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let mut conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    // 立即获取写锁，避免多个调用方取到同一条任务
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| e.to_string())?;
+    let item_id: Option<String> = tx
+        .query_row(
+            "SELECT item_id FROM ai_jobs WHERE status = ?1 AND next_attempt_at <= ?2
+             ORDER BY next_attempt_at, rowid LIMIT 1",
+            params![AI_JOB_PENDING, now],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if let Some(id) = &item_id {
+        tx.execute(
+            "UPDATE ai_jobs SET status = ?1, updated_at = ?2 WHERE item_id = ?3",
+            params![AI_JOB_RUNNING, now, id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(item_id)
+}
+
+/// 将正在执行的任务标记为完成。
+/// # Returns
+/// bool - 任务是否仍然存在（数据项删除后任务会被取消）
+pub fn complete_ai_job(item_id: &str, now: i64) -> Result<bool, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let rows = conn
+        .execute(
+            "UPDATE ai_jobs SET status = ?1, error = NULL, updated_at = ?2 WHERE item_id = ?3",
+            params![AI_JOB_DONE, now, item_id],
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(rows > 0)
+}
+
+/// 记录一次失败：可重试且未达到最大次数时按退避时间重新等待，否则标记为失败。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// error: &str - 错误信息
+/// now: i64 - 当前时间（毫秒时间戳）
+/// retryable: bool - 错误是否可以重试
+/// max_attempts: u32 - 最大尝试次数
+/// # Returns
+/// Option<AiJob> - 更新后的任务，任务已被取消时返回 None
+pub fn fail_ai_job(
+    item_id: &str,
+    error: &str,
+    now: i64,
+    retryable: bool,
+    max_attempts: u32,
+) -> Result<Option<AiJob>, String> {
+    let Some(job) = get_ai_job(item_id)? else {
+        return Ok(None);
+    };
+    let attempts = job.attempts + 1;
+    let (status, next_attempt_at) = if retryable && attempts < max_attempts {
+        (AI_JOB_PENDING, now + ai_retry_delay_ms(attempts))
+    } else {
+        (AI_JOB_FAILED, now)
+    };
+
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "UPDATE ai_jobs SET status = ?1, attempts = ?2, error = ?3, next_attempt_at = ?4, updated_at = ?5
+         WHERE item_id = ?6",
+        params![status, attempts, error, next_attempt_at, now, item_id],
+    )
+    .map_err(|e| e.to_string())?;
+    get_ai_job(item_id)
+}
+
+/// 取消数据项的 AI 任务
+pub fn cancel_ai_job(item_id: &str) -> Result<(), String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.execute("DELETE FROM ai_jobs WHERE item_id = ?1", params![item_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 将上次运行中断时仍在执行的任务恢复为等待状态，应在启动 AI 任务线程前调用。
+/// # Returns
+/// usize - 恢复的任务数量
+pub fn reset_running_ai_jobs(now: i64) -> Result<usize, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "UPDATE ai_jobs SET status = ?1, next_attempt_at = ?2, updated_at = ?2 WHERE status = ?3",
+        params![AI_JOB_PENDING, now, AI_JOB_RUNNING],
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// 获取数据项的 AI 任务
+pub fn get_ai_job(item_id: &str) -> Result<Option<AiJob>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.query_row(
+        "SELECT item_id, status, attempts, error, next_attempt_at, updated_at FROM ai_jobs WHERE item_id = ?1",
+        params![item_id],
+        row_to_job,
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// 统计各状态的任务数量。作为 Tauri command 暴露给前端调用。
+#[tauri::command]
+pub fn get_ai_job_stats() -> Result<AiJobStats, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare("SELECT status, COUNT(*) FROM ai_jobs GROUP BY status")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))
+        .map_err(|e| e.to_string())?;
+
+    let mut stats = AiJobStats::default();
+    for row in rows {
+        let (status, count) = row.map_err(|e| e.to_string())?;
+        let count = count as usize;
+        match status.as_str() {
+            AI_JOB_PENDING => stats.pending = count,
+            AI_JOB_RUNNING => stats.running = count,
+            AI_JOB_DONE => stats.done = count,
+            AI_JOB_FAILED => stats.failed = count,
+            _ => {}
+        }
+    }
+    Ok(stats)
+}
@@ -0,0 +1,285 @@
+use super::{get_db_path, init_db};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+/// 隐私数据审计事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditEvent {
+    /// 隐私数据内容被读取（详情、OCR 文本等）
+    Viewed,
+    /// 隐私数据被写回系统剪贴板
+    Copied,
+    /// 隐私数据随备份一同导出
+    Exported,
+    /// 隐私标记被取消
+    Unmarked,
+}
+
+impl AuditEvent {
+    /// 事件在数据库中的字符串表示
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEvent::Viewed => "viewed",
+            AuditEvent::Copied => "copied",
+            AuditEvent::Exported => "exported",
+            AuditEvent::Unmarked => "unmarked",
+        }
+    }
+
+    /// 从字符串解析事件类型，未知值返回 None
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "viewed" => Some(AuditEvent::Viewed),
+            "copied" => Some(AuditEvent::Copied),
+            "exported" => Some(AuditEvent::Exported),
+            "unmarked" => Some(AuditEvent::Unmarked),
+            _ => None,
+        }
+    }
+}
+
+/// 审计日志记录。只保存数据项 ID，不保存任何内容。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub event_type: String,
+    pub item_id: String,
+    /// 触发事件的命令名称
+    pub origin: String,
+    /// 事件时间（毫秒时间戳）
+    pub timestamp: i64,
+}
+
+/// 在已有连接上追加一条审计记录（供同一事务内的隐私操作使用）
+/// # Param
+/// conn: &Connection - 数据库连接
+/// event: AuditEvent - 事件类型
+/// item_id: &str - 数据项 ID
+/// origin: &str - 触发事件的命令名称
+pub(crate) fn append_audit_event(
+    conn: &Connection,
+    event: AuditEvent,
+    item_id: &str,
+    origin: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO audit_log (event_type, item_id, origin, timestamp) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            event.as_str(),
+            item_id,
+            origin,
+            chrono::Utc::now().timestamp_millis()
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 若数据项为隐私数据，则记录一条审计事件。
+/// 审计失败只打印日志，不影响调用方的正常流程。
+/// # Param
+/// event: AuditEvent - 事件类型
+/// item_id: &str - 数据项 ID
+/// origin: &str - 触发事件的命令名称
+/// # Returns
+/// bool - 是否写入了审计记录
+pub fn audit_private_access(event: AuditEvent, item_id: &str, origin: &str) -> bool {
+    let result = (|| -> Result<bool, String> {
+        let db_path = get_db_path();
+        init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+        let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+        let is_private: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM private_data WHERE item_id = ?1",
+                params![item_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        if is_private == 0 {
+            return Ok(false);
+        }
+        append_audit_event(&conn, event, item_id, origin)?;
+        Ok(true)
+    })();
+
+    result.unwrap_or_else(|e| {
+        eprintln!("⚠️ 写入审计日志失败 ({} {}): {}", event.as_str(), item_id, e);
+        false
+    })
+}
+
+/// 列表、搜索等一次返回多条数据时，为其中的隐私数据各记录一条审计事件。
+/// 审计失败只打印日志，不影响调用方的正常流程。
+/// # Param
+/// event: AuditEvent - 事件类型
+/// item_ids: &[String] - 返回给前端的数据项 ID
+/// origin: &str - 触发事件的命令名称
+/// # Returns
+/// usize - 写入的审计记录数量
+pub fn audit_private_items(event: AuditEvent, item_ids: &[String], origin: &str) -> usize {
+    if item_ids.is_empty() {
+        return 0;
+    }
+
+    let result = (|| -> Result<usize, String> {
+        let db_path = get_db_path();
+        init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+        let mut conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        let mut written = 0;
+        for id in item_ids {
+            let is_private: i64 = tx
+                .query_row(
+                    "SELECT COUNT(*) FROM private_data WHERE item_id = ?1",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            if is_private > 0 {
+                append_audit_event(&tx, event, id, origin)?;
+                written += 1;
+            }
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(written)
+    })();
+
+    result.unwrap_or_else(|e| {
+        eprintln!("⚠️ 写入审计日志失败 ({} {}): {}", event.as_str(), origin, e);
+        0
+    })
+}
+
+/// 导出备份时，为当前所有隐私数据记录一条 exported 事件。
+/// # Param
+/// origin: &str - 触发事件的命令名称
+/// # Returns
+/// Result<usize, String> - 写入的审计记录数量
+pub fn audit_private_export(origin: &str) -> Result<usize, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let mut conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let ids: Vec<String> = {
+        let mut stmt = conn
+            .prepare("SELECT item_id FROM private_data")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?
+    };
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for id in &ids {
+        append_audit_event(&tx, AuditEvent::Exported, id, origin)?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(ids.len())
+}
+
+/// 查询审计日志，按时间倒序返回。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// event_type: Option<String> - 事件类型过滤（viewed/copied/exported/unmarked），为空表示全部
+/// item_id: Option<String> - 数据项 ID 过滤，为空表示全部
+/// limit: Option<u32> - 最多返回的条数，为空表示不限制
+/// # Returns
+/// String - 审计记录列表的 JSON 字符串
+#[tauri::command]
+pub fn get_audit_log(
+    event_type: Option<String>,
+    item_id: Option<String>,
+    limit: Option<u32>,
+) -> Result<String, String> {
+    let entries = query_audit_log(event_type.as_deref(), item_id.as_deref(), limit)?;
+    serde_json::to_string(&entries).map_err(|e| e.to_string())
+}
+
+/// 查询审计日志的内部实现
+pub fn query_audit_log(
+    event_type: Option<&str>,
+    item_id: Option<&str>,
+    limit: Option<u32>,
+) -> Result<Vec<AuditEntry>, String> {
+    let event = match event_type.filter(|s| !s.trim().is_empty()) {
+        Some(s) => Some(AuditEvent::parse(s).ok_or_else(|| format!("未知的审计事件类型: {}", s))?),
+        None => None,
+    };
+
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, event_type, item_id, origin, timestamp FROM audit_log
+             WHERE (?1 IS NULL OR event_type = ?1) AND (?2 IS NULL OR item_id = ?2)
+             ORDER BY timestamp DESC, id DESC
+             LIMIT ?3",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(
+            params![
+                event.map(|e| e.as_str()),
+                item_id,
+                limit.map(|l| l as i64).unwrap_or(-1)
+            ],
+            |row| {
+                Ok(AuditEntry {
+                    id: row.get(0)?,
+                    event_type: row.get(1)?,
+                    item_id: row.get(2)?,
+                    origin: row.get(3)?,
+                    timestamp: row.get(4)?,
+                })
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 将全部审计日志导出为 JSON 文件，保存在当前存储根目录下。作为 Tauri command 暴露给前端调用。
+/// # Returns
+/// String - 导出文件的完整路径
+#[tauri::command]
+pub fn export_audit_log() -> Result<String, String> {
+    let entries = query_audit_log(None, None, None)?;
+    let json = serde_json::to_string_pretty(&entries).map_err(|e| e.to_string())?;
+
+    let root_path = crate::config::get_current_storage_path();
+    let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+    let export_path = root_path.join(format!("audit_log_{}.json", timestamp));
+    std::fs::write(&export_path, json).map_err(|e| format!("无法写入审计日志文件: {}", e))?;
+
+    println!("✅ 审计日志已导出至: {}", export_path.display());
+    Ok(export_path.to_string_lossy().to_string())
+}
+
+/// 删除超过保留天数的审计记录（由后台清理线程调用）。
+/// # Param
+/// days: u32 - 保留天数，0 表示不清理
+/// # Returns
+/// Result<usize, String> - 被删除的记录数量
+pub fn prune_audit_log(days: u32) -> Result<usize, String> {
+    if days == 0 {
+        return Ok(0);
+    }
+
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let cutoff = chrono::Utc::now().timestamp_millis() - days as i64 * 86_400_000;
+    conn.execute(
+        "DELETE FROM audit_log WHERE timestamp < ?1",
+        params![cutoff],
+    )
+    .map_err(|e| e.to_string())
+}
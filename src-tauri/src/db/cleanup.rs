@@ -1,3 +1,4 @@
+use super::core::delete_data_by_ids;
 use super::{get_db_path, init_db};
 use rusqlite::{params, Connection};
 use std::sync::mpsc::Sender;
@@ -17,14 +18,16 @@ pub fn clear_data_expired(days: u32) -> Result<usize, String> {
 
     let cutoff_timestamp = chrono::Utc::now().timestamp() - (days as i64 * 86400);
 
-    let rows_deleted = conn
-        .execute(
-            "DELETE FROM data WHERE timestamp < ?1 AND is_favorite = 0",
-            params![cutoff_timestamp],
-        )
+    let mut stmt = conn
+        .prepare("SELECT id FROM data WHERE timestamp < ?1 AND is_favorite = 0")
+        .map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map(params![cutoff_timestamp], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
 
-    Ok(rows_deleted)
+    delete_data_by_ids(&ids)
 }
 
 /// 按设定的最大历史记录数量删除多余的数据，自动屏蔽未收藏的数据。
@@ -59,20 +62,21 @@ pub fn enforce_max_history_items(max_items: u32) -> Result<usize, String> {
     let to_delete_count = total_count - max_items;
 
     // 删除最旧的记录
-    let rows_deleted = conn
-        .execute(
-            "DELETE FROM data 
-             WHERE id IN (
-                 SELECT id FROM data 
-                 WHERE is_favorite = 0 
-                 ORDER BY timestamp ASC 
-                 LIMIT ?1
-             )",
-            params![to_delete_count],
+    let mut stmt = conn
+        .prepare(
+            "SELECT id FROM data 
+             WHERE is_favorite = 0 
+             ORDER BY timestamp ASC 
+             LIMIT ?1",
         )
         .map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map(params![to_delete_count], |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
 
-    Ok(rows_deleted)
+    delete_data_by_ids(&ids)
 }
 
 /// 设置清理通知 Sender（由 app_setup 调用）
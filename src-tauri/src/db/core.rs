@@ -1,9 +1,13 @@
-use rusqlite::{params, Connection, Result as SqlResult};
+use rusqlite::{params, Connection, OptionalExtension, Result as SqlResult};
 use uuid::Uuid;
 use std::path::{Path, PathBuf};
 use std::fs;
 use crate::clipboard::{ClipboardItem, clipboard_items_to_json, clipboard_item_to_json};
 use super::{get_db_path, init_db, notify_cleanup, check_and_mark_private_item};
+use super::audit::{audit_private_access, audit_private_items, AuditEvent};
+use super::secure_delete::{delete_item_rows, is_private_item, secure_delete_data_by_id, shred_path};
+use super::subtypes::{auto_classify_enabled, classify_and_store};
+use crate::snippet::SNIPPET_TYPE;
 
 /// 将接收到的数据插入数据库。
 /// Param:
@@ -17,6 +21,15 @@ pub fn insert_received_db_data(data: ClipboardItem) -> Result<String, String> {
     init_db(db_path.as_path()).map_err(|e| e.to_string())?;
     let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
 
+    insert_data_row(&conn, &data)?;
+    after_data_inserted(data)
+}
+
+/// 辅助函数：在给定连接（或事务）上写入 data 记录，并按配置自动分类
+/// # Param
+/// conn: &Connection - 数据库连接
+/// data: &ClipboardItem - 要插入的数据项
+pub(crate) fn insert_data_row(conn: &Connection, data: &ClipboardItem) -> Result<(), String> {
     conn.execute("INSERT OR REPLACE INTO data (id, item_type, content, size, is_favorite, notes, timestamp) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
         params![
             data.id,
@@ -30,6 +43,21 @@ pub fn insert_received_db_data(data: ClipboardItem) -> Result<String, String> {
     ) 
         .map_err(|e| e.to_string())?;
 
+    // 按配置对文本内容自动分类，分类失败不影响插入
+    if auto_classify_enabled() {
+        if let Err(e) = classify_and_store(conn, data) {
+            eprintln!("⚠️ 自动分类失败 (ID: {}): {}", data.id, e);
+        }
+    }
+    Ok(())
+}
+
+/// 辅助函数：数据写入（或所在事务提交）成功后，更新最后插入项并通知清理线程
+/// # Param
+/// data: ClipboardItem - 已插入的数据项
+/// # Returns
+/// String - 插入的数据的 JSON 字符串
+pub(crate) fn after_data_inserted(data: ClipboardItem) -> Result<String, String> {
     // 插入成功后，更新全局最后插入项
     crate::clipboard::set_last_inserted(data.clone());
 
@@ -113,6 +141,10 @@ pub fn get_all_data() -> Result<String, String> {
         results.push(item.map_err(|e| e.to_string())?);
     }
 
+    // 列表中包含隐私数据的完整内容，为其记录查看事件
+    let ids: Vec<String> = results.iter().map(|item| item.id.clone()).collect();
+    audit_private_items(AuditEvent::Viewed, &ids, "get_all_data");
+
     clipboard_items_to_json(results)
 }
 
@@ -124,6 +156,20 @@ pub fn get_all_data() -> Result<String, String> {
 /// String - 包含数据记录的 JSON 字符串，若未找到则返回 null
 #[tauri::command]
 pub fn get_data_by_id(id: &str) -> Result<String, String> {
+    if let Some(clipboard_item) = get_item_by_id(id)? {
+        audit_private_access(AuditEvent::Viewed, id, "get_data_by_id");
+        clipboard_item_to_json(clipboard_item)
+    } else {
+        Ok("null".to_string())
+    }
+}
+
+/// 根据数据 ID 获取数据记录（不写入审计日志，由调用方按用途记录）。
+/// # Param
+/// id: &str - 数据 ID
+/// # Returns
+/// Option<ClipboardItem> - 数据记录，若未找到则返回 None
+pub fn get_item_by_id(id: &str) -> Result<Option<ClipboardItem>, String> {
     let db_path = get_db_path();
     init_db(db_path.as_path()).map_err(|e| e.to_string())?;
     let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
@@ -150,11 +196,9 @@ pub fn get_data_by_id(id: &str) -> Result<String, String> {
         })
         .map_err(|e| e.to_string())?;
 
-    if let Some(item) = rows.next() {
-        let clipboard_item = item.map_err(|e| e.to_string())?;
-        clipboard_item_to_json(clipboard_item)
-    } else {
-        Ok("null".to_string())
+    match rows.next() {
+        Some(item) => Ok(Some(item.map_err(|e| e.to_string())?)),
+        None => Ok(None),
     }
 }
 
@@ -170,7 +214,7 @@ pub fn delete_all_data(item_type: Option<&str>, keep_favorites: bool) -> Result<
     init_db(db_path.as_path()).map_err(|e| e.to_string())?;
     let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
 
-    let mut sql = String::from("DELETE FROM data WHERE id IN (SELECT data.id FROM data");
+    let mut sql = String::from("SELECT data.id FROM data");
     let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
     let param_idx = 1;
 
@@ -180,7 +224,7 @@ pub fn delete_all_data(item_type: Option<&str>, keep_favorites: bool) -> Result<
 
     if let Some(t) = item_type {
         match t {
-            "text" | "image" | "file" | "folder" => {
+            "text" | "image" | "file" | "folder" | SNIPPET_TYPE => {
                 type_filter_opt = Some(t);
             }
             "private" => {
@@ -209,21 +253,106 @@ pub fn delete_all_data(item_type: Option<&str>, keep_favorites: bool) -> Result<
         sql.push_str(" AND data.is_favorite = 0");
     }
 
-    sql.push_str(")");
-
-    let rows_affected = conn
-        .execute(
-            &sql,
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let ids = stmt
+        .query_map(
             rusqlite::params_from_iter(params.iter().map(|p| &**p)),
+            |row| row.get::<_, String>(0),
         )
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
 
-    // 重新计算所有收藏夹的 item 数量
-    conn.execute(
+    delete_data_by_ids(&ids)
+}
+
+/// 批量删除数据：在同一个连接与事务中删除全部记录，本地文件与各关联表中的记录也随之清除。
+/// 隐私数据的文件覆写后再删除，批次中包含隐私数据时开启 secure_delete，使原内容所在页被清零。
+/// 任一文件覆写失败或数据库出错时不删除任何记录。
+/// # Param
+/// ids: &[String] - 要删除数据的 ID 列表
+/// # Returns
+/// usize - 从 data 表删除的行数
+pub fn delete_data_by_ids(ids: &[String]) -> Result<usize, String> {
+    if ids.is_empty() {
+        return Ok(0);
+    }
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let mut conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    // 1. 删除本地文件：隐私数据覆写后删除，其余直接删除
+    let mut has_private = false;
+    for id in ids {
+        let private: bool = conn
+            .query_row(
+                "SELECT EXISTS (SELECT 1 FROM private_data WHERE item_id = ?1)",
+                params![id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        has_private |= private;
+
+        let mut paths = Vec::new();
+        let item: Option<(String, String)> = conn
+            .query_row(
+                "SELECT item_type, content FROM data WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+        // 文本与片段的内容不是路径，不做物理删除
+        if let Some((item_type, content)) = item {
+            if item_type != "text" && item_type != SNIPPET_TYPE {
+                let file_path = resolve_item_file_path(&content);
+                // 兼容旧数据：直接尝试原始路径
+                paths.push(if file_path.exists() { file_path } else { PathBuf::from(content) });
+            }
+        }
+        // 同一次复制中保存的图片表示
+        let image: Option<String> = conn
+            .query_row(
+                "SELECT content FROM item_formats WHERE item_id = ?1 AND format = ?2",
+                params![id, super::FORMAT_IMAGE],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| e.to_string())?;
+        paths.extend(image.map(|image| resolve_item_file_path(&image)));
+
+        for path in paths.iter().filter(|path| path.exists()) {
+            if private {
+                shred_path(path).map_err(|e| format!("安全删除文件失败 {:?}: {}", path, e))?;
+            } else {
+                let removed = if path.is_dir() {
+                    fs::remove_dir_all(path)
+                } else {
+                    fs::remove_file(path)
+                };
+                if let Err(e) = removed {
+                    eprintln!("⚠️ 删除本地文件失败 (ID: {}): {:?} - {}", id, path, e);
+                }
+            }
+        }
+    }
+
+    // 2. 在同一事务中删除全部记录，最后统一重新计算收藏夹数量
+    if has_private {
+        conn.pragma_update(None, "secure_delete", true)
+            .map_err(|e| e.to_string())?;
+    }
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut rows_affected = 0;
+    for id in ids {
+        rows_affected += delete_item_rows(&tx, id).map_err(|e| e.to_string())?.1;
+    }
+    tx.execute(
         "UPDATE folders SET num_items = (SELECT COUNT(*) FROM folder_items WHERE folder_items.folder_id = folders.id)",
         [],
     )
     .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
 
     Ok(rows_affected)
 }
@@ -236,11 +365,51 @@ pub fn delete_data(data: ClipboardItem) -> Result<usize, String> {
     delete_data_by_id(&data.id)
 }
 
+/// 辅助函数：将数据库中记录的文件路径解析为本地绝对路径。
+/// 支持 ".\files\"、"./files/"、"files/"、"files\" 开头的相对路径，其余视为绝对路径。
+/// # Param
+/// content: &str - data.content 中记录的路径
+/// # Returns
+/// PathBuf - 本地文件路径
+pub(crate) fn resolve_item_file_path(content: &str) -> PathBuf {
+    // 获取当前配置的存储路径
+    let storage_path = crate::config::get_current_storage_path();
+
+    // 处理相对路径：如果是以 ".\files\" 或 "./files/" 开头的相对路径
+    if content.starts_with(r".\files\") || content.starts_with("./files/") || content.starts_with("files/") {
+        // 从相对路径中提取文件名部分
+        let file_name = if content.contains(r"\files\") {
+            content.split(r"\files\").last().unwrap_or(content).to_string()
+        } else if content.contains("./files/") {
+            content.split("./files/").last().unwrap_or(content).to_string()
+        } else if content.contains("files/") {
+            content.split("files/").last().unwrap_or(content).to_string()
+        } else {
+            content.to_string()
+        };
+
+        // 构建完整路径：storage_path + "files" + 文件名
+        storage_path.join("files").join(file_name)
+    } else if content.starts_with(r"files\") {
+        // 处理 files\xxx 格式
+        let file_name = content.split(r"files\").last().unwrap_or(content);
+        storage_path.join("files").join(file_name)
+    } else {
+        // 如果不是相对路径，直接使用
+        PathBuf::from(content)
+    }
+}
+
 /// 根据 ID 删除数据。作为 Tauri command 暴露给前端调用。
+/// 隐私数据会转入安全删除流程（覆写文件、清除扩展数据与空闲页残留）。
 /// # Param
 /// id: &str - 要删除数据的 ID
 #[tauri::command]
 pub fn delete_data_by_id(id: &str) -> Result<usize, String> {
+    if is_private_item(id)? {
+        return secure_delete_data_by_id(id).map(|report| report.rows_deleted);
+    }
+
     let db_path = get_db_path();
     init_db(db_path.as_path()).map_err(|e| e.to_string())?;
     let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
@@ -253,34 +422,15 @@ pub fn delete_data_by_id(id: &str) -> Result<usize, String> {
         params![id],
         |row| Ok((row.get(0)?, row.get(1)?)), // 获取 item_type 和 content
     );
+    // 文本与片段的内容不是路径，不做物理删除
+    let query_result = query_result
+        .ok()
+        .filter(|(item_type, _)| item_type != "text" && item_type != SNIPPET_TYPE);
 
-    if let Ok((item_type, content)) = query_result {
+    if let Some((item_type, content)) = query_result {
         // 获取当前配置的存储路径
         let storage_path = crate::config::get_current_storage_path();
-        
-        // 处理相对路径：如果是以 ".\files\" 或 "./files/" 开头的相对路径
-        let file_path = if content.starts_with(r".\files\") || content.starts_with("./files/") || content.starts_with("files/") {
-            // 从相对路径中提取文件名部分
-            let file_name = if content.contains(r"\files\") {
-                content.split(r"\files\").last().unwrap_or(&content).to_string()
-            } else if content.contains("./files/") {
-                content.split("./files/").last().unwrap_or(&content).to_string()
-            } else if content.contains("files/") {
-                content.split("files/").last().unwrap_or(&content).to_string()
-            } else {
-                content.to_string()
-            };
-            
-            // 构建完整路径：storage_path + "files" + 文件名
-            storage_path.join("files").join(file_name)
-        } else if content.starts_with(r"files\") {
-            // 处理 files\xxx 格式
-            let file_name = content.split(r"files\").last().unwrap_or(&content);
-            storage_path.join("files").join(file_name)
-        } else {
-            // 如果不是相对路径，直接使用
-            PathBuf::from(&content)
-        };
+        let file_path = resolve_item_file_path(&content);
 
         println!("🗑️ 尝试删除文件: {:?}", file_path);
         println!("🗑️ 存储根目录: {:?}", storage_path);
@@ -322,12 +472,19 @@ pub fn delete_data_by_id(id: &str) -> Result<usize, String> {
         }
     }
 
+    // 同一次复制中保存的图片表示
+    if let Some(image) = super::get_item_format(id, super::FORMAT_IMAGE)? {
+        let image_path = resolve_item_file_path(&image);
+        if let Err(e) = fs::remove_file(&image_path) {
+            eprintln!("⚠️ 删除图片表示失败 (ID: {}): {:?} - {}", id, image_path, e);
+        }
+    }
+
     // ---------------------------------------------------------
     // 2. 执行数据库删除
     // ---------------------------------------------------------
-    let rows_affected = conn
-        .execute("DELETE FROM data WHERE id = ?1", params![id])
-        .map_err(|e| e.to_string())?;
+    // 关联表与安全删除一致；extended_data 中可能有解码出的 Wi-Fi 密码等内容，一并删除
+    let (_, rows_affected) = delete_item_rows(&conn, id).map_err(|e| e.to_string())?;
 
     // 重新计算所有收藏夹的 item 数量
     conn.execute(
@@ -357,6 +514,38 @@ pub fn update_data_content_by_id(id: &str, new_content: &str) -> Result<String,
     )
     .map_err(|e| e.to_string())?;
 
+    // 内容变化后重新建立语义索引
+    conn.execute("DELETE FROM item_embeddings WHERE item_id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+
+    // 同一次复制中保存的文本类表示与新内容不再一致，粘贴时只使用编辑后的内容
+    conn.execute(
+        "DELETE FROM item_formats WHERE item_id = ?1 AND format IN (?2, ?3, ?4)",
+        params![id, super::FORMAT_HTML, super::FORMAT_RTF, super::FORMAT_TEXT],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // 内容变化后重新分类
+    if auto_classify_enabled() {
+        let item_type: Option<String> = conn
+            .query_row("SELECT item_type FROM data WHERE id = ?1", params![id], |row| row.get(0))
+            .ok();
+        if let Some(item_type) = item_type {
+            let item = ClipboardItem {
+                id: id.to_string(),
+                item_type,
+                content: new_content.to_string(),
+                size: None,
+                is_favorite: false,
+                notes: String::new(),
+                timestamp: 0,
+            };
+            if let Err(e) = classify_and_store(&conn, &item) {
+                eprintln!("⚠️ 重新分类失败 (ID: {}): {}", id, e);
+            }
+        }
+    }
+
     // 返回更新后的记录（以 JSON 字符串形式）
     let json = get_data_by_id(id)?;
     if json == "null" {
@@ -567,6 +756,10 @@ pub fn filter_data_by_favorite(is_favorite: bool) -> Result<String, String> {
         results.push(item.map_err(|e| e.to_string())?);
     }
 
+    // 列表中包含隐私数据的完整内容，为其记录查看事件
+    let ids: Vec<String> = results.iter().map(|item| item.id.clone()).collect();
+    audit_private_items(AuditEvent::Viewed, &ids, "filter_data_by_favorite");
+
     clipboard_items_to_json(results)
 }
 
@@ -688,6 +881,10 @@ pub fn filter_data_by_type(item_type: &str) -> Result<String, String> {
         results.push(item.map_err(|e| e.to_string())?);
     }
 
+    // 列表中包含隐私数据的完整内容，为其记录查看事件
+    let ids: Vec<String> = results.iter().map(|item| item.id.clone()).collect();
+    audit_private_items(AuditEvent::Viewed, &ids, "filter_data_by_type");
+
     clipboard_items_to_json(results)
 }
 
@@ -0,0 +1,212 @@
+use super::{get_db_path, init_db};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+/// 同一数据项使用同一模型连续失败达到该次数后不再尝试，内容变化或更换模型后重新计数
+pub const MAX_EMBED_ATTEMPTS: i64 = 3;
+
+/// 需要建立索引的文本：文本数据的内容、图片的 OCR 文本、文件中提取的文本。
+/// 不含隐私数据、没有文本的数据、已使用 ?1 模型建立索引的数据以及失败次数已达 ?2 的数据。
+const UNINDEXED_ITEMS: &str = "
+    SELECT id, body, timestamp FROM (
+        SELECT data.id AS id, data.timestamp AS timestamp,
+               CASE data.item_type
+                   WHEN 'text' THEN data.content
+                   WHEN 'image' THEN extended_data.ocr_text
+                   ELSE extended_data.document_text
+               END AS body
+        FROM data
+        LEFT JOIN extended_data ON data.id = extended_data.item_id
+        WHERE NOT EXISTS (SELECT 1 FROM private_data WHERE private_data.item_id = data.id)
+          AND NOT EXISTS (SELECT 1 FROM item_embeddings
+                          WHERE item_embeddings.item_id = data.id AND item_embeddings.model = ?1
+                            AND (item_embeddings.error IS NULL OR item_embeddings.attempts >= ?2))
+    )
+    WHERE body IS NOT NULL AND TRIM(body) != ''";
+
+/// 语义索引的数量
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingStats {
+    /// 当前模型已建立索引的数据项数
+    pub indexed: usize,
+    /// 等待建立索引的数据项数
+    pub pending: usize,
+    /// 多次失败后不再尝试建立索引的数据项数
+    pub failed: usize,
+}
+
+/// 向量按小端序 f32 存储
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+/// 保存数据项的文本向量，替换原有的向量；数据项不存在或为隐私数据时不写入。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// model: &str - 生成向量的模型
+/// vector: &[f32] - 文本向量
+pub fn save_embedding(item_id: &str, model: &str, vector: &[f32]) -> Result<(), String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO item_embeddings (item_id, model, dims, vector, timestamp)
+         SELECT ?1, ?2, ?3, ?4, ?5
+         WHERE EXISTS (SELECT 1 FROM data WHERE id = ?1)
+           AND NOT EXISTS (SELECT 1 FROM private_data WHERE item_id = ?1)
+         ON CONFLICT(item_id) DO UPDATE SET
+             model = excluded.model, dims = excluded.dims,
+             vector = excluded.vector, timestamp = excluded.timestamp,
+             attempts = 0, error = NULL",
+        params![
+            item_id,
+            model,
+            vector.len() as i64,
+            encode_vector(vector),
+            chrono::Utc::now().timestamp_millis()
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 记录数据项建立索引失败，累加同一模型的连续失败次数；数据项不存在或为隐私数据时不写入。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// model: &str - 生成向量的模型
+/// error: &str - 失败原因
+pub fn record_embedding_failure(item_id: &str, model: &str, error: &str) -> Result<(), String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO item_embeddings (item_id, model, dims, vector, timestamp, attempts, error)
+         SELECT ?1, ?2, 0, X'', ?3, 1, ?4
+         WHERE EXISTS (SELECT 1 FROM data WHERE id = ?1)
+           AND NOT EXISTS (SELECT 1 FROM private_data WHERE item_id = ?1)
+         ON CONFLICT(item_id) DO UPDATE SET
+             attempts = CASE
+                 WHEN item_embeddings.model = excluded.model AND item_embeddings.error IS NOT NULL
+                 THEN item_embeddings.attempts + 1
+                 ELSE 1
+             END,
+             model = excluded.model, dims = 0, vector = X'',
+             timestamp = excluded.timestamp, error = excluded.error",
+        params![item_id, model, chrono::Utc::now().timestamp_millis(), error],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 获取尚未使用指定模型建立索引的数据项，较新的数据优先
+/// # Param
+/// model: &str - 当前使用的模型
+/// limit: usize - 最多返回的数量
+/// # Returns
+/// Vec<(String, String)> - (数据项 ID, 需要建立索引的文本)
+pub fn get_unindexed_items(model: &str, limit: usize) -> Result<Vec<(String, String)>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let sql = format!("{} ORDER BY timestamp DESC LIMIT ?3", UNINDEXED_ITEMS);
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![model, MAX_EMBED_ATTEMPTS, limit as i64], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 获取指定模型生成的全部向量，不含隐私数据与建立索引失败的数据
+/// # Param
+/// model: &str - 当前使用的模型
+/// # Returns
+/// Vec<(String, Vec<f32>)> - (数据项 ID, 文本向量)
+pub fn get_embeddings(model: &str) -> Result<Vec<(String, Vec<f32>)>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT item_id, vector FROM item_embeddings
+             WHERE model = ?1 AND error IS NULL
+               AND NOT EXISTS (SELECT 1 FROM private_data WHERE private_data.item_id = item_embeddings.item_id)",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![model], |row| {
+            let bytes: Vec<u8> = row.get(1)?;
+            Ok((row.get(0)?, decode_vector(&bytes)))
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 删除已被标记为隐私的数据项的向量
+/// # Returns
+/// usize - 删除的数量
+pub fn delete_private_embeddings() -> Result<usize, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "DELETE FROM item_embeddings WHERE item_id IN (SELECT item_id FROM private_data)",
+        [],
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// 统计指定模型的索引数量
+/// # Param
+/// model: &str - 当前使用的模型
+pub fn get_embedding_stats(model: &str) -> Result<EmbeddingStats, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let indexed: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM item_embeddings
+             WHERE model = ?1 AND error IS NULL
+               AND NOT EXISTS (SELECT 1 FROM private_data WHERE private_data.item_id = item_embeddings.item_id)",
+            params![model],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    let pending: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM ({})", UNINDEXED_ITEMS),
+            params![model, MAX_EMBED_ATTEMPTS],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    let failed: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM item_embeddings
+             WHERE model = ?1 AND error IS NOT NULL AND attempts >= ?2
+               AND NOT EXISTS (SELECT 1 FROM private_data WHERE private_data.item_id = item_embeddings.item_id)",
+            params![model, MAX_EMBED_ATTEMPTS],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(EmbeddingStats {
+        indexed: indexed as usize,
+        pending: pending as usize,
+        failed: failed as usize,
+    })
+}
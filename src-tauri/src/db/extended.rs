@@ -1,8 +1,12 @@
+use super::audit::{audit_private_access, audit_private_items, AuditEvent};
 use super::{get_db_path, init_db};
+use crate::barcode::DecodedCode;
 use crate::clipboard::{clipboard_items_to_json, ClipboardItem};
+use crate::ocr::{self, OcrLine, OcrResult};
+use crate::source_app::SourceApp;
 use rusqlite::{params, Connection, OptionalExtension};
 
-/// 插入 OCR 文本数据。
+/// 插入 OCR 文本数据。数据项不存在（如识别期间已被删除）时不写入。
 /// # Param
 /// item_id: &str - 数据项 ID
 /// ocr_text: &str - OCR 识别的文本内容
@@ -13,13 +17,87 @@ pub fn insert_ocr_text(item_id: &str, ocr_text: &str) -> Result<String, String>
     init_db(db_path.as_path()).map_err(|e| e.to_string())?;
     let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
 
+    write_ocr_text(&conn, item_id, ocr_text)?;
+    Ok("ocr inserted".to_string())
+}
+
+/// 在已有连接上写入 OCR 文本，并清除旧的语义索引
+fn write_ocr_text(conn: &Connection, item_id: &str, ocr_text: &str) -> Result<(), String> {
     conn.execute(
-        "INSERT OR REPLACE INTO extended_data (item_id, ocr_text) VALUES (?1, ?2)",
+        "INSERT INTO extended_data (item_id, ocr_text)
+         SELECT ?1, ?2 WHERE EXISTS (SELECT 1 FROM data WHERE id = ?1)
+         ON CONFLICT(item_id) DO UPDATE SET ocr_text = excluded.ocr_text",
         params![item_id, ocr_text],
     )
     .map_err(|e| e.to_string())?;
+    // OCR 文本变化后重新建立语义索引
+    conn.execute("DELETE FROM item_embeddings WHERE item_id = ?1", params![item_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
 
-    Ok("ocr inserted".to_string())
+/// 在已有连接上写入结构化 OCR 结果及其纯文本（供 OCR 任务在同一事务中保存结果并更新状态）
+pub(super) fn write_ocr_result(conn: &Connection, item_id: &str, result: &OcrResult) -> Result<(), String> {
+    write_ocr_text(conn, item_id, &result.text)?;
+
+    let languages = serde_json::to_string(&result.languages).map_err(|e| e.to_string())?;
+    let lines = serde_json::to_string(&result.lines).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT OR REPLACE INTO ocr_results (item_id, provider, languages, confidence, lines)
+         SELECT ?1, ?2, ?3, ?4, ?5 WHERE EXISTS (SELECT 1 FROM data WHERE id = ?1)",
+        params![item_id, result.provider, languages, result.confidence, lines],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 获取数据项的结构化 OCR 结果（不写入审计日志，由调用方按用途记录）。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// # Returns
+/// Option<OcrResult> - OCR 结果，没有记录时返回 None
+pub fn get_ocr_result(item_id: &str) -> Result<Option<OcrResult>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let row: Option<(String, String, Option<f32>, String)> = conn
+        .query_row(
+            "SELECT provider, languages, confidence, lines FROM ocr_results WHERE item_id = ?1",
+            params![item_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    let Some((provider, languages, confidence, lines)) = row else {
+        return Ok(None);
+    };
+    let languages: Vec<String> = serde_json::from_str(&languages).map_err(|e| e.to_string())?;
+    let lines: Vec<OcrLine> = serde_json::from_str(&lines).map_err(|e| e.to_string())?;
+    Ok(Some(OcrResult {
+        provider,
+        languages,
+        confidence,
+        text: ocr::render_text(&lines),
+        lines,
+    }))
+}
+
+/// 返回对应数据项的结构化 OCR 结果。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// # Returns
+/// String - OCR 结果的 JSON 字符串（包含 provider、languages、confidence、lines 与 text），若无则返回空字符串
+#[tauri::command]
+pub fn get_ocr_result_by_item_id(item_id: &str) -> Result<String, String> {
+    match get_ocr_result(item_id)? {
+        Some(result) => {
+            audit_private_access(AuditEvent::Viewed, item_id, "get_ocr_result_by_item_id");
+            serde_json::to_string(&result).map_err(|e| e.to_string())
+        }
+        None => Ok(String::new()),
+    }
 }
 
 /// 返回对应数据项的 OCR 文本。作为 Tauri command 暴露给前端调用。
@@ -29,6 +107,19 @@ pub fn insert_ocr_text(item_id: &str, ocr_text: &str) -> Result<String, String>
 /// String - 包含 OCR 文本的字符串，若无则返回空字符串
 #[tauri::command]
 pub fn get_ocr_text_by_item_id(item_id: &str) -> Result<String, String> {
+    let ocr_text = get_ocr_text(item_id)?;
+    if ocr_text.is_some() {
+        audit_private_access(AuditEvent::Viewed, item_id, "get_ocr_text_by_item_id");
+    }
+    Ok(ocr_text.unwrap_or_default())
+}
+
+/// 获取数据项的 OCR 文本（不写入审计日志，由调用方按用途记录）。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// # Returns
+/// Option<String> - OCR 文本，没有记录时返回 None
+pub fn get_ocr_text(item_id: &str) -> Result<Option<String>, String> {
     let db_path = get_db_path();
     init_db(db_path.as_path()).map_err(|e| e.to_string())?;
     let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
@@ -37,12 +128,11 @@ pub fn get_ocr_text_by_item_id(item_id: &str) -> Result<String, String> {
         .prepare("SELECT ocr_text FROM extended_data WHERE item_id = ?1")
         .map_err(|e| e.to_string())?;
 
-    let ocr_text: Option<String> = stmt
-        .query_row(params![item_id], |row| row.get(0))
+    // 只有条码等其他扩展数据的记录 ocr_text 为 NULL
+    stmt.query_row(params![item_id], |row| row.get(0))
         .optional()
-        .map_err(|e| e.to_string())?;
-
-    Ok(ocr_text.unwrap_or_default())
+        .map(Option::flatten)
+        .map_err(|e| e.to_string())
 }
 
 /// 按 OCR 文本搜索数据项。作为 Tauri command 暴露给前端调用。
@@ -86,9 +176,144 @@ pub fn search_data_by_ocr_text(query: &str) -> Result<String, String> {
         results.push(item.map_err(|e| e.to_string())?);
     }
 
+    // 列表中包含隐私数据的完整内容，为其记录查看事件
+    let ids: Vec<String> = results.iter().map(|item| item.id.clone()).collect();
+    audit_private_items(AuditEvent::Viewed, &ids, "search_data_by_ocr_text");
+
     clipboard_items_to_json(results)
 }
 
+/// 保存图片中解码出的二维码与条码。内容按行拼接写入 barcode_text 以供搜索，完整结果以 JSON 写入 barcode_data。
+/// 数据项已被删除时不写入。
+/// # Param
+/// item_id: &str - 图片数据项 ID
+/// codes: &[DecodedCode] - 解码结果，为空表示图片中没有可识别的码
+/// # Returns
+/// String - 信息。若插入成功返回 "barcodes inserted"，否则返回错误信息
+pub fn insert_barcodes(item_id: &str, codes: &[DecodedCode]) -> Result<String, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let text = codes
+        .iter()
+        .map(|code| code.payload.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let data = serde_json::to_string(codes).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO extended_data (item_id, barcode_text, barcode_data)
+         SELECT ?1, ?2, ?3 WHERE EXISTS (SELECT 1 FROM data WHERE id = ?1)
+         ON CONFLICT(item_id) DO UPDATE SET
+             barcode_text = excluded.barcode_text, barcode_data = excluded.barcode_data",
+        params![item_id, text, data],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok("barcodes inserted".to_string())
+}
+
+/// 获取图片中解码出的二维码与条码（不写入审计日志，由调用方按用途记录）。
+/// # Param
+/// item_id: &str - 图片数据项 ID
+/// # Returns
+/// Option<Vec<DecodedCode>> - 解码结果，尚未解码过时返回 None
+pub fn get_barcodes(item_id: &str) -> Result<Option<Vec<DecodedCode>>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let data: Option<String> = conn
+        .query_row(
+            "SELECT barcode_data FROM extended_data WHERE item_id = ?1",
+            params![item_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .flatten();
+
+    match data {
+        Some(data) => serde_json::from_str(&data)
+            .map(Some)
+            .map_err(|e| e.to_string()),
+        None => Ok(None),
+    }
+}
+
+/// 返回图片中解码出的二维码与条码。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// item_id: &str - 图片数据项 ID
+/// # Returns
+/// Vec<DecodedCode> - 解码结果（format 与 payload），尚未解码或没有可识别的码时为空
+#[tauri::command]
+pub fn get_barcodes_by_item_id(item_id: &str) -> Result<Vec<DecodedCode>, String> {
+    let codes = get_barcodes(item_id)?.unwrap_or_default();
+    if !codes.is_empty() {
+        audit_private_access(AuditEvent::Viewed, item_id, "get_barcodes_by_item_id");
+    }
+    Ok(codes)
+}
+
+/// 插入从文件中提取的文本，供综合搜索检索。数据项已被删除时不写入。
+/// # Param
+/// item_id: &str - 文件数据项 ID
+/// document_text: &str - 提取的文本
+/// # Returns
+/// String - 信息。若插入成功返回 "document text inserted"，否则返回错误信息
+pub fn insert_document_text(item_id: &str, document_text: &str) -> Result<String, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO extended_data (item_id, document_text)
+         SELECT ?1, ?2 WHERE EXISTS (SELECT 1 FROM data WHERE id = ?1)
+         ON CONFLICT(item_id) DO UPDATE SET document_text = excluded.document_text",
+        params![item_id, document_text],
+    )
+    .map_err(|e| e.to_string())?;
+    // 提取的文本变化后重新建立语义索引
+    conn.execute("DELETE FROM item_embeddings WHERE item_id = ?1", params![item_id])
+        .map_err(|e| e.to_string())?;
+
+    Ok("document text inserted".to_string())
+}
+
+/// 获取从文件中提取的文本（不写入审计日志，由调用方按用途记录）。
+/// # Param
+/// item_id: &str - 文件数据项 ID
+/// # Returns
+/// Option<String> - 提取的文本，没有记录时返回 None
+pub fn get_document_text(item_id: &str) -> Result<Option<String>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.query_row(
+        "SELECT document_text FROM extended_data WHERE item_id = ?1",
+        params![item_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .map(Option::flatten)
+    .map_err(|e| e.to_string())
+}
+
+/// 返回从文件中提取的文本。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// item_id: &str - 文件数据项 ID
+/// # Returns
+/// String - 提取的文本，若无则返回空字符串
+#[tauri::command]
+pub fn get_document_text_by_item_id(item_id: &str) -> Result<String, String> {
+    let document_text = get_document_text(item_id)?;
+    if document_text.is_some() {
+        audit_private_access(AuditEvent::Viewed, item_id, "get_document_text_by_item_id");
+    }
+    Ok(document_text.unwrap_or_default())
+}
+
 /// 插入 icon_data 数据。
 /// # Param
 /// item_id: &str - 数据项 ID
@@ -101,7 +326,8 @@ pub fn insert_icon_data(item_id: &str, icon_data: &str) -> Result<String, String
     let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
 
     conn.execute(
-        "INSERT OR REPLACE INTO extended_data (item_id, icon_data) VALUES (?1, ?2)",
+        "INSERT INTO extended_data (item_id, icon_data) VALUES (?1, ?2)
+         ON CONFLICT(item_id) DO UPDATE SET icon_data = excluded.icon_data",
         params![item_id, icon_data],
     )
     .map_err(|e| e.to_string())?;
@@ -127,7 +353,59 @@ pub fn get_icon_data_by_item_id(item_id: &str) -> Result<String, String> {
     let icon_data: Option<String> = stmt
         .query_row(params![item_id], |row| row.get(0))
         .optional()
-        .map_err(|e| e.to_string())?;
+        .map_err(|e| e.to_string())?
+        .flatten();
 
     Ok(icon_data.unwrap_or_default())
 }
+
+/// 插入数据项的来源应用信息。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// source: &SourceApp - 来源应用信息
+/// # Returns
+/// String - 信息。若插入成功返回 "source inserted"，否则返回错误信息
+pub fn insert_item_source(item_id: &str, source: &SourceApp) -> Result<String, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO item_source (item_id, app_name, window_title) VALUES (?1, ?2, ?3)",
+        params![item_id, source.app_name, source.window_title],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok("source inserted".to_string())
+}
+
+/// 根据 item ID 获取来源应用信息。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// # Returns
+/// String - 来源应用信息的 JSON 字符串（包含 app_name 与 window_title），若无则返回空字符串
+#[tauri::command]
+pub fn get_item_source_by_item_id(item_id: &str) -> Result<String, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let source: Option<SourceApp> = conn
+        .query_row(
+            "SELECT app_name, window_title FROM item_source WHERE item_id = ?1",
+            params![item_id],
+            |row| {
+                Ok(SourceApp {
+                    app_name: row.get(0)?,
+                    window_title: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                })
+            },
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    match source {
+        Some(s) => serde_json::to_string(&s).map_err(|e| e.to_string()),
+        None => Ok(String::new()),
+    }
+}
@@ -1,3 +1,4 @@
+use super::audit::{audit_private_items, AuditEvent};
 use super::{get_db_path, init_db};
 use crate::clipboard::{clipboard_items_to_json, folder_items_to_json, ClipboardItem, FolderItem};
 use rusqlite::{params, Connection};
@@ -193,6 +194,10 @@ pub fn filter_data_by_folder(folder_name: &str) -> Result<String, String> {
         results.push(item.map_err(|e| e.to_string())?);
     }
 
+    // 列表中包含隐私数据的完整内容，为其记录查看事件
+    let ids: Vec<String> = results.iter().map(|item| item.id.clone()).collect();
+    audit_private_items(AuditEvent::Viewed, &ids, "filter_data_by_folder");
+
     clipboard_items_to_json(results)
 }
 
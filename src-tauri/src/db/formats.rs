@@ -0,0 +1,90 @@
+use super::{get_db_path, init_db};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+/// HTML 格式
+pub const FORMAT_HTML: &str = "html";
+/// RTF 格式
+pub const FORMAT_RTF: &str = "rtf";
+/// 纯文本（首选表示不是文本时保存）
+pub const FORMAT_TEXT: &str = "text";
+/// 图片（首选表示不是图片时保存），内容为 PNG 文件的相对路径
+pub const FORMAT_IMAGE: &str = "image";
+
+/// 数据项的一种附加表示
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemFormat {
+    /// 格式名称（html / rtf / text / image）
+    pub format: String,
+    /// 该格式的原始内容
+    pub content: String,
+}
+
+/// 插入或替换数据项的一种附加格式。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// format: &str - 格式名称
+/// content: &str - 格式内容
+/// # Returns
+/// String - 信息。若插入成功返回 "format inserted"，否则返回错误信息
+pub fn insert_item_format(item_id: &str, format: &str, content: &str) -> Result<String, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO item_formats (item_id, format, content) VALUES (?1, ?2, ?3)",
+        params![item_id, format, content],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok("format inserted".to_string())
+}
+
+/// 获取数据项的全部附加格式，按格式名称排序。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// # Returns
+/// Result<Vec<ItemFormat>, String> - 附加格式列表
+pub fn get_item_formats(item_id: &str) -> Result<Vec<ItemFormat>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare("SELECT format, content FROM item_formats WHERE item_id = ?1 ORDER BY format")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![item_id], |row| {
+            Ok(ItemFormat {
+                format: row.get(0)?,
+                content: row.get(1)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 获取数据项的指定格式内容，不存在时返回 None。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// format: &str - 格式名称
+pub fn get_item_format(item_id: &str, format: &str) -> Result<Option<String>, String> {
+    Ok(get_item_formats(item_id)?
+        .into_iter()
+        .find(|f| f.format == format)
+        .map(|f| f.content))
+}
+
+/// 根据 item ID 获取全部附加格式。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// # Returns
+/// String - 附加格式列表的 JSON 字符串（format, content），没有附加格式时为 "[]"
+#[tauri::command]
+pub fn get_item_formats_by_item_id(item_id: &str) -> Result<String, String> {
+    let formats = get_item_formats(item_id)?;
+    serde_json::to_string(&formats).map_err(|e| e.to_string())
+}
@@ -0,0 +1,139 @@
+use super::core::{after_data_inserted, insert_data_row};
+use super::privacy::check_and_mark_private_item;
+use super::{get_db_path, init_db};
+use crate::clipboard::ClipboardItem;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+/// 由转换生成的数据项
+pub const LINK_TRANSFORMED: &str = "transformed";
+/// 由多条数据合并生成的数据项
+pub const LINK_MERGED: &str = "merged";
+/// 由翻译生成的数据项
+pub const LINK_TRANSLATED: &str = "translated";
+
+/// 数据项之间的来源关系：item_id 由 source_id 派生而来
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemLink {
+    /// 派生出的数据项 ID
+    pub item_id: String,
+    /// 原始数据项 ID
+    pub source_id: String,
+    /// 关系类型（如 transformed、merged、translated）
+    pub relation: String,
+    /// 关系的补充说明（如使用的转换链、合并时的位置、翻译的目标语言）
+    pub detail: String,
+    /// 建立关系的时间（毫秒时间戳）
+    pub timestamp: i64,
+}
+
+/// 插入派生数据项：在同一事务中写入 data 记录、与各原始数据的来源关系，
+/// 原始数据中有隐私数据时一并写入隐私标记，避免中途失败留下未标记为隐私的派生内容。
+/// 原始数据都不是隐私数据时，按当前配置检查派生出的内容（转换、合并可能拼出新的敏感信息）。
+/// # Param
+/// item: &ClipboardItem - 派生出的数据项
+/// sources: &[(String, String)] - (原始数据项 ID, 补充说明)
+/// relation: &str - 关系类型
+/// # Returns
+/// Result<bool, String> - 派生数据项是否被标记为隐私
+pub fn insert_derived_item(
+    item: &ClipboardItem,
+    sources: &[(String, String)],
+    relation: &str,
+) -> Result<bool, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let mut conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    insert_data_row(&tx, item)?;
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let mut any_private = false;
+    for (source_id, detail) in sources {
+        tx.execute(
+            "INSERT OR REPLACE INTO item_links (item_id, source_id, relation, detail, timestamp) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![item.id, source_id, relation, detail, timestamp],
+        )
+        .map_err(|e| e.to_string())?;
+        let is_private: i64 = tx
+            .query_row(
+                "SELECT COUNT(*) FROM private_data WHERE item_id = ?1",
+                params![source_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+        any_private |= is_private > 0;
+    }
+    // 由隐私数据派生的内容同样视为隐私
+    if any_private {
+        tx.execute(
+            "INSERT OR IGNORE INTO private_data (item_id) VALUES (?1)",
+            params![item.id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    // 继承的隐私标记不参与检查，避免关闭的过滤开关撤销该标记
+    let is_private = any_private || {
+        let cfg = crate::config::CONFIG.get().map(|c| c.read().unwrap().clone()).unwrap_or_default();
+        check_and_mark_private_item(
+            item.clone(),
+            cfg.filter_passwords,
+            cfg.filter_bank_cards,
+            cfg.filter_id_cards,
+            cfg.filter_phone_numbers,
+        )
+        .unwrap_or_else(|e| {
+            eprintln!("❌ 检查隐私数据失败: {:?}", e);
+            false
+        })
+    };
+
+    after_data_inserted(item.clone())?;
+    Ok(is_private)
+}
+
+/// 获取与数据项相关的全部来源关系（无论该数据项是派生项还是原始项）。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// # Returns
+/// Result<Vec<ItemLink>, String> - 来源关系列表，按时间排序
+pub fn get_item_links(item_id: &str) -> Result<Vec<ItemLink>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT item_id, source_id, relation, detail, timestamp FROM item_links
+             WHERE item_id = ?1 OR source_id = ?1
+             ORDER BY timestamp, rowid",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![item_id], |row| {
+            Ok(ItemLink {
+                item_id: row.get(0)?,
+                source_id: row.get(1)?,
+                relation: row.get(2)?,
+                detail: row.get(3)?,
+                timestamp: row.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 根据 item ID 获取来源关系。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// # Returns
+/// String - 来源关系列表的 JSON 字符串（item_id, source_id, relation, detail, timestamp）
+#[tauri::command]
+pub fn get_item_links_by_item_id(item_id: &str) -> Result<String, String> {
+    let links = get_item_links(item_id)?;
+    serde_json::to_string(&links).map_err(|e| e.to_string())
+}
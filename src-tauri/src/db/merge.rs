@@ -0,0 +1,142 @@
+use super::audit::{audit_private_access, AuditEvent};
+use super::core::{delete_data_by_id, get_item_by_id};
+use super::extended::get_ocr_text;
+use super::links::{insert_derived_item, LINK_MERGED};
+use crate::clipboard::ClipboardItem;
+use uuid::Uuid;
+
+/// 合并时的排列顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeOrder {
+    /// 按传入（选中）的顺序
+    Selection,
+    /// 按记录时间从旧到新
+    Oldest,
+    /// 按记录时间从新到旧
+    Newest,
+}
+
+impl MergeOrder {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "selection" => Some(MergeOrder::Selection),
+            "oldest" => Some(MergeOrder::Oldest),
+            "newest" => Some(MergeOrder::Newest),
+            _ => None,
+        }
+    }
+}
+
+/// 获取数据项用于合并的文本：文本与片段使用内容，其他类型使用 OCR 文本
+fn merge_text(item: &ClipboardItem) -> Result<Option<String>, String> {
+    if item.item_type == "text" || item.item_type == crate::snippet::SNIPPET_TYPE {
+        return Ok(Some(item.content.clone()));
+    }
+    Ok(get_ocr_text(&item.id)?.filter(|t| !t.trim().is_empty()))
+}
+
+/// 将多条数据合并为一条新的文本数据，并记录与各原始数据的来源关系。
+/// # Param
+/// ids: &[String] - 要合并的数据项 ID（重复的 ID 只保留一次）
+/// separator: &str - 各段之间的分隔符
+/// order: MergeOrder - 排列顺序
+/// delete_originals: bool - 合并后是否删除原始数据
+/// # Returns
+/// Result<ClipboardItem, String> - 合并得到的新数据项
+pub fn merge_items_internal(
+    ids: &[String],
+    separator: &str,
+    order: MergeOrder,
+    delete_originals: bool,
+) -> Result<ClipboardItem, String> {
+    let mut unique_ids: Vec<&String> = Vec::new();
+    for id in ids {
+        if !unique_ids.contains(&id) {
+            unique_ids.push(id);
+        }
+    }
+    if unique_ids.len() < 2 {
+        return Err("至少需要选择两条数据才能合并".to_string());
+    }
+
+    let mut sources = Vec::new();
+    let mut missing_text = Vec::new();
+    for id in unique_ids {
+        let item = get_item_by_id(id)?.ok_or_else(|| format!("数据不存在: {}", id))?;
+        match merge_text(&item)? {
+            Some(text) => sources.push((item, text)),
+            None => missing_text.push(item.id),
+        }
+    }
+    if !missing_text.is_empty() {
+        return Err(format!("以下数据没有可合并的文本: {}", missing_text.join(", ")));
+    }
+
+    // sort_by_key 是稳定排序，时间相同的数据保持选中顺序
+    match order {
+        MergeOrder::Selection => {}
+        MergeOrder::Oldest => sources.sort_by_key(|(item, _)| item.timestamp),
+        MergeOrder::Newest => sources.sort_by_key(|(item, _)| std::cmp::Reverse(item.timestamp)),
+    }
+
+    let content = sources
+        .iter()
+        .map(|(_, text)| text.as_str())
+        .collect::<Vec<_>>()
+        .join(separator);
+    let merged = ClipboardItem {
+        id: Uuid::new_v4().to_string(),
+        item_type: "text".to_string(),
+        content: content.clone(),
+        size: Some(content.chars().count() as u64),
+        is_favorite: false,
+        notes: "".to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    };
+    let total = sources.len();
+    let links: Vec<(String, String)> = sources
+        .iter()
+        .enumerate()
+        .map(|(index, (item, _))| (item.id.clone(), format!("{}/{}", index + 1, total)))
+        .collect();
+    // 合并了隐私数据的结果同样视为隐私
+    insert_derived_item(&merged, &links, LINK_MERGED)?;
+    for (item, _) in &sources {
+        audit_private_access(AuditEvent::Viewed, &item.id, "merge_items");
+    }
+
+    if delete_originals {
+        for (item, _) in &sources {
+            delete_data_by_id(&item.id)?;
+        }
+    }
+
+    Ok(merged)
+}
+
+/// 将选中的多条数据合并为一条新的文本数据。作为 Tauri command 暴露给前端调用。
+/// 图片等非文本数据使用其 OCR 文本；新数据与各原始数据之间记录 merged 来源关系。
+/// # Param
+/// ids: Vec<String> - 要合并的数据项 ID
+/// separator: Option<&str> - 分隔符，默认换行
+/// order: Option<&str> - 排列顺序：selection（默认，按选中顺序）/ oldest / newest
+/// delete_originals: Option<bool> - 合并后是否删除原始数据，默认保留
+/// # Returns
+/// String - 合并得到的新数据的 JSON 字符串
+#[tauri::command]
+pub fn merge_items(
+    ids: Vec<String>,
+    separator: Option<&str>,
+    order: Option<&str>,
+    delete_originals: Option<bool>,
+) -> Result<String, String> {
+    let order = order.unwrap_or("selection");
+    let order = MergeOrder::parse(order).ok_or_else(|| format!("未知的排列顺序: {}", order))?;
+    let merged = merge_items_internal(
+        &ids,
+        separator.unwrap_or("\n"),
+        order,
+        delete_originals.unwrap_or(false),
+    )?;
+    crate::clipboard::clipboard_item_to_json(merged)
+}
@@ -2,21 +2,43 @@ use rusqlite::{Connection, Result};
 use std::path::{Path, PathBuf};
 use std::sync::RwLock;
 
+pub mod ai_jobs;
+pub mod audit;
 pub mod cleanup;
 pub mod core;
+pub mod embeddings;
 pub mod extended;
 pub mod folders;
+pub mod formats;
+pub mod links;
+pub mod merge;
+pub mod ocr_jobs;
 pub mod privacy;
 pub mod search;
+pub mod secure_delete;
+pub mod subtypes;
 pub mod sync;
+pub mod tags;
+pub mod translations;
 
+pub use self::ai_jobs::*;
+pub use self::audit::*;
 pub use self::cleanup::*;
 pub use self::core::*;
+pub use self::embeddings::*;
 pub use self::extended::*;
 pub use self::folders::*;
+pub use self::formats::*;
+pub use self::links::*;
+pub use self::merge::*;
+pub use self::ocr_jobs::*;
 pub use self::privacy::*;
 pub use self::search::*;
+pub use self::secure_delete::*;
+pub use self::subtypes::*;
 pub use self::sync::*;
+pub use self::tags::*;
+pub use self::translations::*;
 
 static DB_PATH_GLOBAL: RwLock<Option<PathBuf>> = RwLock::new(None);
 
@@ -88,10 +110,106 @@ pub fn init_db(path: &Path) -> Result<()> {
             item_id TEXT PRIMARY KEY NOT NULL,
             ocr_text TEXT,
             icon_data TEXT,
+            barcode_text TEXT,
+            barcode_data TEXT,
+            document_text TEXT,
+            ai_summary TEXT,
             FOREIGN KEY (item_id) REFERENCES data(id) ON DELETE CASCADE
         )",
         [],
     )?;
+    // 旧版本数据库的扩展数据表没有条码解码结果、文件文本与 AI 摘要列
+    add_column_if_missing(&conn, "extended_data", "barcode_text", "TEXT")?;
+    add_column_if_missing(&conn, "extended_data", "barcode_data", "TEXT")?;
+    add_column_if_missing(&conn, "extended_data", "document_text", "TEXT")?;
+    add_column_if_missing(&conn, "extended_data", "ai_summary", "TEXT")?;
+
+    // OCR 结构化结果表，记录各文本行的包围盒、置信度与语言（纯文本保存在 extended_data.ocr_text）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS ocr_results (
+            item_id TEXT PRIMARY KEY NOT NULL,
+            provider TEXT NOT NULL,
+            languages TEXT NOT NULL,
+            confidence REAL,
+            lines TEXT NOT NULL,
+            FOREIGN KEY (item_id) REFERENCES data(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // OCR 任务表，记录图片的识别状态（pending/running/done/failed）、失败次数与重试时间
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS ocr_jobs (
+            item_id TEXT PRIMARY KEY NOT NULL,
+            status TEXT NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            error TEXT,
+            next_attempt_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            FOREIGN KEY (item_id) REFERENCES data(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // AI 任务表，记录文本与图片 OCR 文本生成标签和摘要的状态（pending/running/done/failed）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS ai_jobs (
+            item_id TEXT PRIMARY KEY NOT NULL,
+            status TEXT NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            error TEXT,
+            next_attempt_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL,
+            FOREIGN KEY (item_id) REFERENCES data(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // 标签表，source 区分标签来源（目前只有 AI 生成的 ai）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS item_tags (
+            item_id TEXT NOT NULL,
+            tag TEXT NOT NULL,
+            source TEXT NOT NULL,
+            PRIMARY KEY (item_id, tag),
+            FOREIGN KEY (item_id) REFERENCES data(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // 译文缓存表，每个数据项每种目标语言一条；source_hash 用于判断原文是否已变化
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS item_translations (
+            item_id TEXT NOT NULL,
+            target_lang TEXT NOT NULL,
+            source_lang TEXT,
+            source_hash TEXT NOT NULL,
+            content TEXT NOT NULL,
+            timestamp INTEGER NOT NULL,
+            PRIMARY KEY (item_id, target_lang),
+            FOREIGN KEY (item_id) REFERENCES data(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // 语义搜索的文本向量表，每个数据项一条；model 与当前使用的模型不同时重新建立索引。
+    // 建立索引失败时 error 记录原因、vector 为空，attempts 为连续失败次数
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS item_embeddings (
+            item_id TEXT PRIMARY KEY NOT NULL,
+            model TEXT NOT NULL,
+            dims INTEGER NOT NULL,
+            vector BLOB NOT NULL,
+            timestamp INTEGER NOT NULL,
+            attempts INTEGER NOT NULL DEFAULT 0,
+            error TEXT,
+            FOREIGN KEY (item_id) REFERENCES data(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+    // 旧版本数据库的向量表没有失败记录列
+    add_column_if_missing(&conn, "item_embeddings", "attempts", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(&conn, "item_embeddings", "error", "TEXT")?;
 
     // 隐私表，存储标记为隐私的数据 ID 列表
     conn.execute(
@@ -102,6 +220,94 @@ pub fn init_db(path: &Path) -> Result<()> {
         [],
     )?;
 
+    // 来源应用表，记录每条数据被复制时的来源应用与窗口标题
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS item_source (
+            item_id TEXT PRIMARY KEY NOT NULL,
+            app_name TEXT NOT NULL,
+            window_title TEXT,
+            FOREIGN KEY (item_id) REFERENCES data(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // 附加格式表，记录同一次复制中的富文本等其他表示（data.content 保存纯文本回退）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS item_formats (
+            item_id TEXT NOT NULL,
+            format TEXT NOT NULL,
+            content TEXT NOT NULL,
+            PRIMARY KEY (item_id, format),
+            FOREIGN KEY (item_id) REFERENCES data(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // 子类型表，记录文本数据的自动分类结果（代码片段同时记录语言猜测）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS item_subtype (
+            item_id TEXT PRIMARY KEY NOT NULL,
+            subtype TEXT NOT NULL,
+            language TEXT,
+            FOREIGN KEY (item_id) REFERENCES data(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // 来源关系表，记录由转换、合并等操作派生的数据项与原始数据项的关系
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS item_links (
+            item_id TEXT NOT NULL,
+            source_id TEXT NOT NULL,
+            relation TEXT NOT NULL,
+            detail TEXT NOT NULL DEFAULT '',
+            timestamp INTEGER NOT NULL,
+            PRIMARY KEY (item_id, source_id, relation)
+        )",
+        [],
+    )?;
+
+    // 审计日志表，仅追加：记录隐私数据的查看、复制、导出与取消标记事件（只保存 ID，不保存内容）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            event_type TEXT NOT NULL,
+            item_id TEXT NOT NULL,
+            origin TEXT NOT NULL,
+            timestamp INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TRIGGER IF NOT EXISTS audit_log_no_update
+         BEFORE UPDATE ON audit_log
+         BEGIN
+             SELECT RAISE(ABORT, 'audit_log is append-only');
+         END",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// 表中缺少指定列时追加该列（用于升级旧版本创建的数据库）
+/// # Param
+/// conn: &Connection - 数据库连接
+/// table: &str - 表名
+/// column: &str - 列名
+/// definition: &str - 列的类型与约束
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let exists: i64 = conn.query_row(
+        &format!("SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name = ?1", table),
+        [column],
+        |row| row.get(0),
+    )?;
+    if exists == 0 {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
     Ok(())
 }
 
@@ -110,6 +316,12 @@ pub fn init_db(path: &Path) -> Result<()> {
 mod test_db_adv;
 /// # 单元测试
 #[cfg(test)]
+#[path = "../test_unit/test_db_ai.rs"]
+mod test_db_ai;
+#[cfg(test)]
+#[path = "../test_unit/test_db_audit.rs"]
+mod test_db_audit;
+#[cfg(test)]
 #[path = "../test_unit/test_db_base.rs"]
 mod test_db_base;
 #[cfg(test)]
@@ -122,9 +334,24 @@ mod test_db_extend;
 #[path = "../test_unit/test_db_folder.rs"]
 mod test_db_folder;
 #[cfg(test)]
+#[path = "../test_unit/test_db_formats.rs"]
+mod test_db_formats;
+#[cfg(test)]
+#[path = "../test_unit/test_db_merge.rs"]
+mod test_db_merge;
+#[cfg(test)]
+#[path = "../test_unit/test_db_ocr_jobs.rs"]
+mod test_db_ocr_jobs;
+#[cfg(test)]
 #[path = "../test_unit/test_db_private.rs"]
 mod test_db_private;
 #[cfg(test)]
+#[path = "../test_unit/test_db_secure_delete.rs"]
+mod test_db_secure_delete;
+#[cfg(test)]
+#[path = "../test_unit/test_db_subtypes.rs"]
+mod test_db_subtypes;
+#[cfg(test)]
 #[path = "../test_unit/test_db_sync.rs"]
 mod test_db_sync;
 #[cfg(test)]
@@ -0,0 +1,317 @@
+use super::extended::write_ocr_result;
+use super::{get_db_path, init_db};
+use crate::ocr::OcrResult;
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+
+/// 等待执行（包括等待重试）
+pub const OCR_JOB_PENDING: &str = "pending";
+/// 正在识别
+pub const OCR_JOB_RUNNING: &str = "running";
+/// 识别完成
+pub const OCR_JOB_DONE: &str = "done";
+/// 重试次数用尽后仍然失败
+pub const OCR_JOB_FAILED: &str = "failed";
+
+/// 首次重试前的等待时间（毫秒），之后每次翻倍
+const OCR_RETRY_BASE_MS: i64 = 5_000;
+/// 重试等待时间上限（毫秒）
+const OCR_RETRY_MAX_MS: i64 = 5 * 60 * 1000;
+
+/// 一条 OCR 任务
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OcrJob {
+    /// 图片数据项 ID
+    pub item_id: String,
+    /// 任务状态：pending/running/done/failed
+    pub status: String,
+    /// 已失败的次数
+    pub attempts: u32,
+    /// 最近一次失败的错误信息
+    pub error: Option<String>,
+    /// 最早可执行的时间（毫秒时间戳），用于重试退避
+    pub next_attempt_at: i64,
+    /// 最近一次状态变化的时间（毫秒时间戳）
+    pub updated_at: i64,
+}
+
+/// 各状态的任务数量
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OcrJobStats {
+    pub pending: usize,
+    pub running: usize,
+    pub done: usize,
+    pub failed: usize,
+}
+
+/// 第 attempts 次失败后的重试等待时间（毫秒）：指数退避并设上限
+pub fn ocr_retry_delay_ms(attempts: u32) -> i64 {
+    let exponent = attempts.saturating_sub(1).min(16);
+    (OCR_RETRY_BASE_MS << exponent).min(OCR_RETRY_MAX_MS)
+}
+
+fn row_to_job(row: &rusqlite::Row) -> rusqlite::Result<OcrJob> {
+    Ok(OcrJob {
+        item_id: row.get(0)?,
+        status: row.get(1)?,
+        attempts: row.get(2)?,
+        error: row.get(3)?,
+        next_attempt_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+/// 为图片数据项添加 OCR 任务。已有任务时重置为等待状态（正在识别的任务不受影响）。
+/// # Param
+/// item_id: &str - 图片数据项 ID
+/// now: i64 - 当前时间（毫秒时间戳）
+pub fn enqueue_ocr_job(item_id: &str, now: i64) -> Result<(), String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO ocr_jobs (item_id, status, attempts, error, next_attempt_at, updated_at)
+         VALUES (?1, ?2, 0, NULL, ?3, ?3)
+         ON CONFLICT(item_id) DO UPDATE SET
+             status = excluded.status, attempts = 0, error = NULL,
+             next_attempt_at = excluded.next_attempt_at, updated_at = excluded.updated_at
+         WHERE ocr_jobs.status != ?4",
+        params![item_id, OCR_JOB_PENDING, now, OCR_JOB_RUNNING],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 取出一条已到执行时间的等待任务并标记为正在识别。
+/// # Param
+/// now: i64 - 当前时间（毫秒时间戳）
+/// # Returns
+/// Option<String> - 任务对应的数据项 ID，没有可执行的任务时返回 None
+pub fn claim_next_ocr_job(now: i64) -> Result<Option<String>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let mut conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    // 立即获取写锁，避免多个调用方取到同一条任务
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| e.to_string())?;
+    let item_id: Option<String> = tx
+        .query_row(
+            "SELECT item_id FROM ocr_jobs WHERE status = ?1 AND next_attempt_at <= ?2
+             ORDER BY next_attempt_at, rowid LIMIT 1",
+            params![OCR_JOB_PENDING, now],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if let Some(id) = &item_id {
+        tx.execute(
+            "UPDATE ocr_jobs SET status = ?1, updated_at = ?2 WHERE item_id = ?3",
+            params![OCR_JOB_RUNNING, now, id],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(item_id)
+}
+
+/// 保存识别结果并将任务标记为完成。任务状态检查与写入在同一事务中完成，
+/// 识别期间数据项被删除（任务随之取消）时不会留下孤立的识别结果。
+/// # Param
+/// item_id: &str - 图片数据项 ID
+/// result: &OcrResult - 识别结果
+/// now: i64 - 当前时间（毫秒时间戳）
+/// # Returns
+/// bool - 是否已保存（任务不再处于识别中时返回 false）
+pub fn complete_ocr_job(item_id: &str, result: &OcrResult, now: i64) -> Result<bool, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let mut conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    // 立即获取写锁，避免检查之后任务被并发取消
+    let tx = conn
+        .transaction_with_behavior(TransactionBehavior::Immediate)
+        .map_err(|e| e.to_string())?;
+    let status: Option<String> = tx
+        .query_row(
+            "SELECT status FROM ocr_jobs WHERE item_id = ?1",
+            params![item_id],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    if status.as_deref() != Some(OCR_JOB_RUNNING) {
+        return Ok(false);
+    }
+    write_ocr_result(&tx, item_id, result)?;
+    tx.execute(
+        "UPDATE ocr_jobs SET status = ?1, error = NULL, updated_at = ?2 WHERE item_id = ?3",
+        params![OCR_JOB_DONE, now, item_id],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+/// 记录一次识别失败：未达到最大次数时按退避时间重新等待，否则标记为失败。
+/// # Param
+/// item_id: &str - 图片数据项 ID
+/// error: &str - 错误信息
+/// now: i64 - 当前时间（毫秒时间戳）
+/// max_attempts: u32 - 最大尝试次数
+/// # Returns
+/// Option<OcrJob> - 更新后的任务，任务已被取消时返回 None
+pub fn fail_ocr_job(
+    item_id: &str,
+    error: &str,
+    now: i64,
+    max_attempts: u32,
+) -> Result<Option<OcrJob>, String> {
+    let Some(job) = get_ocr_job(item_id)? else {
+        return Ok(None);
+    };
+    let attempts = job.attempts + 1;
+    let (status, next_attempt_at) = if attempts >= max_attempts {
+        (OCR_JOB_FAILED, now)
+    } else {
+        (OCR_JOB_PENDING, now + ocr_retry_delay_ms(attempts))
+    };
+
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "UPDATE ocr_jobs SET status = ?1, attempts = ?2, error = ?3, next_attempt_at = ?4, updated_at = ?5
+         WHERE item_id = ?6",
+        params![status, attempts, error, next_attempt_at, now, item_id],
+    )
+    .map_err(|e| e.to_string())?;
+    get_ocr_job(item_id)
+}
+
+/// 取消数据项的 OCR 任务
+pub fn cancel_ocr_job(item_id: &str) -> Result<(), String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.execute("DELETE FROM ocr_jobs WHERE item_id = ?1", params![item_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 将上次运行中断时仍处于识别中的任务恢复为等待状态，应在启动 OCR 工作线程前调用。
+/// # Returns
+/// usize - 恢复的任务数量
+pub fn reset_running_ocr_jobs(now: i64) -> Result<usize, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "UPDATE ocr_jobs SET status = ?1, next_attempt_at = ?2, updated_at = ?2 WHERE status = ?3",
+        params![OCR_JOB_PENDING, now, OCR_JOB_RUNNING],
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// 获取数据项的 OCR 任务
+pub fn get_ocr_job(item_id: &str) -> Result<Option<OcrJob>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.query_row(
+        "SELECT item_id, status, attempts, error, next_attempt_at, updated_at FROM ocr_jobs WHERE item_id = ?1",
+        params![item_id],
+        row_to_job,
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// 统计各状态的任务数量
+pub fn get_ocr_job_stats() -> Result<OcrJobStats, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare("SELECT status, COUNT(*) FROM ocr_jobs GROUP BY status")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))
+        .map_err(|e| e.to_string())?;
+
+    let mut stats = OcrJobStats::default();
+    for row in rows {
+        let (status, count) = row.map_err(|e| e.to_string())?;
+        let count = count as usize;
+        match status.as_str() {
+            OCR_JOB_PENDING => stats.pending = count,
+            OCR_JOB_RUNNING => stats.running = count,
+            OCR_JOB_DONE => stats.done = count,
+            OCR_JOB_FAILED => stats.failed = count,
+            _ => {}
+        }
+    }
+    Ok(stats)
+}
+
+/// 获取尚无 OCR 文本且没有进行中或已完成任务的图片（包括识别失败的图片），用于补全识别。
+/// # Returns
+/// Vec<String> - 图片数据项 ID，按记录时间从旧到新
+pub fn get_images_without_ocr() -> Result<Vec<String>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT d.id FROM data d
+             LEFT JOIN extended_data ed ON d.id = ed.item_id
+             WHERE d.item_type = 'image'
+               AND (ed.ocr_text IS NULL OR ed.ocr_text = '')
+               AND NOT EXISTS (
+                   SELECT 1 FROM ocr_jobs j WHERE j.item_id = d.id AND j.status IN (?1, ?2, ?3)
+               )
+             ORDER BY d.timestamp",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![OCR_JOB_PENDING, OCR_JOB_RUNNING, OCR_JOB_DONE], |row| {
+            row.get(0)
+        })
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<String>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 获取 OCR 任务列表。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// status: Option<&str> - 只返回指定状态的任务，为空时返回全部
+/// # Returns
+/// String - 任务列表的 JSON 字符串，按最近更新时间倒序
+#[tauri::command]
+pub fn get_ocr_jobs(status: Option<&str>) -> Result<String, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT item_id, status, attempts, error, next_attempt_at, updated_at FROM ocr_jobs
+             WHERE ?1 IS NULL OR status = ?1
+             ORDER BY updated_at DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let jobs = stmt
+        .query_map(params![status], row_to_job)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<OcrJob>, _>>()
+        .map_err(|e| e.to_string())?;
+    serde_json::to_string(&jobs).map_err(|e| e.to_string())
+}
@@ -1,3 +1,4 @@
+use super::audit::{append_audit_event, AuditEvent};
 use super::{get_db_path, init_db};
 use crate::clipboard::ClipboardItem;
 use aes_gcm::{
@@ -89,8 +90,7 @@ pub fn mark_passwords_as_private(to_add: bool) -> Result<usize, String> {
                 )
                 .map_err(|e| e.to_string())?;
             } else {
-                conn.execute("DELETE FROM private_data WHERE item_id = ?1", params![id])
-                    .map_err(|e| e.to_string())?;
+                unmark_private_item(&conn, &id, "mark_passwords_as_private")?;
             }
             count += 1;
         }
@@ -201,8 +201,7 @@ pub fn mark_bank_cards_as_private(to_add: bool) -> Result<usize, String> {
                     .map_err(|e| e.to_string())?;
                 } else {
                     // 取消标记为隐私数据
-                    conn.execute("DELETE FROM private_data WHERE item_id = ?1", params![id])
-                        .map_err(|e| e.to_string())?;
+                    unmark_private_item(&conn, &id, "mark_bank_cards_as_private")?;
                 }
 
                 count += 1;
@@ -254,8 +253,7 @@ pub fn mark_identity_numbers_as_private(to_add: bool) -> Result<usize, String> {
                 .map_err(|e| e.to_string())?;
             } else {
                 // 取消标记为隐私数据
-                conn.execute("DELETE FROM private_data WHERE item_id = ?1", params![id])
-                    .map_err(|e| e.to_string())?;
+                unmark_private_item(&conn, &id, "mark_identity_numbers_as_private")?;
             }
             count += 1;
         }
@@ -303,8 +301,7 @@ pub fn mark_phone_numbers_as_private(to_add: bool) -> Result<usize, String> {
                 .map_err(|e| e.to_string())?;
             } else {
                 // 取消标记为隐私数据
-                conn.execute("DELETE FROM private_data WHERE item_id = ?1", params![id])
-                    .map_err(|e| e.to_string())?;
+                unmark_private_item(&conn, &id, "mark_phone_numbers_as_private")?;
             }
             count += 1;
         }
@@ -322,13 +319,58 @@ pub fn clear_all_private_data() -> Result<usize, String> {
     init_db(db_path.as_path()).map_err(|e| e.to_string())?;
     let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
 
+    let ids: Vec<String> = {
+        let mut stmt = conn
+            .prepare("SELECT item_id FROM private_data")
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?
+    };
+
+    let mut rows = 0;
+    for id in &ids {
+        rows += unmark_private_item(&conn, id, "clear_all_private_data")?;
+    }
+
+    Ok(rows)
+}
+
+/// 取消单个数据项的隐私标记，并在审计日志中记录 unmarked 事件。
+/// # Param
+/// conn: &Connection - 数据库连接
+/// item_id: &str - 数据项 ID
+/// origin: &str - 触发操作的命令名称
+/// # Returns
+/// Result<usize, String> - 受影响的行数，若失败则返回错误信息
+fn unmark_private_item(conn: &Connection, item_id: &str, origin: &str) -> Result<usize, String> {
     let rows = conn
-        .execute("DELETE FROM private_data", [])
+        .execute("DELETE FROM private_data WHERE item_id = ?1", params![item_id])
         .map_err(|e| e.to_string())?;
-
+    if rows > 0 {
+        append_audit_event(conn, AuditEvent::Unmarked, item_id, origin)?;
+    }
     Ok(rows)
 }
 
+/// 将单个数据项标记为隐私。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// # Returns
+/// Result<usize, String> - 受影响的行数，若失败则返回错误信息
+pub fn mark_item_as_private(item_id: &str) -> Result<usize, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT OR IGNORE INTO private_data (item_id) VALUES (?1)",
+        params![item_id],
+    )
+    .map_err(|e| e.to_string())
+}
+
 /// 根据配置文件的选项，自动设置隐私数据标记。作为 Tauri command 暴露给前端调用。
 /// # Param
 /// password_flag: bool - 是否标记密码
@@ -406,11 +448,7 @@ pub fn check_and_mark_private_item(
             )
             .map_err(|e| e.to_string())?;
         } else {
-            conn.execute(
-                "DELETE FROM private_data WHERE item_id = ?1",
-                params![item.id],
-            )
-            .map_err(|e| e.to_string())?;
+            unmark_private_item(&conn, &item.id, "check_and_mark_private_item")?;
         }
     }
 
@@ -445,11 +483,7 @@ pub fn check_and_mark_private_item(
             )
             .map_err(|e| e.to_string())?;
         } else {
-            conn.execute(
-                "DELETE FROM private_data WHERE item_id = ?1",
-                params![item.id],
-            )
-            .map_err(|e| e.to_string())?;
+            unmark_private_item(&conn, &item.id, "check_and_mark_private_item")?;
         }
     }
 
@@ -463,11 +497,7 @@ pub fn check_and_mark_private_item(
             )
             .map_err(|e| e.to_string())?;
         } else {
-            conn.execute(
-                "DELETE FROM private_data WHERE item_id = ?1",
-                params![item.id],
-            )
-            .map_err(|e| e.to_string())?;
+            unmark_private_item(&conn, &item.id, "check_and_mark_private_item")?;
         }
     }
 
@@ -481,11 +511,7 @@ pub fn check_and_mark_private_item(
             )
             .map_err(|e| e.to_string())?;
         } else {
-            conn.execute(
-                "DELETE FROM private_data WHERE item_id = ?1",
-                params![item.id],
-            )
-            .map_err(|e| e.to_string())?;
+            unmark_private_item(&conn, &item.id, "check_and_mark_private_item")?;
         }
     }
 
@@ -575,6 +601,13 @@ pub fn prepare_encrypted_db_upload(dek_hex: String) -> Result<String, String> {
     // 6. 删除临时文件
     let _ = fs::remove_file(temp_path);
 
+    // 上传的数据库中包含隐私数据（仅加密了 content 与 notes），为其记录导出审计事件
+    match super::audit::audit_private_export("prepare_encrypted_db_upload") {
+        Ok(count) if count > 0 => println!("📝 已记录 {} 条隐私数据上传审计", count),
+        Ok(_) => {}
+        Err(e) => eprintln!("⚠️ 写入上传审计日志失败: {}", e),
+    }
+
     Ok(base64_str)
 }
 
@@ -1,12 +1,16 @@
+use super::audit::{audit_private_items, AuditEvent};
 use super::{get_db_path, init_db};
 use crate::clipboard::{clipboard_items_to_json, ClipboardItem};
+use crate::snippet::SNIPPET_TYPE;
 use rusqlite::Connection;
 
 /// # Param
-/// query: &str - 搜索关键词，可以在 content/notes/ocr_text 字段中进行模糊匹配
+/// query: &str - 搜索关键词，可以在 content/notes/ocr_text 字段、图片中解码出的二维码与条码内容、文件中提取的文本及 AI 生成的摘要与标签中进行模糊匹配
 /// item_type: Option<&str> - 可选的数据类型过滤（如 "text", "image" 等），其他内容则视为folders的ID进行过滤
 /// start_timestamp: Option<i64> - 可选的起始时间戳过滤
 /// end_timestamp: Option<i64> - 可选的结束时间戳过滤
+/// source_app: Option<&str> - 可选的来源应用名称过滤（忽略大小写）
+/// subtype: Option<&str> - 可选的文本子类型过滤（如 "url", "code" 等）
 /// # Returns
 /// String - 包含匹配数据记录的 JSON 字符串，或者错误信息
 #[tauri::command]
@@ -15,6 +19,8 @@ pub fn comprehensive_search(
     item_type: Option<&str>,
     start_timestamp: Option<i64>,
     end_timestamp: Option<i64>,
+    source_app: Option<&str>,
+    subtype: Option<&str>,
 ) -> Result<String, String> {
     let db_path = get_db_path();
     init_db(db_path.as_path()).map_err(|e| e.to_string())?;
@@ -23,7 +29,9 @@ pub fn comprehensive_search(
     let mut sql = String::from(
         "SELECT data.id, data.item_type, data.content, data.size, data.is_favorite, data.notes, data.timestamp 
          FROM data 
-         LEFT JOIN extended_data ON data.id = extended_data.item_id",
+         LEFT JOIN extended_data ON data.id = extended_data.item_id
+         LEFT JOIN item_source ON data.id = item_source.item_id
+         LEFT JOIN item_subtype ON data.id = item_subtype.item_id",
     );
 
     let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(format!("%{}%", query))];
@@ -35,7 +43,7 @@ pub fn comprehensive_search(
 
     if let Some(t) = item_type {
         match t {
-            "text" | "image" | "file" | "folder" => {
+            "text" | "image" | "file" | "folder" | SNIPPET_TYPE => {
                 type_filter_opt = Some(t);
             }
             "private" => {
@@ -51,7 +59,10 @@ pub fn comprehensive_search(
 
     // WHERE 子句
     sql.push_str(
-        " WHERE (data.content LIKE ?1 OR data.notes LIKE ?1 OR extended_data.ocr_text LIKE ?1)",
+        " WHERE (data.content LIKE ?1 OR data.notes LIKE ?1 OR extended_data.ocr_text LIKE ?1
+             OR extended_data.barcode_text LIKE ?1 OR extended_data.document_text LIKE ?1
+             OR extended_data.ai_summary LIKE ?1
+             OR EXISTS (SELECT 1 FROM item_tags WHERE item_tags.item_id = data.id AND item_tags.tag LIKE ?1))",
     );
 
     if let Some(folder_id) = folder_id_opt {
@@ -72,6 +83,21 @@ pub fn comprehensive_search(
         ));
         params.push(Box::new(start));
         params.push(Box::new(end));
+        param_idx += 2;
+    }
+
+    if let Some(app) = source_app {
+        sql.push_str(&format!(
+            " AND item_source.app_name = ?{} COLLATE NOCASE",
+            param_idx
+        ));
+        params.push(Box::new(app.to_string()));
+        param_idx += 1;
+    }
+
+    if let Some(subtype) = subtype {
+        sql.push_str(&format!(" AND item_subtype.subtype = ?{}", param_idx));
+        params.push(Box::new(subtype.to_lowercase()));
     }
 
     sql.push_str(" ORDER BY data.timestamp DESC");
@@ -100,5 +126,9 @@ pub fn comprehensive_search(
         results.push(item.map_err(|e| e.to_string())?);
     }
 
+    // 搜索结果中包含隐私数据的完整内容，为其记录查看事件
+    let ids: Vec<String> = results.iter().map(|item| item.id.clone()).collect();
+    audit_private_items(AuditEvent::Viewed, &ids, "comprehensive_search");
+
     clipboard_items_to_json(results)
 }
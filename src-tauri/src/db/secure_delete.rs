@@ -0,0 +1,262 @@
+use super::core::resolve_item_file_path;
+use super::{get_db_path, init_db};
+use rand::Rng;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// 覆写文件时使用的缓冲区大小
+const SHRED_CHUNK_SIZE: usize = 64 * 1024;
+
+/// 安全删除单条数据的结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SecureDeleteReport {
+    /// 从 data 表删除的行数
+    pub rows_deleted: usize,
+    /// 一并清除的关联表行数（extended_data、private_data、folder_items、item_formats 等）
+    pub related_rows_deleted: usize,
+    /// 被覆写的文件字节数
+    pub file_bytes_scrubbed: u64,
+}
+
+/// 数据库安全整理（VACUUM）的结果
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct VacuumReport {
+    /// 整理前空闲页的数量
+    pub free_pages: i64,
+    /// 页大小（字节）
+    pub page_size: i64,
+    /// 被清除的残留数据字节数（空闲页数量 × 页大小）
+    pub bytes_scrubbed: i64,
+}
+
+/// 删除数据时需要一并清除的关联表（外键级联未启用，需显式删除）。
+/// ocr_jobs、ai_jobs 中的记录被删除即取消尚未完成的任务。
+const ITEM_SIDE_TABLES: [&str; 12] = [
+    "ai_jobs",
+    "extended_data",
+    "folder_items",
+    "item_embeddings",
+    "item_formats",
+    "item_source",
+    "item_subtype",
+    "item_tags",
+    "item_translations",
+    "ocr_jobs",
+    "ocr_results",
+    "private_data",
+];
+
+/// 辅助函数：删除数据项在 data 表及全部关联表中的记录。普通删除与安全删除共用，保证两者清除的范围一致。
+/// # Param
+/// conn: &Connection - 数据库连接（安全删除时为开启了 secure_delete 的事务）
+/// id: &str - 数据项 ID
+/// # Returns
+/// (usize, usize) - (关联表中删除的行数, data 表中删除的行数)
+pub(crate) fn delete_item_rows(conn: &Connection, id: &str) -> rusqlite::Result<(usize, usize)> {
+    let mut related_rows = 0;
+    for table in ITEM_SIDE_TABLES {
+        related_rows += conn.execute(
+            &format!("DELETE FROM {} WHERE item_id = ?1", table),
+            params![id],
+        )?;
+    }
+    // 只删除该数据项作为派生项的来源关系；原始项被删除后，派生项仍保留其来源记录
+    related_rows += conn.execute("DELETE FROM item_links WHERE item_id = ?1", params![id])?;
+    let rows = conn.execute("DELETE FROM data WHERE id = ?1", params![id])?;
+    Ok((related_rows, rows))
+}
+
+/// 判断数据项是否被标记为隐私
+/// # Param
+/// id: &str - 数据项 ID
+/// # Returns
+/// Result<bool, String> - 是否为隐私数据
+pub fn is_private_item(id: &str) -> Result<bool, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM private_data WHERE item_id = ?1",
+            params![id],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    Ok(count > 0)
+}
+
+/// 先以零、再以随机数据覆写文件内容并刷盘，最后删除文件
+/// # Param
+/// path: &Path - 文件路径
+/// # Returns
+/// io::Result<u64> - 被覆写的字节数
+fn shred_file(path: &Path) -> io::Result<u64> {
+    let len = fs::metadata(path)?.len();
+    {
+        let mut file = OpenOptions::new().write(true).open(path)?;
+        let mut buf = vec![0u8; SHRED_CHUNK_SIZE];
+        for pass in 0..2 {
+            if pass == 1 {
+                rand::rng().fill(&mut buf[..]);
+            }
+            file.seek(SeekFrom::Start(0))?;
+            let mut remaining = len;
+            while remaining > 0 {
+                let n = remaining.min(SHRED_CHUNK_SIZE as u64) as usize;
+                file.write_all(&buf[..n])?;
+                remaining -= n as u64;
+            }
+            file.sync_all()?;
+        }
+        // 截断为 0，避免文件系统保留原始长度信息
+        file.set_len(0)?;
+        file.sync_all()?;
+    }
+    fs::remove_file(path)?;
+    Ok(len)
+}
+
+/// 安全删除文件或文件夹：逐个覆写其中的文件后再删除
+/// # Param
+/// path: &Path - 文件或文件夹路径
+/// # Returns
+/// io::Result<u64> - 被覆写的总字节数
+pub fn shred_path(path: &Path) -> io::Result<u64> {
+    if path.is_dir() {
+        let mut total = 0;
+        for entry in WalkDir::new(path).into_iter().filter_map(|e| e.ok()) {
+            if entry.file_type().is_file() {
+                total += shred_file(entry.path())?;
+            }
+        }
+        fs::remove_dir_all(path)?;
+        Ok(total)
+    } else {
+        shred_file(path)
+    }
+}
+
+/// 安全删除单条数据：
+/// 1. 对图片/文件/文件夹类型，覆写本地文件后再删除
+/// 2. 在开启 secure_delete 的连接上删除 data 以及 extended_data 等关联行，使原内容所在页被清零
+/// # Param
+/// id: &str - 要删除数据的 ID
+/// # Returns
+/// Result<SecureDeleteReport, String> - 删除结果，若失败则返回错误信息
+pub fn secure_delete_data_by_id(id: &str) -> Result<SecureDeleteReport, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let mut conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+    conn.pragma_update(None, "secure_delete", true)
+        .map_err(|e| e.to_string())?;
+
+    let mut report = SecureDeleteReport::default();
+
+    // 1. 覆写本地文件
+    let item: Option<(String, String)> = conn
+        .query_row(
+            "SELECT item_type, content FROM data WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    if let Some((item_type, content)) = item {
+        if item_type != "text" && item_type != crate::snippet::SNIPPET_TYPE {
+            let mut file_path = resolve_item_file_path(&content);
+            if !file_path.exists() {
+                // 兼容旧数据：直接尝试原始路径
+                file_path = PathBuf::from(&content);
+            }
+            if file_path.exists() {
+                report.file_bytes_scrubbed = shred_path(&file_path)
+                    .map_err(|e| format!("安全删除文件失败 {:?}: {}", file_path, e))?;
+                println!(
+                    "🧨 已覆写并删除隐私文件: {:?} ({} 字节)",
+                    file_path, report.file_bytes_scrubbed
+                );
+            }
+        }
+    }
+
+    // 同一次复制中保存的图片表示
+    if let Some(image) = super::get_item_format(id, super::FORMAT_IMAGE)? {
+        let image_path = resolve_item_file_path(&image);
+        if image_path.exists() {
+            report.file_bytes_scrubbed += shred_path(&image_path)
+                .map_err(|e| format!("安全删除文件失败 {:?}: {}", image_path, e))?;
+        }
+    }
+
+    // 2. 删除数据库记录
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let (related_rows, rows) = delete_item_rows(&tx, id).map_err(|e| e.to_string())?;
+    report.related_rows_deleted = related_rows;
+    report.rows_deleted = rows;
+    tx.execute(
+        "UPDATE folders SET num_items = (SELECT COUNT(*) FROM folder_items WHERE folder_items.folder_id = folders.id)",
+        [],
+    )
+    .map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(report)
+}
+
+/// 安全删除单条数据。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// id: &str - 要删除数据的 ID
+/// # Returns
+/// String - 删除结果的 JSON 字符串（rows_deleted, related_rows_deleted, file_bytes_scrubbed）
+#[tauri::command]
+pub fn secure_delete_item(id: &str) -> Result<String, String> {
+    let report = secure_delete_data_by_id(id)?;
+    serde_json::to_string(&report).map_err(|e| e.to_string())
+}
+
+/// 在开启 secure_delete 的情况下整理数据库（VACUUM），清除空闲页中残留的已删除内容。
+/// 作为 Tauri command 暴露给前端调用。
+/// # Returns
+/// String - 整理结果的 JSON 字符串（free_pages, page_size, bytes_scrubbed）
+#[tauri::command]
+pub fn secure_vacuum_database() -> Result<String, String> {
+    let report = secure_vacuum()?;
+    println!(
+        "🧹 数据库安全整理完成: 清除 {} 个空闲页，共 {} 字节",
+        report.free_pages, report.bytes_scrubbed
+    );
+    serde_json::to_string(&report).map_err(|e| e.to_string())
+}
+
+/// 安全整理数据库的内部实现
+/// # Returns
+/// Result<VacuumReport, String> - 整理结果，若失败则返回错误信息
+pub fn secure_vacuum() -> Result<VacuumReport, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+    conn.pragma_update(None, "secure_delete", true)
+        .map_err(|e| e.to_string())?;
+
+    let page_size: i64 = conn
+        .query_row("PRAGMA page_size", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    let free_pages: i64 = conn
+        .query_row("PRAGMA freelist_count", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    conn.execute_batch("VACUUM").map_err(|e| e.to_string())?;
+
+    Ok(VacuumReport {
+        free_pages,
+        page_size,
+        bytes_scrubbed: free_pages * page_size,
+    })
+}
@@ -0,0 +1,173 @@
+use super::audit::{audit_private_items, AuditEvent};
+use super::{get_db_path, init_db};
+use crate::classify::{self, Classification, ContentSubtype};
+use crate::clipboard::{clipboard_items_to_json, ClipboardItem};
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// 配置中是否开启了自动分类（配置未初始化时按默认值开启）
+pub(crate) fn auto_classify_enabled() -> bool {
+    crate::config::CONFIG
+        .get()
+        .map(|lock| lock.read().unwrap().auto_classify)
+        .unwrap_or(true)
+}
+
+/// 对数据项分类并保存子类型（供插入、修改内容时在已有连接上调用）。
+/// 非文本数据或无法归类的文本会清除已有的子类型。
+/// # Param
+/// conn: &Connection - 数据库连接
+/// item: &ClipboardItem - 数据项
+/// # Returns
+/// Result<Option<Classification>, String> - 分类结果
+pub(crate) fn classify_and_store(
+    conn: &Connection,
+    item: &ClipboardItem,
+) -> Result<Option<Classification>, String> {
+    let classification = if item.item_type == "text" {
+        classify::classify_text(&item.content)
+    } else {
+        None
+    };
+
+    match &classification {
+        Some(c) => conn.execute(
+            "INSERT OR REPLACE INTO item_subtype (item_id, subtype, language) VALUES (?1, ?2, ?3)",
+            params![item.id, c.subtype.as_str(), c.language],
+        ),
+        None => conn.execute(
+            "DELETE FROM item_subtype WHERE item_id = ?1",
+            params![item.id],
+        ),
+    }
+    .map_err(|e| e.to_string())?;
+
+    Ok(classification)
+}
+
+/// 获取数据项的子类型，未分类时返回 None。
+/// # Param
+/// item_id: &str - 数据项 ID
+pub fn get_item_subtype(item_id: &str) -> Result<Option<Classification>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let row: Option<(String, Option<String>)> = conn
+        .query_row(
+            "SELECT subtype, language FROM item_subtype WHERE item_id = ?1",
+            params![item_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    Ok(row.and_then(|(subtype, language)| {
+        ContentSubtype::parse(&subtype).map(|subtype| Classification { subtype, language })
+    }))
+}
+
+/// 根据 item ID 获取子类型。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// # Returns
+/// String - 子类型的 JSON 字符串（subtype, language），未分类时为 "null"
+#[tauri::command]
+pub fn get_item_subtype_by_item_id(item_id: &str) -> Result<String, String> {
+    let subtype = get_item_subtype(item_id)?;
+    serde_json::to_string(&subtype).map_err(|e| e.to_string())
+}
+
+/// 按子类型筛选数据。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// subtype: &str - 子类型（url/email/phone/code/json/color/path/number/date/markdown）
+/// language: Option<&str> - 可选的代码语言过滤（仅对 code 有意义）
+/// # Returns
+/// String - 包含筛选后数据记录的 JSON 字符串
+#[tauri::command]
+pub fn filter_data_by_subtype(subtype: &str, language: Option<&str>) -> Result<String, String> {
+    let subtype = ContentSubtype::parse(subtype).ok_or_else(|| format!("未知的子类型: {}", subtype))?;
+
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT data.id, data.item_type, data.content, data.size, data.is_favorite, data.notes, data.timestamp
+             FROM data
+             JOIN item_subtype ON data.id = item_subtype.item_id
+             WHERE item_subtype.subtype = ?1 AND (?2 IS NULL OR item_subtype.language = ?2 COLLATE NOCASE)
+             ORDER BY data.timestamp DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let clipboard_iter = stmt
+        .query_map(params![subtype.as_str(), language], |row| {
+            Ok(ClipboardItem {
+                id: row.get(0)?,
+                item_type: row.get(1)?,
+                content: row.get(2)?,
+                size: row.get::<_, Option<i64>>(3)?.map(|v| v as u64),
+                is_favorite: row.get::<_, i32>(4)? != 0,
+                notes: row.get(5)?,
+                timestamp: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut results = Vec::new();
+    for item in clipboard_iter {
+        results.push(item.map_err(|e| e.to_string())?);
+    }
+
+    // 列表中包含隐私数据的完整内容，为其记录查看事件
+    let ids: Vec<String> = results.iter().map(|item| item.id.clone()).collect();
+    audit_private_items(AuditEvent::Viewed, &ids, "filter_data_by_subtype");
+
+    clipboard_items_to_json(results)
+}
+
+/// 为尚未分类的历史文本数据补充子类型（一次性迁移）。作为 Tauri command 暴露给前端调用。
+/// # Returns
+/// usize - 成功归类的数据条数
+#[tauri::command]
+pub fn backfill_item_subtypes() -> Result<usize, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let mut conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let items: Vec<ClipboardItem> = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, item_type, content, size, is_favorite, notes, timestamp FROM data
+                 WHERE item_type = 'text' AND id NOT IN (SELECT item_id FROM item_subtype)",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(ClipboardItem {
+                    id: row.get(0)?,
+                    item_type: row.get(1)?,
+                    content: row.get(2)?,
+                    size: row.get::<_, Option<i64>>(3)?.map(|v| v as u64),
+                    is_favorite: row.get::<_, i32>(4)? != 0,
+                    notes: row.get(5)?,
+                    timestamp: row.get(6)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?
+    };
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut classified = 0;
+    for item in &items {
+        if classify_and_store(&tx, item)?.is_some() {
+            classified += 1;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    println!("🏷️ 子类型回填完成: {} / {} 条文本已归类", classified, items.len());
+    Ok(classified)
+}
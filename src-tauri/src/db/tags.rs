@@ -0,0 +1,217 @@
+use super::audit::{audit_private_items, AuditEvent};
+use super::{get_db_path, init_db};
+use crate::clipboard::{clipboard_items_to_json, ClipboardItem};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// 由 AI 生成的标签
+pub const TAG_SOURCE_AI: &str = "ai";
+
+/// 数据项的 AI 标注：标签与摘要
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AiAnnotations {
+    pub tags: Vec<String>,
+    pub summary: Option<String>,
+}
+
+/// 保存 AI 生成的标签与摘要。传入的项会替换该数据项原有的 AI 结果，为 None 的项保持不变；数据项不存在时不写入。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// tags: Option<&[String]> - 标签
+/// summary: Option<&str> - 摘要
+pub fn save_ai_annotations(
+    item_id: &str,
+    tags: Option<&[String]>,
+    summary: Option<&str>,
+) -> Result<(), String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let mut conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let exists: bool = tx
+        .query_row("SELECT EXISTS (SELECT 1 FROM data WHERE id = ?1)", params![item_id], |row| {
+            row.get(0)
+        })
+        .map_err(|e| e.to_string())?;
+    if !exists {
+        return Ok(());
+    }
+
+    if let Some(tags) = tags {
+        tx.execute(
+            "DELETE FROM item_tags WHERE item_id = ?1 AND source = ?2",
+            params![item_id, TAG_SOURCE_AI],
+        )
+        .map_err(|e| e.to_string())?;
+        for tag in tags {
+            tx.execute(
+                "INSERT OR IGNORE INTO item_tags (item_id, tag, source) VALUES (?1, ?2, ?3)",
+                params![item_id, tag, TAG_SOURCE_AI],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    if let Some(summary) = summary {
+        tx.execute(
+            "INSERT INTO extended_data (item_id, ai_summary) VALUES (?1, ?2)
+             ON CONFLICT(item_id) DO UPDATE SET ai_summary = excluded.ai_summary",
+            params![item_id, summary],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// 获取数据项的标签，按添加顺序排列
+/// # Param
+/// item_id: &str - 数据项 ID
+pub fn get_item_tags(item_id: &str) -> Result<Vec<String>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare("SELECT tag FROM item_tags WHERE item_id = ?1 ORDER BY rowid")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![item_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<String>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 获取数据项的 AI 摘要
+/// # Param
+/// item_id: &str - 数据项 ID
+pub fn get_ai_summary(item_id: &str) -> Result<Option<String>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.query_row(
+        "SELECT ai_summary FROM extended_data WHERE item_id = ?1",
+        params![item_id],
+        |row| row.get(0),
+    )
+    .optional()
+    .map(Option::flatten)
+    .map_err(|e| e.to_string())
+}
+
+/// 批量获取列表中各数据项的标签与摘要。隐私数据不返回。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// item_ids: Vec<String> - 数据项 ID 列表
+/// # Returns
+/// HashMap<String, AiAnnotations> - 以数据项 ID 为键，只包含有标签或摘要的数据项
+#[tauri::command]
+pub fn get_ai_annotations(item_ids: Vec<String>) -> Result<HashMap<String, AiAnnotations>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let mut tag_stmt = conn
+        .prepare("SELECT tag FROM item_tags WHERE item_id = ?1 ORDER BY rowid")
+        .map_err(|e| e.to_string())?;
+    let mut summary_stmt = conn
+        .prepare("SELECT ai_summary FROM extended_data WHERE item_id = ?1")
+        .map_err(|e| e.to_string())?;
+    let mut private_stmt = conn
+        .prepare("SELECT EXISTS (SELECT 1 FROM private_data WHERE item_id = ?1)")
+        .map_err(|e| e.to_string())?;
+
+    let mut result = HashMap::new();
+    for item_id in item_ids {
+        let is_private: bool = private_stmt
+            .query_row(params![item_id], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        if is_private {
+            continue;
+        }
+        let tags = tag_stmt
+            .query_map(params![item_id], |row| row.get(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(|e| e.to_string())?;
+        let summary: Option<String> = summary_stmt
+            .query_row(params![item_id], |row| row.get(0))
+            .optional()
+            .map(Option::flatten)
+            .map_err(|e| e.to_string())?;
+        if !tags.is_empty() || summary.is_some() {
+            result.insert(item_id, AiAnnotations { tags, summary });
+        }
+    }
+    Ok(result)
+}
+
+/// 获取所有标签及使用次数。作为 Tauri command 暴露给前端调用。
+/// # Returns
+/// Vec<(String, usize)> - (标签, 数据项数量)，按数量从多到少排列
+#[tauri::command]
+pub fn get_all_tags() -> Result<Vec<(String, usize)>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT tag, COUNT(*) AS n FROM item_tags
+             WHERE item_id NOT IN (SELECT item_id FROM private_data)
+             GROUP BY tag ORDER BY n DESC, tag",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 按标签筛选数据。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// tag: &str - 标签（忽略大小写）
+/// # Returns
+/// String - 包含筛选后数据记录的 JSON 字符串
+#[tauri::command]
+pub fn filter_data_by_tag(tag: &str) -> Result<String, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT data.id, data.item_type, data.content, data.size, data.is_favorite, data.notes, data.timestamp
+             FROM data
+             WHERE EXISTS (SELECT 1 FROM item_tags WHERE item_tags.item_id = data.id AND item_tags.tag = ?1 COLLATE NOCASE)
+               AND NOT EXISTS (SELECT 1 FROM private_data WHERE private_data.item_id = data.id)
+             ORDER BY data.timestamp DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let clipboard_iter = stmt
+        .query_map(params![tag.trim()], |row| {
+            Ok(ClipboardItem {
+                id: row.get(0)?,
+                item_type: row.get(1)?,
+                content: row.get(2)?,
+                size: row.get::<_, Option<i64>>(3)?.map(|v| v as u64),
+                is_favorite: row.get::<_, i32>(4)? != 0,
+                notes: row.get(5)?,
+                timestamp: row.get(6)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut results = Vec::new();
+    for item in clipboard_iter {
+        results.push(item.map_err(|e| e.to_string())?);
+    }
+
+    // 列表中包含隐私数据的完整内容，为其记录查看事件
+    let ids: Vec<String> = results.iter().map(|item| item.id.clone()).collect();
+    audit_private_items(AuditEvent::Viewed, &ids, "filter_data_by_tag");
+
+    clipboard_items_to_json(results)
+}
@@ -0,0 +1,73 @@
+use super::{get_db_path, init_db};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// 缓存的译文
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedTranslation {
+    /// 目标语言代码
+    pub target_lang: String,
+    /// 检测到的原文语言代码，无法判断时为 None
+    pub source_lang: Option<String>,
+    /// 原文的哈希，原文变化（如编辑内容、重新 OCR）后缓存失效
+    pub source_hash: String,
+    /// 译文
+    pub content: String,
+    /// 翻译时间（毫秒时间戳）
+    pub timestamp: i64,
+}
+
+/// 缓存数据项的译文，同一目标语言的旧译文会被替换；数据项不存在时不写入。
+/// # Param
+/// item_id: &str - 数据项 ID
+/// translation: &CachedTranslation - 译文
+pub fn save_translation(item_id: &str, translation: &CachedTranslation) -> Result<(), String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO item_translations (item_id, target_lang, source_lang, source_hash, content, timestamp)
+         SELECT ?1, ?2, ?3, ?4, ?5, ?6 WHERE EXISTS (SELECT 1 FROM data WHERE id = ?1)
+         ON CONFLICT(item_id, target_lang) DO UPDATE SET
+             source_lang = excluded.source_lang, source_hash = excluded.source_hash,
+             content = excluded.content, timestamp = excluded.timestamp",
+        params![
+            item_id,
+            translation.target_lang,
+            translation.source_lang,
+            translation.source_hash,
+            translation.content,
+            translation.timestamp
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 获取数据项缓存的译文
+/// # Param
+/// item_id: &str - 数据项 ID
+/// target_lang: &str - 目标语言代码
+pub fn get_translation(item_id: &str, target_lang: &str) -> Result<Option<CachedTranslation>, String> {
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).map_err(|e| e.to_string())?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+
+    conn.query_row(
+        "SELECT target_lang, source_lang, source_hash, content, timestamp FROM item_translations
+         WHERE item_id = ?1 AND target_lang = ?2",
+        params![item_id, target_lang],
+        |row| {
+            Ok(CachedTranslation {
+                target_lang: row.get(0)?,
+                source_lang: row.get(1)?,
+                source_hash: row.get(2)?,
+                content: row.get(3)?,
+                timestamp: row.get(4)?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,230 @@
+//! 文件内容提取。
+//! 记录文件时提取常见文档的文本：PDF 文本层、DOCX/ODT 正文 XML、Markdown/TXT/CSV 与源代码等纯文本文件。
+//! 提取结果保存在 extended_data.document_text 中，文件数据项因此可以像图片的 OCR 文本一样被全文搜索。
+use crate::db;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+/// PDF、DOCX、ODT 文档超过该大小时不提取
+const MAX_DOCUMENT_BYTES: u64 = 20 * 1024 * 1024;
+/// 纯文本文件最多读取的字节数，超出部分忽略
+const MAX_TEXT_FILE_BYTES: u64 = 1024 * 1024;
+/// DOCX/ODT 正文 XML 解压后最多读取的字节数，防止压缩炸弹
+const MAX_XML_BYTES: u64 = 32 * 1024 * 1024;
+/// 保存的文本最多字符数
+pub const MAX_EXTRACTED_CHARS: usize = 100_000;
+
+/// 按纯文本读取的扩展名（文档、数据与常见源代码）
+const TEXT_EXTENSIONS: &[&str] = &[
+    "txt", "md", "markdown", "rst", "log", "csv", "tsv", "json", "xml", "yaml", "yml", "toml",
+    "ini", "cfg", "conf", "html", "htm", "css", "scss", "less", "tex", "rs", "py", "js", "jsx",
+    "ts", "tsx", "vue", "java", "kt", "c", "h", "cpp", "hpp", "cc", "cs", "go", "rb", "php",
+    "swift", "sh", "bat", "ps1", "sql", "lua",
+];
+
+/// 支持提取文本的文件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    Pdf,
+    Docx,
+    Odt,
+    PlainText,
+}
+
+/// 根据扩展名（忽略大小写）判断文件类型
+/// # Param
+/// path: &Path - 文件路径
+/// # Returns
+/// Option<DocumentKind> - 文件类型，不支持时返回 None
+pub fn document_kind(path: &Path) -> Option<DocumentKind> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "pdf" => Some(DocumentKind::Pdf),
+        "docx" => Some(DocumentKind::Docx),
+        "odt" => Some(DocumentKind::Odt),
+        _ if TEXT_EXTENSIONS.contains(&ext.as_str()) => Some(DocumentKind::PlainText),
+        _ => None,
+    }
+}
+
+/// 提取文件中的文本
+/// # Param
+/// path: &Path - 文件的绝对路径
+/// # Returns
+/// Option<String> - 整理后的文本；不支持的类型、超过大小限制、二进制内容或没有文字时返回 None
+pub fn extract_text(path: &Path) -> Result<Option<String>, String> {
+    let Some(kind) = document_kind(path) else {
+        return Ok(None);
+    };
+    let metadata = fs::metadata(path).map_err(|e| e.to_string())?;
+    if !metadata.is_file() {
+        return Ok(None);
+    }
+    if kind != DocumentKind::PlainText && metadata.len() > MAX_DOCUMENT_BYTES {
+        println!("⚠️ 文件过大，跳过内容提取: {:?}", path);
+        return Ok(None);
+    }
+
+    let raw = match kind {
+        DocumentKind::Pdf => Some(extract_pdf(path)?),
+        DocumentKind::Docx => Some(extract_office_xml(path, "word/document.xml")?),
+        DocumentKind::Odt => Some(extract_office_xml(path, "content.xml")?),
+        DocumentKind::PlainText => read_text_file(path)?,
+    };
+    Ok(raw.and_then(|raw| normalize_text(&raw)))
+}
+
+/// 读取纯文本文件的开头部分。按 BOM 识别 UTF-16，非 UTF-8 内容按 GBK 解码；含 NUL 字节时视为二进制文件
+fn read_text_file(path: &Path) -> Result<Option<String>, String> {
+    let mut bytes = Vec::new();
+    File::open(path)
+        .map_err(|e| e.to_string())?
+        .take(MAX_TEXT_FILE_BYTES)
+        .read_to_end(&mut bytes)
+        .map_err(|e| e.to_string())?;
+
+    if let Some((encoding, bom_len)) = encoding_rs::Encoding::for_bom(&bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return Ok(Some(text.into_owned()));
+    }
+    if bytes.contains(&0) {
+        return Ok(None);
+    }
+    Ok(Some(decode_text(&bytes)))
+}
+
+/// 将字节解码为文本；读取上限可能截断末尾的多字节字符，截断部分直接丢弃
+pub(crate) fn decode_text(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(e) if e.error_len().is_none() => {
+            String::from_utf8_lossy(&bytes[..e.valid_up_to()]).into_owned()
+        }
+        Err(_) => encoding_rs::GBK.decode(bytes).0.into_owned(),
+    }
+}
+
+/// 提取 PDF 的文本层（扫描版 PDF 没有文本层，结果为空）
+fn extract_pdf(path: &Path) -> Result<String, String> {
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    // pdf-extract 遇到不规范的文件时可能 panic，不能让它影响记录线程
+    match std::panic::catch_unwind(|| pdf_extract::extract_text_from_mem(&bytes)) {
+        Ok(Ok(text)) => Ok(text),
+        Ok(Err(e)) => Err(format!("PDF 解析失败: {}", e)),
+        Err(_) => Err("PDF 解析失败: 文件格式不受支持".to_string()),
+    }
+}
+
+/// 读取 DOCX/ODT 压缩包中的正文 XML 并转换为文本
+fn extract_office_xml(path: &Path, entry: &str) -> Result<String, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("无法读取文档: {}", e))?;
+    let mut xml = String::new();
+    archive
+        .by_name(entry)
+        .map_err(|e| format!("文档中缺少 {}: {}", entry, e))?
+        .take(MAX_XML_BYTES)
+        .read_to_string(&mut xml)
+        .map_err(|e| e.to_string())?;
+    xml_to_text(&xml)
+}
+
+/// 将 DOCX（w:）或 ODT（text:）正文 XML 转换为文本：段落与换行转为换行符，制表符与空格元素保留，
+/// 修订中被删除的文字与域代码不计入
+pub(crate) fn xml_to_text(xml: &str) -> Result<String, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut text = String::new();
+    let mut skip_depth = 0usize;
+
+    loop {
+        match reader.read_event().map_err(|e| e.to_string())? {
+            Event::Start(e) => {
+                if matches!(e.name().as_ref(), b"w:delText" | b"w:instrText") {
+                    skip_depth += 1;
+                }
+            }
+            Event::End(e) => match e.name().as_ref() {
+                b"w:delText" | b"w:instrText" => skip_depth = skip_depth.saturating_sub(1),
+                b"w:p" | b"text:p" | b"text:h" => text.push('\n'),
+                _ => {}
+            },
+            Event::Empty(e) => match e.name().as_ref() {
+                b"w:tab" | b"text:tab" => text.push('\t'),
+                b"w:br" | b"w:cr" | b"text:line-break" => text.push('\n'),
+                b"w:p" | b"text:p" | b"text:h" => text.push('\n'),
+                b"text:s" => {
+                    // text:c 为连续空格数，限制上限以免异常文件展开出巨量空格
+                    let count: usize = e
+                        .try_get_attribute("text:c")
+                        .ok()
+                        .flatten()
+                        .and_then(|attr| attr.unescape_value().ok()?.parse().ok())
+                        .unwrap_or(1)
+                        .min(1024);
+                    text.extend(std::iter::repeat_n(' ', count));
+                }
+                _ => {}
+            },
+            Event::Text(t) if skip_depth == 0 => {
+                let content = t.unescape().map_err(|e| e.to_string())?;
+                // 忽略格式化 XML 时产生的缩进
+                if !(content.contains('\n') && content.trim().is_empty()) {
+                    text.push_str(&content);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        // 每个字符至多 4 字节，此时已足够截断出 MAX_EXTRACTED_CHARS 个字符，不再解析剩余内容
+        if text.len() >= MAX_EXTRACTED_CHARS * 4 {
+            break;
+        }
+    }
+    Ok(text)
+}
+
+/// 整理提取的文本：去掉行尾空白，合并连续空行，截断到 MAX_EXTRACTED_CHARS 个字符
+/// # Param
+/// raw: &str - 原始文本
+/// # Returns
+/// Option<String> - 整理后的文本，没有文字时返回 None
+pub fn normalize_text(raw: &str) -> Option<String> {
+    let mut lines: Vec<&str> = Vec::new();
+    for line in raw.lines() {
+        let line = line.trim_end();
+        if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+    if lines.is_empty() {
+        return None;
+    }
+    Some(lines.join("\n").chars().take(MAX_EXTRACTED_CHARS).collect())
+}
+
+/// 提取文件文本并写入 extended_data.document_text
+/// # Param
+/// item_id: &str - 文件数据项 ID
+/// path: &Path - 文件的绝对路径
+/// # Returns
+/// bool - 是否提取到文本
+pub fn extract_and_store(item_id: &str, path: &Path) -> Result<bool, String> {
+    match extract_text(path)? {
+        Some(text) => {
+            db::insert_document_text(item_id, &text)?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_document.rs"]
+mod test_document;
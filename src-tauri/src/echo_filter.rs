@@ -0,0 +1,172 @@
+//! 剪贴板回声抑制。
+//! 应用自身写入剪贴板后，监控线程会把这次写入当作一次新的复制。写入命令在写入前登记
+//! 所写内容的指纹，监控线程只忽略与登记指纹一致的变化，用户在此期间的其他复制照常记录。
+use crate::change_detect;
+use crate::clipboard_backend::ClipboardRepresentations;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 登记的指纹保留时长。每次登记只抵消一次匹配的变化，未被匹配的登记到期后移除
+pub const ECHO_TTL: Duration = Duration::from_secs(10);
+
+/// 剪贴板内容指纹，按表示区分
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentFingerprint {
+    Text(u64),
+    Files(u64),
+    Image(u64),
+}
+
+impl ContentFingerprint {
+    /// 文本指纹，统一换行符以兼容平台读写差异
+    pub fn text(text: &str) -> Self {
+        let mut hasher = DefaultHasher::new();
+        text.replace("\r\n", "\n").hash(&mut hasher);
+        ContentFingerprint::Text(hasher.finish())
+    }
+
+    /// 文件列表指纹，统一路径分隔符（Windows 下不区分大小写）
+    pub fn files(paths: &[PathBuf]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        for path in paths {
+            normalize_path(path).hash(&mut hasher);
+        }
+        ContentFingerprint::Files(hasher.finish())
+    }
+
+    /// 图片指纹，与 `ClipboardBackend::image_fingerprint` 的计算方式一致
+    pub fn image(hash: u64) -> Self {
+        ContentFingerprint::Image(hash)
+    }
+}
+
+fn normalize_path(path: &Path) -> String {
+    let s = path.to_string_lossy().replace('\\', "/");
+    let s = s.trim_end_matches('/').to_string();
+    if cfg!(target_os = "windows") {
+        s.to_lowercase()
+    } else {
+        s
+    }
+}
+
+/// 计算一次写入的全部指纹。图片需要解码文件，解码失败时跳过图片指纹
+/// # Param
+/// reps: &ClipboardRepresentations - 写入剪贴板的全部表示
+/// # Returns
+/// Vec<ContentFingerprint> - 各表示的指纹
+pub fn fingerprints_of(reps: &ClipboardRepresentations) -> Vec<ContentFingerprint> {
+    let mut fingerprints = Vec::new();
+    if let Some(text) = reps.text.as_deref().filter(|t| !t.is_empty()) {
+        fingerprints.push(ContentFingerprint::text(text));
+    }
+    if !reps.files.is_empty() {
+        fingerprints.push(ContentFingerprint::files(&reps.files));
+    }
+    if let Some(image) = reps.image_path.as_ref().and_then(|p| image::open(p).ok()) {
+        let rgba = image.to_rgba8();
+        fingerprints.push(ContentFingerprint::image(change_detect::image_fingerprint(
+            rgba.width(),
+            rgba.height(),
+            rgba.as_raw(),
+        )));
+    }
+    fingerprints
+}
+
+/// 一次登记的凭据，写入失败时凭此撤销登记
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EchoTicket(u64);
+
+/// 一次已登记的写入
+#[derive(Debug)]
+struct PendingEcho {
+    ticket: EchoTicket,
+    fingerprints: Vec<ContentFingerprint>,
+    expires_at: Instant,
+}
+
+/// 记录应用自身写入剪贴板的内容，供监控线程判断变化是否为回声
+#[derive(Debug)]
+pub struct EchoFilter {
+    pending: Mutex<Vec<PendingEcho>>,
+    next_ticket: AtomicU64,
+}
+
+impl EchoFilter {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(Vec::new()),
+            next_ticket: AtomicU64::new(0),
+        }
+    }
+
+    /// 登记一次写入
+    /// # Param
+    /// fingerprints: Vec<ContentFingerprint> - 写入内容的指纹
+    /// # Returns
+    /// EchoTicket - 本次登记的凭据
+    pub fn register(&self, fingerprints: Vec<ContentFingerprint>) -> EchoTicket {
+        self.register_at(fingerprints, Instant::now())
+    }
+
+    pub fn register_at(&self, fingerprints: Vec<ContentFingerprint>, now: Instant) -> EchoTicket {
+        let ticket = EchoTicket(self.next_ticket.fetch_add(1, Ordering::Relaxed));
+        if fingerprints.is_empty() {
+            return ticket;
+        }
+        let mut pending = self.pending.lock().unwrap();
+        pending.retain(|p| p.expires_at > now);
+        pending.push(PendingEcho {
+            ticket,
+            fingerprints,
+            expires_at: now + ECHO_TTL,
+        });
+        ticket
+    }
+
+    /// 撤销一次登记。写入剪贴板失败时调用，避免之后用户复制相同内容时被当作回声忽略
+    /// # Param
+    /// ticket: EchoTicket - 登记时返回的凭据
+    pub fn withdraw(&self, ticket: EchoTicket) {
+        self.pending.lock().unwrap().retain(|p| p.ticket != ticket);
+    }
+
+    /// 判断剪贴板当前内容是否为已登记写入的回声：全部表示的指纹与某次登记的写入完全一致才视为回声，
+    /// 匹配到的登记随即移除
+    /// # Param
+    /// fingerprints: &[ContentFingerprint] - 剪贴板当前各表示的指纹
+    /// # Returns
+    /// bool - 是否应忽略本次变化
+    pub fn is_echo(&self, fingerprints: &[ContentFingerprint]) -> bool {
+        self.is_echo_at(fingerprints, Instant::now())
+    }
+
+    pub fn is_echo_at(&self, fingerprints: &[ContentFingerprint], now: Instant) -> bool {
+        let mut pending = self.pending.lock().unwrap();
+        pending.retain(|p| p.expires_at > now);
+        match pending
+            .iter()
+            .position(|p| same_fingerprints(&p.fingerprints, fingerprints))
+        {
+            Some(index) => {
+                pending.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// 两组指纹作为集合是否相等（不考虑顺序）
+fn same_fingerprints(a: &[ContentFingerprint], b: &[ContentFingerprint]) -> bool {
+    a.iter().all(|f| b.contains(f)) && b.iter().all(|f| a.contains(f))
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_echo_filter.rs"]
+mod test_echo_filter;
@@ -3,11 +3,29 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 // 声明模块
+mod ai;
+mod ai_queue;
 mod app_setup;
+mod barcode;
+mod capture;
+mod change_detect;
+mod classify;
 mod clipboard;
+mod clipboard_backend;
+mod clipboard_hints;
 mod config;
 mod db;
+mod document;
+mod echo_filter;
 mod ocr;
+mod ocr_queue;
+mod paste_queue;
+mod rich_text;
+mod semantic;
+mod snippet;
+mod source_app;
+mod transform;
+mod translate;
 mod utils;
 
 // 注册性能测试模块 (仅在测试模式下编译)
@@ -17,8 +35,9 @@ mod test_performance;
 
 use app_setup::{
     get_all_shortcuts, get_current_shortcut, update_shortcut, AppShortcutManager,
-    ClipboardSourceState,
+    CapturePauseState, ClipboardSourceState,
 };
+use paste_queue::PasteQueueState;
 use std::path::PathBuf;
 use std::sync::Mutex;
 use tauri::Manager;
@@ -39,9 +58,10 @@ fn main() {
         ))
         .plugin(tauri_plugin_notification::init())
         .manage(AppShortcutManager::new())
-        .manage(ClipboardSourceState {
-            is_frontend_copy: Mutex::new(false),
-        })
+        .manage(ClipboardSourceState::new())
+        .manage(CapturePauseState::new())
+        .manage(PasteQueueState::new())
+        .manage(ai::AiState::default())
         .manage(EncryptionState {
             dek: Mutex::new(None),
         })
@@ -55,6 +75,15 @@ fn main() {
             update_shortcut,
             get_current_shortcut,
             get_all_shortcuts,
+            app_setup::pause_capture,
+            app_setup::resume_capture,
+            app_setup::get_capture_status,
+            paste_queue::set_paste_queue_mode,
+            paste_queue::get_paste_queue,
+            paste_queue::reorder_paste_queue,
+            paste_queue::remove_from_paste_queue,
+            paste_queue::clear_paste_queue,
+            paste_queue::paste_next_in_queue,
             utils::get_file_icon,
             utils::write_files_to_clipboard,
             utils::export_to_zip,
@@ -73,6 +102,8 @@ fn main() {
             db::delete_all_data,
             db::delete_data,
             db::delete_data_by_id,
+            db::secure_delete_item,
+            db::secure_vacuum_database,
             db::update_data_content_by_id,
             db::set_favorite_status_by_id,
             db::favorite_data_by_id,
@@ -91,8 +122,33 @@ fn main() {
             db::filter_data_by_folder,
             db::get_folders_by_item_id,
             db::get_ocr_text_by_item_id,
+            db::get_ocr_result_by_item_id,
+            db::get_ocr_jobs,
+            db::get_barcodes_by_item_id,
+            db::get_document_text_by_item_id,
             db::search_data_by_ocr_text,
             db::get_icon_data_by_item_id,
+            db::get_item_source_by_item_id,
+            db::get_item_formats_by_item_id,
+            db::get_item_subtype_by_item_id,
+            db::filter_data_by_subtype,
+            db::backfill_item_subtypes,
+            db::get_ai_annotations,
+            db::get_all_tags,
+            db::filter_data_by_tag,
+            db::get_ai_job_stats,
+            db::get_item_links_by_item_id,
+            db::merge_items,
+            transform::transform_item,
+            transform::list_transforms,
+            translate::translate_item,
+            semantic::semantic_search,
+            semantic::get_semantic_index_stats,
+            snippet::validate_snippet,
+            snippet::create_snippet,
+            snippet::update_snippet,
+            snippet::preview_snippet,
+            snippet::copy_snippet,
             db::mark_passwords_as_private,
             db::prepare_encrypted_db_upload,
             db::restore_from_encrypted_db,
@@ -100,6 +156,8 @@ fn main() {
             db::mark_identity_numbers_as_private,
             db::mark_phone_numbers_as_private,
             db::clear_all_private_data,
+            db::get_audit_log,
+            db::export_audit_log,
             db::auto_mark_private_data,
             db::check_and_mark_private_item,
             db::trigger_cleanup,
@@ -116,6 +174,13 @@ fn main() {
             db::top_data_by_id,
             ocr::configure_ocr,
             ocr::ocr_image,
+            ocr::ocr_image_structured,
+            ocr::preview_ocr_preprocess,
+            ocr_queue::backfill_ocr,
+            ocr_queue::get_ocr_queue_stats,
+            barcode::copy_barcode_payload,
+            ai::ai_chat,
+            ai::cancel_ai_chat,
             config::get_config_json,
             config::set_config_item,
             config::get_config_item,
@@ -264,25 +329,21 @@ fn main() {
                 let cfg = lock.read().unwrap();
                 println!("👁️ 正在初始化 OCR 引擎...");
 
-                let provider = cfg.ocr_provider.clone();
-                let languages = cfg.ocr_languages.clone();
-
-                // 转换 Vec<String> 为 Vec<&str> 以匹配 configure_ocr 签名
-                let languages_ref: Option<Vec<&str>> = if let Some(ref langs) = languages {
-                    Some(langs.iter().map(|s| s.as_str()).collect())
-                } else {
-                    None
-                };
-
-                let confidence = cfg.ocr_confidence_threshold;
-                let timeout = cfg.ocr_timeout_secs;
-
-                match ocr::configure_ocr(provider, languages_ref, confidence, timeout) {
+                match ocr::init_from_config(&cfg) {
                     Ok(msg) => println!("✅ OCR引擎初始化成功: {}", msg),
                     Err(e) => eprintln!("❌ OCR引擎初始化失败: {}", e),
                 }
             }
 
+            // 9.1 启动 OCR 任务调度线程（继续执行上次未完成的任务）
+            ocr_queue::start_ocr_worker(app.handle().clone());
+
+            // 9.2 启动 AI 标签与摘要任务线程
+            ai_queue::start_ai_worker(app.handle().clone());
+
+            // 9.3 启动语义索引线程
+            semantic::start_semantic_indexer();
+
             // 打印当前配置的存储路径用于验证
             if let Some(lock) = config::CONFIG.get() {
                 let cfg = lock.read().unwrap();
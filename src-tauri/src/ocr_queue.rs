@@ -0,0 +1,205 @@
+//! OCR 任务队列。
+//! 新记录的图片与补全识别的图片写入 ocr_jobs 表，由后台调度线程按并发上限取出执行。
+//! 失败的任务按指数退避重试，数据项删除时任务随之取消；任务状态持久化，重启后继续执行。
+use crate::ai_queue;
+use crate::db::{self, OcrJob, OcrJobStats};
+use crate::ocr;
+use crate::utils;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// 同时执行的 OCR 任务上限
+pub const OCR_MAX_CONCURRENCY: usize = 2;
+/// 每个任务的最大尝试次数
+pub const OCR_MAX_ATTEMPTS: u32 = 3;
+/// 没有通知时检查到期重试任务的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+static OCR_QUEUE_SENDER: RwLock<Option<Sender<()>>> = RwLock::new(None);
+
+/// OCR 任务进度，作为 "ocr-job-progress" 事件的负载
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OcrJobProgress {
+    /// 状态发生变化的任务；补全识别入队或任务被取消时为 None
+    pub job: Option<OcrJob>,
+    /// 各状态的任务数量
+    pub stats: OcrJobStats,
+}
+
+fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// 唤醒调度线程
+fn notify_worker() {
+    if let Some(sender) = OCR_QUEUE_SENDER.read().unwrap().as_ref() {
+        let _ = sender.send(());
+    }
+}
+
+/// 通知前端任务进度
+fn emit_progress(app: &AppHandle, job: Option<OcrJob>) {
+    let stats = match db::get_ocr_job_stats() {
+        Ok(stats) => stats,
+        Err(e) => {
+            eprintln!("❌ 统计 OCR 任务失败: {}", e);
+            return;
+        }
+    };
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit("ocr-job-progress", OcrJobProgress { job, stats });
+    }
+}
+
+/// 为图片添加 OCR 任务并唤醒调度线程
+/// # Param
+/// item_id: &str - 图片数据项 ID
+pub fn enqueue(item_id: &str) -> Result<(), String> {
+    db::enqueue_ocr_job(item_id, now_ms())?;
+    notify_worker();
+    Ok(())
+}
+
+/// 启动 OCR 任务调度线程
+/// **功能**：
+/// - 启动时将上次中断的任务恢复为等待状态
+/// - 收到入队通知或每隔一段时间取出到期任务，同时执行的任务不超过 OCR_MAX_CONCURRENCY
+/// - OCR 引擎未配置时任务保持等待，配置完成后自动开始
+pub fn start_ocr_worker(app: AppHandle) {
+    let (tx, rx) = channel();
+    *OCR_QUEUE_SENDER.write().unwrap() = Some(tx);
+
+    match db::reset_running_ocr_jobs(now_ms()) {
+        Ok(count) if count > 0 => println!("👁️ 已恢复 {} 个中断的 OCR 任务", count),
+        Ok(_) => {}
+        Err(e) => eprintln!("❌ 恢复 OCR 任务失败: {}", e),
+    }
+
+    let running = Arc::new(AtomicUsize::new(0));
+    std::thread::spawn(move || {
+        println!("👁️ OCR 任务调度线程已启动");
+
+        loop {
+            match rx.recv_timeout(POLL_INTERVAL) {
+                Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => {
+                    println!("🛑 OCR 任务调度线程退出");
+                    break;
+                }
+            }
+            // 合并积累的通知
+            while rx.try_recv().is_ok() {}
+
+            if !ocr::is_configured() {
+                continue;
+            }
+
+            while running.load(Ordering::SeqCst) < OCR_MAX_CONCURRENCY {
+                let item_id = match db::claim_next_ocr_job(now_ms()) {
+                    Ok(Some(item_id)) => item_id,
+                    Ok(None) => break,
+                    Err(e) => {
+                        eprintln!("❌ 读取 OCR 任务失败: {}", e);
+                        break;
+                    }
+                };
+
+                running.fetch_add(1, Ordering::SeqCst);
+                let running = running.clone();
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    run_job(&app, &item_id).await;
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    notify_worker();
+                });
+            }
+        }
+    });
+}
+
+/// 执行一个已取出的任务
+async fn run_job(app: &AppHandle, item_id: &str) {
+    let item = match db::get_item_by_id(item_id) {
+        Ok(Some(item)) => item,
+        Ok(None) => {
+            // 数据项已被删除（如被自动清理），取消任务
+            if let Err(e) = db::cancel_ocr_job(item_id) {
+                eprintln!("❌ 取消 OCR 任务失败: {}", e);
+            }
+            emit_progress(app, None);
+            return;
+        }
+        Err(e) => {
+            record_failure(app, item_id, &e);
+            return;
+        }
+    };
+
+    let path = utils::resolve_absolute_path(&PathBuf::from(&item.content));
+    let result = match ocr::recognize(&path.to_string_lossy()).await {
+        Ok(result) => result,
+        Err(e) => {
+            record_failure(app, item_id, &e);
+            return;
+        }
+    };
+
+    // 识别期间数据项被删除时任务已被取消，不再保存结果
+    match db::complete_ocr_job(item_id, &result, now_ms()) {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            record_failure(app, item_id, &e);
+            return;
+        }
+    }
+    // 识别出的文字足够长时生成标签与摘要
+    if let Err(e) = ai_queue::enqueue_if_eligible(item_id, &result.text) {
+        eprintln!("❌ 添加 AI 任务失败: {}", e);
+    }
+
+    println!(
+        "✅ OCR识别成功: {} ({} 行, 置信度 {:?})",
+        item_id,
+        result.lines.len(),
+        result.confidence
+    );
+    emit_progress(app, db::get_ocr_job(item_id).ok().flatten());
+}
+
+/// 记录任务失败，未达到最大次数时稍后重试
+fn record_failure(app: &AppHandle, item_id: &str, error: &str) {
+    eprintln!("❌ OCR 识别失败 ({}): {}", item_id, error);
+    match db::fail_ocr_job(item_id, error, now_ms(), OCR_MAX_ATTEMPTS) {
+        Ok(Some(job)) => emit_progress(app, Some(job)),
+        Ok(None) => {}
+        Err(e) => eprintln!("❌ 更新 OCR 任务状态失败: {}", e),
+    }
+}
+
+/// 为所有尚无 OCR 文本的图片添加识别任务（包括此前识别失败的图片）。作为 Tauri command 暴露给前端调用。
+/// # Returns
+/// usize - 新添加的任务数量
+#[tauri::command]
+pub fn backfill_ocr(app: AppHandle) -> Result<usize, String> {
+    let item_ids = db::get_images_without_ocr()?;
+    let now = now_ms();
+    for item_id in &item_ids {
+        db::enqueue_ocr_job(item_id, now)?;
+    }
+    println!("👁️ 补全 OCR 识别: 添加了 {} 个任务", item_ids.len());
+    notify_worker();
+    emit_progress(&app, None);
+    Ok(item_ids.len())
+}
+
+/// 获取各状态的 OCR 任务数量。作为 Tauri command 暴露给前端调用。
+#[tauri::command]
+pub fn get_ocr_queue_stats() -> Result<OcrJobStats, String> {
+    db::get_ocr_job_stats()
+}
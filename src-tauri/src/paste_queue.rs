@@ -0,0 +1,353 @@
+//! 粘贴队列（顺序粘贴模式）。
+//! 开启后新记录的剪贴板内容按先后顺序进入队列，通过专用快捷键依次写回剪贴板，
+//! 适合先连续复制多个值、再逐个粘贴到表单中的场景。
+use crate::app_setup::ClipboardSourceState;
+use crate::capture::CapturedItem;
+use crate::clipboard::ClipboardItem;
+use crate::db;
+use crate::utils;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// 队列预览文本的最大字符数
+const PREVIEW_CHARS: usize = 40;
+
+/// 队列中的一项
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QueuedItem {
+    /// 数据项 ID
+    pub id: String,
+    /// 数据类型：text/image/file
+    pub item_type: String,
+    /// 简短预览（文本取前若干字符，文件取文件名）
+    pub preview: String,
+}
+
+impl QueuedItem {
+    pub fn from_item(item: &ClipboardItem) -> Self {
+        let preview = if item.item_type == "text" {
+            let line = item.content.split_whitespace().collect::<Vec<_>>().join(" ");
+            let mut preview: String = line.chars().take(PREVIEW_CHARS).collect();
+            if line.chars().count() > PREVIEW_CHARS {
+                preview.push('…');
+            }
+            preview
+        } else {
+            PathBuf::from(&item.content)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| item.content.clone())
+        };
+        Self {
+            id: item.id.clone(),
+            item_type: item.item_type.clone(),
+            preview,
+        }
+    }
+}
+
+/// 粘贴队列的状态快照，同时作为 "paste-queue-changed" 事件的负载
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PasteQueueStatus {
+    /// 是否处于队列模式（开启时新记录的内容会追加到队列）
+    pub enabled: bool,
+    /// 待粘贴的数据项，队首最先粘贴
+    pub items: VecDeque<QueuedItem>,
+    /// 本轮已粘贴的数量
+    pub pasted: usize,
+}
+
+/// 粘贴进度，作为 "paste-queue-progress" 事件的负载
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PasteQueueProgress {
+    /// 本次写入剪贴板的数据项
+    pub item: QueuedItem,
+    /// 本轮已粘贴的数量（包含本次）
+    pub pasted: usize,
+    /// 队列中剩余的数量
+    pub remaining: usize,
+    /// 本轮总数量
+    pub total: usize,
+}
+
+/// 管理粘贴队列
+pub struct PasteQueueState {
+    pub status: Mutex<PasteQueueStatus>,
+}
+impl PasteQueueState {
+    pub fn new() -> Self {
+        Self {
+            status: Mutex::new(PasteQueueStatus {
+                enabled: false,
+                items: VecDeque::new(),
+                pasted: 0,
+            }),
+        }
+    }
+
+    /// 开启或关闭队列模式。关闭后不再追加新内容，已在队列中的内容仍可继续粘贴。
+    /// 在队列为空时开启会重新开始计数。
+    pub fn set_enabled(&self, enabled: bool) -> PasteQueueStatus {
+        let mut status = self.status.lock().unwrap();
+        if enabled && !status.enabled && status.items.is_empty() {
+            status.pasted = 0;
+        }
+        status.enabled = enabled;
+        status.clone()
+    }
+
+    /// 当前是否处于队列模式
+    pub fn is_enabled(&self) -> bool {
+        self.status.lock().unwrap().enabled
+    }
+
+    /// 将数据项追加到队尾（仅在队列模式下生效）
+    /// # Returns
+    /// bool - 是否追加成功
+    pub fn push(&self, item: QueuedItem) -> bool {
+        let mut status = self.status.lock().unwrap();
+        if !status.enabled {
+            return false;
+        }
+        status.items.push_back(item);
+        true
+    }
+
+    /// 取出队首数据项并推进队列
+    /// # Returns
+    /// Option<PasteQueueProgress> - 队列为空时返回 None
+    pub fn advance(&self) -> Option<PasteQueueProgress> {
+        let item = self.pop_front()?;
+        Some(self.mark_pasted(item))
+    }
+
+    /// 取出队首数据项，不计入已粘贴数量（数据项可能已失效，需由调用方确认后再调用 mark_pasted）
+    /// # Returns
+    /// Option<QueuedItem> - 队列为空时返回 None
+    pub fn pop_front(&self) -> Option<QueuedItem> {
+        self.status.lock().unwrap().items.pop_front()
+    }
+
+    /// 将取出的数据项放回队首（写入剪贴板失败时使用）
+    /// # Param
+    /// item: QueuedItem - 取出后未能粘贴的数据项
+    pub fn push_front(&self, item: QueuedItem) {
+        self.status.lock().unwrap().items.push_front(item);
+    }
+
+    /// 记录一次成功的粘贴并返回进度；被跳过的数据项不会计入
+    /// # Param
+    /// item: QueuedItem - 已写入剪贴板的数据项
+    pub fn mark_pasted(&self, item: QueuedItem) -> PasteQueueProgress {
+        let mut status = self.status.lock().unwrap();
+        status.pasted += 1;
+        PasteQueueProgress {
+            item,
+            pasted: status.pasted,
+            remaining: status.items.len(),
+            total: status.pasted + status.items.len(),
+        }
+    }
+
+    /// 按给定的 ID 顺序重排队列
+    /// # Param
+    /// ids: &[String] - 新顺序，必须恰好包含队列中的全部 ID
+    pub fn reorder(&self, ids: &[String]) -> Result<PasteQueueStatus, String> {
+        let mut status = self.status.lock().unwrap();
+
+        let mut current: Vec<&str> = status.items.iter().map(|i| i.id.as_str()).collect();
+        let mut requested: Vec<&str> = ids.iter().map(|s| s.as_str()).collect();
+        current.sort_unstable();
+        requested.sort_unstable();
+        if current != requested {
+            return Err("新顺序必须包含队列中的全部数据项".to_string());
+        }
+
+        let mut remaining = std::mem::take(&mut status.items);
+        for id in ids {
+            if let Some(pos) = remaining.iter().position(|i| &i.id == id) {
+                if let Some(item) = remaining.remove(pos) {
+                    status.items.push_back(item);
+                }
+            }
+        }
+        Ok(status.clone())
+    }
+
+    /// 从队列中移除指定数据项（同一数据项多次入队时全部移除）
+    /// # Returns
+    /// bool - 是否有数据项被移除
+    pub fn remove(&self, id: &str) -> bool {
+        let mut status = self.status.lock().unwrap();
+        let before = status.items.len();
+        status.items.retain(|i| i.id != id);
+        status.items.len() != before
+    }
+
+    /// 清空队列并重置计数
+    pub fn clear(&self) -> PasteQueueStatus {
+        let mut status = self.status.lock().unwrap();
+        status.items.clear();
+        status.pasted = 0;
+        status.clone()
+    }
+
+    /// 获取当前状态
+    pub fn get_status(&self) -> PasteQueueStatus {
+        self.status.lock().unwrap().clone()
+    }
+}
+
+/// 通知前端队列内容已变化
+fn emit_paste_queue_changed(app: &AppHandle, status: &PasteQueueStatus) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.emit("paste-queue-changed", status.clone());
+    }
+}
+
+/// 队列模式下将新记录的内容追加到队列，供剪贴板监控线程调用。
+/// 队列自身写回剪贴板时会在 ClipboardSourceState 中登记所写内容，监控线程会跳过这些变化，因此不会重复入队。
+pub fn enqueue_captured(app: &AppHandle, items: &[CapturedItem]) {
+    let state = app.state::<PasteQueueState>();
+    if !state.is_enabled() {
+        return;
+    }
+    let mut changed = false;
+    for captured in items {
+        changed |= state.push(QueuedItem::from_item(&captured.item));
+    }
+    if changed {
+        emit_paste_queue_changed(app, &state.get_status());
+    }
+}
+
+/// 将队首数据项写入剪贴板并推进队列，供快捷键与前端调用。
+/// 已被删除的数据项会被跳过。
+/// # Returns
+/// Result<Option<PasteQueueProgress>, String> - 本次粘贴的进度，队列为空时返回 None
+pub fn paste_next(app: &AppHandle) -> Result<Option<PasteQueueProgress>, String> {
+    let state = app.state::<PasteQueueState>();
+    while let Some(queued) = state.pop_front() {
+        let Some(item) = db::get_item_by_id(&queued.id)? else {
+            println!("⚠️ 粘贴队列中的数据项已被删除，跳过: {}", queued.id);
+            continue;
+        };
+
+        let source_state = app.state::<ClipboardSourceState>();
+        let written = if item.item_type == "text" {
+            utils::write_to_clipboard(item.content, Some(item.id), app.clone(), source_state)
+                .map(|_| ())
+        } else {
+            let file_path = utils::resolve_absolute_path(&PathBuf::from(&item.content));
+            tauri::async_runtime::block_on(utils::write_file_to_clipboard(
+                app.clone(),
+                file_path.to_string_lossy().to_string(),
+                Some(item.id),
+                source_state,
+            ))
+            .map(|_| ())
+        };
+        // 写入失败时放回队首，下次粘贴时重试
+        if let Err(e) = written {
+            state.push_front(queued);
+            emit_paste_queue_changed(app, &state.get_status());
+            return Err(e);
+        }
+
+        let progress = state.mark_pasted(queued);
+        println!(
+            "📋 粘贴队列: {}/{}，剩余 {}",
+            progress.pasted, progress.total, progress.remaining
+        );
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.emit("paste-queue-progress", progress.clone());
+        }
+        return Ok(Some(progress));
+    }
+
+    println!("📋 粘贴队列为空");
+    Ok(None)
+}
+
+/// 开启或关闭队列模式。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// enabled: bool - 是否开启
+/// # Returns
+/// Result<PasteQueueStatus, String> - 修改后的队列状态
+#[tauri::command]
+pub fn set_paste_queue_mode(
+    app: AppHandle,
+    state: State<PasteQueueState>,
+    enabled: bool,
+) -> Result<PasteQueueStatus, String> {
+    let status = state.set_enabled(enabled);
+    emit_paste_queue_changed(&app, &status);
+    Ok(status)
+}
+
+/// 获取粘贴队列。作为 Tauri command 暴露给前端调用。
+#[tauri::command]
+pub fn get_paste_queue(state: State<PasteQueueState>) -> Result<PasteQueueStatus, String> {
+    Ok(state.get_status())
+}
+
+/// 重排粘贴队列。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// item_ids: Vec<String> - 新的粘贴顺序，必须包含队列中的全部数据项
+/// # Returns
+/// Result<PasteQueueStatus, String> - 重排后的队列状态
+#[tauri::command]
+pub fn reorder_paste_queue(
+    app: AppHandle,
+    state: State<PasteQueueState>,
+    item_ids: Vec<String>,
+) -> Result<PasteQueueStatus, String> {
+    let status = state.reorder(&item_ids)?;
+    emit_paste_queue_changed(&app, &status);
+    Ok(status)
+}
+
+/// 从粘贴队列中移除数据项。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// item_id: String - 数据项 ID
+/// # Returns
+/// Result<PasteQueueStatus, String> - 移除后的队列状态
+#[tauri::command]
+pub fn remove_from_paste_queue(
+    app: AppHandle,
+    state: State<PasteQueueState>,
+    item_id: String,
+) -> Result<PasteQueueStatus, String> {
+    if !state.remove(&item_id) {
+        return Err(format!("粘贴队列中没有该数据项: {}", item_id));
+    }
+    let status = state.get_status();
+    emit_paste_queue_changed(&app, &status);
+    Ok(status)
+}
+
+/// 清空粘贴队列。作为 Tauri command 暴露给前端调用。
+#[tauri::command]
+pub fn clear_paste_queue(
+    app: AppHandle,
+    state: State<PasteQueueState>,
+) -> Result<PasteQueueStatus, String> {
+    let status = state.clear();
+    emit_paste_queue_changed(&app, &status);
+    Ok(status)
+}
+
+/// 将队首数据项写入剪贴板并推进队列。作为 Tauri command 暴露给前端调用。
+/// # Returns
+/// Result<Option<PasteQueueProgress>, String> - 本次粘贴的进度，队列为空时返回 None
+#[tauri::command]
+pub fn paste_next_in_queue(app: AppHandle) -> Result<Option<PasteQueueProgress>, String> {
+    paste_next(&app)
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_paste_queue.rs"]
+mod test_paste_queue;
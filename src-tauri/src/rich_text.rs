@@ -0,0 +1,327 @@
+//! 富文本（HTML / RTF）处理：提取纯文本回退内容，用于显示与搜索。
+//! 仅做轻量解析，不追求完整还原排版。
+use encoding_rs::{Encoding, WINDOWS_1252};
+
+/// 去除 Windows CF_HTML 的头部（Version/StartHTML/... 行），只保留片段内容
+/// # Param
+/// html: &str - 剪贴板中读取到的 HTML
+/// # Returns
+/// String - 去除头部后的 HTML
+pub fn strip_cf_html_header(html: &str) -> String {
+    if !html.starts_with("Version:") {
+        return html.to_string();
+    }
+    if let (Some(start), Some(end)) = (
+        html.find("<!--StartFragment-->"),
+        html.find("<!--EndFragment-->"),
+    ) {
+        let start = start + "<!--StartFragment-->".len();
+        if start <= end {
+            return html[start..end].to_string();
+        }
+    }
+    match html.find('<') {
+        Some(idx) => html[idx..].to_string(),
+        None => String::new(),
+    }
+}
+
+/// 将 HTML 转为纯文本：块级标签换行、去除标签与 script/style、解码常见实体
+/// # Param
+/// html: &str - HTML 内容
+/// # Returns
+/// String - 纯文本
+pub fn html_to_text(html: &str) -> String {
+    let html = strip_cf_html_header(html);
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html.as_str();
+
+    while let Some(lt) = rest.find('<') {
+        out.push_str(&rest[..lt]);
+        rest = &rest[lt..];
+        let Some(gt) = rest.find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = rest[1..gt].trim().to_lowercase();
+        let name: String = tag
+            .trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect();
+        rest = &rest[gt + 1..];
+
+        // 跳过 script/style 的内容
+        if !tag.starts_with('/') && (name == "script" || name == "style") {
+            let close = format!("</{}", name);
+            match rest.to_ascii_lowercase().find(&close) {
+                Some(idx) => {
+                    rest = &rest[idx..];
+                    if let Some(end) = rest.find('>') {
+                        rest = &rest[end + 1..];
+                    }
+                }
+                None => rest = "",
+            }
+            continue;
+        }
+
+        match name.as_str() {
+            "br" | "p" | "div" | "tr" | "li" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+            | "table" | "ul" | "ol"
+                if !out.ends_with('\n') && !out.is_empty() =>
+            {
+                out.push('\n');
+            }
+            "td" | "th" if tag.starts_with('/') => out.push('\t'),
+            _ => {}
+        }
+    }
+    out.push_str(rest);
+
+    let decoded = decode_entities(&out);
+    decoded
+        .lines()
+        .map(|l| l.trim_end_matches(['\t', ' ']).trim_start())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// 解码常见 HTML 实体（含数字实体）
+fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';').filter(|&semi| semi <= 10) else {
+            out.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" | "#39" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// 将 Windows 代码页编号映射为对应的编码，未知代码页按 Windows-1252 处理
+/// # Param
+/// code_page: i32 - \ansicpg 指定的代码页
+/// # Returns
+/// &'static Encoding - 编码
+fn code_page_encoding(code_page: i32) -> &'static Encoding {
+    let label = match code_page {
+        866 => "ibm866",
+        874 => "windows-874",
+        932 => "shift_jis",
+        936 => "gbk",
+        949 => "euc-kr",
+        950 => "big5",
+        1250..=1258 => return Encoding::for_label(format!("windows-{}", code_page).as_bytes()).unwrap_or(WINDOWS_1252),
+        10000 => "macintosh",
+        20866 => "koi8-r",
+        21866 => "koi8-u",
+        28591..=28606 => return Encoding::for_label(format!("iso-8859-{}", code_page - 28590).as_bytes()).unwrap_or(WINDOWS_1252),
+        54936 => "gb18030",
+        65001 => "utf-8",
+        _ => "windows-1252",
+    };
+    Encoding::for_label(label.as_bytes()).unwrap_or(WINDOWS_1252)
+}
+
+/// RTF 解析时的输出缓冲：连续的 \'hh 字节先累积，遇到其他内容时再按代码页整体解码，
+/// 以支持 GBK、Shift_JIS 等多字节编码
+struct RtfOutput {
+    text: String,
+    bytes: Vec<u8>,
+    encoding: &'static Encoding,
+}
+
+impl RtfOutput {
+    fn flush(&mut self) {
+        if !self.bytes.is_empty() {
+            let (decoded, _, _) = self.encoding.decode(&self.bytes);
+            self.text.push_str(&decoded);
+            self.bytes.clear();
+        }
+    }
+
+    fn push(&mut self, c: char) {
+        self.flush();
+        self.text.push(c);
+    }
+}
+
+/// 将 RTF 转为纯文本：去除控制字与嵌套的目标组（字体表、颜色表等），处理 \par、
+/// 按 \ansicpg 代码页解码的 \'hh 转义，以及 \uN Unicode 字符（跳过其后 \ucN 个替代字符）
+/// # Param
+/// rtf: &str - RTF 内容
+/// # Returns
+/// String - 纯文本
+pub fn rtf_to_text(rtf: &str) -> String {
+    let chars: Vec<char> = rtf.chars().collect();
+    let mut out = RtfOutput {
+        text: String::new(),
+        bytes: Vec::new(),
+        encoding: WINDOWS_1252,
+    };
+    // 每层分组的 (是否为需要忽略的目标组, \uc 替代字符数)
+    let mut group_stack: Vec<(bool, usize)> = Vec::new();
+    let mut skipping = false;
+    let mut uc = 1;
+    // \uN 之后尚需跳过的替代字符数
+    let mut fallback_left = 0;
+    // 等待低位代理项的高位代理项
+    let mut high_surrogate: Option<u16> = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '{' => {
+                group_stack.push((skipping, uc));
+                fallback_left = 0;
+                i += 1;
+            }
+            '}' => {
+                (skipping, uc) = group_stack.pop().unwrap_or((false, 1));
+                fallback_left = 0;
+                i += 1;
+            }
+            '\\' => {
+                i += 1;
+                let Some(&next) = chars.get(i) else { break };
+                if next.is_ascii_alphabetic() {
+                    let start = i;
+                    while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                        i += 1;
+                    }
+                    let word: String = chars[start..i].iter().collect();
+                    // 可选的数字参数
+                    let param_start = i;
+                    if i < chars.len() && (chars[i] == '-' || chars[i].is_ascii_digit()) {
+                        i += 1;
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                    let param: Option<i32> = chars[param_start..i]
+                        .iter()
+                        .collect::<String>()
+                        .parse()
+                        .ok();
+                    // 控制字后的一个空格是分隔符
+                    if i < chars.len() && chars[i] == ' ' {
+                        i += 1;
+                    }
+                    if word != "u" && fallback_left > 0 {
+                        // 替代内容中的控制字同样计为一个字符
+                        fallback_left -= 1;
+                        continue;
+                    }
+                    match word.as_str() {
+                        "fonttbl" | "colortbl" | "stylesheet" | "info" | "pict" | "header"
+                        | "footer" | "generator" => skipping = true,
+                        "ansicpg" => {
+                            out.flush();
+                            out.encoding = code_page_encoding(param.unwrap_or(1252));
+                        }
+                        "uc" => uc = param.unwrap_or(1).max(0) as usize,
+                        "u" => {
+                            // 参数为有符号 16 位整数，负数表示 32768 以上的码位
+                            let unit = param.unwrap_or(0).rem_euclid(65536) as u16;
+                            fallback_left = uc;
+                            if skipping {
+                                continue;
+                            }
+                            match (high_surrogate.take(), unit) {
+                                (_, 0xD800..=0xDBFF) => high_surrogate = Some(unit),
+                                (Some(high), 0xDC00..=0xDFFF) => {
+                                    let code = 0x10000
+                                        + (((high as u32) - 0xD800) << 10)
+                                        + ((unit as u32) - 0xDC00);
+                                    out.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                                }
+                                _ => out.push(char::from_u32(unit as u32).unwrap_or('\u{FFFD}')),
+                            }
+                        }
+                        "par" | "line" if !skipping => out.push('\n'),
+                        "tab" | "cell" if !skipping => out.push('\t'),
+                        _ => {}
+                    }
+                } else if next == '*' {
+                    // \* 开头的目标组可以整体忽略
+                    skipping = true;
+                    i += 1;
+                } else if next == '\'' {
+                    let hex: String = chars.iter().skip(i + 1).take(2).collect();
+                    i += 3;
+                    if fallback_left > 0 {
+                        fallback_left -= 1;
+                        continue;
+                    }
+                    if let Ok(b) = u8::from_str_radix(&hex, 16) {
+                        if !skipping {
+                            out.bytes.push(b);
+                        }
+                    }
+                } else {
+                    i += 1;
+                    if fallback_left > 0 {
+                        fallback_left -= 1;
+                        continue;
+                    }
+                    // 转义字符：\\ \{ \}
+                    if !skipping {
+                        out.push(next);
+                    }
+                }
+            }
+            '\r' | '\n' => i += 1,
+            _ => {
+                i += 1;
+                if fallback_left > 0 {
+                    fallback_left -= 1;
+                    continue;
+                }
+                if !skipping {
+                    out.push(c);
+                }
+            }
+        }
+    }
+
+    out.flush();
+    out.text.trim().to_string()
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_rich_text.rs"]
+mod test_rich_text;
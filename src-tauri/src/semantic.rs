@@ -0,0 +1,502 @@
+//! 语义搜索。
+//! 开启 ai_semantic_search 后，后台线程为历史记录的文本建立向量索引（item_embeddings 表）：新数据、内容变化的数据
+//! 以及更换模型后的数据会被增量索引。配置了 ai_embedding_model 时通过 AI 服务的 /embeddings 接口生成向量，
+//! 否则使用本地的哈希向量（只反映字面上的相似，不会发送任何数据）。隐私数据不建立索引。
+//! semantic_search 按余弦相似度排序，再与关键词搜索的结果按倒数排名融合（RRF）得到最终顺序。
+use crate::ai::{AiError, CancelToken, OpenAiCompatibleProvider};
+use crate::clipboard::ClipboardItem;
+use crate::config::{Config, CONFIG};
+use crate::db::{self, EmbeddingStats};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 本地哈希向量的维数
+pub const LOCAL_EMBEDDING_DIMS: usize = 256;
+/// 本地哈希向量的模型标识
+pub const LOCAL_EMBEDDING_MODEL: &str = "local-hash-256";
+/// 生成向量时使用的最多字符数，超出部分截断
+pub const EMBED_MAX_INPUT_CHARS: usize = 2_000;
+/// 默认返回的结果数
+pub const DEFAULT_SEARCH_RESULTS: usize = 20;
+/// 最多返回的结果数
+const MAX_SEARCH_RESULTS: usize = 200;
+/// 相似度低于该值的数据不作为语义匹配结果
+pub const MIN_SIMILARITY: f32 = 0.2;
+/// 倒数排名融合的平滑常数
+const RRF_K: f32 = 60.0;
+/// 每批建立索引的数据项数
+const INDEX_BATCH_SIZE: usize = 16;
+/// 没有待索引数据或未开启时的检查间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+/// 使用远程服务时两批请求之间的间隔（限流）
+const REMOTE_BATCH_INTERVAL: Duration = Duration::from_secs(2);
+/// 生成向量失败后的等待时间
+const RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Embedder::embed 返回的 Future
+pub type EmbedFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<Vec<f32>>, AiError>> + Send + 'a>>;
+
+/// 文本向量生成器
+pub trait Embedder: Send + Sync {
+    /// 模型标识，与向量一起保存；标识变化后重新建立索引
+    fn model(&self) -> &str;
+
+    /// 生成与 texts 一一对应的向量
+    fn embed<'a>(&'a self, texts: &'a [String], cancel: &'a CancelToken) -> EmbedFuture<'a>;
+
+    /// 是否调用远程服务，远程服务的批次之间需要限流
+    fn is_remote(&self) -> bool {
+        false
+    }
+}
+
+/// 本地哈希向量：将单词、单词的三字母片段与中日韩文字的单字和双字组合散列到固定维数的向量中。
+/// 不需要网络与模型文件，但只能匹配字面上相近的文本。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalEmbedder;
+
+impl LocalEmbedder {
+    /// 生成单个文本的向量（已归一化，空文本返回零向量）
+    pub fn embed_text(text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; LOCAL_EMBEDDING_DIMS];
+        for (feature, weight) in features(text) {
+            let hash = fnv1a(feature.as_bytes());
+            let index = (hash % LOCAL_EMBEDDING_DIMS as u64) as usize;
+            // 用另一位决定符号，减少不同特征落入同一维时的相互抵消偏差
+            let sign = if (hash >> 32) & 1 == 0 { 1.0 } else { -1.0 };
+            vector[index] += sign * weight;
+        }
+        let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            vector.iter_mut().for_each(|x| *x /= norm);
+        }
+        vector
+    }
+}
+
+impl Embedder for LocalEmbedder {
+    fn model(&self) -> &str {
+        LOCAL_EMBEDDING_MODEL
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String], _cancel: &'a CancelToken) -> EmbedFuture<'a> {
+        Box::pin(async move { Ok(texts.iter().map(|text| Self::embed_text(text)).collect()) })
+    }
+}
+
+/// 通过 AI 服务的 /embeddings 接口生成向量
+pub struct RemoteEmbedder {
+    provider: OpenAiCompatibleProvider,
+    /// 服务中的向量模型名称
+    embedding_model: String,
+    /// 保存在索引中的模型标识
+    model_id: String,
+}
+
+impl Embedder for RemoteEmbedder {
+    fn model(&self) -> &str {
+        &self.model_id
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String], cancel: &'a CancelToken) -> EmbedFuture<'a> {
+        Box::pin(self.provider.embed(&self.embedding_model, texts, cancel))
+    }
+
+    fn is_remote(&self) -> bool {
+        true
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32, 0x4E00..=0x9FFF | 0x3400..=0x4DBF | 0x3040..=0x30FF | 0xAC00..=0xD7AF)
+}
+
+/// 本地向量的特征与权重：单词（1.0）、单词的三字母片段（0.5）、中日韩单字（0.5）与相邻两字（1.0）
+fn features(text: &str) -> Vec<(String, f32)> {
+    let mut features = Vec::new();
+    let mut word = String::new();
+    let mut previous_cjk: Option<char> = None;
+    let flush = |word: &mut String, features: &mut Vec<(String, f32)>| {
+        if word.is_empty() {
+            return;
+        }
+        let padded: Vec<char> = format!("#{}#", word).chars().collect();
+        for gram in padded.windows(3) {
+            features.push((format!("g:{}", gram.iter().collect::<String>()), 0.5));
+        }
+        features.push((format!("w:{}", word), 1.0));
+        word.clear();
+    };
+
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if is_cjk(c) {
+            flush(&mut word, &mut features);
+            features.push((format!("c:{}", c), 0.5));
+            if let Some(previous) = previous_cjk {
+                features.push((format!("b:{}{}", previous, c), 1.0));
+            }
+            previous_cjk = Some(c);
+        } else if c.is_alphanumeric() {
+            previous_cjk = None;
+            word.push(c);
+        } else {
+            previous_cjk = None;
+            flush(&mut word, &mut features);
+        }
+    }
+    flush(&mut word, &mut features);
+    features
+}
+
+/// 语义搜索使用的模型标识：本地向量为 LOCAL_EMBEDDING_MODEL，远程模型为 "服务名:模型名"
+/// # Param
+/// cfg: &Config - 当前配置
+pub fn embedding_model_id(cfg: &Config) -> String {
+    match remote_model(cfg) {
+        Some(model) => format!("{}:{}", cfg.ai_provider, model),
+        None => LOCAL_EMBEDDING_MODEL.to_string(),
+    }
+}
+
+fn remote_model(cfg: &Config) -> Option<&str> {
+    cfg.ai_embedding_model
+        .as_deref()
+        .map(str::trim)
+        .filter(|model| !model.is_empty())
+}
+
+/// 按配置创建向量生成器：设置了 ai_embedding_model 时使用 AI 服务（需开启 AI 助手），否则使用本地向量
+/// # Param
+/// cfg: &Config - 当前配置
+pub fn configured_embedder(cfg: &Config) -> Result<Arc<dyn Embedder>, AiError> {
+    match remote_model(cfg) {
+        Some(model) => Ok(Arc::new(RemoteEmbedder {
+            provider: OpenAiCompatibleProvider::from_config(cfg)?,
+            embedding_model: model.to_string(),
+            model_id: embedding_model_id(cfg),
+        })),
+        None => Ok(Arc::new(LocalEmbedder)),
+    }
+}
+
+/// 开启语义搜索时返回当前配置
+fn semantic_config() -> Option<Config> {
+    let cfg = CONFIG.get()?.read().unwrap().clone();
+    cfg.ai_semantic_search.then_some(cfg)
+}
+
+/// 生成向量前的文本：去掉首尾空白并截断
+pub fn embedding_input(text: &str) -> String {
+    text.trim().chars().take(EMBED_MAX_INPUT_CHARS).collect()
+}
+
+/// 余弦相似度，维数不同或存在零向量时为 0
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// 按与查询向量的相似度排序，去掉相似度低于 MIN_SIMILARITY 的数据
+/// # Param
+/// query: &[f32] - 查询向量
+/// candidates: Vec<(String, Vec<f32>)> - (数据项 ID, 向量)
+/// limit: usize - 最多返回的数量
+/// # Returns
+/// Vec<(String, f32)> - (数据项 ID, 相似度)，相似度从高到低
+pub fn rank_by_similarity(
+    query: &[f32],
+    candidates: Vec<(String, Vec<f32>)>,
+    limit: usize,
+) -> Vec<(String, f32)> {
+    let mut ranked: Vec<(String, f32)> = candidates
+        .into_iter()
+        .map(|(id, vector)| {
+            let similarity = cosine_similarity(query, &vector);
+            (id, similarity)
+        })
+        .filter(|(_, similarity)| *similarity >= MIN_SIMILARITY)
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked.truncate(limit);
+    ranked
+}
+
+/// 合并排序后的一条结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct FusedHit {
+    pub item_id: String,
+    /// 融合得分
+    pub score: f32,
+    /// 语义相似度，不在语义结果中时为 None
+    pub similarity: Option<f32>,
+    /// 是否匹配关键词
+    pub keyword_match: bool,
+}
+
+/// 倒数排名融合：每个结果在各列表中的得分为 1 / (RRF_K + 名次)，两个列表中都出现的结果得分相加
+/// # Param
+/// semantic: &[(String, f32)] - 语义结果 (数据项 ID, 相似度)，按相似度排序
+/// keyword: &[String] - 关键词搜索结果的数据项 ID，按原有顺序
+/// limit: usize - 最多返回的数量
+pub fn fuse_rankings(semantic: &[(String, f32)], keyword: &[String], limit: usize) -> Vec<FusedHit> {
+    let mut hits: Vec<FusedHit> = Vec::new();
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+
+    for (rank, (id, similarity)) in semantic.iter().enumerate() {
+        positions.insert(id, hits.len());
+        hits.push(FusedHit {
+            item_id: id.clone(),
+            score: 1.0 / (RRF_K + rank as f32 + 1.0),
+            similarity: Some(*similarity),
+            keyword_match: false,
+        });
+    }
+    for (rank, id) in keyword.iter().enumerate() {
+        let score = 1.0 / (RRF_K + rank as f32 + 1.0);
+        match positions.get(id.as_str()) {
+            Some(&position) if !hits[position].keyword_match => {
+                hits[position].score += score;
+                hits[position].keyword_match = true;
+            }
+            Some(_) => {}
+            None => {
+                positions.insert(id, hits.len());
+                hits.push(FusedHit {
+                    item_id: id.clone(),
+                    score,
+                    similarity: None,
+                    keyword_match: true,
+                });
+            }
+        }
+    }
+
+    // 稳定排序，得分相同时语义结果在前
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(limit);
+    hits
+}
+
+/// 语义搜索的一条结果：数据项的字段与得分
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SemanticSearchHit {
+    #[serde(flatten)]
+    pub item: ClipboardItem,
+    pub score: f32,
+    pub similarity: Option<f32>,
+    pub keyword_match: bool,
+}
+
+/// 生成一组文本的向量，并检查返回的数量
+async fn embed_all(embedder: &dyn Embedder, texts: &[String]) -> Result<Vec<Vec<f32>>, AiError> {
+    let vectors = embedder.embed(texts, &CancelToken::new()).await?;
+    if vectors.len() != texts.len() {
+        return Err(AiError::InvalidResponse("向量数量与文本数量不一致".to_string()));
+    }
+    Ok(vectors)
+}
+
+/// 只与请求中的文本有关的错误（内容被拒绝或响应无法解析），逐条重试可以找出出错的数据
+fn is_item_error(error: &AiError) -> bool {
+    match error {
+        AiError::Http { status, .. } => matches!(status, 400 | 413 | 422),
+        AiError::InvalidResponse(_) => true,
+        _ => false,
+    }
+}
+
+/// 为一批尚未索引的数据建立索引，并删除已被标记为隐私的数据的向量。
+/// 整批失败且错误与文本内容有关时逐条重试，仍然失败的数据记录失败次数，达到 MAX_EMBED_ATTEMPTS 后不再尝试
+/// # Param
+/// embedder: &dyn Embedder - 向量生成器
+/// limit: usize - 本批最多处理的数量
+/// # Returns
+/// usize - 本批处理的数量（含失败的数据），为 0 时表示没有待索引的数据
+pub async fn index_pending(embedder: &dyn Embedder, limit: usize) -> Result<usize, String> {
+    db::delete_private_embeddings()?;
+    let items = db::get_unindexed_items(embedder.model(), limit)?;
+    if items.is_empty() {
+        return Ok(0);
+    }
+
+    let texts: Vec<String> = items.iter().map(|(_, text)| embedding_input(text)).collect();
+    match embed_all(embedder, &texts).await {
+        Ok(vectors) => {
+            for ((item_id, _), vector) in items.iter().zip(vectors) {
+                db::save_embedding(item_id, embedder.model(), &vector)?;
+            }
+            return Ok(items.len());
+        }
+        Err(e) if is_item_error(&e) => {
+            eprintln!("⚠️ 批量建立语义索引失败，改为逐条处理: {}", e);
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    for ((item_id, _), text) in items.iter().zip(texts) {
+        match embed_all(embedder, &[text]).await {
+            Ok(vectors) => db::save_embedding(item_id, embedder.model(), &vectors[0])?,
+            Err(e) if is_item_error(&e) => {
+                eprintln!("⚠️ 数据 {} 建立语义索引失败: {}", item_id, e);
+                db::record_embedding_failure(item_id, embedder.model(), &e.to_string())?;
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(items.len())
+}
+
+/// 语义搜索的内部实现：语义结果与关键词搜索结果融合排序
+/// # Param
+/// embedder: &dyn Embedder - 向量生成器，须与建立索引时使用的相同
+/// query: &str - 搜索内容
+/// limit: usize - 最多返回的数量
+pub async fn search_with(
+    embedder: &dyn Embedder,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<SemanticSearchHit>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let vectors = embedder
+        .embed(&[embedding_input(query)], &CancelToken::new())
+        .await
+        .map_err(String::from)?;
+    let query_vector = vectors
+        .into_iter()
+        .next()
+        .ok_or_else(|| String::from(AiError::InvalidResponse("没有返回查询向量".to_string())))?;
+    // 融合前多取一些候选，避免只在一个列表中靠后的结果被截断
+    let semantic = rank_by_similarity(&query_vector, db::get_embeddings(embedder.model())?, limit * 2);
+
+    let keyword_items: Vec<ClipboardItem> =
+        serde_json::from_str(&db::comprehensive_search(query, None, None, None, None, None)?)
+            .map_err(|e| e.to_string())?;
+    let keyword_ids: Vec<String> = keyword_items.iter().map(|item| item.id.clone()).collect();
+    let mut items: HashMap<String, ClipboardItem> = keyword_items
+        .into_iter()
+        .map(|item| (item.id.clone(), item))
+        .collect();
+
+    let mut hits = Vec::new();
+    for hit in fuse_rankings(&semantic, &keyword_ids, limit) {
+        let item = match items.remove(&hit.item_id) {
+            Some(item) => item,
+            None => match db::get_item_by_id(&hit.item_id)? {
+                Some(item) => item,
+                None => continue,
+            },
+        };
+        hits.push(SemanticSearchHit {
+            item,
+            score: hit.score,
+            similarity: hit.similarity,
+            keyword_match: hit.keyword_match,
+        });
+    }
+    Ok(hits)
+}
+
+/// 为一批数据建立索引
+/// # Returns
+/// Duration - 到下一次检查的等待时间
+fn index_next_batch() -> Duration {
+    let Some(cfg) = semantic_config() else {
+        return POLL_INTERVAL;
+    };
+    let embedder = match configured_embedder(&cfg) {
+        Ok(embedder) => embedder,
+        Err(e) => {
+            eprintln!("❌ 语义索引不可用: {}", e);
+            return RETRY_DELAY;
+        }
+    };
+    match tauri::async_runtime::block_on(index_pending(embedder.as_ref(), INDEX_BATCH_SIZE)) {
+        Ok(0) => POLL_INTERVAL,
+        Ok(count) => {
+            println!("🧭 已处理 {} 条数据的语义索引 ({})", count, embedder.model());
+            if embedder.is_remote() {
+                REMOTE_BATCH_INTERVAL
+            } else {
+                Duration::ZERO
+            }
+        }
+        Err(e) => {
+            eprintln!("❌ 建立语义索引失败: {}", e);
+            RETRY_DELAY
+        }
+    }
+}
+
+/// 启动语义索引线程
+/// **功能**：
+/// - 开启语义搜索后持续为尚未索引的数据（新数据、内容变化的数据、更换模型后的数据）分批建立索引
+/// - 没有待索引的数据时每隔 POLL_INTERVAL 检查一次，失败后等待 RETRY_DELAY 再试
+/// - 个别数据无法建立索引时不影响其他数据，多次失败后跳过该数据
+pub fn start_semantic_indexer() {
+    std::thread::spawn(|| {
+        println!("🧭 语义索引线程已启动");
+        loop {
+            let delay = index_next_batch();
+            std::thread::sleep(delay);
+        }
+    });
+}
+
+/// 语义搜索：按语义相似度与关键词匹配综合排序历史记录。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// query: String - 搜索内容
+/// k: Option<usize> - 最多返回的数量，默认 DEFAULT_SEARCH_RESULTS
+/// # Returns
+/// String - 结果的 JSON 字符串：数据项的字段以及 score、similarity、keyword_match
+#[tauri::command]
+pub async fn semantic_search(query: String, k: Option<usize>) -> Result<String, String> {
+    let cfg = semantic_config().ok_or_else(|| "语义搜索未开启".to_string())?;
+    let embedder = configured_embedder(&cfg)?;
+    let limit = k.unwrap_or(DEFAULT_SEARCH_RESULTS).clamp(1, MAX_SEARCH_RESULTS);
+    let hits = search_with(embedder.as_ref(), &query, limit).await?;
+    serde_json::to_string(&hits).map_err(|e| e.to_string())
+}
+
+/// 获取当前模型的语义索引进度。作为 Tauri command 暴露给前端调用。
+/// # Returns
+/// EmbeddingStats - 已索引、等待索引与多次失败后跳过的数据项数
+#[tauri::command]
+pub fn get_semantic_index_stats() -> Result<EmbeddingStats, String> {
+    let model = CONFIG
+        .get()
+        .map(|lock| embedding_model_id(&lock.read().unwrap()))
+        .unwrap_or_else(|| LOCAL_EMBEDDING_MODEL.to_string());
+    db::get_embedding_stats(&model)
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_semantic.rs"]
+mod test_semantic;
@@ -0,0 +1,340 @@
+//! 代码片段（模板）。
+//! 片段以 item_type = "snippet" 存放在 data 表中，因此收藏夹、搜索等功能同样适用。
+//! 复制片段时在 Rust 端展开其中的占位符：
+//! `{{date:%Y-%m-%d}}`、`{{clipboard}}`、`{{uuid}}`、`{{input:名称}}`、`{{item:<id>}}`。
+//! 需要输出字面量 `{{` 时写作 `\{{`。
+use crate::app_setup::ClipboardSourceState;
+use crate::clipboard::ClipboardItem;
+use crate::db;
+use crate::utils;
+use arboard::Clipboard;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::State;
+use uuid::Uuid;
+
+/// 片段在 data 表中的类型
+pub const SNIPPET_TYPE: &str = "snippet";
+
+/// `{{date}}` 未指定格式时使用的日期格式
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// `{{item:<id>}}` 引用其他片段时允许的最大嵌套层数
+const MAX_ITEM_DEPTH: usize = 8;
+
+/// 片段中的占位符
+#[derive(Debug, Clone, PartialEq)]
+pub enum Placeholder {
+    /// 当前时间，参数为 strftime 格式
+    Date(String),
+    /// 当前剪贴板中的文本
+    Clipboard,
+    /// 随机 UUID
+    Uuid,
+    /// 复制时由用户填写的值，参数为名称
+    Input(String),
+    /// 其他历史记录的文本，参数为数据项 ID
+    Item(String),
+}
+
+/// 解析后的片段组成部分
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+/// 片段校验结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SnippetInfo {
+    /// 复制时需要用户填写的输入名称（按首次出现的顺序，已去重）
+    pub inputs: Vec<String>,
+    /// 引用的数据项 ID
+    pub items: Vec<String>,
+}
+
+/// 展开占位符时使用的上下文
+pub struct RenderContext<'a> {
+    /// 当前时间
+    pub now: DateTime<Local>,
+    /// 当前剪贴板文本
+    pub clipboard: Option<String>,
+    /// 用户填写的输入值
+    pub inputs: &'a HashMap<String, String>,
+    /// 预览模式：缺少的输入显示为 `<名称>` 而不是报错
+    pub preview: bool,
+}
+
+/// 解析片段模板，所有错误一并返回。
+/// # Param
+/// template: &str - 片段内容
+/// # Returns
+/// Result<Vec<Segment>, String> - 解析结果，失败时返回以"；"分隔的全部错误
+pub fn parse_template(template: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut errors = Vec::new();
+    let mut text = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        // `\{{` 输出字面量 `{{`
+        if rest[..start].ends_with('\\') {
+            text.push_str(&rest[..start - 1]);
+            text.push_str("{{");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        text.push_str(&rest[..start]);
+
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            let offset = template.len() - rest.len() + start;
+            errors.push(format!(
+                "占位符未闭合（第 {} 个字符处）",
+                template[..offset].chars().count() + 1
+            ));
+            rest = "";
+            break;
+        };
+
+        match parse_placeholder(&after[..end]) {
+            Ok(placeholder) => {
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                segments.push(Segment::Placeholder(placeholder));
+            }
+            Err(e) => errors.push(e),
+        }
+        rest = &after[end + 2..];
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+
+    if errors.is_empty() {
+        Ok(segments)
+    } else {
+        Err(errors.join("；"))
+    }
+}
+
+/// 解析单个占位符（不含两侧花括号）
+fn parse_placeholder(raw: &str) -> Result<Placeholder, String> {
+    let (name, arg) = match raw.split_once(':') {
+        Some((name, arg)) => (name.trim(), Some(arg.trim())),
+        None => (raw.trim(), None),
+    };
+    let required = |arg: Option<&str>| match arg {
+        Some(a) if !a.is_empty() => Ok(a.to_string()),
+        _ => Err(format!("占位符 {{{{{}}}}} 缺少参数", name)),
+    };
+    let no_arg = |placeholder: Placeholder| match arg {
+        None => Ok(placeholder),
+        Some(_) => Err(format!("占位符 {{{{{}}}}} 不接受参数", name)),
+    };
+
+    match name.to_lowercase().as_str() {
+        "date" => {
+            let format = arg.filter(|a| !a.is_empty()).unwrap_or(DEFAULT_DATE_FORMAT);
+            if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                return Err(format!("日期格式无效: {}", format));
+            }
+            Ok(Placeholder::Date(format.to_string()))
+        }
+        "clipboard" => no_arg(Placeholder::Clipboard),
+        "uuid" => no_arg(Placeholder::Uuid),
+        "input" => required(arg).map(Placeholder::Input),
+        "item" => required(arg).map(Placeholder::Item),
+        _ => Err(format!("未知的占位符: {{{{{}}}}}", raw.trim())),
+    }
+}
+
+/// 校验片段模板，并列出需要用户填写的输入与引用的数据项
+pub fn inspect_template(template: &str) -> Result<SnippetInfo, String> {
+    let mut info = SnippetInfo {
+        inputs: Vec::new(),
+        items: Vec::new(),
+    };
+    for segment in parse_template(template)? {
+        match segment {
+            Segment::Placeholder(Placeholder::Input(name)) if !info.inputs.contains(&name) => {
+                info.inputs.push(name)
+            }
+            Segment::Placeholder(Placeholder::Item(id)) if !info.items.contains(&id) => {
+                info.items.push(id)
+            }
+            _ => {}
+        }
+    }
+    Ok(info)
+}
+
+/// 展开片段中的占位符。
+/// # Param
+/// template: &str - 片段内容
+/// ctx: &RenderContext - 展开上下文
+/// resolve_item: &dyn Fn(&str) -> Result<String, String> - 根据数据项 ID 获取要插入的文本
+/// # Returns
+/// Result<String, String> - 展开后的文本
+pub fn render_template(
+    template: &str,
+    ctx: &RenderContext,
+    resolve_item: &dyn Fn(&str) -> Result<String, String>,
+) -> Result<String, String> {
+    let mut output = String::new();
+    for segment in parse_template(template)? {
+        match segment {
+            Segment::Text(text) => output.push_str(&text),
+            Segment::Placeholder(Placeholder::Date(format)) => {
+                output.push_str(&ctx.now.format(&format).to_string())
+            }
+            Segment::Placeholder(Placeholder::Clipboard) => {
+                output.push_str(ctx.clipboard.as_deref().unwrap_or(""))
+            }
+            Segment::Placeholder(Placeholder::Uuid) => {
+                output.push_str(&Uuid::new_v4().to_string())
+            }
+            Segment::Placeholder(Placeholder::Input(name)) => match ctx.inputs.get(&name) {
+                Some(value) => output.push_str(value),
+                None if ctx.preview => output.push_str(&format!("<{}>", name)),
+                None => return Err(format!("缺少输入值: {}", name)),
+            },
+            Segment::Placeholder(Placeholder::Item(id)) => output.push_str(&resolve_item(&id)?),
+        }
+    }
+    Ok(output)
+}
+
+/// 展开片段，`{{item:<id>}}` 从数据库读取被引用数据项的文本
+fn render_with_items(template: &str, ctx: &RenderContext, depth: usize) -> Result<String, String> {
+    if depth > MAX_ITEM_DEPTH {
+        return Err("片段引用层级过深，可能存在循环引用".to_string());
+    }
+    render_template(template, ctx, &|id| item_text(id, ctx, depth))
+}
+
+/// 获取被引用数据项的文本：文本直接使用，片段递归展开，图片等使用 OCR 文本
+fn item_text(id: &str, ctx: &RenderContext, depth: usize) -> Result<String, String> {
+    let item = db::get_item_by_id(id)?.ok_or_else(|| format!("引用的数据不存在: {}", id))?;
+    db::audit_private_access(db::AuditEvent::Viewed, id, "snippet");
+    match item.item_type.as_str() {
+        "text" => Ok(item.content),
+        SNIPPET_TYPE => render_with_items(&item.content, ctx, depth + 1),
+        _ => db::get_ocr_text(id)?
+            .filter(|t| !t.trim().is_empty())
+            .ok_or_else(|| format!("引用的数据没有可插入的文本: {}", id)),
+    }
+}
+
+/// 读取当前剪贴板文本，读取失败时视为空
+fn current_clipboard_text() -> Option<String> {
+    Clipboard::new().ok()?.get_text().ok()
+}
+
+/// 校验片段内容。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// template: &str - 片段内容
+/// # Returns
+/// String - 校验结果的 JSON 字符串（inputs, items），校验失败时返回错误信息
+#[tauri::command]
+pub fn validate_snippet(template: &str) -> Result<String, String> {
+    let info = inspect_template(template)?;
+    serde_json::to_string(&info).map_err(|e| e.to_string())
+}
+
+/// 新建片段。片段默认收藏，不会被历史记录清理删除。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// template: &str - 片段内容
+/// notes: Option<&str> - 备注（可作为片段名称）
+/// # Returns
+/// String - 新建片段的 JSON 字符串
+#[tauri::command]
+pub fn create_snippet(template: &str, notes: Option<&str>) -> Result<String, String> {
+    inspect_template(template)?;
+    let item = ClipboardItem {
+        id: Uuid::new_v4().to_string(),
+        item_type: SNIPPET_TYPE.to_string(),
+        content: template.to_string(),
+        size: Some(template.chars().count() as u64),
+        is_favorite: true,
+        notes: notes.unwrap_or("").to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    };
+    db::insert_received_db_data(item)
+}
+
+/// 修改片段内容。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// id: &str - 片段 ID
+/// template: &str - 新的片段内容
+/// # Returns
+/// String - 修改后片段的 JSON 字符串
+#[tauri::command]
+pub fn update_snippet(id: &str, template: &str) -> Result<String, String> {
+    let item = db::get_item_by_id(id)?.ok_or_else(|| format!("数据不存在: {}", id))?;
+    if item.item_type != SNIPPET_TYPE {
+        return Err("该数据不是片段".to_string());
+    }
+    inspect_template(template)?;
+    db::update_data_content_by_id(id, template)
+}
+
+/// 预览片段展开后的内容，缺少的输入显示为 `<名称>`。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// template: &str - 片段内容
+/// inputs: Option<HashMap<String, String>> - 已填写的输入值
+/// # Returns
+/// String - 展开后的文本
+#[tauri::command]
+pub fn preview_snippet(
+    template: &str,
+    inputs: Option<HashMap<String, String>>,
+) -> Result<String, String> {
+    let inputs = inputs.unwrap_or_default();
+    let ctx = RenderContext {
+        now: Local::now(),
+        clipboard: current_clipboard_text(),
+        inputs: &inputs,
+        preview: true,
+    };
+    render_with_items(template, &ctx, 0)
+}
+
+/// 展开片段并写入剪贴板。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// id: &str - 片段 ID
+/// inputs: Option<HashMap<String, String>> - 用户填写的输入值
+/// # Returns
+/// String - 写入剪贴板的文本
+#[tauri::command]
+pub fn copy_snippet(
+    id: &str,
+    inputs: Option<HashMap<String, String>>,
+    state: State<'_, ClipboardSourceState>,
+) -> Result<String, String> {
+    let item = db::get_item_by_id(id)?.ok_or_else(|| format!("数据不存在: {}", id))?;
+    if item.item_type != SNIPPET_TYPE {
+        return Err("该数据不是片段".to_string());
+    }
+
+    let inputs = inputs.unwrap_or_default();
+    let ctx = RenderContext {
+        now: Local::now(),
+        clipboard: current_clipboard_text(),
+        inputs: &inputs,
+        preview: false,
+    };
+    let text = render_with_items(&item.content, &ctx, 0)?;
+
+    utils::write_derived_text_to_clipboard(&text, id, "copy_snippet", &state)?;
+
+    Ok(text)
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_snippet.rs"]
+mod test_snippet;
@@ -0,0 +1,180 @@
+//! 剪贴板内容来源应用的识别。
+//! - Windows：优先使用剪贴板所有者窗口（GetClipboardOwner），其次为前台窗口
+//! - macOS：通过 osascript 读取最前端应用与其窗口标题
+//! - Linux：不识别来源，见 `SystemSourceAppProvider`
+use serde::{Deserialize, Serialize};
+
+/// 来源应用信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceApp {
+    /// 应用名称（Windows 下为不含扩展名的进程名，macOS 下为应用名称）
+    pub app_name: String,
+    /// 窗口标题
+    pub window_title: String,
+}
+
+/// 来源应用识别接口，便于在测试中替换为固定结果
+pub trait SourceAppProvider: Send + Sync {
+    /// 获取当前剪贴板内容的来源应用，无法识别时返回 None
+    fn current_source(&self) -> Option<SourceApp>;
+}
+
+/// 基于系统 API 的来源应用识别实现。
+/// Linux 下始终返回 None：CLIPBOARD 选区的所有者通常是应用的隐藏窗口，需要通过 XGetSelectionOwner
+/// 与 `_NET_WM_PID` 追溯到进程，现有依赖无法完成；以前台窗口代替会把后台程序（如 xclip、
+/// 密码管理器的自动复制）写入的内容记到错误的应用上。无法识别时照常记录，不会误判为忽略的应用
+pub struct SystemSourceAppProvider;
+
+impl SourceAppProvider for SystemSourceAppProvider {
+    fn current_source(&self) -> Option<SourceApp> {
+        #[cfg(target_os = "windows")]
+        {
+            windows_source_app()
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            macos_source_app()
+        }
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        {
+            None
+        }
+    }
+}
+
+/// 规范化应用名称用于比较：去除路径、".exe"/".app" 后缀并转为小写
+/// # Param
+/// name: &str - 应用名称或可执行文件路径
+/// # Returns
+/// String - 规范化后的名称
+pub fn normalize_app_name(name: &str) -> String {
+    let base = name
+        .trim()
+        .rsplit(|c| c == '/' || c == '\\')
+        .next()
+        .unwrap_or("")
+        .to_lowercase();
+    base.strip_suffix(".exe")
+        .or_else(|| base.strip_suffix(".app"))
+        .unwrap_or(&base)
+        .to_string()
+}
+
+/// 判断来源应用是否在忽略列表中（按应用名匹配，忽略大小写与扩展名）
+/// # Param
+/// source: &SourceApp - 来源应用
+/// ignored_apps: &[String] - 配置中的忽略应用列表
+/// # Returns
+/// bool - 是否应忽略
+pub fn is_ignored_app(source: &SourceApp, ignored_apps: &[String]) -> bool {
+    let name = normalize_app_name(&source.app_name);
+    if name.is_empty() {
+        return false;
+    }
+    ignored_apps
+        .iter()
+        .map(|app| normalize_app_name(app))
+        .any(|app| !app.is_empty() && app == name)
+}
+
+#[cfg(target_os = "windows")]
+fn windows_source_app() -> Option<SourceApp> {
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::{CloseHandle, HWND};
+    use windows::Win32::System::DataExchange::GetClipboardOwner;
+    use windows::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId,
+    };
+
+    unsafe fn window_title(hwnd: HWND) -> String {
+        let mut buf = [0u16; 512];
+        let len = GetWindowTextW(hwnd, &mut buf);
+        String::from_utf16_lossy(&buf[..len.max(0) as usize])
+    }
+
+    unsafe {
+        let foreground = GetForegroundWindow();
+        // 剪贴板所有者通常是应用的隐藏窗口，没有所有者时退回前台窗口
+        let hwnd = match GetClipboardOwner() {
+            Ok(owner) if !owner.0.is_null() => owner,
+            _ => foreground,
+        };
+        if hwnd.0.is_null() {
+            return None;
+        }
+
+        let mut pid: u32 = 0;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid as *mut u32));
+        if pid == 0 {
+            return None;
+        }
+
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buf = [0u16; 1024];
+        let mut size = buf.len() as u32;
+        let query = QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_WIN32,
+            PWSTR(buf.as_mut_ptr()),
+            &mut size,
+        );
+        let _ = CloseHandle(process);
+        query.ok()?;
+        let exe_path = String::from_utf16_lossy(&buf[..size as usize]);
+
+        // 所有者窗口没有标题时，若前台窗口属于同一进程则使用前台窗口标题
+        let mut title = window_title(hwnd);
+        if title.is_empty() && hwnd != foreground && !foreground.0.is_null() {
+            let mut fg_pid: u32 = 0;
+            GetWindowThreadProcessId(foreground, Some(&mut fg_pid as *mut u32));
+            if fg_pid == pid {
+                title = window_title(foreground);
+            }
+        }
+
+        Some(SourceApp {
+            app_name: normalize_app_name(&exe_path),
+            window_title: title,
+        })
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn macos_source_app() -> Option<SourceApp> {
+    use std::process::Command;
+
+    let script = r#"tell application "System Events"
+    set frontApp to first application process whose frontmost is true
+    set appName to name of frontApp
+    set winTitle to ""
+    try
+        set winTitle to name of front window of frontApp
+    end try
+end tell
+return appName & linefeed & winTitle"#;
+
+    let output = Command::new("osascript").args(["-e", script]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut lines = text.lines();
+    let app_name = lines.next()?.trim().to_string();
+    if app_name.is_empty() {
+        return None;
+    }
+    Some(SourceApp {
+        app_name,
+        window_title: lines.next().unwrap_or("").trim().to_string(),
+    })
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_source_app.rs"]
+mod test_source_app;
@@ -0,0 +1,393 @@
+/// AI 服务客户端单元测试（使用本地模拟 HTTP 服务）
+use super::*;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+// --- 测试辅助函数 ---
+
+/// 模拟服务收到的请求
+struct RecordedRequest {
+    request_line: String,
+    headers: Vec<String>,
+    body: Value,
+}
+
+impl RecordedRequest {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    }
+}
+
+/// 启动只处理一个请求的模拟服务：先写出响应头，再按 (延迟毫秒, 内容) 依次写出响应体
+/// # Returns
+/// (String, Receiver<RecordedRequest>) - 接口地址与收到的请求
+fn mock_server(
+    status: &str,
+    content_type: &str,
+    chunks: Vec<(u64, String)>,
+) -> (String, mpsc::Receiver<RecordedRequest>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+    let head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nConnection: close\r\n\r\n",
+        status, content_type
+    );
+
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut headers = Vec::new();
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end().to_string();
+            if line.is_empty() {
+                break;
+            }
+            headers.push(line);
+        }
+        let length: usize = headers
+            .iter()
+            .find_map(|line| {
+                let (key, value) = line.split_once(':')?;
+                key.eq_ignore_ascii_case("content-length")
+                    .then(|| value.trim().parse().ok())?
+            })
+            .unwrap_or(0);
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        let _ = tx.send(RecordedRequest {
+            request_line: request_line.trim_end().to_string(),
+            headers,
+            body: serde_json::from_slice(&body).unwrap_or(Value::Null),
+        });
+
+        let mut stream = stream;
+        if stream.write_all(head.as_bytes()).is_err() {
+            return;
+        }
+        for (delay, chunk) in chunks {
+            thread::sleep(Duration::from_millis(delay));
+            // 客户端取消或超时后会关闭连接
+            if stream.write_all(chunk.as_bytes()).and_then(|_| stream.flush()).is_err() {
+                return;
+            }
+        }
+    });
+    (base_url, rx)
+}
+
+/// 一个流式输出事件
+fn sse_delta(content: &str) -> String {
+    format!(
+        "data: {}\n\n",
+        json!({ "choices": [{ "index": 0, "delta": { "content": content } }] })
+    )
+}
+
+fn provider(base_url: &str, api_key: Option<&str>, timeout_ms: u64) -> OpenAiCompatibleProvider {
+    OpenAiCompatibleProvider::new(
+        "custom",
+        base_url,
+        api_key.map(str::to_string),
+        "test-model",
+        0.3,
+        Duration::from_millis(timeout_ms),
+    )
+    .unwrap()
+}
+
+fn ask(text: &str) -> ChatRequest {
+    ChatRequest {
+        messages: vec![ChatMessage::system("be brief"), ChatMessage::user(text)],
+        ..Default::default()
+    }
+}
+
+// --- 测试用例 ---
+
+#[test]
+fn test_sse_parser_handles_split_chunks() {
+    let mut parser = SseParser::default();
+    let stream = "data: {\"a\":\"你好\"}\r\n\r\n: keep-alive\n\nevent: message\ndata: line1\ndata: line2\n\ndata: [DONE]";
+    let bytes = stream.as_bytes();
+
+    // 逐字节输入，多字节字符与 CRLF 都会被拆开
+    let mut events = Vec::new();
+    for byte in bytes {
+        events.extend(parser.push(std::slice::from_ref(byte)));
+    }
+    assert_eq!(events, vec!["{\"a\":\"你好\"}".to_string(), "line1\nline2".to_string()]);
+    assert_eq!(parser.finish().as_deref(), Some("[DONE]"));
+    assert_eq!(parser.finish(), None);
+}
+
+#[test]
+fn test_parse_stream_data() {
+    assert_eq!(parse_stream_data("[DONE]").unwrap(), None);
+    assert_eq!(
+        parse_stream_data(r#"{"choices":[{"delta":{"content":"hi"}}]}"#).unwrap().as_deref(),
+        Some("hi")
+    );
+    // 只有角色或结束原因的事件没有内容
+    assert_eq!(
+        parse_stream_data(r#"{"choices":[{"delta":{"role":"assistant"}}]}"#).unwrap().as_deref(),
+        Some("")
+    );
+    assert_eq!(
+        parse_stream_data(r#"{"error":{"message":"quota exceeded"}}"#),
+        Err(AiError::InvalidResponse("quota exceeded".to_string()))
+    );
+    assert!(parse_stream_data("not json").is_err());
+}
+
+#[tokio::test]
+async fn test_chat_streams_deltas() {
+    let chunks = vec![
+        (0, ": connected\n\n".to_string()),
+        (0, sse_delta("Hello")),
+        (30, sse_delta(", 世界")),
+        (30, "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n".to_string()),
+        (0, "data: [DONE]\n\n".to_string()),
+    ];
+    let (base_url, requests) = mock_server("200 OK", "text/event-stream", chunks);
+    let provider = provider(&format!("{}/", base_url), Some("sk-test"), 2000);
+
+    let mut deltas = Vec::new();
+    let mut on_delta = |delta: &str| deltas.push(delta.to_string());
+    let reply = provider
+        .chat(&ask("hi"), &CancelToken::new(), &mut on_delta)
+        .await
+        .unwrap();
+    assert_eq!(reply, "Hello, 世界");
+    assert_eq!(deltas, vec!["Hello", ", 世界"]);
+
+    let request = requests.recv().unwrap();
+    assert_eq!(request.request_line, "POST /v1/chat/completions HTTP/1.1");
+    assert_eq!(request.header("authorization"), Some("Bearer sk-test"));
+    assert_eq!(request.body["model"], "test-model");
+    assert_eq!(request.body["stream"], true);
+    assert!((request.body["temperature"].as_f64().unwrap() - 0.3).abs() < 1e-6);
+    assert_eq!(request.body["messages"][0], json!({ "role": "system", "content": "be brief" }));
+    assert_eq!(request.body["messages"][1], json!({ "role": "user", "content": "hi" }));
+    assert!(request.body.get("max_tokens").is_none());
+}
+
+#[tokio::test]
+async fn test_chat_stream_without_done_marker() {
+    // 连接关闭时结束，最后一个事件没有以空行结尾
+    let chunks = vec![(0, sse_delta("a")), (0, sse_delta("b").trim_end().to_string())];
+    let (base_url, requests) = mock_server("200 OK", "text/event-stream; charset=utf-8", chunks);
+    let provider = provider(&base_url, None, 2000);
+
+    let request = ChatRequest {
+        temperature: Some(1.0),
+        max_tokens: Some(16),
+        ..ask("x")
+    };
+    assert_eq!(provider.complete(&request, &CancelToken::new()).await.unwrap(), "ab");
+
+    let request = requests.recv().unwrap();
+    assert_eq!(request.header("authorization"), None);
+    assert_eq!(request.body["temperature"], 1.0);
+    assert_eq!(request.body["max_tokens"], 16);
+}
+
+#[tokio::test]
+async fn test_chat_non_stream_fallback() {
+    let body = json!({ "choices": [{ "message": { "role": "assistant", "content": "完整回复" } }] });
+    let (base_url, _requests) = mock_server("200 OK", "application/json", vec![(0, body.to_string())]);
+    let provider = provider(&base_url, None, 2000);
+
+    let mut deltas = Vec::new();
+    let mut on_delta = |delta: &str| deltas.push(delta.to_string());
+    let reply = provider.chat(&ask("x"), &CancelToken::new(), &mut on_delta).await;
+    assert_eq!(reply.as_deref(), Ok("完整回复"));
+    assert_eq!(deltas, vec!["完整回复"]);
+}
+
+#[tokio::test]
+async fn test_chat_http_errors() {
+    let body = json!({ "error": { "message": "Incorrect API key provided", "type": "invalid_request_error" } });
+    let (base_url, _requests) =
+        mock_server("401 Unauthorized", "application/json", vec![(0, body.to_string())]);
+    let error = provider(&base_url, Some("bad"), 2000)
+        .complete(&ask("x"), &CancelToken::new())
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        AiError::Http {
+            status: 401,
+            message: "Incorrect API key provided".to_string()
+        }
+    );
+    assert!(!error.is_retryable());
+
+    let (base_url, _requests) = mock_server("503 Service Unavailable", "text/plain", vec![]);
+    let error = provider(&base_url, None, 2000)
+        .complete(&ask("x"), &CancelToken::new())
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error,
+        AiError::Http {
+            status: 503,
+            message: "Service Unavailable".to_string()
+        }
+    );
+    assert!(error.is_retryable());
+}
+
+#[tokio::test]
+async fn test_chat_times_out_between_chunks() {
+    let chunks = vec![(0, sse_delta("partial")), (2000, sse_delta("late"))];
+    let (base_url, _requests) = mock_server("200 OK", "text/event-stream", chunks);
+    let provider = provider(&base_url, None, 200);
+
+    let started = std::time::Instant::now();
+    let mut deltas = Vec::new();
+    let mut on_delta = |delta: &str| deltas.push(delta.to_string());
+    let result = provider.chat(&ask("x"), &CancelToken::new(), &mut on_delta).await;
+    assert_eq!(result, Err(AiError::Timeout));
+    assert!(AiError::Timeout.is_retryable());
+    assert_eq!(deltas, vec!["partial"]);
+    assert!(started.elapsed() < Duration::from_millis(1500));
+}
+
+#[tokio::test]
+async fn test_chat_cancellation() {
+    let chunks = vec![(0, sse_delta("first")), (2000, sse_delta("second"))];
+    let (base_url, _requests) = mock_server("200 OK", "text/event-stream", chunks);
+    let client = provider(&base_url, None, 5000);
+
+    // 收到第一段输出后取消
+    let cancel = CancelToken::new();
+    let canceller = cancel.clone();
+    let started = std::time::Instant::now();
+    let mut on_delta = |_: &str| canceller.cancel();
+    let result = client.chat(&ask("x"), &cancel, &mut on_delta).await;
+    assert_eq!(result, Err(AiError::Cancelled));
+    assert!(started.elapsed() < Duration::from_millis(1500));
+
+    // 已取消的令牌不会发出请求
+    let (base_url, requests) = mock_server("200 OK", "text/event-stream", vec![]);
+    let result = provider(&base_url, None, 5000).complete(&ask("x"), &cancel).await;
+    assert_eq!(result, Err(AiError::Cancelled));
+    assert!(requests.recv_timeout(Duration::from_millis(200)).is_err());
+}
+
+#[test]
+fn test_ai_state_cancel_by_request_id() {
+    let state = AiState::default();
+    let first = state.register("req-1");
+    assert!(!first.is_cancelled());
+
+    // 相同 ID 的新请求取代旧请求
+    let second = state.register("req-1");
+    assert!(first.is_cancelled());
+    state.finish("req-1", &first);
+    assert!(state.cancel("req-1"));
+    assert!(second.is_cancelled());
+    assert!(!state.cancel("req-1"));
+
+    let third = state.register("req-2");
+    state.finish("req-2", &third);
+    assert!(!state.cancel("req-2"));
+    assert!(!third.is_cancelled());
+}
+
+#[test]
+fn test_provider_from_config() {
+    let mut cfg = Config {
+        ai_provider: "deepseek".to_string(),
+        ai_model: "deepseek-chat".to_string(),
+        ..Default::default()
+    };
+    assert!(matches!(
+        OpenAiCompatibleProvider::from_config(&cfg),
+        Err(AiError::Config(_))
+    ));
+
+    cfg.ai_enabled = true;
+    let provider = OpenAiCompatibleProvider::from_config(&cfg).unwrap();
+    assert_eq!(provider.name(), "deepseek");
+    assert_eq!(provider.endpoint(), "https://api.deepseek.com/v1/chat/completions");
+    assert_eq!(provider.timeout, Duration::from_secs(DEFAULT_AI_TIMEOUT_SECS));
+
+    // 自定义地址优先，空白 API Key 视为未设置
+    cfg.ai_base_url = Some("http://localhost:11434/v1/".to_string());
+    cfg.ai_api_key = Some("  ".to_string());
+    cfg.ai_timeout_secs = Some(5);
+    let provider = OpenAiCompatibleProvider::from_config(&cfg).unwrap();
+    assert_eq!(provider.endpoint(), "http://localhost:11434/v1/chat/completions");
+    assert_eq!(provider.api_key, None);
+    assert_eq!(provider.timeout, Duration::from_secs(5));
+
+    // 自定义服务必须设置地址，模型不能为空
+    cfg.ai_provider = "custom".to_string();
+    cfg.ai_base_url = None;
+    assert!(matches!(
+        OpenAiCompatibleProvider::from_config(&cfg),
+        Err(AiError::Config(_))
+    ));
+    cfg.ai_provider = "openai".to_string();
+    cfg.ai_model = " ".to_string();
+    assert!(matches!(
+        OpenAiCompatibleProvider::from_config(&cfg),
+        Err(AiError::Config(_))
+    ));
+}
+
+#[test]
+fn test_parse_embeddings() {
+    // 按 index 排序
+    let body = json!({ "data": [
+        { "index": 1, "embedding": [0.5, -1] },
+        { "index": 0, "embedding": [1.0, 0.25] },
+    ] });
+    assert_eq!(
+        parse_embeddings(&body.to_string(), 2).unwrap(),
+        vec![vec![1.0, 0.25], vec![0.5, -1.0]]
+    );
+
+    assert!(parse_embeddings(&body.to_string(), 3).is_err());
+    let duplicated = json!({ "data": [{ "index": 0, "embedding": [1] }, { "index": 0, "embedding": [2] }] });
+    assert!(parse_embeddings(&duplicated.to_string(), 2).is_err());
+    assert!(parse_embeddings(r#"{"data": [{"embedding": ["x"]}]}"#, 1).is_err());
+    assert!(parse_embeddings(r#"{"object": "list"}"#, 0).is_err());
+}
+
+#[tokio::test]
+async fn test_embed_requests_embeddings_endpoint() {
+    let body = json!({ "data": [{ "index": 0, "embedding": [0.1, 0.2] }, { "index": 1, "embedding": [0.3, 0.4] }] });
+    let (base_url, requests) = mock_server("200 OK", "application/json", vec![(0, body.to_string())]);
+    let client = provider(&base_url, Some("sk-test"), 2000);
+
+    let inputs = vec!["a".to_string(), "b".to_string()];
+    let vectors = client
+        .embed("embed-model", &inputs, &CancelToken::new())
+        .await
+        .unwrap();
+    assert_eq!(vectors, vec![vec![0.1, 0.2], vec![0.3, 0.4]]);
+
+    let request = requests.recv().unwrap();
+    assert_eq!(request.request_line, "POST /v1/embeddings HTTP/1.1");
+    assert_eq!(request.header("authorization"), Some("Bearer sk-test"));
+    assert_eq!(request.body, json!({ "model": "embed-model", "input": ["a", "b"] }));
+
+    let (base_url, _requests) = mock_server("429 Too Many Requests", "text/plain", vec![]);
+    let error = provider(&base_url, None, 2000)
+        .embed("embed-model", &inputs, &CancelToken::new())
+        .await
+        .unwrap_err();
+    assert!(error.is_retryable());
+}
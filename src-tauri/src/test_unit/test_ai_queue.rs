@@ -0,0 +1,67 @@
+/// AI 标签与摘要任务单元测试
+use super::*;
+
+fn tags(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn test_is_long_enough_counts_characters() {
+    assert!(!is_long_enough(&format!("  {}  ", "a".repeat(AI_MIN_TEXT_CHARS - 1))));
+    assert!(is_long_enough(&"a".repeat(AI_MIN_TEXT_CHARS)));
+    // 按字符而不是字节计算
+    assert!(!is_long_enough(&"中".repeat(AI_MIN_TEXT_CHARS / 2)));
+    assert!(is_long_enough(&"中".repeat(AI_MIN_TEXT_CHARS)));
+}
+
+#[test]
+fn test_build_request_for_enabled_options() {
+    let text = format!("  {}  ", "x".repeat(AI_MAX_INPUT_CHARS + 10));
+    let request = build_request(&text, EnrichOptions { tags: true, summary: false });
+    assert_eq!(request.messages.len(), 2);
+    assert_eq!(request.messages[0].role, "system");
+    assert!(request.messages[0].content.contains("\"tags\""));
+    assert!(!request.messages[0].content.contains("\"summary\""));
+    // 内容去掉首尾空白并截断
+    assert_eq!(request.messages[1].content, "x".repeat(AI_MAX_INPUT_CHARS));
+
+    let request = build_request("text", EnrichOptions { tags: false, summary: true });
+    assert!(!request.messages[0].content.contains("\"tags\""));
+    assert!(request.messages[0].content.contains("\"summary\""));
+}
+
+#[test]
+fn test_parse_enrichment_replies() {
+    let reply = r#"{"tags": ["Rust", "异步编程"], "summary": "介绍 tokio 的用法。"}"#;
+    assert_eq!(
+        parse_enrichment(reply).unwrap(),
+        Enrichment {
+            tags: tags(&["Rust", "异步编程"]),
+            summary: Some("介绍 tokio 的用法。".to_string()),
+        }
+    );
+
+    // 代码块包裹、标签为逗号分隔的字符串、缺少摘要
+    let reply = "好的：\n```json\n{\"tags\": \"会议，日程、 周报\"}\n```";
+    assert_eq!(
+        parse_enrichment(reply).unwrap(),
+        Enrichment {
+            tags: tags(&["会议", "日程", "周报"]),
+            summary: None,
+        }
+    );
+
+    assert_eq!(
+        parse_enrichment(r#"{"summary": "  "}"#).unwrap(),
+        Enrichment::default()
+    );
+    assert!(parse_enrichment("无法处理").is_err());
+    assert!(parse_enrichment("} {").is_err());
+    assert!(parse_enrichment("{tags: [}").is_err());
+}
+
+#[test]
+fn test_normalize_tags() {
+    let raw = tags(&[" #rust ", "Rust", "\"数据库\"", "", "#", &"长".repeat(30), "a", "b", "c", "d"]);
+    assert_eq!(normalize_tags(&raw), tags(&["rust", "数据库", "a", "b", "c"]));
+}
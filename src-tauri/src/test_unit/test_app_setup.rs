@@ -7,6 +7,7 @@ use crate::app_setup::AppShortcutManager;
 use crate::app_setup::SHORTCUT_CONFIGS;
 use crate::app_setup::STORAGE_KEY_TO_HANDLER_KEY;
 use crate::app_setup::copy_dir_all;
+use crate::app_setup::CapturePauseState;
 // --- 1. 快捷键格式化逻辑测试 (私有函数) ---
 #[test]
 fn test_normalize_shortcut_format() {
@@ -130,4 +131,47 @@ fn test_copy_dir_all() {
     // 验证内容
     let content1 = fs::read_to_string(dst_path.join("file1.txt")).unwrap();
     assert_eq!(content1, "content1\n");
-}
\ No newline at end of file
+}
+
+// --- 5. 暂停记录状态测试 ---
+#[test]
+fn test_capture_pause_state() {
+    let state = CapturePauseState::new();
+    assert!(!state.is_paused());
+
+    // 手动暂停：没有自动恢复时间
+    let status = state.pause(1_000, None);
+    assert!(status.paused);
+    assert_eq!(status.resume_at, None);
+    assert!(!state.check_auto_resume(i64::MAX));
+    assert!(state.is_paused());
+
+    // 手动恢复
+    let status = state.resume();
+    assert!(!status.paused);
+    assert_eq!(status.resume_at, None);
+
+    // 定时暂停：60 秒后自动恢复
+    let status = state.pause(1_000, Some(60));
+    assert_eq!(status.resume_at, Some(61_000));
+    assert!(!state.check_auto_resume(60_999));
+    assert!(state.is_paused());
+    assert!(state.check_auto_resume(61_000));
+    assert!(!state.is_paused());
+    assert_eq!(state.get_status().resume_at, None);
+
+    // 已恢复后不会重复触发
+    assert!(!state.check_auto_resume(70_000));
+}
+
+#[test]
+fn test_toggle_capture_shortcut_registered() {
+    let config = SHORTCUT_CONFIGS
+        .get("toggleCapture")
+        .expect("toggleCapture shortcut missing");
+    assert_eq!(config.storage_key, "global_shortcut_6");
+    assert_eq!(
+        STORAGE_KEY_TO_HANDLER_KEY.get("global_shortcut_6"),
+        Some(&"toggleCapture")
+    );
+}
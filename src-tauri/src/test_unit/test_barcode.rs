@@ -0,0 +1,183 @@
+/// 二维码与条码解码单元测试（测试图片在测试中生成）
+use super::*;
+use image::{imageops, Luma};
+
+// --- 测试辅助函数 ---
+
+/// 将条空宽度（从条开始）展开为模块序列，true 表示深色
+fn widths_to_modules(widths: &[u8]) -> Vec<bool> {
+    widths
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &w)| std::iter::repeat_n(i % 2 == 0, w as usize))
+        .collect()
+}
+
+/// 按 EAN-13 规则编码 13 位数字（含校验位）
+fn ean13_modules(digits: &str) -> Vec<bool> {
+    let digits: Vec<usize> = digits.bytes().map(|b| (b - b'0') as usize).collect();
+    let parity = EAN_FIRST_DIGIT_PARITY[digits[0]];
+    let mut modules = widths_to_modules(&[1, 1, 1]);
+    for (i, &d) in digits[1..7].iter().enumerate() {
+        let p = EAN_L_PATTERNS[d];
+        // L/G 组以空开始
+        let widths = if parity & (1 << (5 - i)) != 0 {
+            [p[3], p[2], p[1], p[0]]
+        } else {
+            p
+        };
+        modules.extend(widths_to_modules(&widths).iter().map(|m| !m));
+    }
+    modules.extend(widths_to_modules(&[1, 1, 1, 1, 1]).iter().map(|m| !m));
+    for &d in &digits[7..] {
+        modules.extend(widths_to_modules(&EAN_L_PATTERNS[d]));
+    }
+    modules.extend(widths_to_modules(&[1, 1, 1]));
+    modules
+}
+
+/// 按 Code 128 规则编码符号值（首个为起始符），自动追加校验位与终止符
+fn code128_modules(values: &[usize]) -> Vec<bool> {
+    let check = (values[0]
+        + values[1..]
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| (i + 1) * v)
+            .sum::<usize>())
+        % 103;
+    let mut modules = Vec::new();
+    for &v in values.iter().chain([check, CODE128_STOP].iter()) {
+        modules.extend(widths_to_modules(&CODE128_PATTERNS[v]));
+    }
+    modules.extend([true, true]);
+    modules
+}
+
+/// 将模块序列绘制为白底黑条的条码图片，四周留出空白
+fn render_barcode(modules: &[bool], module_px: u32, height: u32) -> DynamicImage {
+    let margin = 10 * module_px;
+    let width = modules.len() as u32 * module_px + margin * 2;
+    let image = GrayImage::from_fn(width, height + 20, |x, y| {
+        let inside = x >= margin && x < width - margin && (10..10 + height).contains(&y);
+        if inside && modules[((x - margin) / module_px) as usize] {
+            Luma([20])
+        } else {
+            Luma([235])
+        }
+    });
+    DynamicImage::ImageLuma8(image)
+}
+
+/// 使用 qrcode 生成二维码图片
+fn render_qr(payload: &str, module_px: u32) -> DynamicImage {
+    let code = qrcode::QrCode::new(payload.as_bytes()).unwrap();
+    let size = code.width() as u32;
+    let colors = code.to_colors();
+    let quiet = 4;
+    let image = GrayImage::from_fn((size + quiet * 2) * module_px, (size + quiet * 2) * module_px, |x, y| {
+        let (mx, my) = (x / module_px, y / module_px);
+        let dark = mx >= quiet
+            && my >= quiet
+            && mx < size + quiet
+            && my < size + quiet
+            && colors[((my - quiet) * size + mx - quiet) as usize] == qrcode::Color::Dark;
+        Luma([if dark { 0 } else { 255 }])
+    });
+    DynamicImage::ImageLuma8(image)
+}
+
+fn code(format: BarcodeFormat, payload: &str) -> DecodedCode {
+    DecodedCode {
+        format,
+        payload: payload.to_string(),
+    }
+}
+
+// --- 测试用例 ---
+
+#[test]
+fn test_code128_table_is_consistent() {
+    for (value, pattern) in CODE128_PATTERNS.iter().enumerate() {
+        assert_eq!(pattern.iter().map(|&w| w as u32).sum::<u32>(), 11, "symbol {}", value);
+        assert_eq!(
+            CODE128_PATTERNS.iter().filter(|p| *p == pattern).count(),
+            1,
+            "symbol {} is not unique",
+            value
+        );
+    }
+}
+
+#[test]
+fn test_decode_ean13_and_upca() {
+    let image = render_barcode(&ean13_modules("4006381333931"), 3, 60);
+    assert_eq!(decode_image(&image), vec![code(BarcodeFormat::Ean13, "4006381333931")]);
+
+    // 旋转或倒置的条码同样可以识别
+    let rotated = DynamicImage::ImageRgba8(imageops::rotate90(&image.to_rgba8()));
+    assert_eq!(decode_image(&rotated), vec![code(BarcodeFormat::Ean13, "4006381333931")]);
+    let flipped = DynamicImage::ImageRgba8(imageops::rotate180(&image.to_rgba8()));
+    assert_eq!(decode_image(&flipped), vec![code(BarcodeFormat::Ean13, "4006381333931")]);
+
+    // 首位为 0 的 EAN-13 即 UPC-A
+    let upc = render_barcode(&ean13_modules("0036000291452"), 2, 40);
+    assert_eq!(decode_image(&upc), vec![code(BarcodeFormat::UpcA, "036000291452")]);
+
+    // 校验位错误时不识别
+    let corrupted = render_barcode(&ean13_modules("4006381333932"), 3, 60);
+    assert!(decode_image(&corrupted).is_empty());
+}
+
+#[test]
+fn test_decode_code128() {
+    // 字符集 B：可打印 ASCII
+    let text = "SmartPaste-2024";
+    let mut values = vec![104];
+    values.extend(text.bytes().map(|b| (b - 32) as usize));
+    let image = render_barcode(&code128_modules(&values), 2, 50);
+    assert_eq!(decode_image(&image), vec![code(BarcodeFormat::Code128, text)]);
+
+    // 字符集 C：两位数字一个符号
+    let image = render_barcode(&code128_modules(&[105, 12, 34, 56, 78]), 3, 50);
+    assert_eq!(decode_image(&image), vec![code(BarcodeFormat::Code128, "12345678")]);
+
+    // 深色背景上的浅色条码
+    let mut inverted = render_barcode(&code128_modules(&[104, 33, 34]), 3, 50);
+    inverted.invert();
+    assert!(looks_inverted(&inverted.to_luma8()));
+    assert!(!looks_inverted(&image.to_luma8()));
+    assert_eq!(decode_image(&inverted), vec![code(BarcodeFormat::Code128, "AB")]);
+}
+
+#[test]
+fn test_code128_text_code_sets() {
+    // A 'A'，SHIFT 后按 B 解释 'a'，A 'B'，切换到 C "12"，FNC1 被忽略
+    assert_eq!(code128_text(&[103, 33, 98, 65, 34, 99, 12, 102]).as_deref(), Some("AaB12"));
+    // 字符集 A 的控制字符
+    assert_eq!(code128_text(&[103, 73]).as_deref(), Some("\t"));
+    assert_eq!(code128_text(&[104, 106]), None);
+}
+
+#[test]
+fn test_decode_qr_code() {
+    let payload = "WIFI:S:Home Network;T:WPA;P:p@ss;word;;";
+    let image = render_qr(payload, 4);
+    assert_eq!(decode_image(&image), vec![code(BarcodeFormat::QrCode, payload)]);
+
+    let url = "https://example.com/pay?id=42";
+    let mut dark_mode = render_qr(url, 3);
+    dark_mode.invert();
+    assert_eq!(decode_image(&dark_mode), vec![code(BarcodeFormat::QrCode, url)]);
+}
+
+#[test]
+fn test_no_codes_in_plain_images() {
+    let blank = DynamicImage::ImageLuma8(GrayImage::from_pixel(120, 80, Luma([255])));
+    assert!(decode_image(&blank).is_empty());
+
+    // 规则的条纹不是合法条码
+    let stripes = DynamicImage::ImageLuma8(GrayImage::from_fn(300, 60, |x, _| {
+        Luma([if (x / 3) % 2 == 0 { 0 } else { 255 }])
+    }));
+    assert!(decode_image(&stripes).is_empty());
+}
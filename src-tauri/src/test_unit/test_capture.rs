@@ -0,0 +1,629 @@
+/// 剪贴板捕获流程单元测试
+/// 使用内存剪贴板与固定来源应用驱动 CapturePipeline，无需启动 Tauri 应用
+use super::*;
+use crate::clipboard_backend::{ClipboardImage, ClipboardRepresentations, RichText};
+use crate::clipboard_hints::{ClipboardFormatReader, KDE_PASSWORD_HINT, REDACTED_PLACEHOLDER};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+// --- 测试辅助结构 ---
+
+/// 内存剪贴板的内容
+#[derive(Default)]
+struct MemoryState {
+    text: Option<String>,
+    html: Option<String>,
+    rtf: Option<String>,
+    image: Option<ClipboardImage>,
+    files: Option<Vec<PathBuf>>,
+    /// 额外格式（如密码管理器提示）
+    formats: HashMap<String, Vec<u8>>,
+    /// 变化计数；None 表示模拟不支持变化计数的平台
+    token: Option<u64>,
+}
+
+/// 内存剪贴板：每次写入都会清空其他格式并推进变化计数
+#[derive(Default)]
+struct MemoryClipboard {
+    state: Mutex<MemoryState>,
+    /// 富文本读取次数
+    rich_text_reads: AtomicUsize,
+}
+
+impl MemoryClipboard {
+    fn with_token() -> Self {
+        let clipboard = Self::default();
+        clipboard.state.lock().unwrap().token = Some(1);
+        clipboard
+    }
+
+    fn replace(&self, f: impl FnOnce(&mut MemoryState)) {
+        let mut state = self.state.lock().unwrap();
+        let token = state.token.map(|t| t + 1);
+        *state = MemoryState {
+            token,
+            ..Default::default()
+        };
+        f(&mut state);
+    }
+
+    fn set_text(&self, text: &str) {
+        self.replace(|s| s.text = Some(text.to_string()));
+    }
+
+    fn set_image(&self, width: u32, height: u32, seed: u8) {
+        let rgba = (0..(width * height * 4) as usize)
+            .map(|i| (i as u8).wrapping_add(seed))
+            .collect();
+        self.replace(|s| {
+            s.image = Some(ClipboardImage {
+                width,
+                height,
+                rgba,
+            })
+        });
+    }
+
+    fn set_files(&self, paths: Vec<PathBuf>) {
+        self.replace(|s| s.files = Some(paths));
+    }
+
+    /// 模拟密码管理器写入带提示格式的文本
+    fn set_text_with_format(&self, text: &str, format: &str) {
+        self.replace(|s| {
+            s.text = Some(text.to_string());
+            s.formats.insert(format.to_string(), b"secret".to_vec());
+        });
+    }
+
+    /// 模拟浏览器、Office 等应用同时写入纯文本与富文本格式
+    fn set_rich_text(&self, text: Option<&str>, html: Option<&str>, rtf: Option<&str>) {
+        self.replace(|s| {
+            s.text = text.map(str::to_string);
+            s.html = html.map(str::to_string);
+            s.rtf = rtf.map(str::to_string);
+        });
+    }
+
+    /// 模拟一次复制同时写入多种表示（如表格选区：文本 + HTML + 图片）
+    fn set_multi(&self, text: Option<&str>, html: Option<&str>, image_seed: Option<u8>, files: Option<Vec<PathBuf>>) {
+        self.replace(|s| {
+            s.text = text.map(str::to_string);
+            s.html = html.map(str::to_string);
+            s.image = image_seed.map(|seed| ClipboardImage {
+                width: 2,
+                height: 2,
+                rgba: vec![seed; 16],
+            });
+            s.files = files;
+        });
+    }
+
+    /// 修改内容但不推进变化计数（用于验证计数未变时不读取）
+    fn set_text_silently(&self, text: &str) {
+        self.state.lock().unwrap().text = Some(text.to_string());
+    }
+}
+
+impl ClipboardFormatReader for MemoryClipboard {
+    fn available_formats(&self) -> Result<Vec<String>, String> {
+        Ok(self.state.lock().unwrap().formats.keys().cloned().collect())
+    }
+
+    fn read_format(&self, format: &str) -> Result<Vec<u8>, String> {
+        self.state
+            .lock()
+            .unwrap()
+            .formats
+            .get(format)
+            .cloned()
+            .ok_or_else(|| format!("format not found: {}", format))
+    }
+}
+
+impl ClipboardBackend for MemoryClipboard {
+    fn change_token(&self) -> Option<u64> {
+        self.state.lock().unwrap().token
+    }
+
+    fn read_text(&self) -> Option<String> {
+        self.state.lock().unwrap().text.clone()
+    }
+
+    fn read_rich_text(&self) -> RichText {
+        self.rich_text_reads.fetch_add(1, Ordering::SeqCst);
+        let state = self.state.lock().unwrap();
+        RichText {
+            html: state.html.clone(),
+            rtf: state.rtf.clone(),
+        }
+    }
+
+    fn image_fingerprint(&self) -> Option<u64> {
+        let state = self.state.lock().unwrap();
+        let image = state.image.as_ref()?;
+        Some(crate::change_detect::image_fingerprint(
+            image.width,
+            image.height,
+            &image.rgba,
+        ))
+    }
+
+    fn read_image(&self) -> Option<ClipboardImage> {
+        self.state.lock().unwrap().image.clone()
+    }
+
+    fn read_files(&self) -> Option<Vec<PathBuf>> {
+        self.state.lock().unwrap().files.clone()
+    }
+
+    fn write_text(&self, text: &str) -> Result<(), String> {
+        self.set_text(text);
+        Ok(())
+    }
+
+    fn write_files(&self, paths: &[PathBuf]) -> Result<(), String> {
+        self.set_files(paths.to_vec());
+        Ok(())
+    }
+
+    fn write_representations(&self, reps: &ClipboardRepresentations) -> Result<(), String> {
+        self.replace(|s| {
+            s.text = reps.text.clone();
+            s.html = reps.html.clone();
+            s.rtf = reps.rtf.clone();
+            s.files = Some(reps.files.clone()).filter(|f| !f.is_empty());
+        });
+        Ok(())
+    }
+}
+
+/// 返回固定来源的假实现
+struct FixedSource(Option<SourceApp>);
+
+impl SourceAppProvider for FixedSource {
+    fn current_source(&self) -> Option<SourceApp> {
+        self.0.clone()
+    }
+}
+
+fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    crate::db::TEST_RUN_LOCK
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+}
+
+/// 准备独立的数据库与文件目录，返回测试配置
+fn setup() -> CaptureSettings {
+    let tmp = std::env::temp_dir().join(format!("smartpaste_test_capture_{}", Uuid::new_v4()));
+    let files_dir = tmp.join("files");
+    fs::create_dir_all(&files_dir).unwrap();
+    db::set_db_path(tmp.join("smartpaste.db"));
+    CaptureSettings {
+        files_dir,
+        ignore_big_file_mb: 5,
+        ignore_short_text_len: 0,
+        hint_policy: HintPolicy::Skip,
+        ignored_apps: Vec::new(),
+        filter_passwords: true,
+        filter_bank_cards: true,
+        filter_id_cards: true,
+        filter_phone_numbers: true,
+    }
+}
+
+fn teardown(settings: &CaptureSettings) {
+    let _ = fs::remove_dir_all(settings.files_dir.parent().unwrap());
+}
+
+/// 创建流程并执行首轮基准读取
+fn pipeline_with(
+    clipboard: &Arc<MemoryClipboard>,
+    source: Option<SourceApp>,
+    settings: &CaptureSettings,
+) -> CapturePipeline<MemoryClipboard> {
+    let mut pipeline = CapturePipeline::new(clipboard.clone(), Box::new(FixedSource(source)));
+    assert!(matches!(
+        pipeline.step(TickInput::default(), settings),
+        CaptureOutcome::Baseline
+    ));
+    pipeline
+}
+
+fn captured(outcome: CaptureOutcome) -> Vec<CapturedItem> {
+    match outcome {
+        CaptureOutcome::Captured(items) => items,
+        other => panic!("expected Captured, got {:?}", other),
+    }
+}
+
+fn skipped(outcome: CaptureOutcome) -> SkipReason {
+    match outcome {
+        CaptureOutcome::Skipped(reason) => reason,
+        other => panic!("expected Skipped, got {:?}", other),
+    }
+}
+
+fn count_items() -> usize {
+    let json = db::get_all_data().unwrap();
+    serde_json::from_str::<Vec<ClipboardItem>>(&json).unwrap().len()
+}
+
+// --- 测试用例 ---
+
+#[test]
+fn test_baseline_is_not_recorded() {
+    let _g = test_lock();
+    let settings = setup();
+    let clipboard = Arc::new(MemoryClipboard::default());
+    clipboard.set_text("already here");
+
+    let mut pipeline = pipeline_with(&clipboard, None, &settings);
+    assert!(matches!(
+        pipeline.step(TickInput::default(), &settings),
+        CaptureOutcome::Unchanged
+    ));
+    assert_eq!(count_items(), 0);
+
+    teardown(&settings);
+}
+
+#[test]
+fn test_text_capture_and_dedup() {
+    let _g = test_lock();
+    let settings = setup();
+    let clipboard = Arc::new(MemoryClipboard::default());
+    let source = SourceApp {
+        app_name: "code".to_string(),
+        window_title: "main.rs".to_string(),
+    };
+    let mut pipeline = pipeline_with(&clipboard, Some(source.clone()), &settings);
+
+    clipboard.set_text("hello world");
+    let items = captured(pipeline.step(TickInput::default(), &settings));
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].kind, CaptureKind::Text);
+    assert_eq!(items[0].item.content, "hello world");
+    assert_eq!(items[0].item.size, Some(11));
+
+    // 来源应用已保存
+    let json = db::get_item_source_by_item_id(&items[0].item.id).unwrap();
+    assert_eq!(serde_json::from_str::<SourceApp>(&json).unwrap(), source);
+
+    // 相同内容不重复记录
+    assert!(matches!(
+        pipeline.step(TickInput::default(), &settings),
+        CaptureOutcome::Unchanged
+    ));
+    assert_eq!(count_items(), 1);
+
+    teardown(&settings);
+}
+
+#[test]
+fn test_short_text_filter() {
+    let _g = test_lock();
+    let mut settings = setup();
+    settings.ignore_short_text_len = 5;
+    let clipboard = Arc::new(MemoryClipboard::default());
+    let mut pipeline = pipeline_with(&clipboard, None, &settings);
+
+    clipboard.set_text("abc");
+    assert_eq!(
+        skipped(pipeline.step(TickInput::default(), &settings)),
+        SkipReason::TooShort
+    );
+    clipboard.set_text("abcdef");
+    assert_eq!(captured(pipeline.step(TickInput::default(), &settings)).len(), 1);
+
+    teardown(&settings);
+}
+
+#[test]
+fn test_privacy_marking_on_capture() {
+    let _g = test_lock();
+    let settings = setup();
+    let clipboard = Arc::new(MemoryClipboard::default());
+    let mut pipeline = pipeline_with(&clipboard, None, &settings);
+
+    clipboard.set_text("card 4111 1111 1111 1111");
+    let items = captured(pipeline.step(TickInput::default(), &settings));
+    assert!(db::is_private_item(&items[0].item.id).unwrap());
+
+    teardown(&settings);
+}
+
+#[test]
+fn test_echo_suppression_by_fingerprint() {
+    let _g = test_lock();
+    let settings = setup();
+    let clipboard = Arc::new(MemoryClipboard::default());
+    let mut pipeline = pipeline_with(&clipboard, None, &settings);
+    let echoes = EchoFilter::new();
+    let input = TickInput {
+        echoes: Some(&echoes),
+        paused: false,
+    };
+    let text_write = |text: &str| ClipboardRepresentations {
+        text: Some(text.to_string()),
+        ..Default::default()
+    };
+
+    // 应用自身写入的内容不记录
+    echoes.register(crate::echo_filter::fingerprints_of(&text_write("written by app")));
+    clipboard.write_text("written by app").unwrap();
+    assert_eq!(skipped(pipeline.step(input, &settings)), SkipReason::FrontendCopy);
+
+    // 紧接着的用户复制照常记录
+    clipboard.set_text("user copy right after");
+    assert_eq!(captured(pipeline.step(input, &settings)).len(), 1);
+
+    // 写入较慢、多轮之后才出现在剪贴板中时仍被忽略
+    echoes.register(crate::echo_filter::fingerprints_of(&text_write("slow write")));
+    for _ in 0..50 {
+        assert!(matches!(pipeline.step(input, &settings), CaptureOutcome::Unchanged));
+    }
+    clipboard.write_text("slow write").unwrap();
+    assert_eq!(skipped(pipeline.step(input, &settings)), SkipReason::FrontendCopy);
+
+    // 多种表示一起写回时同样被忽略
+    let reps = ClipboardRepresentations {
+        text: Some("Bold text".to_string()),
+        html: Some("<b>Bold</b> text".to_string()),
+        ..Default::default()
+    };
+    echoes.register(crate::echo_filter::fingerprints_of(&reps));
+    clipboard.write_representations(&reps).unwrap();
+    assert_eq!(skipped(pipeline.step(input, &settings)), SkipReason::FrontendCopy);
+
+    assert_eq!(count_items(), 1);
+
+    teardown(&settings);
+}
+
+#[test]
+fn test_paused_updates_state_without_recording() {
+    let _g = test_lock();
+    let settings = setup();
+    let clipboard = Arc::new(MemoryClipboard::default());
+    let mut pipeline = pipeline_with(&clipboard, None, &settings);
+
+    clipboard.set_text("copied while paused");
+    let paused = TickInput {
+        echoes: None,
+        paused: true,
+    };
+    assert_eq!(skipped(pipeline.step(paused, &settings)), SkipReason::Paused);
+
+    // 恢复后不会补记暂停期间的内容
+    assert!(matches!(
+        pipeline.step(TickInput::default(), &settings),
+        CaptureOutcome::Unchanged
+    ));
+    assert_eq!(count_items(), 0);
+
+    teardown(&settings);
+}
+
+#[test]
+fn test_password_hint_skip_and_redact() {
+    let _g = test_lock();
+    let mut settings = setup();
+    let clipboard = Arc::new(MemoryClipboard::default());
+    let mut pipeline = pipeline_with(&clipboard, None, &settings);
+
+    clipboard.set_text_with_format("hunter2", KDE_PASSWORD_HINT);
+    assert_eq!(
+        skipped(pipeline.step(TickInput::default(), &settings)),
+        SkipReason::PasswordHint(KDE_PASSWORD_HINT.to_string())
+    );
+
+    settings.hint_policy = HintPolicy::Private;
+    clipboard.set_text_with_format("hunter3", KDE_PASSWORD_HINT);
+    let items = captured(pipeline.step(TickInput::default(), &settings));
+    assert_eq!(items[0].item.content, REDACTED_PLACEHOLDER);
+    assert!(items[0].redacted);
+    assert!(db::is_private_item(&items[0].item.id).unwrap());
+
+    teardown(&settings);
+}
+
+#[test]
+fn test_ignored_source_app() {
+    let _g = test_lock();
+    let mut settings = setup();
+    settings.ignored_apps = vec!["KeePassXC".to_string()];
+    let clipboard = Arc::new(MemoryClipboard::default());
+    let source = SourceApp {
+        app_name: "keepassxc".to_string(),
+        window_title: "Vault".to_string(),
+    };
+    let mut pipeline = pipeline_with(&clipboard, Some(source), &settings);
+
+    clipboard.set_text("from the vault");
+    assert_eq!(
+        skipped(pipeline.step(TickInput::default(), &settings)),
+        SkipReason::IgnoredApp("keepassxc".to_string())
+    );
+    assert_eq!(count_items(), 0);
+
+    teardown(&settings);
+}
+
+#[test]
+fn test_image_capture_saves_png() {
+    let _g = test_lock();
+    let settings = setup();
+    let clipboard = Arc::new(MemoryClipboard::default());
+    let mut pipeline = pipeline_with(&clipboard, None, &settings);
+
+    clipboard.set_image(4, 3, 7);
+    let items = captured(pipeline.step(TickInput::default(), &settings));
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].kind, CaptureKind::Image);
+    assert_eq!(items[0].item.item_type, "image");
+    assert!(items[0].item.content.starts_with("files"));
+    let path = items[0].absolute_path.clone().unwrap();
+    let saved = image::open(&path).unwrap();
+    assert_eq!((saved.width(), saved.height()), (4, 3));
+
+    // 同一张图片不重复记录，换图后记录
+    assert!(matches!(
+        pipeline.step(TickInput::default(), &settings),
+        CaptureOutcome::Unchanged
+    ));
+    clipboard.set_image(4, 3, 8);
+    assert_eq!(captured(pipeline.step(TickInput::default(), &settings)).len(), 1);
+
+    teardown(&settings);
+}
+
+#[test]
+fn test_file_capture_and_size_limit() {
+    let _g = test_lock();
+    let mut settings = setup();
+    settings.ignore_big_file_mb = 1;
+    let src_dir = settings.files_dir.parent().unwrap().join("src");
+    fs::create_dir_all(src_dir.join("folder")).unwrap();
+    let small = src_dir.join("notes.txt");
+    let photo = src_dir.join("photo.PNG");
+    let big = src_dir.join("big.bin");
+    fs::write(&small, b"small file").unwrap();
+    fs::write(&photo, b"not really a png").unwrap();
+    fs::write(&big, vec![0u8; 2 * 1024 * 1024]).unwrap();
+    fs::write(src_dir.join("folder").join("inner.txt"), b"inner").unwrap();
+
+    let clipboard = Arc::new(MemoryClipboard::default());
+    let mut pipeline = pipeline_with(&clipboard, None, &settings);
+
+    clipboard.set_files(vec![
+        small.clone(),
+        photo.clone(),
+        big.clone(),
+        src_dir.join("folder"),
+    ]);
+    let items = captured(pipeline.step(TickInput::default(), &settings));
+    let types: Vec<&str> = items.iter().map(|c| c.item.item_type.as_str()).collect();
+    assert_eq!(types, vec!["file", "image", "folder"]);
+    assert!(items.iter().all(|c| c.kind == CaptureKind::File));
+    assert_eq!(items[0].item.size, Some(10));
+    assert!(items[0].absolute_path.as_ref().unwrap().exists());
+    assert!(items[2]
+        .absolute_path
+        .as_ref()
+        .unwrap()
+        .join("inner.txt")
+        .exists());
+
+    teardown(&settings);
+}
+
+#[test]
+fn test_unchanged_token_skips_reads() {
+    let _g = test_lock();
+    let settings = setup();
+    let clipboard = Arc::new(MemoryClipboard::with_token());
+    let mut pipeline = pipeline_with(&clipboard, None, &settings);
+    assert!(pipeline.has_native_change_token());
+
+    // 内容变化但计数未变：不读取
+    clipboard.set_text_silently("invisible");
+    assert!(matches!(
+        pipeline.step(TickInput::default(), &settings),
+        CaptureOutcome::Unchanged
+    ));
+
+    clipboard.set_text("visible");
+    let items = captured(pipeline.step(TickInput::default(), &settings));
+    assert_eq!(items[0].item.content, "visible");
+
+    teardown(&settings);
+}
+
+#[test]
+fn test_rich_text_formats_are_saved() {
+    let _g = test_lock();
+    let settings = setup();
+    let clipboard = Arc::new(MemoryClipboard::default());
+    let mut pipeline = pipeline_with(&clipboard, None, &settings);
+
+    let cf_html = "Version:0.9\r\nStartHTML:0000000105\r\nEndHTML:0000000160\r\n<html><body><!--StartFragment--><b>Bold</b> text<!--EndFragment--></body></html>";
+    clipboard.set_rich_text(Some("Bold text"), Some(cf_html), Some(r"{\rtf1\ansi {\b Bold} text}"));
+    let items = captured(pipeline.step(TickInput::default(), &settings));
+    assert_eq!(items[0].item.content, "Bold text");
+
+    let formats = db::get_item_formats(&items[0].item.id).unwrap();
+    let html = formats.iter().find(|f| f.format == db::FORMAT_HTML).unwrap();
+    assert_eq!(html.content, "<b>Bold</b> text");
+    assert!(formats.iter().any(|f| f.format == db::FORMAT_RTF));
+
+    // 只有 HTML 时，从 HTML 提取纯文本；每轮只读取一次富文本
+    clipboard.set_rich_text(None, Some("<p>Only&nbsp;HTML</p>"), None);
+    let reads = clipboard.rich_text_reads.load(Ordering::SeqCst);
+    let items = captured(pipeline.step(TickInput::default(), &settings));
+    assert_eq!(clipboard.rich_text_reads.load(Ordering::SeqCst), reads + 1);
+    assert_eq!(items[0].item.content, "Only HTML");
+    assert!(!items[0].redacted);
+    assert_eq!(db::get_item_formats(&items[0].item.id).unwrap().len(), 1);
+
+    teardown(&settings);
+}
+
+#[test]
+fn test_preferred_representation_rule() {
+    use Representation::*;
+    // (图片, 文件, 文本, 富文本)
+    assert_eq!(preferred_representation(true, true, true, true), Some(Files));
+    assert_eq!(preferred_representation(true, false, true, true), Some(Text));
+    assert_eq!(preferred_representation(true, false, true, false), Some(Image));
+    assert_eq!(preferred_representation(true, false, false, true), Some(Image));
+    assert_eq!(preferred_representation(false, false, true, false), Some(Text));
+    assert_eq!(preferred_representation(false, false, false, false), None);
+}
+
+#[test]
+fn test_multi_representation_capture() {
+    let _g = test_lock();
+    let settings = setup();
+    let clipboard = Arc::new(MemoryClipboard::default());
+    let mut pipeline = pipeline_with(&clipboard, None, &settings);
+
+    // 表格选区：文本为主体，HTML 与图片作为其他表示
+    clipboard.set_multi(Some("A1\tB1"), Some("<table><tr><td>A1</td><td>B1</td></tr></table>"), Some(3), None);
+    let items = captured(pipeline.step(TickInput::default(), &settings));
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].item.item_type, "text");
+    let id = items[0].item.id.clone();
+    assert!(db::get_item_format(&id, db::FORMAT_HTML).unwrap().is_some());
+    assert_eq!(db::get_item_format(&id, db::FORMAT_TEXT).unwrap(), None);
+    let image = db::get_item_format(&id, db::FORMAT_IMAGE).unwrap().unwrap();
+    assert!(settings.files_dir.parent().unwrap().join(&image).exists());
+
+    // 图片 + 纯文本（无富文本）：图片为主体，文本作为其他表示
+    clipboard.set_multi(Some("caption"), None, Some(4), None);
+    let items = captured(pipeline.step(TickInput::default(), &settings));
+    assert_eq!(items[0].item.item_type, "image");
+    assert_eq!(
+        db::get_item_format(&items[0].item.id, db::FORMAT_TEXT).unwrap().as_deref(),
+        Some("caption")
+    );
+
+    // 文件 + 文本（文件名）：文件为主体，不保存图标图片
+    let src = settings.files_dir.parent().unwrap().join("report.txt");
+    fs::write(&src, "report").unwrap();
+    clipboard.set_multi(Some("report.txt"), None, Some(5), Some(vec![src]));
+    let items = captured(pipeline.step(TickInput::default(), &settings));
+    assert_eq!(items[0].item.item_type, "file");
+    let formats = db::get_item_formats(&items[0].item.id).unwrap();
+    assert_eq!(formats.len(), 1);
+    assert_eq!(formats[0].format, db::FORMAT_TEXT);
+
+    // 再次复制同样的组合不重复记录
+    assert!(matches!(
+        pipeline.step(TickInput::default(), &settings),
+        CaptureOutcome::Unchanged
+    ));
+
+    teardown(&settings);
+}
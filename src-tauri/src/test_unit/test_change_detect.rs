@@ -0,0 +1,110 @@
+/// 剪贴板变化检测单元测试
+use super::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+// --- 测试辅助结构 ---
+
+/// 可手动推进的变化计数，0 表示平台不支持
+struct FakeTokenSource {
+    token: Arc<AtomicU64>,
+}
+
+impl ChangeTokenSource for FakeTokenSource {
+    fn change_token(&self) -> Option<u64> {
+        match self.token.load(Ordering::SeqCst) {
+            0 => None,
+            t => Some(t),
+        }
+    }
+}
+
+fn make_image(width: u32, height: u32, seed: u8) -> Vec<u8> {
+    (0..(width * height * 4) as usize)
+        .map(|i| (i as u8).wrapping_mul(31).wrapping_add(seed))
+        .collect()
+}
+
+// --- 测试用例 ---
+
+#[test]
+fn test_detector_follows_native_token() {
+    let token = Arc::new(AtomicU64::new(5));
+    let mut detector = ChangeDetector::new(FakeTokenSource {
+        token: token.clone(),
+    });
+
+    assert!(detector.has_native_token());
+    // 第一次轮询总是视为变化
+    assert!(detector.poll());
+    assert!(!detector.poll());
+    assert!(!detector.poll());
+
+    token.store(6, Ordering::SeqCst);
+    assert!(detector.poll());
+    assert!(!detector.poll());
+}
+
+#[test]
+fn test_detector_without_native_token_always_reads() {
+    let token = Arc::new(AtomicU64::new(0));
+    let mut detector = ChangeDetector::new(FakeTokenSource {
+        token: token.clone(),
+    });
+
+    assert!(!detector.has_native_token());
+    assert!(detector.poll());
+    assert!(detector.poll());
+
+    // 计数恢复可用后重新按计数判断
+    token.store(9, Ordering::SeqCst);
+    assert!(detector.poll());
+    assert!(!detector.poll());
+}
+
+#[test]
+fn test_image_fingerprint_detects_changes() {
+    let a = make_image(640, 480, 1);
+    let b = make_image(640, 480, 2);
+
+    assert_eq!(image_fingerprint(640, 480, &a), image_fingerprint(640, 480, &a.clone()));
+    assert_ne!(image_fingerprint(640, 480, &a), image_fingerprint(640, 480, &b));
+    // 相同像素、不同尺寸
+    assert_ne!(image_fingerprint(640, 480, &a), image_fingerprint(480, 640, &a));
+
+    // 修改头部或尾部像素
+    let mut head = a.clone();
+    head[0] ^= 0xff;
+    assert_ne!(image_fingerprint(640, 480, &a), image_fingerprint(640, 480, &head));
+    let mut tail = a.clone();
+    let last = tail.len() - 1;
+    tail[last] ^= 0xff;
+    assert_ne!(image_fingerprint(640, 480, &a), image_fingerprint(640, 480, &tail));
+}
+
+#[test]
+fn test_image_fingerprint_detects_change_in_the_middle() {
+    // 4K 截图中间的一小块区域变化（如光标、时钟），头尾完全相同
+    let (width, height) = (3840u32, 2160u32);
+    let a = make_image(width, height, 1);
+    let original = image_fingerprint(width, height, &a);
+    for offset in [a.len() / 2, a.len() / 2 + 3, a.len() / 3 + 17] {
+        let mut b = a.clone();
+        b[offset] ^= 0x01;
+        assert_ne!(original, image_fingerprint(width, height, &b), "偏移 {} 处的变化未被检测到", offset);
+    }
+}
+
+#[test]
+fn test_fast_hash_covers_every_byte() {
+    let a = vec![7u8; 10_000];
+    for offset in [0, 4_999, 5_000, 9_999] {
+        let mut b = a.clone();
+        b[offset] = 8;
+        assert_ne!(fast_hash(&a), fast_hash(&b));
+    }
+    // 长度不同（包括末尾补零的情况）
+    assert_ne!(fast_hash(&a), fast_hash(&a[..9_999]));
+    assert_ne!(fast_hash(&[0u8; 3]), fast_hash(&[0u8; 4]));
+    assert_eq!(fast_hash(b""), fast_hash(b""));
+}
@@ -0,0 +1,67 @@
+/// 文本内容分类单元测试
+use super::*;
+
+fn subtype(text: &str) -> Option<ContentSubtype> {
+    classify_text(text).map(|c| c.subtype)
+}
+
+#[test]
+fn test_single_line_subtypes() {
+    assert_eq!(subtype("https://example.com/a?b=1"), Some(ContentSubtype::Url));
+    assert_eq!(subtype("www.example.com"), Some(ContentSubtype::Url));
+    assert_eq!(subtype("  alice@example.com \n"), Some(ContentSubtype::Email));
+    assert_eq!(subtype("#1e90ff"), Some(ContentSubtype::Color));
+    assert_eq!(subtype("rgba(0, 128, 255, 0.5)"), Some(ContentSubtype::Color));
+    assert_eq!(subtype("2024-03-15"), Some(ContentSubtype::Date));
+    assert_eq!(subtype("2024-03-15T08:30:00+08:00"), Some(ContentSubtype::Date));
+    assert_eq!(subtype("13812345678"), Some(ContentSubtype::Phone));
+    assert_eq!(subtype("+86 010-6275 1234"), Some(ContentSubtype::Phone));
+    assert_eq!(subtype("12345678"), Some(ContentSubtype::Number));
+    assert_eq!(subtype("-1,234.50"), Some(ContentSubtype::Number));
+    assert_eq!(subtype("3.14"), Some(ContentSubtype::Number));
+    assert_eq!(subtype(r"C:\Users\alice\report.docx"), Some(ContentSubtype::Path));
+    assert_eq!(subtype("~/projects/app/main.rs"), Some(ContentSubtype::Path));
+    assert_eq!(subtype("hello world"), None);
+    assert_eq!(subtype("   "), None);
+}
+
+#[test]
+fn test_json_markdown_and_code() {
+    assert_eq!(subtype("{\n  \"a\": [1, 2],\n  \"b\": null\n}"), Some(ContentSubtype::Json));
+    // 不合法的 JSON 不归为 JSON
+    assert_ne!(subtype("{ a: 1 }"), Some(ContentSubtype::Json));
+
+    let md = "# 标题\n\n- 第一项\n- 第二项\n\n详见 [文档](https://example.com)";
+    assert_eq!(subtype(md), Some(ContentSubtype::Markdown));
+
+    let rust = "fn main() {\n    let mut v = Vec::new();\n    println!(\"{:?}\", v);\n}";
+    let c = classify_text(rust).unwrap();
+    assert_eq!(c.subtype, ContentSubtype::Code);
+    assert_eq!(c.language.as_deref(), Some("rust"));
+
+    let python = "def add(a, b):\n    return a + b\n\nprint(add(1, 2))";
+    assert_eq!(classify_text(python).unwrap().language.as_deref(), Some("python"));
+
+    let sql = "SELECT id, name FROM users WHERE age > 18;";
+    let c = classify_text(sql).unwrap();
+    assert_eq!(c.subtype, ContentSubtype::Code);
+    assert_eq!(c.language.as_deref(), Some("sql"));
+
+    // 围栏代码块使用标注的语言
+    let fenced = "```TypeScript\nconst x: number = 1;\n```";
+    assert_eq!(classify_text(fenced).unwrap().language.as_deref(), Some("typescript"));
+
+    // 普通段落不是代码
+    assert_eq!(subtype("今天天气不错。\n我们去公园散步吧。"), None);
+}
+
+#[test]
+fn test_subtype_string_roundtrip() {
+    for s in [
+        "url", "email", "phone", "code", "json", "color", "path", "number", "date", "markdown",
+    ] {
+        assert_eq!(ContentSubtype::parse(s).unwrap().as_str(), s);
+    }
+    assert_eq!(ContentSubtype::parse("URL"), Some(ContentSubtype::Url));
+    assert_eq!(ContentSubtype::parse("video"), None);
+}
@@ -0,0 +1,149 @@
+/// 剪贴板提示检测单元测试
+/// 使用假剪贴板模拟各平台密码管理器写入的提示格式
+use super::*;
+use std::collections::HashMap;
+
+// --- 测试辅助结构 ---
+
+/// 假剪贴板：以 格式名 -> 数据 的映射模拟系统剪贴板
+struct FakeClipboard {
+    formats: HashMap<String, Vec<u8>>,
+    fail: bool,
+}
+
+impl FakeClipboard {
+    fn new() -> Self {
+        Self {
+            formats: HashMap::new(),
+            fail: false,
+        }
+    }
+
+    fn with(mut self, format: &str, data: &[u8]) -> Self {
+        self.formats.insert(format.to_string(), data.to_vec());
+        self
+    }
+}
+
+impl ClipboardFormatReader for FakeClipboard {
+    fn available_formats(&self) -> Result<Vec<String>, String> {
+        if self.fail {
+            return Err("clipboard unavailable".to_string());
+        }
+        Ok(self.formats.keys().cloned().collect())
+    }
+
+    fn read_format(&self, format: &str) -> Result<Vec<u8>, String> {
+        self.formats
+            .get(format)
+            .cloned()
+            .ok_or_else(|| format!("format not found: {}", format))
+    }
+}
+
+// --- 测试用例 ---
+
+#[test]
+fn test_plain_text_is_captured() {
+    let clipboard = FakeClipboard::new()
+        .with("text/plain", b"hello")
+        .with("UTF8_STRING", b"hello");
+
+    assert_eq!(detect_password_hint(&clipboard), None);
+    assert_eq!(decide(&clipboard, HintPolicy::Skip), HintDecision::Capture);
+    assert_eq!(decide(&clipboard, HintPolicy::Private), HintDecision::Capture);
+}
+
+#[test]
+fn test_kde_password_hint_detected() {
+    let clipboard = FakeClipboard::new()
+        .with("text/plain", b"hunter2")
+        .with(KDE_PASSWORD_HINT, b"secret");
+
+    assert_eq!(
+        detect_password_hint(&clipboard),
+        Some(KDE_PASSWORD_HINT.to_string())
+    );
+    assert_eq!(
+        decide(&clipboard, HintPolicy::Skip),
+        HintDecision::Skip(KDE_PASSWORD_HINT.to_string())
+    );
+    assert_eq!(
+        decide(&clipboard, HintPolicy::Private),
+        HintDecision::Redact(KDE_PASSWORD_HINT.to_string())
+    );
+    // ignore 策略下照常记录
+    assert_eq!(decide(&clipboard, HintPolicy::Ignore), HintDecision::Capture);
+}
+
+#[test]
+fn test_windows_exclude_monitor_detected() {
+    let clipboard = FakeClipboard::new()
+        .with("CF_UNICODETEXT", b"p\0a\0s\0s\0")
+        .with(WIN_EXCLUDE_MONITOR, &[]);
+
+    assert_eq!(
+        detect_password_hint(&clipboard),
+        Some(WIN_EXCLUDE_MONITOR.to_string())
+    );
+}
+
+#[test]
+fn test_windows_can_include_history_value() {
+    // 值为 0：不允许进入历史记录
+    let excluded = FakeClipboard::new().with(WIN_CAN_INCLUDE_HISTORY, &0u32.to_le_bytes());
+    assert_eq!(
+        detect_password_hint(&excluded),
+        Some(WIN_CAN_INCLUDE_HISTORY.to_string())
+    );
+
+    // 值为 1：允许，照常记录
+    let allowed = FakeClipboard::new().with(WIN_CAN_INCLUDE_HISTORY, &1u32.to_le_bytes());
+    assert_eq!(detect_password_hint(&allowed), None);
+
+    // 数据不完整时保守处理为排除
+    let broken = FakeClipboard::new().with(WIN_CAN_INCLUDE_HISTORY, &[0u8]);
+    assert!(detect_password_hint(&broken).is_some());
+}
+
+#[test]
+fn test_macos_concealed_and_transient_detected() {
+    let concealed = FakeClipboard::new().with(MAC_CONCEALED_TYPE, b"");
+    assert_eq!(
+        detect_password_hint(&concealed),
+        Some(MAC_CONCEALED_TYPE.to_string())
+    );
+
+    let transient = FakeClipboard::new().with(MAC_TRANSIENT_TYPE, b"");
+    assert_eq!(
+        detect_password_hint(&transient),
+        Some(MAC_TRANSIENT_TYPE.to_string())
+    );
+}
+
+#[test]
+fn test_unreadable_clipboard_is_captured() {
+    let mut clipboard = FakeClipboard::new().with(KDE_PASSWORD_HINT, b"secret");
+    clipboard.fail = true;
+
+    assert_eq!(detect_password_hint(&clipboard), None);
+    assert_eq!(decide(&clipboard, HintPolicy::Skip), HintDecision::Capture);
+}
+
+#[test]
+fn test_parse_hint_policy() {
+    assert_eq!(parse_hint_policy("skip"), HintPolicy::Skip);
+    assert_eq!(parse_hint_policy(" Private "), HintPolicy::Private);
+    assert_eq!(parse_hint_policy("ignore"), HintPolicy::Ignore);
+    // 未知值回退到最安全的 skip
+    assert_eq!(parse_hint_policy("unknown"), HintPolicy::Skip);
+}
+
+#[test]
+fn test_redacted_item_hides_content() {
+    let item = build_redacted_item();
+    assert_eq!(item.item_type, "text");
+    assert_eq!(item.content, REDACTED_PLACEHOLDER);
+    assert!(!item.id.is_empty());
+    assert!(!item.is_favorite);
+}
@@ -366,6 +366,10 @@ fn test_config_coverage_extensions() {
         ConfigKey::AutoSave,
         ConfigKey::AutoClassify,
         ConfigKey::OcrAutoRecognition,
+        ConfigKey::OcrPreprocess,
+        ConfigKey::OcrAdaptiveThreshold,
+        ConfigKey::OcrDeskew,
+        ConfigKey::OcrCropBorders,
         ConfigKey::DeleteConfirmation,
         ConfigKey::KeepFavoritesOnDelete,
         ConfigKey::AutoSort,
@@ -429,6 +433,7 @@ fn test_config_coverage_extensions() {
         ConfigKey::RetentionDays,
         ConfigKey::IgnoreShortTextLen,
         ConfigKey::IgnoreBigFileMb,
+        ConfigKey::OcrUpscaleMinHeight,
     ];
     for key in u32_keys {
         update_simple_config_item(&key, json!(10)).unwrap();
@@ -446,6 +451,8 @@ fn test_config_coverage_extensions() {
         "global_shortcut_3",
         "global_shortcut_4",
         "global_shortcut_5",
+        "global_shortcut_6",
+        "global_shortcut_7",
         "ignore_short_text_len",
         "ignore_big_file_mb",
         "auto_classify",
@@ -461,11 +468,16 @@ fn test_config_coverage_extensions() {
         "ai_auto_summary",
         "ai_translation",
         "ai_web_search",
+        "ai_timeout_secs",
+        "ai_semantic_search",
+        "ai_embedding_model",
         "sensitive_filter",
         "filter_passwords",
         "filter_bank_cards",
         "filter_id_cards",
         "filter_phone_numbers",
+        "password_hint_policy",
+        "audit_retention_days",
         "storage_path",
         "auto_backup",
         "backup_frequency",
@@ -480,6 +492,11 @@ fn test_config_coverage_extensions() {
         "bio",
         "avatar_path",
         "ocr_provider",
+        "ocr_preprocess",
+        "ocr_upscale_min_height",
+        "ocr_adaptive_threshold",
+        "ocr_deskew",
+        "ocr_crop_borders",
     ];
 
     for key in all_keys {
@@ -658,18 +658,18 @@ fn test_comprehensive_search_with_ocr() {
 
     // 3. 测试搜索 Content
     let res_content =
-        comprehensive_search("world", None, None, None).expect("search content failed");
+        comprehensive_search("world", None, None, None, None, None).expect("search content failed");
     let items_content: Vec<ClipboardItem> =
         serde_json::from_str(&res_content).expect("parse content res");
     assert!(items_content.iter().any(|i| i.id == item_text.id));
 
     // 4. 测试搜索 Note
-    let res_note = comprehensive_search("important", None, None, None).expect("search note failed");
+    let res_note = comprehensive_search("important", None, None, None, None, None).expect("search note failed");
     let items_note: Vec<ClipboardItem> = serde_json::from_str(&res_note).expect("parse note res");
     assert!(items_note.iter().any(|i| i.id == item_note.id));
 
     // 5. 测试搜索 OCR (新功能验证)
-    let res_ocr = comprehensive_search("detected", None, None, None).expect("search ocr failed");
+    let res_ocr = comprehensive_search("detected", None, None, None, None, None).expect("search ocr failed");
     let items_ocr: Vec<ClipboardItem> = serde_json::from_str(&res_ocr).expect("parse ocr res");
     assert_eq!(items_ocr.len(), 1, "should find exactly one item by ocr");
     assert_eq!(
@@ -679,14 +679,14 @@ fn test_comprehensive_search_with_ocr() {
 
     // 6. 测试组合搜索 (OCR + Type)
     let res_combo =
-        comprehensive_search("keyword", Some("image"), None, None).expect("search combo failed");
+        comprehensive_search("keyword", Some("image"), None, None, None, None).expect("search combo failed");
     let items_combo: Vec<ClipboardItem> =
         serde_json::from_str(&res_combo).expect("parse combo res");
     assert_eq!(items_combo.len(), 1);
     assert_eq!(items_combo[0].id, item_img_ocr.id);
 
     // 7. 测试组合搜索 (OCR + Wrong Type)
-    let res_wrong_type = comprehensive_search("detected", Some("text"), None, None)
+    let res_wrong_type = comprehensive_search("detected", Some("text"), None, None, None, None)
         .expect("search wrong type failed");
     let items_wrong: Vec<ClipboardItem> =
         serde_json::from_str(&res_wrong_type).expect("parse wrong type res");
@@ -702,7 +702,7 @@ fn test_comprehensive_search_with_ocr() {
     add_item_to_folder(&folder_id, &item_img_ocr.id).expect("add to folder failed");
 
     // 在该收藏夹内搜索 OCR 关键词
-    let res_folder_search = comprehensive_search("detected", Some(&folder_id), None, None)
+    let res_folder_search = comprehensive_search("detected", Some(&folder_id), None, None, None, None)
         .expect("search in folder failed");
     let items_folder: Vec<ClipboardItem> =
         serde_json::from_str(&res_folder_search).expect("parse folder search res");
@@ -710,14 +710,14 @@ fn test_comprehensive_search_with_ocr() {
     assert_eq!(items_folder[0].id, item_img_ocr.id);
 
     // 在该收藏夹内搜索不匹配的关键词
-    let res_folder_empty = comprehensive_search("nonexistent", Some(&folder_id), None, None)
+    let res_folder_empty = comprehensive_search("nonexistent", Some(&folder_id), None, None, None, None)
         .expect("search in folder empty failed");
     let items_folder_empty: Vec<ClipboardItem> =
         serde_json::from_str(&res_folder_empty).expect("parse folder empty res");
     assert_eq!(items_folder_empty.len(), 0);
 
     // 使用错误的收藏夹 ID 搜索
-    let res_wrong_folder = comprehensive_search("detected", Some("wrong-folder-id"), None, None)
+    let res_wrong_folder = comprehensive_search("detected", Some("wrong-folder-id"), None, None, None, None)
         .expect("search wrong folder failed");
     let items_wrong_folder: Vec<ClipboardItem> =
         serde_json::from_str(&res_wrong_folder).expect("parse wrong folder res");
@@ -748,3 +748,143 @@ fn test_get_ocr_text_direct() {
     let res3 = get_ocr_text_by_item_id("non-existent").expect("get ocr failed");
     assert_eq!(res3, "", "Should return empty string for non-existent item");
 }
+
+#[test]
+fn test_insert_get_ocr_result() {
+    let _g = test_lock();
+    set_test_db_path();
+    clear_db_file();
+
+    let item = make_item("ocr-result-1", "image", "/path/img.png");
+    insert_received_db_data(item.clone()).unwrap();
+    assert_eq!(get_ocr_result_by_item_id(&item.id).unwrap(), "");
+
+    let lines = crate::ocr::parse_ocr_output("Total: 42\n\n合计", Some(0.9));
+    let result = crate::ocr::build_result(
+        "tesseract".to_string(),
+        vec!["eng".to_string(), "chi".to_string()],
+        Some(0.9),
+        lines,
+    );
+    enqueue_ocr_job(&item.id, 0).unwrap();
+    assert_eq!(claim_next_ocr_job(0).unwrap().as_deref(), Some(item.id.as_str()));
+    assert!(complete_ocr_job(&item.id, &result, 10).expect("insert ocr result failed"));
+
+    // 结构化结果完整保存，纯文本同时可供 OCR 搜索
+    assert_eq!(get_ocr_result(&item.id).unwrap(), Some(result.clone()));
+    assert_eq!(get_ocr_text(&item.id).unwrap().as_deref(), Some("Total: 42\n\n合计"));
+    let found: Vec<ClipboardItem> =
+        serde_json::from_str(&search_data_by_ocr_text("合计").unwrap()).unwrap();
+    assert_eq!(found.len(), 1);
+
+    // 删除数据时一并删除结构化结果，之后写入的识别结果与文本不会留下孤立记录
+    delete_data_by_id(&item.id).unwrap();
+    assert_eq!(get_ocr_result(&item.id).unwrap(), None);
+    assert!(!complete_ocr_job(&item.id, &result, 20).unwrap());
+    insert_ocr_text(&item.id, "late").unwrap();
+    assert_eq!(get_ocr_text(&item.id).unwrap(), None);
+
+    clear_db_file();
+}
+
+#[test]
+fn test_insert_get_barcodes_and_search() {
+    use crate::barcode::{BarcodeFormat, DecodedCode};
+
+    let _g = test_lock();
+    set_test_db_path();
+    clear_db_file();
+
+    let item = make_item("barcode-1", "image", "/path/qr.png");
+    let other = make_item("barcode-2", "image", "/path/plain.png");
+    insert_received_db_data(item.clone()).unwrap();
+    insert_received_db_data(other.clone()).unwrap();
+    assert_eq!(get_barcodes(&item.id).unwrap(), None);
+    assert!(get_barcodes_by_item_id(&item.id).unwrap().is_empty());
+
+    let codes = vec![
+        DecodedCode {
+            format: BarcodeFormat::QrCode,
+            payload: "WIFI:S:Office;T:WPA;P:hunter2;;".to_string(),
+        },
+        DecodedCode {
+            format: BarcodeFormat::Ean13,
+            payload: "4006381333931".to_string(),
+        },
+    ];
+    insert_ocr_text(&item.id, "scan me").unwrap();
+    insert_barcodes(&item.id, &codes).expect("insert barcodes failed");
+    insert_barcodes(&other.id, &[]).unwrap();
+
+    // 已解码但没有码的图片返回空列表，与未解码区分
+    assert_eq!(get_barcodes(&item.id).unwrap(), Some(codes.clone()));
+    assert_eq!(get_barcodes(&other.id).unwrap(), Some(Vec::new()));
+    // 只有条码记录的图片仍视为没有 OCR 文本
+    assert_eq!(get_ocr_text(&other.id).unwrap(), None);
+    assert_eq!(get_ocr_text_by_item_id(&other.id).unwrap(), "");
+
+    // 写入条码结果不影响 OCR 文本，反之亦然
+    insert_ocr_text(&item.id, "scan me again").unwrap();
+    assert_eq!(get_ocr_text(&item.id).unwrap().as_deref(), Some("scan me again"));
+    assert_eq!(get_barcodes_by_item_id(&item.id).unwrap(), codes);
+
+    // 解码出的内容可以在综合搜索中检索
+    for query in ["Office", "4006381333931"] {
+        let found: Vec<ClipboardItem> = serde_json::from_str(
+            &comprehensive_search(query, Some("image"), None, None, None, None).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(found.len(), 1, "query {}", query);
+        assert_eq!(found[0].id, item.id);
+    }
+
+    // 数据项不存在时不写入
+    insert_barcodes("missing", &codes).unwrap();
+    assert_eq!(get_barcodes("missing").unwrap(), None);
+
+    // 删除数据时一并删除解码结果
+    delete_data_by_id(&item.id).unwrap();
+    assert_eq!(get_barcodes(&item.id).unwrap(), None);
+
+    clear_db_file();
+}
+
+#[test]
+fn test_insert_get_document_text_and_search() {
+    let _g = test_lock();
+    set_test_db_path();
+    clear_db_file();
+
+    let item = make_item("doc-1", "file", "files/report.pdf");
+    insert_received_db_data(item.clone()).unwrap();
+    assert_eq!(get_document_text(&item.id).unwrap(), None);
+    assert_eq!(get_document_text_by_item_id(&item.id).unwrap(), "");
+
+    insert_icon_data(&item.id, "data:image/png;base64,AAAA").unwrap();
+    insert_document_text(&item.id, "Quarterly revenue grew 12%\n第三季度").expect("insert document text failed");
+    assert_eq!(
+        get_document_text_by_item_id(&item.id).unwrap(),
+        "Quarterly revenue grew 12%\n第三季度"
+    );
+    // 与图标数据共存
+    assert_eq!(
+        get_icon_data_by_item_id(&item.id).unwrap(),
+        "data:image/png;base64,AAAA"
+    );
+
+    // 文件正文可以在综合搜索中检索
+    let found: Vec<ClipboardItem> = serde_json::from_str(
+        &comprehensive_search("第三季度", Some("file"), None, None, None, None).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, item.id);
+
+    // 数据项不存在时不写入，删除数据时一并删除
+    insert_document_text("missing", "orphan").unwrap();
+    assert_eq!(get_document_text("missing").unwrap(), None);
+    delete_data_by_id(&item.id).unwrap();
+    assert_eq!(get_document_text(&item.id).unwrap(), None);
+
+    clear_db_file();
+}
@@ -0,0 +1,193 @@
+use super::*;
+use crate::clipboard::ClipboardItem;
+use uuid::Uuid;
+
+// --- 测试辅助函数 ---
+
+fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    crate::db::TEST_RUN_LOCK
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+}
+
+fn set_test_db_path() {
+    let mut p = std::env::temp_dir();
+    p.push(format!("smartpaste_test_ai_{}.db", Uuid::new_v4()));
+    set_db_path(p);
+}
+
+fn insert_text(id: &str, content: &str, timestamp: i64) {
+    insert_received_db_data(ClipboardItem {
+        id: id.to_string(),
+        item_type: "text".to_string(),
+        content: content.to_string(),
+        size: Some(content.len() as u64),
+        is_favorite: false,
+        notes: "".to_string(),
+        timestamp,
+    })
+    .unwrap();
+}
+
+fn status_of(item_id: &str) -> Option<String> {
+    get_ai_job(item_id).unwrap().map(|job| job.status)
+}
+
+fn strings(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+fn search_ids(query: &str) -> Vec<String> {
+    let items: Vec<ClipboardItem> =
+        serde_json::from_str(&comprehensive_search(query, None, None, None, None, None).unwrap())
+            .unwrap();
+    items.into_iter().map(|item| item.id).collect()
+}
+
+// --- 测试用例 ---
+
+#[test]
+fn test_ai_job_lifecycle() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_text("t1", "first", 1);
+    insert_text("t2", "second", 2);
+    insert_text("secret", "private", 3);
+    mark_item_as_private("secret").unwrap();
+
+    // 延迟到 not_before 之后才可取出；隐私数据与不存在的数据不入队
+    assert!(enqueue_ai_job("t1", 100, 50).unwrap());
+    assert!(enqueue_ai_job("t2", 200, 60).unwrap());
+    assert!(!enqueue_ai_job("secret", 100, 50).unwrap());
+    assert!(!enqueue_ai_job("missing", 100, 50).unwrap());
+    assert_eq!(status_of("secret"), None);
+    assert_eq!(claim_next_ai_job(99).unwrap(), None);
+
+    assert_eq!(claim_next_ai_job(150).unwrap().as_deref(), Some("t1"));
+    assert_eq!(claim_next_ai_job(150).unwrap(), None);
+    // 正在执行的任务不会被重新入队
+    assert!(!enqueue_ai_job("t1", 150, 150).unwrap());
+    assert_eq!(status_of("t1").as_deref(), Some(AI_JOB_RUNNING));
+
+    // 可重试的失败回到等待状态，不可重试的失败直接标记为失败
+    let job = fail_ai_job("t1", "timeout", 160, true, 3).unwrap().unwrap();
+    assert_eq!(job.status, AI_JOB_PENDING);
+    assert_eq!(job.attempts, 1);
+    assert_eq!(job.error.as_deref(), Some("timeout"));
+    assert_eq!(job.next_attempt_at, 160 + ai_retry_delay_ms(1));
+    assert_eq!(claim_next_ai_job(500).unwrap().as_deref(), Some("t2"));
+    let job = fail_ai_job("t2", "401", 510, false, 3).unwrap().unwrap();
+    assert_eq!(job.status, AI_JOB_FAILED);
+    assert_eq!(job.attempts, 1);
+
+    let retry_at = 160 + ai_retry_delay_ms(1);
+    assert_eq!(claim_next_ai_job(retry_at).unwrap().as_deref(), Some("t1"));
+    assert!(complete_ai_job("t1", retry_at + 100).unwrap());
+    let job = get_ai_job("t1").unwrap().unwrap();
+    assert_eq!(job.status, AI_JOB_DONE);
+    assert_eq!(job.error, None);
+
+    assert_eq!(
+        get_ai_job_stats().unwrap(),
+        AiJobStats {
+            pending: 0,
+            running: 0,
+            done: 1,
+            failed: 1
+        }
+    );
+
+    // 重启时恢复中断的任务；删除数据时取消任务
+    assert!(enqueue_ai_job("t2", 2_000, 2_000).unwrap());
+    assert_eq!(claim_next_ai_job(2_000).unwrap().as_deref(), Some("t2"));
+    assert_eq!(reset_running_ai_jobs(3_000).unwrap(), 1);
+    assert_eq!(status_of("t2").as_deref(), Some(AI_JOB_PENDING));
+    delete_data_by_id("t2").unwrap();
+    assert_eq!(status_of("t2"), None);
+    assert_eq!(fail_ai_job("t2", "gone", 3_100, false, 3).unwrap(), None);
+    assert!(!complete_ai_job("t2", 3_100).unwrap());
+}
+
+#[test]
+fn test_save_and_get_ai_annotations() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_text("a", "release notes for version 2", 1);
+    insert_text("b", "meeting agenda", 2);
+    insert_text("secret", "private text", 3);
+
+    save_ai_annotations("a", Some(&strings(&["Release", "版本"])), Some("版本 2 的更新说明")).unwrap();
+    assert_eq!(get_item_tags("a").unwrap(), strings(&["Release", "版本"]));
+    assert_eq!(get_ai_summary("a").unwrap().as_deref(), Some("版本 2 的更新说明"));
+
+    // 只替换传入的部分
+    save_ai_annotations("a", Some(&strings(&["changelog"])), None).unwrap();
+    assert_eq!(get_item_tags("a").unwrap(), strings(&["changelog"]));
+    assert_eq!(get_ai_summary("a").unwrap().as_deref(), Some("版本 2 的更新说明"));
+    save_ai_annotations("b", None, Some("周会议程")).unwrap();
+    assert_eq!(get_item_tags("b").unwrap(), Vec::<String>::new());
+
+    // 数据项不存在时不写入
+    save_ai_annotations("missing", Some(&strings(&["x"])), Some("x")).unwrap();
+    assert_eq!(get_item_tags("missing").unwrap(), Vec::<String>::new());
+    assert_eq!(get_ai_summary("missing").unwrap(), None);
+
+    // 批量获取时跳过没有标注的数据与隐私数据
+    save_ai_annotations("secret", Some(&strings(&["changelog", "密码"])), Some("机密")).unwrap();
+    mark_item_as_private("secret").unwrap();
+    let annotations =
+        get_ai_annotations(strings(&["a", "b", "secret", "missing"])).unwrap();
+    assert_eq!(annotations.len(), 2);
+    assert_eq!(
+        annotations["a"],
+        AiAnnotations {
+            tags: strings(&["changelog"]),
+            summary: Some("版本 2 的更新说明".to_string()),
+        }
+    );
+    assert_eq!(annotations["b"].summary.as_deref(), Some("周会议程"));
+    assert_eq!(get_all_tags().unwrap(), vec![("changelog".to_string(), 1)]);
+
+    // 删除数据时一并删除标签与摘要
+    delete_data_by_id("a").unwrap();
+    assert_eq!(get_item_tags("a").unwrap(), Vec::<String>::new());
+    assert_eq!(get_ai_summary("a").unwrap(), None);
+}
+
+#[test]
+fn test_search_and_filter_by_ai_annotations() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_text("a", "fn main() { println!(\"hi\"); }", 1);
+    insert_text("b", "SELECT * FROM users", 2);
+    save_ai_annotations("a", Some(&strings(&["Rust", "示例代码"])), Some("打印问候语")).unwrap();
+    save_ai_annotations("b", Some(&strings(&["SQL"])), Some("查询所有用户")).unwrap();
+
+    assert_eq!(search_ids("示例"), strings(&["a"]));
+    assert_eq!(search_ids("所有用户"), strings(&["b"]));
+    assert_eq!(search_ids("nothing"), Vec::<String>::new());
+
+    let filtered: Vec<ClipboardItem> =
+        serde_json::from_str(&filter_data_by_tag(" rust ").unwrap()).unwrap();
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].id, "a");
+    let filtered: Vec<ClipboardItem> =
+        serde_json::from_str(&filter_data_by_tag("示例").unwrap()).unwrap();
+    assert!(filtered.is_empty());
+
+    // 打标签后被标记为隐私的数据不再出现在按标签筛选的结果中
+    mark_item_as_private("a").unwrap();
+    let filtered: Vec<ClipboardItem> =
+        serde_json::from_str(&filter_data_by_tag("rust").unwrap()).unwrap();
+    assert!(filtered.is_empty());
+}
+
+#[test]
+fn test_ai_retry_delay_backoff() {
+    assert_eq!(ai_retry_delay_ms(1), 30_000);
+    assert_eq!(ai_retry_delay_ms(2), 60_000);
+    assert_eq!(ai_retry_delay_ms(u32::MAX), 30_000 << 8);
+}
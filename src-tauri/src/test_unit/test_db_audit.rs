@@ -0,0 +1,177 @@
+use super::*;
+use crate::clipboard::ClipboardItem;
+use rusqlite::{params, Connection};
+use std::fs;
+use uuid::Uuid;
+
+// --- 测试辅助函数 ---
+
+fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    crate::db::TEST_RUN_LOCK
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+}
+
+fn set_test_db_path() {
+    let mut p = std::env::temp_dir();
+    p.push(format!("smartpaste_test_audit_{}.db", Uuid::new_v4()));
+    set_db_path(p);
+}
+
+fn make_item(id: &str, content: &str, notes: &str) -> ClipboardItem {
+    ClipboardItem {
+        id: id.to_string(),
+        item_type: "text".to_string(),
+        content: content.to_string(),
+        size: Some(content.len() as u64),
+        is_favorite: false,
+        notes: notes.to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    }
+}
+
+// --- 测试用例 ---
+
+#[test]
+fn test_view_of_private_item_is_audited() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_received_db_data(make_item("secret", "hunter2", "")).unwrap();
+    insert_received_db_data(make_item("public", "hello", "")).unwrap();
+    mark_item_as_private("secret").unwrap();
+
+    get_data_by_id("secret").unwrap();
+    get_data_by_id("public").unwrap();
+
+    let entries = query_audit_log(None, None, None).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].event_type, "viewed");
+    assert_eq!(entries[0].item_id, "secret");
+    assert_eq!(entries[0].origin, "get_data_by_id");
+
+    // 审计日志中不包含任何内容
+    let json = get_audit_log(None, None, None).unwrap();
+    assert!(!json.contains("hunter2"));
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_listing_and_upload_of_private_items_are_audited() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_received_db_data(make_item("secret", "hunter2 token", "")).unwrap();
+    insert_received_db_data(make_item("public", "hello token", "")).unwrap();
+    mark_item_as_private("secret").unwrap();
+
+    get_all_data().unwrap();
+    comprehensive_search("token", None, None, None, None, None).unwrap();
+    // 结果中不含隐私数据时不记录
+    comprehensive_search("hello", None, None, None, None, None).unwrap();
+
+    let viewed = query_audit_log(Some("viewed"), None, None).unwrap();
+    let origins: Vec<&str> = viewed.iter().map(|e| e.origin.as_str()).collect();
+    assert_eq!(viewed.len(), 2);
+    assert!(viewed.iter().all(|e| e.item_id == "secret"));
+    assert!(origins.contains(&"get_all_data"));
+    assert!(origins.contains(&"comprehensive_search"));
+
+    prepare_encrypted_db_upload("00".repeat(32)).unwrap();
+    let exported = query_audit_log(Some("exported"), None, None).unwrap();
+    assert_eq!(exported.len(), 1);
+    assert_eq!(exported[0].item_id, "secret");
+    assert_eq!(exported[0].origin, "prepare_encrypted_db_upload");
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_filtered_listings_of_private_items_are_audited() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_received_db_data(make_item("secret", "hunter2 token", "")).unwrap();
+    mark_item_as_private("secret").unwrap();
+    let folder_id = create_new_folder("私密").unwrap();
+    add_item_to_folder(&folder_id, "secret").unwrap();
+
+    filter_data_by_favorite(false).unwrap();
+    filter_data_by_type("text").unwrap();
+    filter_data_by_folder("私密").unwrap();
+
+    let viewed = query_audit_log(Some("viewed"), None, None).unwrap();
+    let mut origins: Vec<&str> = viewed.iter().map(|e| e.origin.as_str()).collect();
+    origins.sort();
+    assert_eq!(
+        origins,
+        vec!["filter_data_by_favorite", "filter_data_by_folder", "filter_data_by_type"]
+    );
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_unmark_and_export_are_audited() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_received_db_data(make_item("pwd", "abc", "my password")).unwrap();
+    insert_received_db_data(make_item("other", "xyz", "")).unwrap();
+    mark_passwords_as_private(true).unwrap();
+    mark_item_as_private("other").unwrap();
+
+    assert!(audit_private_access(AuditEvent::Copied, "pwd", "write_to_clipboard"));
+    assert_eq!(audit_private_export("export_to_zip").unwrap(), 2);
+
+    mark_passwords_as_private(false).unwrap();
+    clear_all_private_data().unwrap();
+
+    let unmarked = query_audit_log(Some("unmarked"), None, None).unwrap();
+    let origins: Vec<(&str, &str)> = unmarked
+        .iter()
+        .map(|e| (e.item_id.as_str(), e.origin.as_str()))
+        .collect();
+    assert_eq!(unmarked.len(), 2);
+    assert!(origins.contains(&("pwd", "mark_passwords_as_private")));
+    assert!(origins.contains(&("other", "clear_all_private_data")));
+
+    assert_eq!(query_audit_log(Some("exported"), None, None).unwrap().len(), 2);
+    assert_eq!(query_audit_log(Some("copied"), Some("pwd"), None).unwrap().len(), 1);
+    assert_eq!(query_audit_log(None, None, Some(3)).unwrap().len(), 3);
+    assert!(query_audit_log(Some("bogus"), None, None).is_err());
+
+    // 取消标记后不再审计
+    assert!(!audit_private_access(AuditEvent::Viewed, "pwd", "get_data_by_id"));
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_audit_log_is_append_only_and_pruned() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_received_db_data(make_item("secret", "hunter2", "")).unwrap();
+    mark_item_as_private("secret").unwrap();
+    audit_private_access(AuditEvent::Viewed, "secret", "get_data_by_id");
+
+    let conn = Connection::open(get_db_path()).unwrap();
+    let res = conn.execute("UPDATE audit_log SET item_id = 'forged'", []);
+    assert!(res.is_err());
+
+    // 插入一条 100 天前的记录
+    let old = chrono::Utc::now().timestamp_millis() - 100 * 86_400_000;
+    conn.execute(
+        "INSERT INTO audit_log (event_type, item_id, origin, timestamp) VALUES ('viewed', 'secret', 'test', ?1)",
+        params![old],
+    )
+    .unwrap();
+
+    assert_eq!(prune_audit_log(0).unwrap(), 0);
+    assert_eq!(prune_audit_log(90).unwrap(), 1);
+    assert_eq!(query_audit_log(None, None, None).unwrap().len(), 1);
+
+    let _ = fs::remove_file(get_db_path());
+}
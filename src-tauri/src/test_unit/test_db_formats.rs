@@ -0,0 +1,94 @@
+use super::*;
+use crate::clipboard::ClipboardItem;
+use std::fs;
+use uuid::Uuid;
+
+// --- 测试辅助函数 ---
+
+fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    crate::db::TEST_RUN_LOCK
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+}
+
+fn set_test_db_path() {
+    let mut p = std::env::temp_dir();
+    p.push(format!("smartpaste_test_formats_{}.db", Uuid::new_v4()));
+    set_db_path(p);
+}
+
+fn make_item(id: &str, content: &str) -> ClipboardItem {
+    ClipboardItem {
+        id: id.to_string(),
+        item_type: "text".to_string(),
+        content: content.to_string(),
+        size: Some(content.len() as u64),
+        is_favorite: false,
+        notes: "".to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    }
+}
+
+// --- 测试用例 ---
+
+#[test]
+fn test_item_formats_roundtrip() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_received_db_data(make_item("rich", "Hello world")).unwrap();
+    insert_item_format("rich", FORMAT_HTML, "<p>Hello <b>world</b></p>").unwrap();
+    insert_item_format("rich", FORMAT_RTF, r"{\rtf1 Hello \b world\b0}").unwrap();
+    // 同一格式重复写入时替换
+    insert_item_format("rich", FORMAT_HTML, "<p>Hello <i>world</i></p>").unwrap();
+
+    let formats = get_item_formats("rich").unwrap();
+    assert_eq!(formats.len(), 2);
+    assert_eq!(formats[0].format, FORMAT_HTML);
+    assert_eq!(formats[0].content, "<p>Hello <i>world</i></p>");
+    assert_eq!(
+        get_item_format("rich", FORMAT_RTF).unwrap().as_deref(),
+        Some(r"{\rtf1 Hello \b world\b0}")
+    );
+    assert_eq!(get_item_format("rich", "image").unwrap(), None);
+
+    assert_eq!(get_item_formats_by_item_id("missing").unwrap(), "[]");
+
+    // 纯文本回退内容可被搜索
+    let res = comprehensive_search("world", None, None, None, None, None).unwrap();
+    let items: Vec<ClipboardItem> = serde_json::from_str(&res).unwrap();
+    assert_eq!(items.len(), 1);
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_edit_drops_stale_text_representations() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_received_db_data(make_item("rich", "Hello world")).unwrap();
+    insert_item_format("rich", FORMAT_HTML, "<p>Hello <b>world</b></p>").unwrap();
+    insert_item_format("rich", FORMAT_RTF, r"{\rtf1 Hello \b world\b0}").unwrap();
+    insert_item_format("rich", FORMAT_IMAGE, "files/rich.png").unwrap();
+
+    update_data_content_by_id("rich", "Goodbye world").unwrap();
+
+    // 编辑后粘贴只写入新的纯文本，不再带上旧的 HTML / RTF
+    let reps = crate::utils::with_stored_representations(
+        "rich",
+        crate::clipboard_backend::ClipboardRepresentations {
+            text: Some("Goodbye world".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(reps.text.as_deref(), Some("Goodbye world"));
+    assert_eq!(reps.html, None);
+    assert_eq!(reps.rtf, None);
+    let formats = get_item_formats("rich").unwrap();
+    assert_eq!(formats.len(), 1);
+    assert_eq!(formats[0].format, FORMAT_IMAGE);
+
+    let _ = fs::remove_file(get_db_path());
+}
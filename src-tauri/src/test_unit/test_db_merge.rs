@@ -0,0 +1,137 @@
+use super::*;
+use crate::clipboard::ClipboardItem;
+use std::fs;
+use uuid::Uuid;
+
+// --- 测试辅助函数 ---
+
+fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    crate::db::TEST_RUN_LOCK
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+}
+
+fn set_test_db_path() {
+    let mut p = std::env::temp_dir();
+    p.push(format!("smartpaste_test_merge_{}.db", Uuid::new_v4()));
+    set_db_path(p);
+}
+
+fn insert_item(id: &str, item_type: &str, content: &str, timestamp: i64) {
+    insert_received_db_data(ClipboardItem {
+        id: id.to_string(),
+        item_type: item_type.to_string(),
+        content: content.to_string(),
+        size: Some(content.len() as u64),
+        is_favorite: false,
+        notes: "".to_string(),
+        timestamp,
+    })
+    .unwrap();
+}
+
+fn ids(v: &[&str]) -> Vec<String> {
+    v.iter().map(|s| s.to_string()).collect()
+}
+
+// --- 测试用例 ---
+
+#[test]
+fn test_merge_order_separator_and_links() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_item("a", "text", "https://a.example.com", 300);
+    insert_item("b", "text", "https://b.example.com", 100);
+    insert_item("img", "image", "files/shot.png", 200);
+    insert_ocr_text("img", "截图中的文字").unwrap();
+
+    // 按选中顺序，重复的 ID 只合并一次
+    let merged: ClipboardItem = serde_json::from_str(
+        &merge_items(ids(&["a", "img", "b", "a"]), Some(" | "), None, None).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(merged.item_type, "text");
+    assert_eq!(merged.content, "https://a.example.com | 截图中的文字 | https://b.example.com");
+
+    let links = get_item_links(&merged.id).unwrap();
+    assert_eq!(links.len(), 3);
+    assert!(links.iter().all(|l| l.relation == LINK_MERGED && l.item_id == merged.id));
+    let img_link = links.iter().find(|l| l.source_id == "img").unwrap();
+    assert_eq!(img_link.detail, "2/3");
+
+    // 按时间排序，默认换行分隔，原始数据保留
+    let oldest: ClipboardItem = serde_json::from_str(
+        &merge_items(ids(&["a", "b", "img"]), None, Some("oldest"), Some(false)).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(oldest.content, "https://b.example.com\n截图中的文字\nhttps://a.example.com");
+    let newest = merge_items_internal(&ids(&["b", "a"]), ",", MergeOrder::Newest, false).unwrap();
+    assert_eq!(newest.content, "https://a.example.com,https://b.example.com");
+    assert!(get_item_by_id("a").unwrap().is_some());
+
+    assert!(merge_items(ids(&["a", "b"]), None, Some("random"), None).is_err());
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_merge_rejects_invalid_selection() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_item("t", "text", "hello", 1);
+    insert_item("f", "file", "files/report.pdf", 2);
+
+    assert!(merge_items(ids(&["t"]), None, None, None).is_err());
+    assert!(merge_items(ids(&["t", "t"]), None, None, None).is_err());
+    assert!(merge_items(ids(&["t", "missing"]), None, None, None).is_err());
+    // 没有 OCR 文本的文件无法合并，此时不会生成新数据
+    let err = merge_items(ids(&["t", "f"]), None, None, None).unwrap_err();
+    assert!(err.contains("没有可合并的文本: f"), "{}", err);
+    let all: Vec<ClipboardItem> = serde_json::from_str(&get_all_data().unwrap()).unwrap();
+    assert_eq!(all.len(), 2);
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_merge_delete_originals_and_privacy() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_item("log1", "text", "INFO start", 1);
+    insert_item("log2", "text", "ERROR boom", 2);
+    mark_item_as_private("log2").unwrap();
+
+    let merged = merge_items_internal(&ids(&["log1", "log2"]), "\n", MergeOrder::Selection, true)
+        .unwrap();
+    assert_eq!(merged.content, "INFO start\nERROR boom");
+
+    // 原始数据已删除，来源关系仍然保留
+    assert!(get_item_by_id("log1").unwrap().is_none());
+    assert!(get_item_by_id("log2").unwrap().is_none());
+    assert_eq!(get_item_links(&merged.id).unwrap().len(), 2);
+
+    // 合并了隐私数据的结果同样是隐私数据
+    assert!(is_private_item(&merged.id).unwrap());
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_merged_text_is_checked_for_private_content() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    // 原始数据都不是隐私数据，合并后拼出了手机号
+    insert_item("p1", "text", "tel:138", 1);
+    insert_item("p2", "text", "12345678", 2);
+    let merged = merge_items_internal(&ids(&["p1", "p2"]), "", MergeOrder::Selection, false)
+        .unwrap();
+    assert_eq!(merged.content, "tel:13812345678");
+    assert!(!is_private_item("p1").unwrap());
+    assert!(is_private_item(&merged.id).unwrap());
+
+    let _ = fs::remove_file(get_db_path());
+}
@@ -0,0 +1,165 @@
+use super::*;
+use crate::clipboard::ClipboardItem;
+use std::fs;
+use uuid::Uuid;
+
+// --- 测试辅助函数 ---
+
+fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    crate::db::TEST_RUN_LOCK
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+}
+
+fn set_test_db_path() {
+    let mut p = std::env::temp_dir();
+    p.push(format!("smartpaste_test_ocr_jobs_{}.db", Uuid::new_v4()));
+    set_db_path(p);
+}
+
+fn insert_item(id: &str, item_type: &str, timestamp: i64) {
+    insert_received_db_data(ClipboardItem {
+        id: id.to_string(),
+        item_type: item_type.to_string(),
+        content: format!("files/{}.png", id),
+        size: Some(1),
+        is_favorite: false,
+        notes: "".to_string(),
+        timestamp,
+    })
+    .unwrap();
+}
+
+fn empty_result() -> crate::ocr::OcrResult {
+    crate::ocr::build_result(String::new(), Vec::new(), None, Vec::new())
+}
+
+fn status_of(item_id: &str) -> Option<String> {
+    get_ocr_job(item_id).unwrap().map(|job| job.status)
+}
+
+// --- 测试用例 ---
+
+#[test]
+fn test_ocr_retry_delay_backoff() {
+    assert_eq!(ocr_retry_delay_ms(1), 5_000);
+    assert_eq!(ocr_retry_delay_ms(2), 10_000);
+    assert_eq!(ocr_retry_delay_ms(3), 20_000);
+    assert_eq!(ocr_retry_delay_ms(10), 5 * 60 * 1000);
+    assert_eq!(ocr_retry_delay_ms(u32::MAX), 5 * 60 * 1000);
+}
+
+#[test]
+fn test_ocr_job_lifecycle_with_retries() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_item("img1", "image", 1);
+    insert_item("img2", "image", 2);
+    enqueue_ocr_job("img1", 100).unwrap();
+    enqueue_ocr_job("img2", 200).unwrap();
+
+    // 按入队时间取出，取出后不会被再次取出
+    assert_eq!(claim_next_ocr_job(150).unwrap().as_deref(), Some("img1"));
+    assert_eq!(claim_next_ocr_job(150).unwrap(), None);
+    assert_eq!(status_of("img1").as_deref(), Some(OCR_JOB_RUNNING));
+
+    // 识别中的任务再次入队不受影响
+    enqueue_ocr_job("img1", 300).unwrap();
+    assert_eq!(status_of("img1").as_deref(), Some(OCR_JOB_RUNNING));
+
+    // 第一次失败：等待退避时间后重试
+    let job = fail_ocr_job("img1", "engine busy", 1_000, 3).unwrap().unwrap();
+    assert_eq!(job.status, OCR_JOB_PENDING);
+    assert_eq!(job.attempts, 1);
+    assert_eq!(job.error.as_deref(), Some("engine busy"));
+    assert_eq!(job.next_attempt_at, 1_000 + ocr_retry_delay_ms(1));
+    assert_eq!(claim_next_ocr_job(1_000).unwrap().as_deref(), Some("img2"));
+    assert_eq!(claim_next_ocr_job(1_000).unwrap(), None);
+    assert_eq!(
+        claim_next_ocr_job(job.next_attempt_at).unwrap().as_deref(),
+        Some("img1")
+    );
+
+    // 达到最大次数后标记为失败
+    fail_ocr_job("img1", "engine busy", 10_000, 3).unwrap();
+    claim_next_ocr_job(i64::MAX).unwrap();
+    let job = fail_ocr_job("img1", "timeout", 60_000, 3).unwrap().unwrap();
+    assert_eq!(job.status, OCR_JOB_FAILED);
+    assert_eq!(job.attempts, 3);
+    assert_eq!(job.error.as_deref(), Some("timeout"));
+    assert_eq!(claim_next_ocr_job(i64::MAX).unwrap(), None);
+
+    assert!(complete_ocr_job("img2", &empty_result(), 2_000).unwrap());
+    assert_eq!(
+        get_ocr_job_stats().unwrap(),
+        OcrJobStats {
+            pending: 0,
+            running: 0,
+            done: 1,
+            failed: 1
+        }
+    );
+    let failed: Vec<OcrJob> =
+        serde_json::from_str(&get_ocr_jobs(Some(OCR_JOB_FAILED)).unwrap()).unwrap();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].item_id, "img1");
+
+    // 重新入队会清空失败记录
+    enqueue_ocr_job("img1", 70_000).unwrap();
+    let job = get_ocr_job("img1").unwrap().unwrap();
+    assert_eq!((job.status.as_str(), job.attempts, job.error), (OCR_JOB_PENDING, 0, None));
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_ocr_job_cancelled_on_delete_and_reset_on_restart() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_item("img1", "image", 1);
+    insert_item("img2", "image", 2);
+    enqueue_ocr_job("img1", 0).unwrap();
+    enqueue_ocr_job("img2", 0).unwrap();
+    assert_eq!(claim_next_ocr_job(0).unwrap().as_deref(), Some("img1"));
+
+    // 删除数据项即取消任务，识别完成后也不会再写回状态
+    delete_data_by_id("img1").unwrap();
+    assert_eq!(get_ocr_job("img1").unwrap(), None);
+    assert!(!complete_ocr_job("img1", &empty_result(), 10).unwrap());
+    assert_eq!(fail_ocr_job("img1", "x", 10, 3).unwrap(), None);
+
+    // 重启时将中断的任务恢复为等待
+    assert_eq!(claim_next_ocr_job(0).unwrap().as_deref(), Some("img2"));
+    assert_eq!(reset_running_ocr_jobs(50).unwrap(), 1);
+    assert_eq!(status_of("img2").as_deref(), Some(OCR_JOB_PENDING));
+    assert_eq!(claim_next_ocr_job(50).unwrap().as_deref(), Some("img2"));
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_images_without_ocr_for_backfill() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_item("old", "image", 1);
+    insert_item("new", "image", 3);
+    insert_item("done", "image", 2);
+    insert_item("queued", "image", 4);
+    insert_item("failed", "image", 5);
+    insert_item("text", "text", 6);
+    insert_ocr_text("done", "已识别").unwrap();
+    enqueue_ocr_job("queued", 0).unwrap();
+    enqueue_ocr_job("failed", 0).unwrap();
+    claim_next_ocr_job(0).unwrap();
+    claim_next_ocr_job(0).unwrap();
+    fail_ocr_job("failed", "boom", 0, 1).unwrap();
+    complete_ocr_job("queued", &empty_result(), 0).unwrap();
+
+    // 已有文本或已完成识别的图片（即使没有识别出文字）不会重复识别，失败的图片会重新识别
+    assert_eq!(get_images_without_ocr().unwrap(), vec!["old", "new", "failed"]);
+
+    let _ = fs::remove_file(get_db_path());
+}
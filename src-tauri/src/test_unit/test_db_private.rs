@@ -268,7 +268,7 @@ fn test_get_all_private_data() {
 
     // 3. 获取所有隐私数据
     let json_result =
-        comprehensive_search("", Some("private"), None, None).expect("comprehensive_search failed");
+        comprehensive_search("", Some("private"), None, None, None, None).expect("comprehensive_search failed");
     let items: Vec<ClipboardItem> =
         serde_json::from_str(&json_result).expect("failed to parse json");
 
@@ -0,0 +1,226 @@
+use super::*;
+use crate::clipboard::ClipboardItem;
+use crate::source_app::SourceApp;
+use rusqlite::Connection;
+use std::fs;
+use uuid::Uuid;
+
+// --- 测试辅助函数 ---
+
+fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    crate::db::TEST_RUN_LOCK
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+}
+
+fn set_test_db_path() {
+    let mut p = std::env::temp_dir();
+    p.push(format!("smartpaste_test_secure_delete_{}.db", Uuid::new_v4()));
+    set_db_path(p);
+}
+
+fn make_item(id: &str, item_type: &str, content: &str) -> ClipboardItem {
+    ClipboardItem {
+        id: id.to_string(),
+        item_type: item_type.to_string(),
+        content: content.to_string(),
+        size: Some(content.len() as u64),
+        is_favorite: false,
+        notes: "".to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    }
+}
+
+fn count_rows(table: &str, column: &str, id: &str) -> i64 {
+    let conn = Connection::open(get_db_path()).unwrap();
+    conn.query_row(
+        &format!("SELECT COUNT(*) FROM {} WHERE {} = ?1", table, column),
+        [id],
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+// --- 测试用例 ---
+
+#[test]
+fn test_shred_path_overwrites_and_removes() {
+    let dir = std::env::temp_dir().join(format!("smartpaste_shred_{}", Uuid::new_v4()));
+    fs::create_dir_all(dir.join("nested")).unwrap();
+    fs::write(dir.join("a.txt"), b"top secret").unwrap();
+    fs::write(dir.join("nested").join("b.bin"), vec![7u8; 100_000]).unwrap();
+
+    let bytes = shred_path(&dir).unwrap();
+    assert_eq!(bytes, 10 + 100_000);
+    assert!(!dir.exists());
+}
+
+#[test]
+fn test_private_file_item_is_securely_deleted() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    let file = std::env::temp_dir().join(format!("smartpaste_private_{}.png", Uuid::new_v4()));
+    fs::write(&file, b"private image bytes").unwrap();
+    let content = file.to_string_lossy().to_string();
+
+    insert_received_db_data(make_item("secret-img", "image", &content)).unwrap();
+    insert_ocr_text("secret-img", "account password 123456").unwrap();
+    insert_item_source(
+        "secret-img",
+        &SourceApp {
+            app_name: "keepassxc".to_string(),
+            window_title: "Vault".to_string(),
+        },
+    )
+    .unwrap();
+    mark_item_as_private("secret-img").unwrap();
+    assert!(is_private_item("secret-img").unwrap());
+
+    let report = secure_delete_data_by_id("secret-img").unwrap();
+    assert_eq!(report.rows_deleted, 1);
+    // extended_data + item_source + private_data
+    assert_eq!(report.related_rows_deleted, 3);
+    assert_eq!(report.file_bytes_scrubbed, "private image bytes".len() as u64);
+    assert!(!file.exists());
+
+    for (table, column) in [
+        ("data", "id"),
+        ("extended_data", "item_id"),
+        ("item_source", "item_id"),
+        ("private_data", "item_id"),
+    ] {
+        assert_eq!(count_rows(table, column, "secret-img"), 0, "{} 未清除", table);
+    }
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_delete_data_by_id_routes_private_items() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_received_db_data(make_item("private-text", "text", "my password")).unwrap();
+    insert_ocr_text("private-text", "my password").unwrap();
+    mark_item_as_private("private-text").unwrap();
+    insert_received_db_data(make_item("plain-text", "text", "hello")).unwrap();
+
+    assert_eq!(delete_data_by_id("private-text").unwrap(), 1);
+    assert_eq!(count_rows("extended_data", "item_id", "private-text"), 0);
+    assert_eq!(count_rows("private_data", "item_id", "private-text"), 0);
+
+    // 非隐私数据走普通删除流程
+    assert!(!is_private_item("plain-text").unwrap());
+    assert_eq!(delete_data_by_id("plain-text").unwrap(), 1);
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_bulk_delete_shreds_private_items() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    let file = std::env::temp_dir().join(format!("smartpaste_private_{}.png", Uuid::new_v4()));
+    fs::write(&file, b"private image bytes").unwrap();
+    insert_received_db_data(make_item("secret-img", "image", &file.to_string_lossy())).unwrap();
+    insert_ocr_text("secret-img", "account password 123456").unwrap();
+    mark_item_as_private("secret-img").unwrap();
+    insert_received_db_data(ClipboardItem {
+        timestamp: 1,
+        ..make_item("plain-text", "text", "hello")
+    })
+    .unwrap();
+    insert_item_source(
+        "plain-text",
+        &SourceApp {
+            app_name: "editor".to_string(),
+            window_title: "notes".to_string(),
+        },
+    )
+    .unwrap();
+
+    // 清空隐私数据时覆写文件并清除关联表
+    assert_eq!(delete_all_data(Some("private"), false).unwrap(), 1);
+    assert!(!file.exists());
+    for table in ["extended_data", "private_data"] {
+        assert_eq!(count_rows(table, "item_id", "secret-img"), 0, "{} 未清除", table);
+    }
+
+    // 按数量清理同样不留下关联表中的记录
+    assert_eq!(enforce_max_history_items(1).unwrap(), 0);
+    insert_received_db_data(make_item("newer-text", "text", "world")).unwrap();
+    assert_eq!(enforce_max_history_items(1).unwrap(), 1);
+    assert_eq!(count_rows("data", "id", "plain-text"), 0);
+    assert_eq!(count_rows("item_source", "item_id", "plain-text"), 0);
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_bulk_delete_mixed_items_in_one_pass() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    let plain_file = std::env::temp_dir().join(format!("smartpaste_plain_{}.txt", Uuid::new_v4()));
+    fs::write(&plain_file, b"plain file").unwrap();
+    let private_file = std::env::temp_dir().join(format!("smartpaste_private_{}.png", Uuid::new_v4()));
+    fs::write(&private_file, b"private image bytes").unwrap();
+    insert_received_db_data(make_item("plain-file", "file", &plain_file.to_string_lossy())).unwrap();
+    insert_received_db_data(make_item("secret-img", "image", &private_file.to_string_lossy())).unwrap();
+    mark_item_as_private("secret-img").unwrap();
+    insert_received_db_data(make_item("note", "text", "hello")).unwrap();
+
+    let folder_id = create_new_folder("工作").unwrap();
+    for id in ["plain-file", "secret-img", "note"] {
+        add_item_to_folder(&folder_id, id).unwrap();
+    }
+
+    let ids: Vec<String> = ["plain-file", "secret-img", "note", "missing"]
+        .iter()
+        .map(|id| id.to_string())
+        .collect();
+    assert_eq!(delete_data_by_ids(&ids).unwrap(), 3);
+    assert!(!plain_file.exists());
+    assert!(!private_file.exists());
+    for id in ["plain-file", "secret-img", "note"] {
+        assert_eq!(count_rows("data", "id", id), 0);
+        assert_eq!(count_rows("folder_items", "item_id", id), 0);
+    }
+    let conn = Connection::open(get_db_path()).unwrap();
+    let num_items: i64 = conn
+        .query_row("SELECT num_items FROM folders WHERE id = ?1", [&folder_id], |row| row.get(0))
+        .unwrap();
+    assert_eq!(num_items, 0);
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_secure_vacuum_reports_scrubbed_pages() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    let big = "x".repeat(64 * 1024);
+    for i in 0..10 {
+        insert_received_db_data(make_item(&format!("bulk-{}", i), "text", &big)).unwrap();
+    }
+    for i in 0..10 {
+        delete_data_by_id(&format!("bulk-{}", i)).unwrap();
+    }
+
+    let report = secure_vacuum().unwrap();
+    assert!(report.page_size > 0);
+    assert!(report.free_pages > 0);
+    assert_eq!(report.bytes_scrubbed, report.free_pages * report.page_size);
+
+    // 整理后空闲页应全部回收
+    let again = secure_vacuum().unwrap();
+    assert_eq!(again.free_pages, 0);
+
+    let json = secure_vacuum_database().unwrap();
+    assert!(json.contains("bytes_scrubbed"));
+
+    let _ = fs::remove_file(get_db_path());
+}
@@ -0,0 +1,121 @@
+use super::*;
+use crate::classify::ContentSubtype;
+use crate::clipboard::ClipboardItem;
+use rusqlite::Connection;
+use std::fs;
+use uuid::Uuid;
+
+// --- 测试辅助函数 ---
+
+fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    crate::db::TEST_RUN_LOCK
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+}
+
+fn set_test_db_path() {
+    let mut p = std::env::temp_dir();
+    p.push(format!("smartpaste_test_subtypes_{}.db", Uuid::new_v4()));
+    set_db_path(p);
+}
+
+fn make_item(id: &str, item_type: &str, content: &str) -> ClipboardItem {
+    ClipboardItem {
+        id: id.to_string(),
+        item_type: item_type.to_string(),
+        content: content.to_string(),
+        size: Some(content.len() as u64),
+        is_favorite: false,
+        notes: "".to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    }
+}
+
+fn ids(json: &str) -> Vec<String> {
+    serde_json::from_str::<Vec<ClipboardItem>>(json)
+        .unwrap()
+        .into_iter()
+        .map(|i| i.id)
+        .collect()
+}
+
+// --- 测试用例 ---
+
+#[test]
+fn test_classified_on_insert_and_update() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_received_db_data(make_item("u", "text", "https://example.com")).unwrap();
+    insert_received_db_data(make_item("c", "text", "fn main() {\n    println!(\"hi\");\n}")).unwrap();
+    insert_received_db_data(make_item("p", "text", "just some words")).unwrap();
+    insert_received_db_data(make_item("img", "image", "files/a.png")).unwrap();
+
+    assert_eq!(get_item_subtype("u").unwrap().unwrap().subtype, ContentSubtype::Url);
+    let code = get_item_subtype("c").unwrap().unwrap();
+    assert_eq!(code.subtype, ContentSubtype::Code);
+    assert_eq!(code.language.as_deref(), Some("rust"));
+    assert_eq!(get_item_subtype("p").unwrap(), None);
+    assert_eq!(get_item_subtype("img").unwrap(), None);
+    assert_eq!(get_item_subtype_by_item_id("p").unwrap(), "null");
+
+    // 修改内容后重新分类
+    update_data_content_by_id("p", "alice@example.com").unwrap();
+    assert_eq!(get_item_subtype("p").unwrap().unwrap().subtype, ContentSubtype::Email);
+    update_data_content_by_id("u", "plain again").unwrap();
+    assert_eq!(get_item_subtype("u").unwrap(), None);
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_filter_and_search_by_subtype() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_received_db_data(make_item("u1", "text", "https://a.example.com")).unwrap();
+    insert_received_db_data(make_item("u2", "text", "https://b.example.com")).unwrap();
+    insert_received_db_data(make_item("s", "text", "SELECT * FROM t WHERE a = 1;")).unwrap();
+    insert_received_db_data(make_item("e", "text", "bob@example.com")).unwrap();
+
+    let mut urls = ids(&filter_data_by_subtype("url", None).unwrap());
+    urls.sort();
+    assert_eq!(urls, vec!["u1", "u2"]);
+    assert_eq!(ids(&filter_data_by_subtype("code", Some("SQL")).unwrap()), vec!["s"]);
+    assert!(ids(&filter_data_by_subtype("code", Some("rust")).unwrap()).is_empty());
+    assert!(filter_data_by_subtype("video", None).is_err());
+
+    let res = comprehensive_search("example", None, None, None, None, Some("email")).unwrap();
+    assert_eq!(ids(&res), vec!["e"]);
+
+    let _ = fs::remove_file(get_db_path());
+}
+
+#[test]
+fn test_backfill_existing_items() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    // 模拟分类功能上线前的历史数据：直接写入 data 表
+    let db_path = get_db_path();
+    init_db(db_path.as_path()).unwrap();
+    let conn = Connection::open(&db_path).unwrap();
+    for (id, content) in [("old1", "#ff8800"), ("old2", "2024-01-01"), ("old3", "hello")] {
+        conn.execute(
+            "INSERT INTO data (id, item_type, content, size, is_favorite, notes, timestamp) VALUES (?1, 'text', ?2, 0, 0, '', 0)",
+            rusqlite::params![id, content],
+        )
+        .unwrap();
+    }
+    drop(conn);
+
+    assert_eq!(backfill_item_subtypes().unwrap(), 2);
+    assert_eq!(get_item_subtype("old1").unwrap().unwrap().subtype, ContentSubtype::Color);
+    assert_eq!(get_item_subtype("old2").unwrap().unwrap().subtype, ContentSubtype::Date);
+    assert_eq!(get_item_subtype("old3").unwrap(), None);
+
+    // 已分类的数据不会重复处理
+    assert_eq!(backfill_item_subtypes().unwrap(), 0);
+
+    let _ = fs::remove_file(get_db_path());
+}
@@ -0,0 +1,173 @@
+/// 文件内容提取单元测试（测试文档在测试中生成）
+use super::*;
+use std::io::Write;
+
+// --- 测试辅助函数 ---
+
+/// 生成只包含一页文字的最小 PDF
+fn minimal_pdf(text: &str) -> Vec<u8> {
+    let content = format!("BT /F1 12 Tf 72 720 Td ({}) Tj ET", text);
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Contents 4 0 R \
+         /Resources << /Font << /F1 5 0 R >> >> >>"
+            .to_string(),
+        format!("<< /Length {} >>\nstream\n{}\nendstream", content.len(), content),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).bytes());
+    }
+    let xref_offset = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).bytes());
+    for offset in offsets {
+        pdf.extend(format!("{:010} 00000 n \n", offset).bytes());
+    }
+    pdf.extend(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        )
+        .bytes(),
+    );
+    pdf
+}
+
+/// 生成只包含指定条目的 zip 压缩包（DOCX/ODT）
+fn write_zip(path: &Path, entry: &str, content: &str) {
+    let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+    zip.start_file(entry, zip::write::FileOptions::default())
+        .unwrap();
+    zip.write_all(content.as_bytes()).unwrap();
+    zip.finish().unwrap();
+}
+
+// --- 测试用例 ---
+
+#[test]
+fn test_document_kind_by_extension() {
+    assert_eq!(document_kind(Path::new("a/report.PDF")), Some(DocumentKind::Pdf));
+    assert_eq!(document_kind(Path::new("memo.docx")), Some(DocumentKind::Docx));
+    assert_eq!(document_kind(Path::new("memo.odt")), Some(DocumentKind::Odt));
+    for name in ["notes.md", "data.csv", "main.rs", "README.txt"] {
+        assert_eq!(document_kind(Path::new(name)), Some(DocumentKind::PlainText), "{}", name);
+    }
+    assert_eq!(document_kind(Path::new("setup.exe")), None);
+    assert_eq!(document_kind(Path::new("Makefile")), None);
+}
+
+#[test]
+fn test_extract_plain_text_files() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let md = dir.path().join("notes.md");
+    fs::write(&md, "# 标题\r\n\r\n\r\n\r\n正文 keyword   \r\n").unwrap();
+    assert_eq!(extract_text(&md).unwrap().as_deref(), Some("# 标题\n\n正文 keyword"));
+
+    // Excel 导出的 CSV 常为 GBK 编码
+    let csv = dir.path().join("data.csv");
+    fs::write(&csv, encoding_rs::GBK.encode("姓名,金额\n张三,42").0).unwrap();
+    assert_eq!(extract_text(&csv).unwrap().as_deref(), Some("姓名,金额\n张三,42"));
+
+    // 带 BOM 的 UTF-16 文本
+    let txt = dir.path().join("utf16.txt");
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend("hello 世界".encode_utf16().flat_map(|u| u.to_le_bytes()));
+    fs::write(&txt, bytes).unwrap();
+    assert_eq!(extract_text(&txt).unwrap().as_deref(), Some("hello 世界"));
+
+    // 扩展名是文本但内容是二进制、空文件、不支持的类型
+    let binary = dir.path().join("fake.txt");
+    fs::write(&binary, [0x89, b'P', b'N', b'G', 0, 0, 1]).unwrap();
+    assert_eq!(extract_text(&binary).unwrap(), None);
+    let empty = dir.path().join("empty.txt");
+    fs::write(&empty, "  \n\n").unwrap();
+    assert_eq!(extract_text(&empty).unwrap(), None);
+    let exe = dir.path().join("tool.exe");
+    fs::write(&exe, "MZ").unwrap();
+    assert_eq!(extract_text(&exe).unwrap(), None);
+    assert_eq!(extract_text(dir.path()).unwrap(), None);
+}
+
+#[test]
+fn test_plain_text_size_caps() {
+    let dir = tempfile::tempdir().unwrap();
+    let big = dir.path().join("big.log");
+    // 每个汉字 3 字节，读取上限会截断最后一个字符
+    fs::write(&big, "中".repeat(MAX_TEXT_FILE_BYTES as usize / 3 + 10)).unwrap();
+
+    let text = extract_text(&big).unwrap().unwrap();
+    assert_eq!(text.chars().count(), MAX_EXTRACTED_CHARS);
+    assert!(text.chars().all(|c| c == '中'));
+
+    assert_eq!(decode_text("ab中".as_bytes().split_last().unwrap().1), "ab");
+}
+
+#[test]
+fn test_xml_to_text_docx_and_odt() {
+    let docx = r#"<w:document><w:body>
+        <w:p><w:r><w:t>Invoice</w:t><w:tab/><w:t xml:space="preserve">No. 42 &amp; more</w:t></w:r></w:p>
+        <w:p><w:r><w:t>line</w:t><w:br/><w:t>break</w:t></w:r>
+        <w:r><w:instrText>PAGE</w:instrText><w:delText>removed</w:delText></w:r></w:p>
+        <w:p/><w:p><w:r><w:t>合计</w:t></w:r></w:p>
+    </w:body></w:document>"#;
+    let text = normalize_text(&xml_to_text(docx).unwrap()).unwrap();
+    assert_eq!(text, "Invoice\tNo. 42 & more\nline\nbreak\n\n合计");
+
+    let odt = r#"<office:document-content><office:body><office:text>
+        <text:h>Title</text:h>
+        <text:p>a<text:s text:c="3"/>b<text:span>c</text:span><text:line-break/>d</text:p>
+    </office:text></office:body></office:document-content>"#;
+    let text = normalize_text(&xml_to_text(odt).unwrap()).unwrap();
+    assert_eq!(text, "Title\na   bc\nd");
+
+    // 异常的空格数被限制，超长正文在达到上限后停止解析
+    let huge_space = r#"<text:p>a<text:s text:c="4000000000"/>b</text:p>"#;
+    assert_eq!(xml_to_text(huge_space).unwrap().len(), 1024 + 3);
+    let long = format!("<text:p>{}</text:p>", "<text:s text:c=\"1000\"/>".repeat(1000));
+    assert!(xml_to_text(&long).unwrap().len() <= MAX_EXTRACTED_CHARS * 4 + 1024);
+}
+
+#[test]
+fn test_extract_office_documents() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let docx = dir.path().join("memo.docx");
+    write_zip(
+        &docx,
+        "word/document.xml",
+        "<w:document><w:body><w:p><w:r><w:t>quarterly report</w:t></w:r></w:p></w:body></w:document>",
+    );
+    assert_eq!(extract_text(&docx).unwrap().as_deref(), Some("quarterly report"));
+
+    let odt = dir.path().join("memo.odt");
+    write_zip(&odt, "content.xml", "<office:text><text:p>会议纪要</text:p></office:text>");
+    assert_eq!(extract_text(&odt).unwrap().as_deref(), Some("会议纪要"));
+
+    // 不是合法压缩包或缺少正文时返回错误
+    let broken = dir.path().join("broken.docx");
+    fs::write(&broken, "not a zip").unwrap();
+    assert!(extract_text(&broken).is_err());
+    let wrong = dir.path().join("wrong.docx");
+    write_zip(&wrong, "content.xml", "<text:p>x</text:p>");
+    assert!(extract_text(&wrong).is_err());
+}
+
+#[test]
+fn test_extract_pdf_text_layer() {
+    let dir = tempfile::tempdir().unwrap();
+    let pdf = dir.path().join("scan.pdf");
+    fs::write(&pdf, minimal_pdf("Hello PDF world")).unwrap();
+    assert_eq!(extract_text(&pdf).unwrap().as_deref(), Some("Hello PDF world"));
+
+    let broken = dir.path().join("broken.pdf");
+    fs::write(&broken, "%PDF-1.4 garbage").unwrap();
+    assert!(extract_text(&broken).is_err());
+}
@@ -0,0 +1,113 @@
+/// 剪贴板回声抑制单元测试
+use super::*;
+
+#[test]
+fn test_echo_matches_only_registered_content() {
+    let filter = EchoFilter::new();
+    let now = Instant::now();
+    assert!(!filter.is_echo_at(&[ContentFingerprint::text("a")], now));
+
+    filter.register_at(vec![ContentFingerprint::text("a")], now);
+    assert!(filter.is_echo_at(&[ContentFingerprint::text("a")], now));
+    assert!(!filter.is_echo_at(&[ContentFingerprint::text("b")], now));
+
+    // 每次登记只抵消一次变化，之后用户复制相同内容照常记录
+    let later = now + Duration::from_secs(3);
+    assert!(!filter.is_echo_at(&[ContentFingerprint::text("a")], later));
+
+    // 超过保留时长后不再忽略
+    filter.register_at(vec![ContentFingerprint::text("a")], now);
+    assert!(!filter.is_echo_at(&[ContentFingerprint::text("a")], now + ECHO_TTL));
+
+    // 空登记不产生任何效果
+    filter.register_at(Vec::new(), now);
+    assert!(!filter.is_echo_at(&[], now));
+}
+
+#[test]
+fn test_echo_requires_the_full_fingerprint_set() {
+    let filter = EchoFilter::new();
+    let now = Instant::now();
+    let text = ContentFingerprint::text("caption");
+    let image = ContentFingerprint::image(42);
+    filter.register_at(vec![text, image], now);
+
+    // 只有部分表示一致，或多出其他表示时都不是回声
+    assert!(!filter.is_echo_at(&[text], now));
+    assert!(!filter.is_echo_at(&[text, image, ContentFingerprint::text("x")], now));
+
+    // 顺序不同但集合一致时视为回声
+    assert!(filter.is_echo_at(&[image, text], now));
+    assert!(!filter.is_echo_at(&[text, image], now));
+}
+
+#[test]
+fn test_withdrawn_registration_is_not_an_echo() {
+    let filter = EchoFilter::new();
+    let now = Instant::now();
+    let failed = filter.register_at(vec![ContentFingerprint::text("a")], now);
+    let kept = filter.register_at(vec![ContentFingerprint::text("b")], now);
+    assert_ne!(failed, kept);
+
+    // 写入失败后撤销登记，用户随后复制相同内容照常记录
+    filter.withdraw(failed);
+    assert!(!filter.is_echo_at(&[ContentFingerprint::text("a")], now));
+    // 只撤销对应的那一次登记
+    assert!(filter.is_echo_at(&[ContentFingerprint::text("b")], now));
+
+    // 撤销已被匹配或不存在的登记不产生任何效果
+    filter.withdraw(kept);
+    filter.withdraw(filter.register_at(Vec::new(), now));
+}
+
+#[test]
+fn test_fingerprint_normalization() {
+    assert_eq!(ContentFingerprint::text("a\r\nb"), ContentFingerprint::text("a\nb"));
+    assert_ne!(ContentFingerprint::text("a"), ContentFingerprint::text("a "));
+
+    let written = vec![PathBuf::from("/tmp/a.txt"), PathBuf::from("/tmp/dir/")];
+    let read_back = vec![PathBuf::from("/tmp/a.txt"), PathBuf::from("/tmp/dir")];
+    assert_eq!(ContentFingerprint::files(&written), ContentFingerprint::files(&read_back));
+    let reordered = vec![PathBuf::from("/tmp/dir"), PathBuf::from("/tmp/a.txt")];
+    assert_ne!(ContentFingerprint::files(&written), ContentFingerprint::files(&reordered));
+
+    // 文本与文件列表的指纹互不混淆
+    assert_ne!(
+        ContentFingerprint::text("/tmp/a.txt"),
+        ContentFingerprint::files(&[PathBuf::from("/tmp/a.txt")])
+    );
+}
+
+#[test]
+fn test_fingerprints_of_written_representations() {
+    let dir = tempfile::tempdir().unwrap();
+    let image_path = dir.path().join("img.png");
+    let rgba: Vec<u8> = (0..2 * 3 * 4).map(|i| i as u8).collect();
+    image::save_buffer(&image_path, &rgba, 2, 3, image::ColorType::Rgba8).unwrap();
+
+    let reps = ClipboardRepresentations {
+        text: Some("caption".to_string()),
+        html: Some("<p>caption</p>".to_string()),
+        image_path: Some(image_path),
+        ..Default::default()
+    };
+    let fingerprints = fingerprints_of(&reps);
+    assert_eq!(
+        fingerprints,
+        vec![
+            ContentFingerprint::text("caption"),
+            ContentFingerprint::image(change_detect::image_fingerprint(2, 3, &rgba)),
+        ]
+    );
+
+    // 读取不到的图片不影响其他指纹
+    let missing = ClipboardRepresentations {
+        files: vec![PathBuf::from("/tmp/x")],
+        image_path: Some(dir.path().join("missing.png")),
+        ..Default::default()
+    };
+    assert_eq!(
+        fingerprints_of(&missing),
+        vec![ContentFingerprint::files(&[PathBuf::from("/tmp/x")])]
+    );
+}
@@ -59,3 +59,157 @@ async fn test_ocr_image() -> Result<(), String> {
 
     Ok(())
 }
+
+#[test]
+/// 测试 tesseract 风格的单词级输出：按行合并单词、合并包围盒、换算置信度
+fn test_parse_word_level_output() {
+    let raw = r#"[
+        {"block_num": 1, "par_num": 1, "line_num": 1, "left": 10, "top": 5, "width": 40, "height": 12, "conf": "96.5", "text": "Hello"},
+        {"block_num": 1, "par_num": 1, "line_num": 1, "left": 60, "top": 6, "width": 50, "height": 12, "conf": 93.5, "text": "world"},
+        {"block_num": 1, "par_num": 1, "line_num": 2, "left": 10, "top": 25, "width": 20, "height": 12, "conf": -1, "text": " "},
+        {"block_num": 2, "par_num": 1, "line_num": 1, "left": 10, "top": 60, "width": 20, "height": 12, "conf": 90, "text": "你好"},
+        {"block_num": 2, "par_num": 1, "line_num": 1, "left": 32, "top": 60, "width": 20, "height": 12, "conf": 80, "text": "世界"}
+    ]"#;
+    let lines = parse_ocr_output(raw, None);
+    assert_eq!(lines.len(), 2);
+
+    assert_eq!(lines[0].text, "Hello world");
+    assert_eq!(lines[0].block, 1);
+    assert_eq!(
+        lines[0].bbox,
+        Some(OcrBox { x: 10.0, y: 5.0, width: 100.0, height: 13.0 })
+    );
+    assert!((lines[0].confidence.unwrap() - 0.95).abs() < 1e-4);
+    assert_eq!(lines[0].language.as_deref(), Some("eng"));
+
+    // 中文单词之间不加空格
+    assert_eq!(lines[1].text, "你好世界");
+    assert_eq!(lines[1].language.as_deref(), Some("chi"));
+
+    let result = build_result("tesseract".to_string(), vec!["eng".to_string()], None, lines);
+    assert_eq!(result.text, "Hello world\n\n你好世界");
+    assert!((result.confidence.unwrap() - 0.9).abs() < 1e-4);
+}
+
+#[test]
+/// 测试行级输出与置信度阈值过滤
+fn test_parse_line_level_output_with_threshold() {
+    let raw = r#"[
+        {"text": "Invoice  No. 42", "confidence": 0.97, "bounding_box": {"x": 1, "y": 2, "width": 3, "height": 4}},
+        {"text": "~~#~", "confidence": 0.31},
+        {"text": "こんにちは", "confidence": 0.88, "language": "ja"}
+    ]"#;
+    let lines = filter_by_confidence(parse_ocr_output(raw, Some(0.5)), 0.8);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].text, "Invoice No. 42");
+    assert_eq!(lines[0].bbox, Some(OcrBox { x: 1.0, y: 2.0, width: 3.0, height: 4.0 }));
+    // 提供者给出的语言优先
+    assert_eq!(lines[1].language.as_deref(), Some("ja"));
+    assert_eq!(render_text(&lines), "Invoice No. 42\nこんにちは");
+}
+
+#[test]
+/// 测试纯文本输出：保留英文单词间的空格，空行分隔文本块，使用整体置信度
+fn test_parse_plain_text_output() {
+    let lines = parse_ocr_output("The quick  brown fox\r\n\r\n\r\n中 文 识 别\n", Some(0.7));
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0].text, "The quick brown fox");
+    assert_eq!(lines[1].text, "中文识别");
+    assert_eq!(lines[1].block, 1);
+    assert!(lines.iter().all(|l| l.confidence == Some(0.7) && l.bbox.is_none()));
+    assert_eq!(render_text(&lines), "The quick brown fox\n\n中文识别");
+
+    // 低于阈值时整张图片的文本都会被过滤
+    assert!(filter_by_confidence(lines, 0.8).is_empty());
+    assert!(parse_ocr_output("", None).is_empty());
+    assert_eq!(detect_language("1234 ..."), None);
+}
+
+#[test]
+/// 测试从配置构建引擎：无效配置在创建引擎前即返回错误
+fn test_init_from_config_rejects_invalid_config() {
+    let mut cfg = crate::config::Config::default();
+    cfg.ocr_languages = Some(vec!["eng".to_string(), "klingon".to_string()]);
+    let err = init_from_config(&cfg).unwrap_err();
+    assert!(err.contains("klingon"), "{}", err);
+
+    cfg.ocr_languages = Some(Vec::new());
+    cfg.ocr_confidence_threshold = Some(1.5);
+    assert!(init_from_config(&cfg).unwrap_err().contains("confidence"));
+
+    assert!(crate::config::ConfigKey::OcrLanguages.affects_ocr_engine());
+    assert!(!crate::config::ConfigKey::OcrAutoRecognition.affects_ocr_engine());
+}
+
+/// 生成白底图片，在给定矩形内填充指定灰度
+fn canvas(width: u32, height: u32, background: u8, rects: &[(u32, u32, u32, u32, u8)]) -> GrayImage {
+    GrayImage::from_fn(width, height, |x, y| {
+        let ink = rects
+            .iter()
+            .find(|&&(rx, ry, rw, rh, _)| x >= rx && x < rx + rw && y >= ry && y < ry + rh);
+        Luma([ink.map_or(background, |r| r.4)])
+    })
+}
+
+fn options(upscale_min_height: u32, adaptive_threshold: bool, deskew: bool, crop_borders: bool) -> PreprocessOptions {
+    PreprocessOptions {
+        upscale_min_height,
+        adaptive_threshold,
+        deskew,
+        crop_borders,
+    }
+}
+
+#[test]
+/// 测试裁剪边框与放大，并将包围盒映射回原图坐标
+fn test_preprocess_crop_and_upscale() {
+    let image = DynamicImage::ImageLuma8(canvas(100, 40, 255, &[(30, 15, 40, 10, 0)]));
+
+    let result = preprocess_image(&image, &options(300, false, false, true));
+    assert_eq!((result.offset_x, result.offset_y), (26, 11));
+    // 裁剪后高 18 像素，最多放大 4 倍
+    assert_eq!(result.scale, 4.0);
+    assert_eq!(result.image.dimensions(), (48 * 4, 18 * 4));
+    let mapped = result.map_box(OcrBox { x: 16.0, y: 16.0, width: 160.0, height: 40.0 });
+    assert_eq!(mapped, OcrBox { x: 30.0, y: 15.0, width: 40.0, height: 10.0 });
+
+    // 足够高的图片不放大，颜色一致的图片不裁剪
+    let plain = DynamicImage::ImageLuma8(canvas(50, 20, 200, &[]));
+    let result = preprocess_image(&plain, &options(10, false, false, true));
+    assert_eq!((result.offset_x, result.offset_y, result.scale), (0, 0, 1.0));
+    assert_eq!(result.image.dimensions(), (50, 20));
+}
+
+#[test]
+/// 测试自适应二值化：低对比度与深色背景的文字都变为白底黑字
+fn test_preprocess_adaptive_threshold() {
+    for (background, text) in [(200u8, 140u8), (30, 220)] {
+        let image = DynamicImage::ImageLuma8(canvas(120, 60, background, &[(40, 20, 40, 6, text)]));
+        let result = preprocess_image(&image, &options(0, true, false, false));
+        let binary = &result.image;
+        assert!(binary.pixels().all(|p| p.0[0] == 0 || p.0[0] == 255));
+        assert_eq!(binary.get_pixel(60, 22).0[0], 0, "text for bg {}", background);
+        assert_eq!(binary.get_pixel(5, 5).0[0], 255, "background for bg {}", background);
+    }
+}
+
+#[test]
+/// 测试纠偏：旋转后的文字行能被检测出倾斜角度并纠正
+fn test_preprocess_deskew() {
+    let lines: Vec<(u32, u32, u32, u32, u8)> =
+        (0..6).map(|i| (40, 40 + i * 35, 320, 8, 0)).collect();
+    let straight = canvas(400, 300, 255, &lines);
+    assert_eq!(estimate_skew(&straight), 0.0);
+
+    let skewed = rotate(&straight, 3.0);
+    assert!((estimate_skew(&skewed) - 3.0).abs() <= SKEW_STEP_DEGREES);
+
+    let result = preprocess_image(&DynamicImage::ImageLuma8(skewed), &options(0, false, true, false));
+    assert!((result.skew_degrees - 3.0).abs() <= SKEW_STEP_DEGREES);
+    assert!(estimate_skew(&result.image).abs() <= SKEW_STEP_DEGREES);
+
+    // 轻微倾斜不处理
+    let slight = rotate(&straight, 0.25);
+    let result = preprocess_image(&DynamicImage::ImageLuma8(slight), &options(0, false, true, false));
+    assert_eq!(result.skew_degrees, 0.0);
+}
@@ -0,0 +1,139 @@
+/// 粘贴队列单元测试
+use super::*;
+use crate::app_setup::{SHORTCUT_CONFIGS, STORAGE_KEY_TO_HANDLER_KEY};
+
+fn queued(id: &str) -> QueuedItem {
+    QueuedItem {
+        id: id.to_string(),
+        item_type: "text".to_string(),
+        preview: id.to_string(),
+    }
+}
+
+fn queue_ids(state: &PasteQueueState) -> Vec<String> {
+    state.get_status().items.into_iter().map(|i| i.id).collect()
+}
+
+#[test]
+fn test_push_only_in_queue_mode() {
+    let state = PasteQueueState::new();
+    assert!(!state.push(queued("a")));
+    assert!(state.get_status().items.is_empty());
+
+    state.set_enabled(true);
+    assert!(state.push(queued("a")));
+    assert!(state.push(queued("b")));
+
+    // 关闭后不再追加，但已有内容保留
+    state.set_enabled(false);
+    assert!(!state.push(queued("c")));
+    assert_eq!(queue_ids(&state), vec!["a", "b"]);
+}
+
+#[test]
+fn test_advance_fifo_with_progress() {
+    let state = PasteQueueState::new();
+    state.set_enabled(true);
+    for id in ["a", "b", "c"] {
+        state.push(queued(id));
+    }
+
+    let p = state.advance().unwrap();
+    assert_eq!((p.item.id.as_str(), p.pasted, p.remaining, p.total), ("a", 1, 2, 3));
+    // 粘贴过程中继续复制，总数随之增加
+    state.push(queued("d"));
+    let p = state.advance().unwrap();
+    assert_eq!((p.item.id.as_str(), p.pasted, p.remaining, p.total), ("b", 2, 2, 4));
+    state.advance();
+    let p = state.advance().unwrap();
+    assert_eq!((p.item.id.as_str(), p.remaining), ("d", 0));
+    assert_eq!(state.advance(), None);
+
+    // 队列清空后重新开启，计数从头开始
+    state.set_enabled(false);
+    state.set_enabled(true);
+    assert_eq!(state.get_status().pasted, 0);
+}
+
+#[test]
+fn test_skipped_items_are_not_counted() {
+    let state = PasteQueueState::new();
+    state.set_enabled(true);
+    for id in ["deleted", "b", "c"] {
+        state.push(queued(id));
+    }
+
+    // 队首数据项已被删除：取出后直接跳过，不计入进度
+    assert_eq!(state.pop_front().unwrap().id, "deleted");
+    let item = state.pop_front().unwrap();
+    let p = state.mark_pasted(item);
+    assert_eq!((p.item.id.as_str(), p.pasted, p.remaining, p.total), ("b", 1, 1, 2));
+    assert_eq!(state.get_status().pasted, 1);
+
+    // 写入剪贴板失败时放回队首，不丢失也不计入进度
+    let item = state.pop_front().unwrap();
+    state.push_front(item);
+    let status = state.get_status();
+    assert_eq!(status.items.front().unwrap().id, "c");
+    assert_eq!((status.items.len(), status.pasted), (1, 1));
+}
+
+#[test]
+fn test_reorder_remove_and_clear() {
+    let state = PasteQueueState::new();
+    state.set_enabled(true);
+    for id in ["a", "b", "c", "a"] {
+        state.push(queued(id));
+    }
+
+    let ids = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    state.reorder(&ids(&["c", "a", "a", "b"])).unwrap();
+    assert_eq!(queue_ids(&state), vec!["c", "a", "a", "b"]);
+
+    // 缺少或多出数据项时拒绝重排，队列保持不变
+    assert!(state.reorder(&ids(&["c", "a", "b"])).is_err());
+    assert!(state.reorder(&ids(&["c", "a", "a", "b", "x"])).is_err());
+    assert_eq!(queue_ids(&state), vec!["c", "a", "a", "b"]);
+
+    assert!(state.remove("a"));
+    assert!(!state.remove("a"));
+    assert_eq!(queue_ids(&state), vec!["c", "b"]);
+
+    state.advance();
+    let status = state.clear();
+    assert!(status.items.is_empty());
+    assert_eq!(status.pasted, 0);
+    assert!(status.enabled);
+}
+
+#[test]
+fn test_queued_item_preview() {
+    let item = |item_type: &str, content: &str| ClipboardItem {
+        id: "x".to_string(),
+        item_type: item_type.to_string(),
+        content: content.to_string(),
+        size: None,
+        is_favorite: false,
+        notes: "".to_string(),
+        timestamp: 0,
+    };
+
+    assert_eq!(QueuedItem::from_item(&item("text", "  张三\n 13800000000 ")).preview, "张三 13800000000");
+    let long = "a".repeat(100);
+    let preview = QueuedItem::from_item(&item("text", &long)).preview;
+    assert_eq!(preview.chars().count(), PREVIEW_CHARS + 1);
+    assert!(preview.ends_with('…'));
+    assert_eq!(QueuedItem::from_item(&item("file", "files/1700000000-report.pdf")).preview, "1700000000-report.pdf");
+}
+
+#[test]
+fn test_paste_queue_shortcut_registered() {
+    let config = SHORTCUT_CONFIGS
+        .get("pasteQueueNext")
+        .expect("pasteQueueNext shortcut missing");
+    assert_eq!(config.storage_key, "global_shortcut_7");
+    assert_eq!(
+        STORAGE_KEY_TO_HANDLER_KEY.get("global_shortcut_7"),
+        Some(&"pasteQueueNext")
+    );
+}
@@ -66,4 +66,79 @@ mod tests {
             let _ = std::fs::remove_file(&p);
         }
     }
+
+    #[test]
+    fn test_clipboard_change_detection_performance() {
+        use crate::change_detect::{self, ChangeDetector, ChangeTokenSource};
+
+        /// 计数始终不变的变化计数来源，模拟剪贴板内容未变化
+        struct FixedToken;
+
+        impl ChangeTokenSource for FixedToken {
+            fn change_token(&self) -> Option<u64> {
+                Some(1)
+            }
+        }
+
+        println!("🚀 开始剪贴板变化检测性能测试 (4K RGBA 截图)...");
+
+        // 模拟一张 3840x2160 的 RGBA 截图（约 33MB）
+        let (width, height) = (3840u32, 2160u32);
+        let mut image: Vec<u8> = (0..(width * height * 4) as usize)
+            .map(|i| (i % 251) as u8)
+            .collect();
+        let iterations = 10u32; // 相当于 100ms 轮询下的 1 秒
+
+        // 旧实现：每轮读取图片（剪贴板插件复制一次），再复制为 Vec<u8> 与上一次逐字节比较
+        let mut last_image_bytes = image.clone();
+        let start_full = Instant::now();
+        for _ in 0..iterations {
+            let read = std::hint::black_box(image.clone());
+            let current = read.to_vec();
+            assert!(current == last_image_bytes);
+            last_image_bytes = current;
+        }
+        let full_copy = start_full.elapsed();
+
+        // 没有变化计数时：每轮仍需读取图片，但只计算指纹
+        let last_hash = change_detect::image_fingerprint(width, height, &image);
+        let start_hash = Instant::now();
+        for _ in 0..iterations {
+            let read = std::hint::black_box(image.clone());
+            assert_eq!(change_detect::image_fingerprint(width, height, &read), last_hash);
+        }
+        let hashed = start_hash.elapsed();
+
+        // 有变化计数时：计数不变即跳过全部读取
+        let mut detector = ChangeDetector::new(FixedToken);
+        assert!(detector.poll());
+        let start_token = Instant::now();
+        for _ in 0..iterations {
+            assert!(!detector.poll());
+        }
+        let token = start_token.elapsed();
+
+        println!(
+            "⏱️ [Test] 完整复制比较: 每秒耗时 {:?}（每轮 {:?}）",
+            full_copy,
+            full_copy / iterations
+        );
+        println!(
+            "⏱️ [Test] 读取后计算指纹: 每秒耗时 {:?}（每轮 {:?}）",
+            hashed,
+            hashed / iterations
+        );
+        println!(
+            "⏱️ [Test] 变化计数: 每秒耗时 {:?}（每轮 {:?}）",
+            token,
+            token / iterations
+        );
+        assert!(token < full_copy);
+        assert!(token < hashed);
+
+        // 图片中间的单个字节变化也能被检测到
+        let middle = image.len() / 2;
+        image[middle] ^= 0x01;
+        assert_ne!(change_detect::image_fingerprint(width, height, &image), last_hash);
+    }
 }
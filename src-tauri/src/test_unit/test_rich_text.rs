@@ -0,0 +1,68 @@
+/// 富文本处理单元测试
+use super::*;
+
+#[test]
+fn test_html_to_text_basic() {
+    let html = "<p>Hello <b>world</b></p><p>Second&nbsp;line &amp; more</p>";
+    assert_eq!(html_to_text(html), "Hello world\nSecond line & more");
+}
+
+#[test]
+fn test_html_to_text_table_and_links() {
+    let html = "<table><tr><td>a</td><td>b</td></tr><tr><td>1</td><td>2</td></tr></table>\
+                <a href=\"https://example.com\">link</a>";
+    assert_eq!(html_to_text(html), "a\tb\n1\t2\nlink");
+}
+
+#[test]
+fn test_html_to_text_skips_script_and_style() {
+    let html = "<style>p { color: red; }</style><p>visible</p><SCRIPT>alert(1)</SCRIPT>";
+    assert_eq!(html_to_text(html), "visible");
+}
+
+#[test]
+fn test_html_entities() {
+    assert_eq!(
+        html_to_text("&lt;tag&gt; &#20013;&#x6587; &quot;q&quot;"),
+        "<tag> 中文 \"q\""
+    );
+    // 无法识别的实体保持原样
+    assert_eq!(
+        html_to_text("a & b &unknown; 中文&"),
+        "a & b &unknown; 中文&"
+    );
+}
+
+#[test]
+fn test_strip_cf_html_header() {
+    let cf_html = "Version:0.9\r\nStartHTML:0000000105\r\nEndHTML:0000000199\r\n\
+                   StartFragment:0000000141\r\nEndFragment:0000000163\r\n\
+                   <html><body><!--StartFragment--><b>bold</b><!--EndFragment--></body></html>";
+    assert_eq!(strip_cf_html_header(cf_html), "<b>bold</b>");
+    assert_eq!(html_to_text(cf_html), "bold");
+    assert_eq!(strip_cf_html_header("<i>x</i>"), "<i>x</i>");
+}
+
+#[test]
+fn test_rtf_to_text() {
+    let rtf = r"{\rtf1\ansi\deff0{\fonttbl{\f0 Calibri;}}{\colortbl;\red255\green0\blue0;}
+{\*\generator Riched20;}\f0\fs22 Hello \b bold\b0\par Second line\tab end \{x\}\'e9}";
+    assert_eq!(rtf_to_text(rtf), "Hello bold\nSecond line\tend {x}é");
+}
+
+#[test]
+fn test_rtf_unicode_and_code_pages() {
+    // \uN 后跳过 \ucN 个替代字符；负数参数与代理对
+    assert_eq!(rtf_to_text(r"{\rtf1\ansi\uc1 caf\u233?s}"), "cafés");
+    assert_eq!(rtf_to_text(r"{\rtf1\ansi\uc2 \u20013\'d6\'d0\u25991\'ce\'c4}"), "中文");
+    assert_eq!(rtf_to_text(r"{\rtf1\ansi\u-3913?}"), "\u{F0B7}");
+    assert_eq!(rtf_to_text(r"{\rtf1\ansi\uc1\u-10179?\u-8694?}"), "😊");
+    // \uc 作用于当前分组
+    assert_eq!(rtf_to_text(r"{\rtf1\ansi{\uc0\u20320}\u22909?}"), "你好");
+
+    // \'hh 按 \ansicpg 指定的代码页解码
+    assert_eq!(rtf_to_text(r"{\rtf1\ansi\ansicpg936 \'c4\'e3\'ba\'c3}"), "你好");
+    assert_eq!(rtf_to_text(r"{\rtf1\ansi\ansicpg932 \'82\'a0}"), "あ");
+    assert_eq!(rtf_to_text(r"{\rtf1\ansi\ansicpg1251 \'cf\'f0\'e8\'e2\'e5\'f2}"), "Привет");
+    assert_eq!(rtf_to_text(r"{\rtf1\ansi \'93quoted\'94}"), "\u{201C}quoted\u{201D}");
+}
@@ -0,0 +1,291 @@
+/// 语义搜索单元测试
+use super::*;
+use crate::db::MAX_EMBED_ATTEMPTS;
+use uuid::Uuid;
+
+fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    crate::db::TEST_RUN_LOCK
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+}
+
+fn set_test_db_path() {
+    let mut p = std::env::temp_dir();
+    p.push(format!("smartpaste_test_semantic_{}.db", Uuid::new_v4()));
+    db::set_db_path(p);
+}
+
+fn insert_text(id: &str, content: &str, timestamp: i64) {
+    db::insert_received_db_data(ClipboardItem {
+        id: id.to_string(),
+        item_type: "text".to_string(),
+        content: content.to_string(),
+        size: Some(content.len() as u64),
+        is_favorite: false,
+        notes: "".to_string(),
+        timestamp,
+    })
+    .unwrap();
+}
+
+fn run<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+fn similarity(a: &str, b: &str) -> f32 {
+    cosine_similarity(&LocalEmbedder::embed_text(a), &LocalEmbedder::embed_text(b))
+}
+
+fn ids(hits: &[SemanticSearchHit]) -> Vec<&str> {
+    hits.iter().map(|hit| hit.item.id.as_str()).collect()
+}
+
+/// 模型标识不同的本地向量，用于模拟更换模型
+struct RenamedEmbedder;
+
+impl Embedder for RenamedEmbedder {
+    fn model(&self) -> &str {
+        "renamed"
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String], _cancel: &'a CancelToken) -> EmbedFuture<'a> {
+        Box::pin(async move { Ok(texts.iter().map(|text| LocalEmbedder::embed_text(text)).collect()) })
+    }
+}
+
+/// 拒绝包含 "poison" 的文本（整批请求一起失败），或在 offline 时返回网络错误
+struct FlakyEmbedder {
+    offline: bool,
+}
+
+impl Embedder for FlakyEmbedder {
+    fn model(&self) -> &str {
+        "flaky"
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String], _cancel: &'a CancelToken) -> EmbedFuture<'a> {
+        Box::pin(async move {
+            if self.offline {
+                return Err(AiError::Network("connection refused".to_string()));
+            }
+            if texts.iter().any(|text| text.contains("poison")) {
+                return Err(AiError::Http {
+                    status: 400,
+                    message: "input rejected".to_string(),
+                });
+            }
+            Ok(texts.iter().map(|text| LocalEmbedder::embed_text(text)).collect())
+        })
+    }
+}
+
+#[test]
+fn test_local_embedder() {
+    let vector = LocalEmbedder::embed_text("SELECT * FROM sessions");
+    assert_eq!(vector.len(), LOCAL_EMBEDDING_DIMS);
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    assert!((norm - 1.0).abs() < 1e-5);
+    assert_eq!(vector, LocalEmbedder::embed_text("SELECT * FROM sessions"));
+    assert!(LocalEmbedder::embed_text("  ,. ").iter().all(|x| *x == 0.0));
+
+    // 大小写与词形变化
+    assert!(similarity("User Sessions", "user session") > 0.6);
+    let related = similarity("sql query about user sessions", "SELECT * FROM sessions WHERE user_id = 42");
+    let unrelated = similarity("sql query about user sessions", "周末去公园野餐的照片");
+    assert!(related > unrelated);
+    assert!(related >= MIN_SIMILARITY);
+    // 中文按单字与相邻两字匹配
+    assert!(similarity("会议纪要", "周一的会议纪要已经整理好") > similarity("会议纪要", "纪念日的会场"));
+}
+
+#[test]
+fn test_cosine_similarity_edge_cases() {
+    assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+    assert!((cosine_similarity(&[1.0, 0.0], &[-1.0, 0.0]) + 1.0).abs() < 1e-6);
+    assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]), 0.0);
+    assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+    assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+
+    let candidates = vec![
+        ("far".to_string(), vec![0.0, 1.0]),
+        ("near".to_string(), vec![0.9, 0.1]),
+        ("exact".to_string(), vec![1.0, 0.0]),
+    ];
+    let ranked = rank_by_similarity(&[1.0, 0.0], candidates, 10);
+    let order: Vec<&str> = ranked.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(order, vec!["exact", "near"]);
+}
+
+#[test]
+fn test_fuse_rankings() {
+    let semantic = vec![
+        ("a".to_string(), 0.9),
+        ("b".to_string(), 0.8),
+        ("c".to_string(), 0.5),
+    ];
+    let keyword = vec!["c".to_string(), "d".to_string()];
+    let fused = fuse_rankings(&semantic, &keyword, 10);
+    let order: Vec<&str> = fused.iter().map(|hit| hit.item_id.as_str()).collect();
+    // 两个列表中都出现的结果排在最前；只出现在一个列表中时名次相同的语义结果在前
+    assert_eq!(order, vec!["c", "a", "b", "d"]);
+    assert_eq!(fused[0].similarity, Some(0.5));
+    assert!(fused[0].keyword_match);
+    assert!(!fused[1].keyword_match);
+    assert_eq!(fused[3].similarity, None);
+    assert!(fused[3].keyword_match);
+
+    assert_eq!(fuse_rankings(&semantic, &keyword, 2).len(), 2);
+    assert!(fuse_rankings(&[], &[], 5).is_empty());
+}
+
+#[test]
+fn test_embedding_model_id() {
+    let mut cfg = Config {
+        ai_provider: "openai".to_string(),
+        ..Default::default()
+    };
+    assert_eq!(embedding_model_id(&cfg), LOCAL_EMBEDDING_MODEL);
+    assert_eq!(configured_embedder(&cfg).unwrap().model(), LOCAL_EMBEDDING_MODEL);
+
+    cfg.ai_embedding_model = Some(" text-embedding-3-small ".to_string());
+    assert_eq!(embedding_model_id(&cfg), "openai:text-embedding-3-small");
+    // 远程模型需要开启 AI 助手
+    assert!(configured_embedder(&cfg).is_err());
+    cfg.ai_enabled = true;
+    cfg.ai_model = "gpt-4o-mini".to_string();
+    let embedder = configured_embedder(&cfg).unwrap();
+    assert_eq!(embedder.model(), "openai:text-embedding-3-small");
+    assert!(embedder.is_remote());
+
+    assert_eq!(embedding_input(&format!("  {}  ", "字".repeat(EMBED_MAX_INPUT_CHARS + 5))).chars().count(), EMBED_MAX_INPUT_CHARS);
+}
+
+#[test]
+fn test_incremental_indexing() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_text("a", "SELECT * FROM sessions WHERE user_id = 42", 1);
+    insert_text("b", "周末去公园野餐", 2);
+    insert_text("empty", "   ", 3);
+    insert_text("secret", "password hunter2", 4);
+    db::mark_item_as_private("secret").unwrap();
+
+    assert_eq!(
+        db::get_embedding_stats(LOCAL_EMBEDDING_MODEL).unwrap(),
+        EmbeddingStats { indexed: 0, pending: 2, failed: 0 }
+    );
+    assert_eq!(run(index_pending(&LocalEmbedder, 1)).unwrap(), 1);
+    assert_eq!(run(index_pending(&LocalEmbedder, 10)).unwrap(), 1);
+    assert_eq!(run(index_pending(&LocalEmbedder, 10)).unwrap(), 0);
+    assert_eq!(
+        db::get_embedding_stats(LOCAL_EMBEDDING_MODEL).unwrap(),
+        EmbeddingStats { indexed: 2, pending: 0, failed: 0 }
+    );
+
+    // 内容变化后重新建立索引
+    db::update_data_content_by_id("b", "会议纪要").unwrap();
+    assert_eq!(db::get_embedding_stats(LOCAL_EMBEDDING_MODEL).unwrap().pending, 1);
+    assert_eq!(run(index_pending(&LocalEmbedder, 10)).unwrap(), 1);
+    let stored: HashMap<String, Vec<f32>> = db::get_embeddings(LOCAL_EMBEDDING_MODEL)
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(stored["b"], LocalEmbedder::embed_text("会议纪要"));
+
+    // 标记为隐私后删除向量；更换模型后全部重新索引
+    db::mark_item_as_private("a").unwrap();
+    assert_eq!(run(index_pending(&LocalEmbedder, 10)).unwrap(), 0);
+    assert_eq!(
+        db::get_embedding_stats(LOCAL_EMBEDDING_MODEL).unwrap(),
+        EmbeddingStats { indexed: 1, pending: 0, failed: 0 }
+    );
+    assert_eq!(db::get_embedding_stats("renamed").unwrap().pending, 1);
+    assert_eq!(run(index_pending(&RenamedEmbedder, 10)).unwrap(), 1);
+    assert_eq!(db::get_embeddings(LOCAL_EMBEDDING_MODEL).unwrap(), vec![]);
+
+    // 删除数据时一并删除向量
+    db::delete_data_by_id("b").unwrap();
+    assert_eq!(db::get_embeddings("renamed").unwrap(), vec![]);
+}
+
+#[test]
+fn test_failing_items_do_not_block_indexing() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_text("a", "first note", 1);
+    insert_text("bad", "poison pill", 2);
+    insert_text("b", "second note", 3);
+
+    // 网络错误与具体数据无关，不记录失败
+    assert!(run(index_pending(&FlakyEmbedder { offline: true }, 10)).is_err());
+    assert_eq!(db::get_embedding_stats("flaky").unwrap().pending, 3);
+
+    // 整批失败后逐条处理，其他数据照常建立索引
+    let embedder = FlakyEmbedder { offline: false };
+    assert_eq!(run(index_pending(&embedder, 10)).unwrap(), 3);
+    assert_eq!(
+        db::get_embedding_stats("flaky").unwrap(),
+        EmbeddingStats { indexed: 2, pending: 1, failed: 0 }
+    );
+    let mut indexed: Vec<String> = db::get_embeddings("flaky")
+        .unwrap()
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    indexed.sort();
+    assert_eq!(indexed, vec!["a", "b"]);
+
+    // 达到最大尝试次数后不再尝试
+    for _ in 1..MAX_EMBED_ATTEMPTS {
+        assert_eq!(run(index_pending(&embedder, 10)).unwrap(), 1);
+    }
+    assert_eq!(run(index_pending(&embedder, 10)).unwrap(), 0);
+    assert_eq!(
+        db::get_embedding_stats("flaky").unwrap(),
+        EmbeddingStats { indexed: 2, pending: 0, failed: 1 }
+    );
+
+    // 内容变化后重新计数
+    db::update_data_content_by_id("bad", "fixed note").unwrap();
+    assert_eq!(run(index_pending(&embedder, 10)).unwrap(), 1);
+    assert_eq!(
+        db::get_embedding_stats("flaky").unwrap(),
+        EmbeddingStats { indexed: 3, pending: 0, failed: 0 }
+    );
+}
+
+#[test]
+fn test_hybrid_search() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    insert_text("sql", "SELECT * FROM sessions WHERE user_id = 42", 1);
+    insert_text("picnic", "周末去公园野餐", 2);
+    insert_text("notes", "meeting notes: rotate the API keys", 3);
+    insert_text("secret", "user sessions token abc123", 4);
+    db::mark_item_as_private("secret").unwrap();
+    run(index_pending(&LocalEmbedder, 10)).unwrap();
+
+    // 关键词搜索找不到，语义结果可以找到；隐私数据不参与语义匹配
+    let hits = run(search_with(&LocalEmbedder, "that SQL query about user sessions", 5)).unwrap();
+    assert_eq!(ids(&hits), vec!["sql"]);
+    assert!(hits[0].similarity.unwrap() >= MIN_SIMILARITY);
+    assert!(!hits[0].keyword_match);
+
+    // 同时匹配关键词的结果排在前面
+    let hits = run(search_with(&LocalEmbedder, "sessions", 5)).unwrap();
+    assert_eq!(hits[0].item.id, "sql");
+    assert!(hits[0].keyword_match && hits[0].similarity.is_some());
+
+    assert!(run(search_with(&LocalEmbedder, "   ", 5)).unwrap().is_empty());
+    let json = serde_json::to_value(&hits[0]).unwrap();
+    assert_eq!(json["id"], "sql");
+    assert_eq!(json["item_type"], "text");
+    assert!(json["score"].as_f64().unwrap() > 0.0);
+}
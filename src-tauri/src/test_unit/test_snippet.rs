@@ -0,0 +1,159 @@
+/// 代码片段单元测试
+use super::*;
+use chrono::TimeZone;
+use std::fs;
+
+fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    crate::db::TEST_RUN_LOCK
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+}
+
+fn set_test_db_path() {
+    let mut p = std::env::temp_dir();
+    p.push(format!("smartpaste_test_snippet_{}.db", Uuid::new_v4()));
+    db::set_db_path(p);
+}
+
+fn context(inputs: &HashMap<String, String>, preview: bool) -> RenderContext<'_> {
+    RenderContext {
+        now: Local.with_ymd_and_hms(2024, 3, 15, 8, 30, 0).unwrap(),
+        clipboard: Some("剪贴板内容".to_string()),
+        inputs,
+        preview,
+    }
+}
+
+fn no_items(id: &str) -> Result<String, String> {
+    Err(format!("unexpected item {}", id))
+}
+
+#[test]
+fn test_parse_and_validate() {
+    let segments = parse_template("Hi {{ input:Name }}, id={{uuid}}").unwrap();
+    assert_eq!(
+        segments,
+        vec![
+            Segment::Text("Hi ".to_string()),
+            Segment::Placeholder(Placeholder::Input("Name".to_string())),
+            Segment::Text(", id=".to_string()),
+            Segment::Placeholder(Placeholder::Uuid),
+        ]
+    );
+    assert_eq!(
+        parse_template("{{date}}").unwrap(),
+        vec![Segment::Placeholder(Placeholder::Date("%Y-%m-%d".to_string()))]
+    );
+
+    let info = inspect_template("{{input:A}} {{input:B}} {{input:A}} {{item:x1}}").unwrap();
+    assert_eq!(info.inputs, vec!["A", "B"]);
+    assert_eq!(info.items, vec!["x1"]);
+
+    // 错误会一并返回
+    let err = parse_template("{{foo}} {{input}} {{uuid:1}} {{date:%Q}}").unwrap_err();
+    assert!(err.contains("未知的占位符: {{foo}}"), "{}", err);
+    assert!(err.contains("{{input}} 缺少参数"), "{}", err);
+    assert!(err.contains("{{uuid}} 不接受参数"), "{}", err);
+    assert!(err.contains("日期格式无效"), "{}", err);
+    assert!(parse_template("abc {{clipboard").unwrap_err().contains("第 5 个字符处"));
+
+    // 转义的花括号与不含占位符的文本
+    assert_eq!(
+        parse_template(r"\{{name}} {}").unwrap(),
+        vec![Segment::Text("{{name}} {}".to_string())]
+    );
+}
+
+#[test]
+fn test_render_placeholders() {
+    let mut inputs = HashMap::new();
+    inputs.insert("Name".to_string(), "张三".to_string());
+    let ctx = context(&inputs, false);
+
+    let out = render_template(
+        "{{date:%Y/%m/%d %H:%M}} {{input:Name}}: {{clipboard}}",
+        &ctx,
+        &no_items,
+    )
+    .unwrap();
+    assert_eq!(out, "2024/03/15 08:30 张三: 剪贴板内容");
+
+    let out = render_template("{{uuid}}", &ctx, &no_items).unwrap();
+    assert!(Uuid::parse_str(&out).is_ok());
+
+    let out = render_template("[{{item:abc}}]", &ctx, &|id| Ok(id.to_uppercase())).unwrap();
+    assert_eq!(out, "[ABC]");
+
+    // 缺少输入：复制时报错，预览时显示占位
+    assert!(render_template("{{input:Email}}", &ctx, &no_items)
+        .unwrap_err()
+        .contains("Email"));
+    let preview = context(&inputs, true);
+    assert_eq!(
+        render_template("{{input:Name}} <{{input:Email}}>", &preview, &no_items).unwrap(),
+        "张三 <<Email>>"
+    );
+}
+
+#[test]
+fn test_snippet_storage_and_item_references() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    db::insert_received_db_data(ClipboardItem {
+        id: "addr".to_string(),
+        item_type: "text".to_string(),
+        content: "北京市海淀区".to_string(),
+        size: None,
+        is_favorite: false,
+        notes: "".to_string(),
+        timestamp: 0,
+    })
+    .unwrap();
+
+    assert!(create_snippet("{{nope}}", None).is_err());
+    let inner: ClipboardItem =
+        serde_json::from_str(&create_snippet("地址：{{item:addr}}", Some("地址")).unwrap()).unwrap();
+    assert_eq!(inner.item_type, SNIPPET_TYPE);
+    assert!(inner.is_favorite);
+
+    let outer_template = format!("{{{{input:Name}}}}，{{{{item:{}}}}}", inner.id);
+    let outer: ClipboardItem =
+        serde_json::from_str(&create_snippet(&outer_template, None).unwrap()).unwrap();
+
+    let mut inputs = HashMap::new();
+    inputs.insert("Name".to_string(), "李四".to_string());
+    let ctx = context(&inputs, false);
+    assert_eq!(
+        render_with_items(&outer.content, &ctx, 0).unwrap(),
+        "李四，地址：北京市海淀区"
+    );
+
+    // 片段互相引用时报错而不是无限递归
+    update_snippet(&inner.id, &format!("{{{{item:{}}}}}", outer.id)).unwrap();
+    assert!(render_with_items(&outer.content, &ctx, 0)
+        .unwrap_err()
+        .contains("层级过深"));
+
+    // 只能修改片段，且新内容同样需要校验
+    assert!(update_snippet("addr", "x").is_err());
+    assert!(update_snippet(&inner.id, "{{bad}}").is_err());
+
+    // 片段出现在按类型筛选的结果中
+    let snippets: Vec<ClipboardItem> =
+        serde_json::from_str(&db::filter_data_by_type(SNIPPET_TYPE).unwrap()).unwrap();
+    assert_eq!(snippets.len(), 2);
+    // 搜索时片段同样按类型筛选，而不是被当作收藏夹 ID
+    let found: Vec<ClipboardItem> = serde_json::from_str(
+        &db::comprehensive_search("地址", Some(SNIPPET_TYPE), None, None, None, None).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, inner.id);
+
+    // 片段内容不是文件路径，删除时只删除记录
+    assert_eq!(db::delete_data_by_id(&outer.id).unwrap(), 1);
+    assert!(db::get_item_by_id(&outer.id).unwrap().is_none());
+
+    let _ = fs::remove_file(db::get_db_path());
+}
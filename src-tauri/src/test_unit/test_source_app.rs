@@ -0,0 +1,124 @@
+/// 来源应用识别单元测试
+use super::*;
+use crate::clipboard::ClipboardItem;
+use crate::db;
+use uuid::Uuid;
+
+// --- 测试辅助结构 ---
+
+/// 返回固定来源的假实现
+struct FakeSourceAppProvider {
+    source: Option<SourceApp>,
+}
+
+impl SourceAppProvider for FakeSourceAppProvider {
+    fn current_source(&self) -> Option<SourceApp> {
+        self.source.clone()
+    }
+}
+
+fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    crate::db::TEST_RUN_LOCK
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+}
+
+fn set_test_db_path() {
+    let mut p = std::env::temp_dir();
+    p.push(format!("smartpaste_test_source_{}.db", Uuid::new_v4()));
+    db::set_db_path(p);
+}
+
+fn make_item(id: &str, content: &str) -> ClipboardItem {
+    ClipboardItem {
+        id: id.to_string(),
+        item_type: "text".to_string(),
+        content: content.to_string(),
+        size: Some(content.len() as u64),
+        is_favorite: false,
+        notes: "".to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    }
+}
+
+fn app(name: &str, title: &str) -> SourceApp {
+    SourceApp {
+        app_name: name.to_string(),
+        window_title: title.to_string(),
+    }
+}
+
+// --- 测试用例 ---
+
+#[test]
+fn test_normalize_app_name() {
+    assert_eq!(normalize_app_name("KeePassXC.exe"), "keepassxc");
+    assert_eq!(
+        normalize_app_name(r"C:\Program Files\KeePass\KeePass.EXE"),
+        "keepass"
+    );
+    assert_eq!(normalize_app_name("/usr/bin/firefox"), "firefox");
+    assert_eq!(normalize_app_name("1Password.app"), "1password");
+    assert_eq!(normalize_app_name("  "), "");
+}
+
+#[test]
+fn test_is_ignored_app() {
+    let ignored = vec!["KeePassXC".to_string(), "bitwarden.exe".to_string()];
+
+    assert!(is_ignored_app(&app("keepassxc", "Vault"), &ignored));
+    assert!(is_ignored_app(&app("Bitwarden", ""), &ignored));
+    assert!(!is_ignored_app(&app("firefox", "KeePassXC docs"), &ignored));
+    // 空名称永不匹配
+    assert!(!is_ignored_app(&app("", ""), &ignored));
+    assert!(!is_ignored_app(&app("keepassxc", ""), &[]));
+}
+
+#[test]
+fn test_fake_provider_drives_ignore_decision() {
+    let ignored = vec!["keepassxc".to_string()];
+
+    let provider = FakeSourceAppProvider {
+        source: Some(app("KeePassXC", "Passwords.kdbx")),
+    };
+    let source = provider.current_source().unwrap();
+    assert!(is_ignored_app(&source, &ignored));
+
+    // 无法识别来源时照常记录
+    let unknown = FakeSourceAppProvider { source: None };
+    assert!(unknown
+        .current_source()
+        .map_or(true, |s| !is_ignored_app(&s, &ignored)));
+}
+
+#[test]
+fn test_item_source_storage_and_search() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    db::insert_received_db_data(make_item("from-code", "fn main() {}")).unwrap();
+    db::insert_received_db_data(make_item("from-browser", "fn main() in docs")).unwrap();
+    db::insert_received_db_data(make_item("unknown", "fn main() elsewhere")).unwrap();
+
+    db::insert_item_source("from-code", &app("Code", "main.rs - crate")).unwrap();
+    db::insert_item_source("from-browser", &app("firefox", "Docs")).unwrap();
+
+    // 读取来源
+    let json = db::get_item_source_by_item_id("from-code").unwrap();
+    let source: SourceApp = serde_json::from_str(&json).unwrap();
+    assert_eq!(source, app("Code", "main.rs - crate"));
+    assert_eq!(db::get_item_source_by_item_id("unknown").unwrap(), "");
+
+    // 按来源过滤搜索（忽略大小写）
+    let res = db::comprehensive_search("fn main", None, None, None, Some("code"), None).unwrap();
+    let items: Vec<ClipboardItem> = serde_json::from_str(&res).unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].id, "from-code");
+
+    // 不带来源过滤时返回全部
+    let res = db::comprehensive_search("fn main", None, None, None, None, None).unwrap();
+    let items: Vec<ClipboardItem> = serde_json::from_str(&res).unwrap();
+    assert_eq!(items.len(), 3);
+
+    let _ = std::fs::remove_file(db::get_db_path());
+}
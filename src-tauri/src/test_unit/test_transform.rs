@@ -0,0 +1,118 @@
+/// 文本转换单元测试
+use super::*;
+use std::fs;
+
+fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    crate::db::TEST_RUN_LOCK
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+}
+
+fn set_test_db_path() {
+    let mut p = std::env::temp_dir();
+    p.push(format!("smartpaste_test_transform_{}.db", Uuid::new_v4()));
+    db::set_db_path(p);
+}
+
+fn make_item(id: &str, item_type: &str, content: &str) -> ClipboardItem {
+    ClipboardItem {
+        id: id.to_string(),
+        item_type: item_type.to_string(),
+        content: content.to_string(),
+        size: Some(content.len() as u64),
+        is_favorite: false,
+        notes: "".to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    }
+}
+
+#[test]
+fn test_basic_transforms() {
+    assert_eq!(apply_chain("  Hello  ", "trim").unwrap(), "Hello");
+    assert_eq!(apply_chain("Hello", "upper").unwrap(), "HELLO");
+    assert_eq!(apply_chain("Hello", "LOWER").unwrap(), "hello");
+    assert_eq!(apply_chain("你好, world", "base64_encode").unwrap(), "5L2g5aW9LCB3b3JsZA==");
+    assert_eq!(apply_chain("5L2g5aW9LCB3b3JsZA==", "base64_decode").unwrap(), "你好, world");
+    assert_eq!(apply_chain("a b&c=测", "url_encode").unwrap(), "a%20b%26c%3D%E6%B5%8B");
+    assert_eq!(apply_chain("a%20b%26c%3D%E6%B5%8B", "url_decode").unwrap(), "a b&c=测");
+    assert!(apply_chain("%zz", "url_decode").is_err());
+    assert_eq!(apply_chain("say \"hi\"\n", "escape_json").unwrap(), r#"say \"hi\"\n"#);
+    assert_eq!(apply_chain("O'Brien", "escape_sql").unwrap(), "O''Brien");
+    assert_eq!(apply_chain("it's", "escape_shell").unwrap(), r"'it'\''s'");
+}
+
+#[test]
+fn test_json_transforms_keep_key_order() {
+    let minified = r#"{"b":1,"a":[1,2],"s":"x, y: {z}","e":{}}"#;
+    let pretty = apply_chain(minified, "json_pretty").unwrap();
+    assert_eq!(
+        pretty,
+        "{\n  \"b\": 1,\n  \"a\": [\n    1,\n    2\n  ],\n  \"s\": \"x, y: {z}\",\n  \"e\": {}\n}"
+    );
+    assert_eq!(apply_chain(&pretty, "json_minify").unwrap(), minified);
+    assert!(apply_chain("{not json}", "json_pretty").is_err());
+}
+
+#[test]
+fn test_cleanup_transforms() {
+    let messy = "a\u{200B}b\u{00A0}\u{00A0}c\t d  \n\n\n\ne";
+    assert_eq!(apply_chain(messy, "strip_formatting").unwrap(), "ab c d\n\ne");
+
+    let pdf = "This is a para-\ngraph copied from\na PDF file.\n\n第二段中文\n内容";
+    assert_eq!(
+        apply_chain(pdf, "join_lines").unwrap(),
+        "This is a paragraph copied from a PDF file.\n\n第二段中文内容"
+    );
+}
+
+#[test]
+fn test_chain_and_errors() {
+    assert_eq!(apply_chain("  {\"a\": 1}  ", "trim | json_minify | upper").unwrap(), "{\"A\":1}");
+    assert_eq!(apply_chain("x", "trim | nope").unwrap_err(), "未知的转换: nope");
+    assert!(apply_chain("x", " | ").is_err());
+    // 出错时带上失败的转换名称
+    assert!(apply_chain("###", "base64_decode").unwrap_err().starts_with("base64_decode:"));
+}
+
+#[test]
+fn test_run_transform_saves_linked_item() {
+    let _g = test_lock();
+    set_test_db_path();
+
+    db::insert_received_db_data(make_item("src", "text", "  hello  ")).unwrap();
+    db::mark_item_as_private("src").unwrap();
+
+    let result = run_transform("src", "trim|upper", false).unwrap();
+    assert_eq!(result.content, "HELLO");
+    assert_eq!(result.new_item_id, None);
+
+    let result = run_transform("src", "trim|upper", true).unwrap();
+    let new_id = result.new_item_id.unwrap();
+    let new_item = db::get_item_by_id(&new_id).unwrap().unwrap();
+    assert_eq!(new_item.content, "HELLO");
+    // 原数据不变，新数据继承隐私标记
+    assert_eq!(db::get_item_by_id("src").unwrap().unwrap().content, "  hello  ");
+    assert!(db::is_private_item(&new_id).unwrap());
+
+    let links = db::get_item_links("src").unwrap();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].item_id, new_id);
+    assert_eq!(links[0].relation, db::LINK_TRANSFORMED);
+    assert_eq!(links[0].detail, "trim|upper");
+
+    // 图片使用 OCR 文本，没有 OCR 文本时报错
+    db::insert_received_db_data(make_item("img", "image", "files/a.png")).unwrap();
+    assert!(run_transform("img", "upper", false).is_err());
+    db::insert_ocr_text("img", "scanned text").unwrap();
+    assert_eq!(run_transform("img", "upper", false).unwrap().content, "SCANNED TEXT");
+    assert!(run_transform("missing", "upper", false).is_err());
+
+    // 原数据不是隐私数据时，检查转换得到的内容
+    db::insert_received_db_data(make_item("encoded", "text", "dGVsOjEzODEyMzQ1Njc4")).unwrap();
+    let decoded = run_transform("encoded", "base64_decode", true).unwrap();
+    assert_eq!(decoded.content, "tel:13812345678");
+    assert!(!db::is_private_item("encoded").unwrap());
+    assert!(db::is_private_item(&decoded.new_item_id.unwrap()).unwrap());
+
+    let _ = fs::remove_file(db::get_db_path());
+}
@@ -0,0 +1,219 @@
+/// 翻译单元测试
+use super::*;
+use crate::ai::{AiError, AiFuture};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// 确定性的假服务：译文为 "译:" 加上原文的大写形式，并记录调用次数
+#[derive(Default)]
+struct FakeProvider {
+    calls: AtomicUsize,
+    fail: bool,
+}
+
+impl AiProvider for FakeProvider {
+    fn name(&self) -> &str {
+        "fake"
+    }
+
+    fn chat<'a>(
+        &'a self,
+        request: &'a ChatRequest,
+        _cancel: &'a CancelToken,
+        on_delta: &'a mut (dyn FnMut(&str) + Send),
+    ) -> AiFuture<'a> {
+        Box::pin(async move {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.fail {
+                return Err(AiError::Timeout);
+            }
+            let reply = format!("译:{}", request.messages[1].content.to_uppercase());
+            on_delta(&reply);
+            Ok(reply)
+        })
+    }
+}
+
+impl FakeProvider {
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+fn test_lock() -> std::sync::MutexGuard<'static, ()> {
+    crate::db::TEST_RUN_LOCK
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+}
+
+fn set_test_db_path() {
+    let mut p = std::env::temp_dir();
+    p.push(format!("smartpaste_test_translate_{}.db", Uuid::new_v4()));
+    db::set_db_path(p);
+}
+
+fn insert_text(id: &str, content: &str) {
+    db::insert_received_db_data(ClipboardItem {
+        id: id.to_string(),
+        item_type: "text".to_string(),
+        content: content.to_string(),
+        size: Some(content.len() as u64),
+        is_favorite: false,
+        notes: "".to_string(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+    })
+    .unwrap();
+}
+
+fn run<F: Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[test]
+fn test_detect_language() {
+    assert_eq!(detect_language("今天的会议改到下午三点"), Some("zh"));
+    assert_eq!(detect_language("今日の会議は午後三時に変更されました"), Some("ja"));
+    assert_eq!(detect_language("오늘 회의는 오후 세 시로 변경되었습니다"), Some("ko"));
+    assert_eq!(detect_language("Встреча перенесена на три часа"), Some("ru"));
+    assert_eq!(detect_language("The meeting is moved to three and it is in the main hall"), Some("en"));
+    assert_eq!(detect_language("La réunion est reportée et les invités sont dans la salle"), Some("fr"));
+    assert_eq!(detect_language("Die Besprechung ist verschoben und der Raum ist nicht frei"), Some("de"));
+    // 中文夹杂少量英文单词
+    assert_eq!(detect_language("请把 release notes 发到群里，谢谢大家"), Some("zh"));
+    // 无法判断
+    assert_eq!(detect_language("12345 !!!"), None);
+    assert_eq!(detect_language("foo bar baz"), None);
+}
+
+#[test]
+fn test_normalize_lang_and_hash() {
+    assert_eq!(normalize_lang(" zh_TW ").unwrap(), "zh-tw");
+    assert_eq!(normalize_lang("EN").unwrap(), "en");
+    assert!(normalize_lang("").is_err());
+    assert!(normalize_lang("e").is_err());
+    assert!(normalize_lang("en; drop").is_err());
+    assert_eq!(language_name("zh-tw"), "繁體中文");
+    assert_eq!(language_name("sw"), "sw");
+
+    assert_eq!(source_hash(""), "cbf29ce484222325");
+    assert_eq!(source_hash("a"), "af63dc4c8601ec8c");
+    assert_ne!(source_hash("hello"), source_hash("hello "));
+}
+
+#[test]
+fn test_build_request_names_languages() {
+    let request = build_request("Bonjour", Some("fr"), "zh");
+    assert_eq!(request.messages.len(), 2);
+    assert!(request.messages[0].content.contains("简体中文"));
+    assert!(request.messages[0].content.contains("Français"));
+    assert_eq!(request.messages[1].content, "Bonjour");
+
+    let request = build_request("???", None, "en");
+    assert!(request.messages[0].content.contains("English"));
+    assert!(!request.messages[0].content.contains("原文语言"));
+}
+
+#[test]
+fn test_translate_caches_per_language() {
+    let _g = test_lock();
+    set_test_db_path();
+    let provider = FakeProvider::default();
+    insert_text("t1", "the cat is on the mat");
+
+    let first = run(translate_with(&provider, "t1", "ZH", false)).unwrap();
+    assert_eq!(
+        first,
+        TranslationResult {
+            content: "译:THE CAT IS ON THE MAT".to_string(),
+            source_lang: Some("en".to_string()),
+            target_lang: "zh".to_string(),
+            cached: false,
+            new_item_id: None,
+        }
+    );
+    assert_eq!(provider.calls(), 1);
+
+    // 同一语言命中缓存，不再请求 AI 服务
+    let second = run(translate_with(&provider, "t1", "zh", false)).unwrap();
+    assert!(second.cached);
+    assert_eq!(second.content, first.content);
+    assert_eq!(provider.calls(), 1);
+
+    // 其他语言单独缓存
+    assert!(!run(translate_with(&provider, "t1", "ja", false)).unwrap().cached);
+    assert_eq!(provider.calls(), 2);
+    assert_eq!(db::get_translation("t1", "zh").unwrap().unwrap().content, first.content);
+    assert!(db::get_translation("t1", "ja").unwrap().is_some());
+
+    // 原文变化后缓存失效
+    db::update_data_content_by_id("t1", "the dog is in the garden").unwrap();
+    let third = run(translate_with(&provider, "t1", "zh", false)).unwrap();
+    assert!(!third.cached);
+    assert_eq!(third.content, "译:THE DOG IS IN THE GARDEN");
+    assert_eq!(provider.calls(), 3);
+
+    // 删除数据时一并删除译文
+    db::delete_data_by_id("t1").unwrap();
+    assert_eq!(db::get_translation("t1", "zh").unwrap(), None);
+}
+
+#[test]
+fn test_translate_skips_same_language_and_refuses_private() {
+    let _g = test_lock();
+    set_test_db_path();
+    let provider = FakeProvider::default();
+    insert_text("zh", "这是一段已经是中文的文本");
+    insert_text("secret", "my password is hunter2 and the pin is 1234");
+    db::mark_item_as_private("secret").unwrap();
+
+    let result = run(translate_with(&provider, "zh", "zh", false)).unwrap();
+    assert_eq!(result.content, "这是一段已经是中文的文本");
+    assert_eq!(result.source_lang.as_deref(), Some("zh"));
+    assert!(!result.cached);
+
+    assert!(run(translate_with(&provider, "secret", "zh", false)).is_err());
+    assert!(run(translate_with(&provider, "missing", "zh", false)).is_err());
+    assert!(run(translate_with(&provider, "zh", "not a lang", false)).is_err());
+    assert_eq!(provider.calls(), 0);
+
+    // 请求失败时不写入缓存
+    let failing = FakeProvider {
+        fail: true,
+        ..Default::default()
+    };
+    insert_text("en", "the report is ready for review");
+    assert!(run(translate_with(&failing, "en", "zh", false)).is_err());
+    assert_eq!(failing.calls(), 1);
+    assert_eq!(db::get_translation("en", "zh").unwrap(), None);
+}
+
+#[test]
+fn test_translate_save_as_new_links_items() {
+    let _g = test_lock();
+    set_test_db_path();
+    let provider = FakeProvider::default();
+    insert_text("src", "the build is green and the release is out");
+
+    let result = run(translate_with(&provider, "src", "fr", true)).unwrap();
+    let new_id = result.new_item_id.expect("应创建新记录");
+    let new_item = db::get_item_by_id(&new_id).unwrap().unwrap();
+    assert_eq!(new_item.item_type, "text");
+    assert_eq!(new_item.content, result.content);
+
+    let links = db::get_item_links(&new_id).unwrap();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].item_id, new_id);
+    assert_eq!(links[0].source_id, "src");
+    assert_eq!(links[0].relation, db::LINK_TRANSLATED);
+    assert_eq!(links[0].detail, "fr");
+
+    // 缓存命中时同样可以另存
+    let again = run(translate_with(&provider, "src", "fr", true)).unwrap();
+    assert!(again.cached);
+    assert_ne!(again.new_item_id, Some(new_id));
+    assert_eq!(provider.calls(), 1);
+}
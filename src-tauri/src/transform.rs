@@ -0,0 +1,415 @@
+//! "粘贴为"文本转换。
+//! 转换以注册表形式集中定义，可用 `|` 串联为转换链（如 `trim | json_pretty`），
+//! 结果可直接写入剪贴板，或另存为与原数据项关联的新历史记录。
+use crate::app_setup::ClipboardSourceState;
+use crate::clipboard::ClipboardItem;
+use crate::db;
+use crate::utils;
+use base64::{engine::general_purpose, Engine as _};
+use serde::Serialize;
+use tauri::State;
+use uuid::Uuid;
+
+/// 一种文本转换
+pub struct TransformDef {
+    /// 转换名称，用于转换链
+    pub name: &'static str,
+    /// 转换说明
+    pub description: &'static str,
+    apply: fn(&str) -> Result<String, String>,
+}
+
+impl TransformDef {
+    /// 对文本执行转换
+    pub fn apply(&self, text: &str) -> Result<String, String> {
+        (self.apply)(text)
+    }
+}
+
+/// 转换注册表
+const TRANSFORMS: &[TransformDef] = &[
+    TransformDef { name: "trim", description: "去除首尾空白", apply: trim },
+    TransformDef { name: "upper", description: "转为大写", apply: upper },
+    TransformDef { name: "lower", description: "转为小写", apply: lower },
+    TransformDef { name: "json_pretty", description: "JSON 格式化", apply: json_pretty },
+    TransformDef { name: "json_minify", description: "JSON 压缩", apply: json_minify },
+    TransformDef { name: "base64_encode", description: "Base64 编码", apply: base64_encode },
+    TransformDef { name: "base64_decode", description: "Base64 解码", apply: base64_decode },
+    TransformDef { name: "url_encode", description: "URL 编码", apply: url_encode },
+    TransformDef { name: "url_decode", description: "URL 解码", apply: url_decode },
+    TransformDef {
+        name: "strip_formatting",
+        description: "清除格式（不可见字符、多余空白）",
+        apply: strip_formatting,
+    },
+    TransformDef { name: "join_lines", description: "合并 PDF 复制产生的断行", apply: join_lines },
+    TransformDef { name: "escape_json", description: "转义为 JSON 字符串内容", apply: escape_json },
+    TransformDef { name: "escape_sql", description: "转义为 SQL 字符串内容", apply: escape_sql },
+    TransformDef { name: "escape_shell", description: "转义为 Shell 参数", apply: escape_shell },
+];
+
+/// 转换的名称与说明（供前端展示）
+#[derive(Debug, Clone, Serialize)]
+pub struct TransformInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// 转换结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransformResult {
+    /// 转换后的文本
+    pub content: String,
+    /// 另存为新记录时的数据项 ID
+    pub new_item_id: Option<String>,
+}
+
+/// 按名称查找转换（忽略大小写）
+pub fn find_transform(name: &str) -> Option<&'static TransformDef> {
+    let name = name.trim();
+    TRANSFORMS.iter().find(|t| t.name.eq_ignore_ascii_case(name))
+}
+
+/// 解析以 `|` 分隔的转换链
+/// # Param
+/// spec: &str - 转换链，如 "trim | upper"
+/// # Returns
+/// Result<Vec<&TransformDef>, String> - 转换列表，含未知转换时返回错误
+pub fn parse_chain(spec: &str) -> Result<Vec<&'static TransformDef>, String> {
+    let chain = spec
+        .split('|')
+        .filter(|s| !s.trim().is_empty())
+        .map(|name| find_transform(name).ok_or_else(|| format!("未知的转换: {}", name.trim())))
+        .collect::<Result<Vec<_>, _>>()?;
+    if chain.is_empty() {
+        return Err("未指定转换".to_string());
+    }
+    Ok(chain)
+}
+
+/// 依次执行转换链
+/// # Param
+/// text: &str - 原始文本
+/// spec: &str - 转换链
+/// # Returns
+/// Result<String, String> - 转换后的文本，任一步失败时返回带转换名称的错误
+pub fn apply_chain(text: &str, spec: &str) -> Result<String, String> {
+    parse_chain(spec)?
+        .into_iter()
+        .try_fold(text.to_string(), |acc, t| {
+            t.apply(&acc).map_err(|e| format!("{}: {}", t.name, e))
+        })
+}
+
+fn trim(text: &str) -> Result<String, String> {
+    Ok(text.trim().to_string())
+}
+
+fn upper(text: &str) -> Result<String, String> {
+    Ok(text.to_uppercase())
+}
+
+fn lower(text: &str) -> Result<String, String> {
+    Ok(text.to_lowercase())
+}
+
+/// 校验 JSON，避免对非 JSON 文本做格式化
+fn validate_json(text: &str) -> Result<(), String> {
+    serde_json::from_str::<serde_json::Value>(text)
+        .map(|_| ())
+        .map_err(|e| format!("不是合法的 JSON: {}", e))
+}
+
+/// 按字符重排 JSON 空白，保留键的原始顺序
+fn reformat_json(text: &str, indent: Option<&str>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let newline = |out: &mut String, depth: usize| {
+        if let Some(indent) = indent {
+            out.push('\n');
+            out.push_str(&indent.repeat(depth));
+        }
+    };
+
+    let mut chars = text.trim().chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            '{' | '[' => {
+                out.push(c);
+                // 空对象 / 空数组保持在同一行
+                while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                    chars.next();
+                }
+                if matches!(chars.peek(), Some('}') | Some(']')) {
+                    out.push(chars.next().unwrap());
+                } else {
+                    depth += 1;
+                    newline(&mut out, depth);
+                }
+            }
+            '}' | ']' => {
+                depth = depth.saturating_sub(1);
+                newline(&mut out, depth);
+                out.push(c);
+            }
+            ',' => {
+                out.push(c);
+                newline(&mut out, depth);
+            }
+            ':' => {
+                out.push(':');
+                if indent.is_some() {
+                    out.push(' ');
+                }
+            }
+            c if c.is_whitespace() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn json_pretty(text: &str) -> Result<String, String> {
+    validate_json(text)?;
+    Ok(reformat_json(text, Some("  ")))
+}
+
+fn json_minify(text: &str) -> Result<String, String> {
+    validate_json(text)?;
+    Ok(reformat_json(text, None))
+}
+
+fn base64_encode(text: &str) -> Result<String, String> {
+    Ok(general_purpose::STANDARD.encode(text.as_bytes()))
+}
+
+fn base64_decode(text: &str) -> Result<String, String> {
+    let cleaned: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let bytes = general_purpose::STANDARD
+        .decode(&cleaned)
+        .or_else(|_| general_purpose::URL_SAFE.decode(&cleaned))
+        .or_else(|_| general_purpose::STANDARD_NO_PAD.decode(&cleaned))
+        .or_else(|_| general_purpose::URL_SAFE_NO_PAD.decode(&cleaned))
+        .map_err(|e| format!("不是合法的 Base64: {}", e))?;
+    String::from_utf8(bytes).map_err(|_| "解码结果不是 UTF-8 文本".to_string())
+}
+
+/// 按 RFC 3986 编码，保留非保留字符
+fn url_encode(text: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    for b in text.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    Ok(out)
+}
+
+fn url_decode(text: &str) -> Result<String, String> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = text
+                .get(i + 1..i + 3)
+                .and_then(|h| u8::from_str_radix(h, 16).ok())
+                .ok_or_else(|| format!("位置 {} 处的百分号编码无效", i))?;
+            out.push(hex);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).map_err(|_| "解码结果不是 UTF-8 文本".to_string())
+}
+
+/// 去除不可见字符，将特殊空白统一为普通空格，压缩连续空白与空行
+fn strip_formatting(text: &str) -> Result<String, String> {
+    let cleaned: String = text
+        .chars()
+        .filter(|c| !matches!(c, '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}' | '\u{00AD}'))
+        .map(|c| match c {
+            '\u{00A0}' | '\u{2000}'..='\u{200A}' | '\u{202F}' | '\u{3000}' => ' ',
+            c => c,
+        })
+        .collect();
+
+    let mut lines: Vec<String> = Vec::new();
+    for line in cleaned.lines() {
+        let collapsed = line
+            .split([' ', '\t'])
+            .filter(|w| !w.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        // 连续空行只保留一行
+        if collapsed.is_empty() && lines.last().is_some_and(|l| l.is_empty()) {
+            continue;
+        }
+        lines.push(collapsed);
+    }
+    Ok(lines.join("\n").trim().to_string())
+}
+
+/// 是否为中日韩字符（合并断行时不插入空格）
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{2E80}'..='\u{9FFF}' | '\u{AC00}'..='\u{D7AF}' | '\u{F900}'..='\u{FAFF}' | '\u{FF00}'..='\u{FFEF}')
+}
+
+/// 合并段落内的断行：空行视为段落分隔；行尾连字符断词时去掉连字符；中日韩文字之间不加空格
+fn join_lines(text: &str) -> Result<String, String> {
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if current.is_empty() {
+            current.push_str(line);
+            continue;
+        }
+        let prev = current.chars().last().unwrap_or(' ');
+        let before_hyphen = current.chars().rev().nth(1).unwrap_or(' ');
+        let next = line.chars().next().unwrap_or(' ');
+        if prev == '-' && before_hyphen.is_alphabetic() && next.is_lowercase() {
+            current.pop();
+        } else if !(is_cjk(prev) || is_cjk(next)) {
+            current.push(' ');
+        }
+        current.push_str(line);
+    }
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    Ok(paragraphs.join("\n\n"))
+}
+
+fn escape_json(text: &str) -> Result<String, String> {
+    let quoted = serde_json::to_string(text).map_err(|e| e.to_string())?;
+    Ok(quoted[1..quoted.len() - 1].to_string())
+}
+
+fn escape_sql(text: &str) -> Result<String, String> {
+    Ok(text.replace('\'', "''"))
+}
+
+/// 使用单引号包裹为 POSIX Shell 参数
+fn escape_shell(text: &str) -> Result<String, String> {
+    Ok(format!("'{}'", text.replace('\'', r"'\''")))
+}
+
+/// 执行转换的内部实现：读取数据项文本，执行转换链，按需另存为新记录。
+/// 非文本数据使用其 OCR 文本。
+/// # Param
+/// id: &str - 数据项 ID
+/// transform: &str - 转换链
+/// save_as_new: bool - 是否另存为与原数据项关联的新记录
+/// # Returns
+/// Result<TransformResult, String> - 转换结果
+pub fn run_transform(id: &str, transform: &str, save_as_new: bool) -> Result<TransformResult, String> {
+    let item = db::get_item_by_id(id)?.ok_or_else(|| format!("数据不存在: {}", id))?;
+    let source_text = if item.item_type == "text" {
+        item.content
+    } else {
+        db::get_ocr_text(id)?
+            .filter(|t| !t.trim().is_empty())
+            .ok_or_else(|| "该数据没有可转换的文本".to_string())?
+    };
+    db::audit_private_access(db::AuditEvent::Viewed, id, "transform_item");
+
+    let content = apply_chain(&source_text, transform)?;
+
+    let new_item_id = if save_as_new {
+        let new_item = ClipboardItem {
+            id: Uuid::new_v4().to_string(),
+            item_type: "text".to_string(),
+            content: content.clone(),
+            size: Some(content.chars().count() as u64),
+            is_favorite: false,
+            notes: "".to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        };
+        // 由隐私数据转换得到的内容同样视为隐私，其余内容按配置检查是否包含敏感信息
+        db::insert_derived_item(
+            &new_item,
+            &[(id.to_string(), transform.trim().to_string())],
+            db::LINK_TRANSFORMED,
+        )?;
+        Some(new_item.id)
+    } else {
+        None
+    };
+
+    Ok(TransformResult {
+        content,
+        new_item_id,
+    })
+}
+
+/// 对数据项执行转换（可串联），按需写入剪贴板或另存为新记录。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// id: &str - 数据项 ID
+/// transform: &str - 转换链，多个转换以 `|` 分隔，如 "trim | json_pretty"
+/// write_to_clipboard: bool - 是否将结果写入剪贴板
+/// save_as_new: Option<bool> - 是否另存为与原数据项关联的新记录，默认否
+/// # Returns
+/// String - 转换结果的 JSON 字符串（content, new_item_id）
+#[tauri::command]
+pub fn transform_item(
+    id: &str,
+    transform: &str,
+    write_to_clipboard: bool,
+    save_as_new: Option<bool>,
+    state: State<'_, ClipboardSourceState>,
+) -> Result<String, String> {
+    let result = run_transform(id, transform, save_as_new.unwrap_or(false))?;
+
+    if write_to_clipboard {
+        utils::write_derived_text_to_clipboard(&result.content, id, "transform_item", &state)?;
+    }
+
+    serde_json::to_string(&result).map_err(|e| e.to_string())
+}
+
+/// 获取全部可用转换。作为 Tauri command 暴露给前端调用。
+/// # Returns
+/// String - 转换列表的 JSON 字符串（name, description）
+#[tauri::command]
+pub fn list_transforms() -> Result<String, String> {
+    let infos: Vec<TransformInfo> = TRANSFORMS
+        .iter()
+        .map(|t| TransformInfo {
+            name: t.name,
+            description: t.description,
+        })
+        .collect();
+    serde_json::to_string(&infos).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_transform.rs"]
+mod test_transform;
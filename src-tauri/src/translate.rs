@@ -0,0 +1,311 @@
+//! 数据项翻译。
+//! 本地按文字系统与常用词判断原文语言，通过配置的 AI 服务翻译为目标语言。
+//! 译文按数据项与目标语言缓存，结果可写入剪贴板，或另存为与原数据项关联的新历史记录。
+use crate::ai::{self, AiProvider, CancelToken, ChatMessage, ChatRequest};
+use crate::app_setup::ClipboardSourceState;
+use crate::clipboard::ClipboardItem;
+use crate::config::CONFIG;
+use crate::db::{self, CachedTranslation};
+use crate::utils;
+use serde::Serialize;
+use tauri::State;
+use uuid::Uuid;
+
+/// 可翻译文本的最多字符数
+pub const MAX_TRANSLATE_CHARS: usize = 20_000;
+
+/// 拉丁字母语言的常用词，用于区分原文语言
+const LATIN_STOPWORDS: &[(&str, &[&str])] = &[
+    ("en", &["the", "and", "is", "are", "of", "to", "in", "that", "with", "for", "this", "it", "you", "was"]),
+    ("fr", &["le", "la", "les", "des", "et", "est", "une", "du", "pour", "dans", "avec", "pas", "sur", "au"]),
+    ("de", &["der", "die", "das", "und", "ist", "nicht", "ein", "eine", "zu", "mit", "den", "von", "ich", "auf"]),
+    ("es", &["el", "los", "las", "y", "es", "una", "por", "para", "con", "del", "se", "está", "como", "pero"]),
+    ("pt", &["o", "os", "as", "é", "uma", "um", "para", "com", "não", "do", "da", "em", "mais", "você"]),
+    ("it", &["il", "lo", "gli", "che", "è", "una", "per", "con", "non", "della", "sono", "di", "anche", "questo"]),
+];
+
+/// 翻译结果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TranslationResult {
+    /// 译文
+    pub content: String,
+    /// 检测到的原文语言代码，无法判断时为 None
+    pub source_lang: Option<String>,
+    /// 目标语言代码
+    pub target_lang: String,
+    /// 是否来自缓存
+    pub cached: bool,
+    /// 另存为新记录时的数据项 ID
+    pub new_item_id: Option<String>,
+}
+
+/// 配置中是否开启了翻译
+fn translation_enabled() -> bool {
+    CONFIG
+        .get()
+        .map(|lock| lock.read().unwrap().ai_translation)
+        .unwrap_or(false)
+}
+
+/// 规范化语言代码：去掉首尾空白，转为小写，下划线替换为连字符（如 zh_TW -> zh-tw）
+/// # Param
+/// code: &str - 语言代码
+pub fn normalize_lang(code: &str) -> Result<String, String> {
+    let code = code.trim().to_ascii_lowercase().replace('_', "-");
+    let valid = (2..=16).contains(&code.len())
+        && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && !code.starts_with('-');
+    if valid {
+        Ok(code)
+    } else {
+        Err(format!("无效的目标语言: {}", code))
+    }
+}
+
+/// 语言代码在提示词中使用的名称，未知的代码原样使用
+pub fn language_name(code: &str) -> &str {
+    match code {
+        "zh" | "zh-cn" | "zh-hans" => "简体中文",
+        "zh-tw" | "zh-hk" | "zh-hant" => "繁體中文",
+        "en" => "English",
+        "ja" => "日本語",
+        "ko" => "한국어",
+        "fr" => "Français",
+        "de" => "Deutsch",
+        "es" => "Español",
+        "pt" => "Português",
+        "it" => "Italiano",
+        "ru" => "Русский",
+        "ar" => "العربية",
+        "th" => "ไทย",
+        "el" => "Ελληνικά",
+        "he" => "עברית",
+        _ => code,
+    }
+}
+
+/// 检测文本语言：先按文字系统判断，拉丁字母文本再按常用词判断
+/// # Param
+/// text: &str - 文本
+/// # Returns
+/// Option<&'static str> - 语言代码，无法判断时返回 None
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let (mut han, mut kana, mut hangul, mut cyrillic, mut arabic) = (0usize, 0usize, 0usize, 0usize, 0usize);
+    let (mut thai, mut greek, mut hebrew, mut latin) = (0usize, 0usize, 0usize, 0usize);
+    for c in text.chars() {
+        match c as u32 {
+            0x4E00..=0x9FFF | 0x3400..=0x4DBF => han += 1,
+            0x3040..=0x30FF => kana += 1,
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => hangul += 1,
+            0x0400..=0x04FF => cyrillic += 1,
+            0x0600..=0x06FF => arabic += 1,
+            0x0E00..=0x0E7F => thai += 1,
+            0x0370..=0x03FF => greek += 1,
+            0x0590..=0x05FF => hebrew += 1,
+            _ if c.is_alphabetic() && (c.is_ascii() || ('\u{00C0}'..='\u{024F}').contains(&c)) => {
+                latin += 1
+            }
+            _ => {}
+        }
+    }
+
+    // 日文同时使用汉字与假名，假名占一定比例即视为日文
+    let cjk = han + kana;
+    let scripts = [
+        (if kana > 0 && kana * 10 >= cjk { "ja" } else { "zh" }, cjk),
+        ("ko", hangul),
+        ("ru", cyrillic),
+        ("ar", arabic),
+        ("th", thai),
+        ("el", greek),
+        ("he", hebrew),
+    ];
+    let (lang, count) = scripts.iter().copied().max_by_key(|&(_, count)| count)?;
+    // 以较多的一种文字为准；非拉丁文字与拉丁字母数量相同时，非拉丁文字每个字符信息量更大
+    if count > 0 && count * 2 >= latin {
+        return Some(lang);
+    }
+    if latin == 0 {
+        return None;
+    }
+    detect_latin_language(text)
+}
+
+/// 按常用词出现次数判断拉丁字母文本的语言，最高得分不足 2 或与次高得分相同时返回 None
+fn detect_latin_language(text: &str) -> Option<&'static str> {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+    let mut scores: Vec<(&'static str, usize)> = LATIN_STOPWORDS
+        .iter()
+        .map(|(lang, stopwords)| {
+            let score = words.iter().filter(|w| stopwords.contains(&w.as_str())).count();
+            (*lang, score)
+        })
+        .collect();
+    scores.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+    match scores.as_slice() {
+        [(lang, best), (_, second), ..] if *best >= 2 && best > second => Some(lang),
+        _ => None,
+    }
+}
+
+/// 原文哈希（64 位 FNV-1a 的十六进制表示），在不同版本与平台间保持稳定，用于判断缓存是否失效
+pub fn source_hash(text: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in text.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// 构造翻译请求
+/// # Param
+/// text: &str - 原文
+/// source_lang: Option<&str> - 检测到的原文语言
+/// target_lang: &str - 目标语言代码
+pub fn build_request(text: &str, source_lang: Option<&str>, target_lang: &str) -> ChatRequest {
+    let mut system = format!(
+        "你是翻译引擎。将用户提供的文本翻译为{}。保留原有的换行、列表、代码、链接与专有名词，只输出译文，不要添加解释。",
+        language_name(target_lang)
+    );
+    if let Some(source) = source_lang {
+        system.push_str(&format!("原文语言：{}。", language_name(source)));
+    }
+    ChatRequest {
+        messages: vec![ChatMessage::system(system), ChatMessage::user(text)],
+        temperature: Some(0.2),
+        max_tokens: None,
+    }
+}
+
+/// 翻译的内部实现：读取数据项文本，优先使用缓存，否则通过 AI 服务翻译并缓存，按需另存为新记录。
+/// 非文本数据使用其 OCR 文本；隐私数据不会发送给 AI 服务。
+/// # Param
+/// provider: &dyn AiProvider - AI 服务
+/// id: &str - 数据项 ID
+/// target_lang: &str - 目标语言代码（如 en、zh、zh-TW）
+/// save_as_new: bool - 是否另存为与原数据项关联的新记录
+/// # Returns
+/// Result<TranslationResult, String> - 翻译结果
+pub async fn translate_with(
+    provider: &dyn AiProvider,
+    id: &str,
+    target_lang: &str,
+    save_as_new: bool,
+) -> Result<TranslationResult, String> {
+    let target_lang = normalize_lang(target_lang)?;
+    let item = db::get_item_by_id(id)?.ok_or_else(|| format!("数据不存在: {}", id))?;
+    if db::is_private_item(id)? {
+        return Err("隐私数据不会发送给 AI 服务".to_string());
+    }
+    let source_text = if item.item_type == "text" {
+        item.content
+    } else {
+        db::get_ocr_text(id)?
+            .ok_or_else(|| "该数据没有可翻译的文本".to_string())?
+    };
+    if source_text.trim().is_empty() {
+        return Err("该数据没有可翻译的文本".to_string());
+    }
+    if source_text.chars().count() > MAX_TRANSLATE_CHARS {
+        return Err(format!("文本过长，最多翻译 {} 个字符", MAX_TRANSLATE_CHARS));
+    }
+
+    let source_lang = detect_language(&source_text);
+    let hash = source_hash(&source_text);
+    let cached = db::get_translation(id, &target_lang)?.filter(|t| t.source_hash == hash);
+
+    let (content, cached) = if let Some(cached) = cached {
+        (cached.content, true)
+    } else if source_lang == Some(target_lang.as_str()) {
+        // 原文已是目标语言
+        (source_text, false)
+    } else {
+        let request = build_request(&source_text, source_lang, &target_lang);
+        let reply = provider
+            .complete(&request, &CancelToken::new())
+            .await
+            .map_err(String::from)?;
+        let content = reply.trim().to_string();
+        if content.is_empty() {
+            return Err("AI 服务返回了空的译文".to_string());
+        }
+        db::save_translation(
+            id,
+            &CachedTranslation {
+                target_lang: target_lang.clone(),
+                source_lang: source_lang.map(str::to_string),
+                source_hash: hash,
+                content: content.clone(),
+                timestamp: chrono::Utc::now().timestamp_millis(),
+            },
+        )?;
+        (content, false)
+    };
+
+    let new_item_id = if save_as_new {
+        let new_item = ClipboardItem {
+            id: Uuid::new_v4().to_string(),
+            item_type: "text".to_string(),
+            content: content.clone(),
+            size: Some(content.chars().count() as u64),
+            is_favorite: false,
+            notes: "".to_string(),
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        };
+        // 与来源关系在同一事务中写入，由隐私数据翻译出的内容同样标记为隐私
+        db::insert_derived_item(
+            &new_item,
+            &[(id.to_string(), target_lang.clone())],
+            db::LINK_TRANSLATED,
+        )?;
+        Some(new_item.id)
+    } else {
+        None
+    };
+
+    Ok(TranslationResult {
+        content,
+        source_lang: source_lang.map(str::to_string),
+        target_lang,
+        cached,
+        new_item_id,
+    })
+}
+
+/// 使用配置的 AI 服务翻译数据项，按需写入剪贴板或另存为新记录。作为 Tauri command 暴露给前端调用。
+/// # Param
+/// id: String - 数据项 ID
+/// target_lang: String - 目标语言代码（如 en、zh、zh-TW）
+/// write_to_clipboard: bool - 是否将译文写入剪贴板
+/// save_as_new: Option<bool> - 是否另存为与原数据项关联的新记录，默认否
+/// # Returns
+/// String - 翻译结果的 JSON 字符串（content, source_lang, target_lang, cached, new_item_id）
+#[tauri::command]
+pub async fn translate_item(
+    id: String,
+    target_lang: String,
+    write_to_clipboard: bool,
+    save_as_new: Option<bool>,
+    state: State<'_, ClipboardSourceState>,
+) -> Result<String, String> {
+    if !translation_enabled() {
+        return Err("AI 翻译未开启".to_string());
+    }
+    let provider = ai::configured_provider()?;
+    let result = translate_with(provider.as_ref(), &id, &target_lang, save_as_new.unwrap_or(false)).await?;
+
+    if write_to_clipboard {
+        utils::write_derived_text_to_clipboard(&result.content, &id, "translate_item", &state)?;
+    }
+
+    serde_json::to_string(&result).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+#[path = "test_unit/test_translate.rs"]
+mod test_translate;
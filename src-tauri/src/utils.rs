@@ -1,4 +1,5 @@
 use crate::app_setup::ClipboardSourceState;
+use crate::clipboard_backend::ClipboardRepresentations;
 use arboard::Clipboard;
 use base64::{engine::general_purpose, Engine as _};
 use clipboard_rs::{Clipboard as ClipboardRsTrait, ClipboardContext};
@@ -166,6 +167,13 @@ pub fn export_to_zip() -> Result<String, String> {
     // 5. 完成写入
     zip.finish().map_err(|e| format!("ZIP 写入失败: {}", e))?;
 
+    // 备份中包含隐私数据，为其记录导出审计事件
+    match crate::db::audit_private_export("export_to_zip") {
+        Ok(count) if count > 0 => println!("📝 已记录 {} 条隐私数据导出审计", count),
+        Ok(_) => {}
+        Err(e) => eprintln!("⚠️ 写入导出审计日志失败: {}", e),
+    }
+
     println!("✅ 数据已备份至: {}", zip_path.display());
 
     // 返回生成的 ZIP 文件名或完整路径
@@ -352,16 +360,20 @@ pub fn import_data_from_zip(app: tauri::AppHandle) -> Result<String, String> {
 #[tauri::command]
 pub fn write_to_clipboard(
     text: String,
+    item_id: Option<String>,
     _app_handle: tauri::AppHandle,
     state: State<'_, ClipboardSourceState>,
 ) -> Result<(), String> {
-    // 设置标志，表示这是前端触发的复制
-    *state.is_frontend_copy.lock().unwrap() = true;
-
-    let mut clipboard = Clipboard::new().map_err(|e| e.to_string())?;
-    clipboard.set_text(text).map_err(|e| e.to_string())?;
-
-    Ok(())
+    // 历史记录带有多种表示时（HTML、RTF、图片等），一并写回
+    let reps = ClipboardRepresentations {
+        text: Some(text),
+        ..Default::default()
+    };
+    let reps = match item_id.as_deref() {
+        Some(id) => with_stored_representations(id, reps)?,
+        None => reps,
+    };
+    write_representations_to_clipboard(&reps, item_id.as_deref(), "write_to_clipboard", &state)
 }
 /// 将指定的文本写入系统剪贴板。作为 Tauri command 暴露给前端调用。
 /// 将文件写入剪贴板（去除时间戳前缀）
@@ -369,15 +381,110 @@ pub fn write_to_clipboard(
 pub async fn write_file_to_clipboard(
     _app_handle: tauri::AppHandle,
     file_path: String,
+    item_id: Option<String>,
     state: State<'_, ClipboardSourceState>,
 ) -> Result<(), String> {
-    *state.is_frontend_copy.lock().unwrap() = true;
-
     // 直接复用修复后的处理逻辑，它现在支持文件夹且没有权限问题
     let final_path = process_file_for_clipboard(&file_path)?;
 
-    // 写入剪贴板 (复用列表逻辑，只不过列表里只有一个)
-    copy_files_list_to_clipboard(vec![final_path])
+    let reps = ClipboardRepresentations {
+        files: vec![final_path],
+        ..Default::default()
+    };
+    let reps = match item_id.as_deref() {
+        Some(id) => with_stored_representations(id, reps)?,
+        None => reps,
+    };
+    write_representations_to_clipboard(&reps, item_id.as_deref(), "write_file_to_clipboard", &state)
+}
+
+/// 写入剪贴板的公共流程：登记写入的内容（监控线程不会把这次写入记录为新的复制），写入全部表示，
+/// 写入成功后，复制的是历史记录中的隐私数据时写入审计日志
+/// # Param
+/// reps: &ClipboardRepresentations - 要写入的全部表示
+/// item_id: Option<&str> - 内容所属的数据项 ID
+/// origin: &str - 触发写入的命令名称，记录在审计日志中
+/// state: &ClipboardSourceState - 应用自身写入的登记状态
+pub(crate) fn write_representations_to_clipboard(
+    reps: &ClipboardRepresentations,
+    item_id: Option<&str>,
+    origin: &str,
+    state: &ClipboardSourceState,
+) -> Result<(), String> {
+    let ticket = state.register_write(reps);
+    let written = if reps.is_text_only() {
+        Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set_text(reps.text.clone().unwrap_or_default()))
+            .map_err(|e| e.to_string())
+    } else if reps.is_files_only() {
+        // 写入剪贴板 (复用列表逻辑)
+        copy_files_list_to_clipboard(reps.files.clone())
+    } else {
+        crate::clipboard_backend::write_representations_to_system(reps)
+    };
+    if let Err(e) = written {
+        // 写入失败，撤销登记，之后用户复制相同内容时不会被当作回声忽略
+        state.withdraw_write(ticket);
+        return Err(e);
+    }
+
+    if let Some(id) = item_id {
+        crate::db::audit_private_access(crate::db::AuditEvent::Copied, id, origin);
+    }
+    Ok(())
+}
+
+/// 将由数据项派生的文本（转换、翻译、片段展开、条码内容等）写入剪贴板。
+/// 与 write_to_clipboard 不同，不附带数据项保存的其他表示，这些表示与派生的文本并不一致
+/// # Param
+/// text: &str - 要写入的文本
+/// item_id: &str - 来源数据项 ID
+/// origin: &str - 触发写入的命令名称
+/// state: &ClipboardSourceState - 应用自身写入的登记状态
+pub(crate) fn write_derived_text_to_clipboard(
+    text: &str,
+    item_id: &str,
+    origin: &str,
+    state: &ClipboardSourceState,
+) -> Result<(), String> {
+    let reps = ClipboardRepresentations {
+        text: Some(text.to_string()),
+        ..Default::default()
+    };
+    write_representations_to_clipboard(&reps, Some(item_id), origin, state)
+}
+
+/// 合并数据项在 item_formats 中保存的其他表示，已有的表示保持不变
+/// # Param
+/// item_id: &str - 数据项 ID
+/// reps: ClipboardRepresentations - 首选表示
+/// # Returns
+/// ClipboardRepresentations - 包含全部表示的写入内容
+pub(crate) fn with_stored_representations(
+    item_id: &str,
+    mut reps: ClipboardRepresentations,
+) -> Result<ClipboardRepresentations, String> {
+    for format in crate::db::get_item_formats(item_id)? {
+        match format.format.as_str() {
+            crate::db::FORMAT_TEXT => {
+                reps.text.get_or_insert(format.content);
+            }
+            crate::db::FORMAT_HTML => {
+                reps.html.get_or_insert(format.content);
+            }
+            crate::db::FORMAT_RTF => {
+                reps.rtf.get_or_insert(format.content);
+            }
+            crate::db::FORMAT_IMAGE => {
+                let path = resolve_absolute_path(&PathBuf::from(&format.content));
+                if path.exists() {
+                    reps.image_path.get_or_insert(path);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(reps)
 }
 
 fn copy_dir_all(src: &Path, dst: &Path) -> io::Result<()> {
@@ -508,8 +615,6 @@ pub async fn write_files_to_clipboard(
     file_paths: Vec<String>,
     state: State<'_, ClipboardSourceState>,
 ) -> Result<(), String> {
-    *state.is_frontend_copy.lock().unwrap() = true;
-
     if file_paths.is_empty() {
         return Err("未选择任何内容".to_string());
     }
@@ -530,10 +635,12 @@ pub async fn write_files_to_clipboard(
         return Err("所有内容处理失败".to_string());
     }
 
-    // 写入剪贴板 (复用之前的函数)
-    copy_files_list_to_clipboard(final_paths)?;
-
-    Ok(())
+    // 写入剪贴板 (复用公共写入流程)
+    let reps = ClipboardRepresentations {
+        files: final_paths,
+        ..Default::default()
+    };
+    write_representations_to_clipboard(&reps, None, "copy_files", &state)
 }
 /// 跨平台地将文件复制到系统剪贴板。作为 Tauri command 暴露给前端调用。
 /// 此函数会根据编译的目标操作系统（Windows, macOS, Linux）调用相应的底层实现。
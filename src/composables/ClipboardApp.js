@@ -132,7 +132,10 @@ export function useClipboardApp() {
         currentCategory = currentFolder.value.id
       }
 
-      if (startTime.value && endTime.value) {
+      // 开启语义搜索后，在全部记录中搜索时按语义相似度与关键词综合排序
+      if (settings.ai_semantic_search && searchQuery.value.trim() && !currentCategory && !(startTime.value && endTime.value)) {
+        result = await invoke('semantic_search', { query: searchQuery.value })
+      } else if (startTime.value && endTime.value) {
         const startTimestamp = new Date(startTime.value).getTime()
         const endTimestamp = new Date(endTime.value).getTime()
         result = await invoke('comprehensive_search', { 
@@ -197,12 +200,23 @@ export function useClipboardApp() {
     try {
       if (item.item_type === 'text') {
         // 对于文本类型，使用原来的文本复制方法
-        await invoke('write_to_clipboard', { text: item.content })
+        await invoke('write_to_clipboard', { text: item.content, itemId: item.id })
         showMessage('已复制文本')
+      } else if (item.item_type === 'snippet') {
+        // 片段：先收集需要填写的输入，再由后端展开占位符
+        const { inputs: inputNames } = JSON.parse(await invoke('validate_snippet', { template: item.content }))
+        const inputs = {}
+        for (const name of inputNames) {
+          const value = window.prompt(`请输入 ${name}`)
+          if (value === null) return
+          inputs[name] = value
+        }
+        await invoke('copy_snippet', { id: item.id, inputs })
+        showMessage('已复制片段')
       } else {
         // 对于文件和图片类型，使用新的文件复制方法
         const filePath = normalizedPath.value + item.content
-        await invoke('write_file_to_clipboard', { filePath: filePath })
+        await invoke('write_file_to_clipboard', { filePath: filePath, itemId: item.id })
         showMessage(`已复制文件: ${getFileName(item.content)}`)
       }
 
@@ -476,6 +490,34 @@ export function useClipboardApp() {
     showOcrModal.value = true
   }
 
+  // 复制图片中二维码/条码的内容
+  const copyBarcode = async (item) => {
+    try {
+      const payload = await invoke('copy_barcode_payload', { itemId: item.id })
+      showMessage(`已复制：${payload}`)
+    } catch (error) {
+      console.error('复制二维码/条码内容失败:', error)
+      showMessage(`复制失败: ${error}`)
+    }
+  }
+
+  // 翻译文本并复制译文：中文译为英文，其他语言译为中文
+  const translateItem = async (item) => {
+    try {
+      const targetLang = /[\u4e00-\u9fa5]/.test(item.content) ? 'en' : 'zh'
+      const result = JSON.parse(await invoke('translate_item', {
+        id: item.id,
+        targetLang,
+        writeToClipboard: true,
+        saveAsNew: false
+      }))
+      showMessage(result.cached ? '已复制译文（缓存）' : '已复制译文')
+    } catch (error) {
+      console.error('翻译失败:', error)
+      showMessage(`翻译失败: ${error}`)
+    }
+  }
+
   // 复制OCR内容
   const copyOCR = async () => {
     if (!ocrText.value || ocrText.value.trim() === '') {
@@ -1015,6 +1057,27 @@ export function useClipboardApp() {
         })
     )   
     await Promise.all(promises)
+    // 批量获取 AI 生成的标签与摘要
+    await loadAiAnnotations(array)
+  }
+
+  // 将 AI 标签与摘要合并到列表项
+  function applyAiAnnotations(item, annotations) {
+    item.aiTags = annotations?.tags || []
+    item.aiSummary = annotations?.summary || ''
+  }
+
+  async function loadAiAnnotations(items) {
+    const ids = items.filter(item => ['text', 'image'].includes(item.item_type)).map(item => item.id)
+    if (ids.length === 0) return
+    try {
+      const annotations = await invoke('get_ai_annotations', { itemIds: ids })
+      for (const item of items) {
+        applyAiAnnotations(item, annotations[item.id])
+      }
+    } catch (error) {
+      console.error('获取 AI 标签与摘要失败:', error)
+    }
   }
 
   // 递归版本的带重试功能的图标获取函数
@@ -1097,6 +1160,7 @@ export function useClipboardApp() {
   }
   
   let unlistenShortcutEvent;
+  let unlistenAiAnnotations;
   // 生命周期
   onMounted(async () => {
 
@@ -1114,9 +1178,6 @@ export function useClipboardApp() {
       console.log('路由参数已清除')
     }
 
-    // OCR配置
-    await invoke('configure_ocr', {})
-    
     // 开启后端监听
     await setupClipboardRelay()
     
@@ -1168,6 +1229,15 @@ export function useClipboardApp() {
         // 3. 关闭可能的删除确认 UI (cancelDeleteAll)
         cancelDeleteAll(); 
     });
+
+    // 后台生成 AI 标签与摘要后更新对应的列表项
+    unlistenAiAnnotations = await listen('ai-annotations-updated', (event) => {
+      const { item_id, annotations } = event.payload
+      const item = filteredHistory.value.find(i => i.id === item_id)
+      if (item) {
+        applyAiAnnotations(item, annotations)
+      }
+    })
    
   })
 
@@ -1175,6 +1245,9 @@ export function useClipboardApp() {
     if (unlistenShortcutEvent) {
         unlistenShortcutEvent();
     }
+    if (unlistenAiAnnotations) {
+      unlistenAiAnnotations()
+    }
     removeWindowListeners()
   })
 
@@ -1240,6 +1313,8 @@ export function useClipboardApp() {
     showOCR,
     copyOCR,
     cancelOCR,
+    copyBarcode,
+    translateItem,
     removeItem,
     showFolder,
     addFolder,
@@ -263,7 +263,9 @@ export function usePreferences() {
     global_shortcut_2: '显示/隐藏剪贴板', 
     global_shortcut_3: '显示/隐藏AI助手',
     global_shortcut_4: '显示/隐藏设置页面',
-    global_shortcut_5: '清空剪贴板历史'
+    global_shortcut_5: '清空剪贴板历史',
+    global_shortcut_6: '暂停/恢复记录',
+    global_shortcut_7: '粘贴队列下一项'
   }
   const shortcutKeys = Object.keys(shortcutDisplayNames)
 
@@ -16,6 +16,8 @@ export const useSettingsStore = defineStore('settings', () => {
     global_shortcut_3: "Shift+A",
     global_shortcut_4: "Shift+V",
     global_shortcut_5: "Shift+Delete",
+    global_shortcut_6: "Shift+Ctrl+P",
+    global_shortcut_7: "Shift+Alt+V",
     max_history_items: 500,
     ignore_short_text_len: 3,
     ignore_big_file_mb: 5,
@@ -32,6 +34,9 @@ export const useSettingsStore = defineStore('settings', () => {
     ai_auto_summary: false,
     ai_translation: false,
     ai_web_search: false,
+    ai_timeout_secs: null,
+    ai_semantic_search: false,
+    ai_embedding_model: null,
     sensitive_filter: true,
     filter_passwords: true,
     filter_bank_cards: true,
@@ -55,7 +60,12 @@ export const useSettingsStore = defineStore('settings', () => {
     ocr_provider: null,
     ocr_languages: [],
     ocr_confidence_threshold: null,
-    ocr_timeout_secs: null
+    ocr_timeout_secs: null,
+    ocr_preprocess: false,
+    ocr_upscale_min_height: 300,
+    ocr_adaptive_threshold: true,
+    ocr_deskew: true,
+    ocr_crop_borders: true
   }
 
   // 创建响应式设置对象，初始值为默认设置